@@ -0,0 +1,224 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+#![doc = include_str!("../README.md")]
+
+use std::collections::BTreeMap;
+
+use gnuv2_demangle::{
+    demangle, features, DemangleConfig, DemangleConfigBuilder, ReturnTypeStyle, VERSION,
+};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+/// Demangle a single symbol using one of the `style` presets.
+///
+/// Valid styles are `"g2dem"`, `"g"`, `"cfilt"`, `"c++filt"` and `"c"`.
+///
+/// Returns `undefined` if the symbol can't be demangled or if the style is
+/// not recognized.
+#[wasm_bindgen]
+#[must_use]
+pub fn demangle_js(sym: &str, style: &str) -> Option<String> {
+    let config = config_from_style(style)?;
+
+    demangle(sym, &config).ok()
+}
+
+/// Demangle a single symbol using one of the `style` presets, with individual
+/// flags overridden by the `options` object.
+///
+/// Throws an `Error` with the reason if the symbol can't be demangled, or if
+/// either `style` or `options` are not valid.
+#[wasm_bindgen]
+pub fn demangle_with_options_js(
+    sym: &str,
+    style: &str,
+    options: JsValue,
+) -> Result<String, JsError> {
+    let config = config_from_js(style, options)?;
+
+    demangle(sym, &config).map_err(|e| JsError::new(&format!("{e:?}")))
+}
+
+/// Demangle every line of `text` using one of the `style` presets.
+///
+/// Returns an array with one `{input, output, error}` object per line. Only
+/// one of `output` and `error` will be non-`null`.
+///
+/// Throws an `Error` if the style is not recognized.
+#[wasm_bindgen]
+pub fn demangle_batch_js(text: &str, style: &str) -> Result<JsValue, JsError> {
+    demangle_batch_with_options_js(text, style, JsValue::UNDEFINED)
+}
+
+/// Same as [`demangle_batch_js`], but with individual flags overridden by the
+/// `options` object.
+#[wasm_bindgen]
+pub fn demangle_batch_with_options_js(
+    text: &str,
+    style: &str,
+    options: JsValue,
+) -> Result<JsValue, JsError> {
+    let config = config_from_js(style, options)?;
+
+    let entries: Vec<BatchEntry> = text
+        .lines()
+        .map(|input| match demangle(input, &config) {
+            Ok(output) => BatchEntry {
+                input,
+                output: Some(output),
+                error: None,
+            },
+            Err(e) => BatchEntry {
+                input,
+                output: None,
+                error: Some(format!("{e:?}")),
+            },
+        })
+        .collect();
+
+    // Use `null` instead of `undefined` for the missing fields.
+    let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+    Ok(entries.serialize(&serializer)?)
+}
+
+/// The version of the demangler.
+#[wasm_bindgen]
+#[must_use]
+pub fn version_js() -> String {
+    VERSION.to_string()
+}
+
+/// List every flag accepted by the `options` object.
+///
+/// Returns an array with one `{name, doc, defaultG2dem, defaultCfilt}` object
+/// per flag, where `name` is the camelCase name accepted by `options`.
+#[wasm_bindgen]
+pub fn features_js() -> Result<JsValue, JsError> {
+    let entries: Vec<FeatureEntry> = features()
+        .iter()
+        .map(|feature| FeatureEntry {
+            name: to_camel_case(feature.name),
+            doc: feature.doc,
+            default_g2dem: feature.default_g2dem,
+            default_cfilt: feature.default_cfilt,
+        })
+        .collect();
+
+    let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+    Ok(entries.serialize(&serializer)?)
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FeatureEntry {
+    name: String,
+    doc: &'static str,
+    default_g2dem: bool,
+    default_cfilt: bool,
+}
+
+fn to_camel_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            out.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[derive(Debug, Serialize)]
+struct BatchEntry<'s> {
+    input: &'s str,
+    output: Option<String>,
+    error: Option<String>,
+}
+
+fn config_from_style(style: &str) -> Option<DemangleConfig> {
+    match style {
+        "g2dem" | "g" => Some(DemangleConfig::new_g2dem()),
+        "cfilt" | "c++filt" | "c" => Some(DemangleConfig::new_cfilt()),
+        _ => None,
+    }
+}
+
+fn config_from_js(style: &str, options: JsValue) -> Result<DemangleConfig, JsError> {
+    let config = config_from_style(style).ok_or_else(|| {
+        JsError::new(&format!(
+            "Unknown demangling style `{style}`. Valid options are: `g2dem`, `g`, `cfilt`, `c++filt` and `c`"
+        ))
+    })?;
+    let options: Option<BTreeMap<String, bool>> = serde_wasm_bindgen::from_value(options)?;
+
+    // Individual flags of `DemangleConfig`, using JS' naming conventions.
+    // Missing flags keep the value from the selected style.
+    let mut builder = DemangleConfigBuilder::from(config);
+    for (name, value) in options.into_iter().flatten() {
+        builder = match name.as_str() {
+            "fixNamespacedGlobalConstructorBug" => {
+                builder.fix_namespaced_global_constructor_bug(value)
+            }
+            "fixArrayLengthArg" => builder.fix_array_length_arg(value),
+            "fixArrayLengthSkipZero" => builder.fix_array_length_skip_zero(value),
+            "demangleGlobalKeyedFrames" => builder.demangle_global_keyed_frames(value),
+            "ellipsisEmitSpaceAfterComma" => builder.ellipsis_emit_space_after_comma(value),
+            "fixExtensionInt" => builder.fix_extension_int(value),
+            "fixArrayInReturnPosition" => builder.fix_array_in_return_position(value),
+            "fixFunctionPointersInTemplateLists" => {
+                builder.fix_function_pointers_in_template_lists(value)
+            }
+            "simplifyStlContainers" => builder.simplify_stl_containers(value),
+            "demangleConstructorVariants" => builder.demangle_constructor_variants(value),
+            "emitEnumCastsInTemplates" => builder.emit_enum_casts_in_templates(value),
+            "fixFunctionPointerInReturnPosition" => {
+                builder.fix_function_pointer_in_return_position(value)
+            }
+            "emitPlainRestrict" => builder.emit_plain_restrict(value),
+            "demangleCloneSuffix" => builder.demangle_clone_suffix(value),
+            "emitCloneSuffix" => builder.emit_clone_suffix(value),
+            "tolerateStrayUnderscoresBetweenArgs" => {
+                builder.tolerate_stray_underscores_between_args(value)
+            }
+            "fixFunctionPointerTemplateLookbacks" => {
+                builder.fix_function_pointer_template_lookbacks(value)
+            }
+            "emitIntegerSuffixesInTemplates" => builder.emit_integer_suffixes_in_templates(value),
+            "emitWideCharacterLiteralsInTemplates" => {
+                builder.emit_wide_character_literals_in_templates(value)
+            }
+            "fixTemplateParameterLevels" => builder.fix_template_parameter_levels(value),
+            "completeTruncatedSymbols" => builder.complete_truncated_symbols(value),
+            "omitParameterList" => builder.omit_parameter_list(value),
+            "omitReturnType" => builder.return_type_style(if value {
+                ReturnTypeStyle::Omit
+            } else {
+                ReturnTypeStyle::CfiltLeading
+            }),
+            "omitTemplateArgsInScope" => builder.omit_template_args_in_scope(value),
+            "demangleGlobalKeyedTemplateInstantiations" => {
+                builder.demangle_global_keyed_template_instantiations(value)
+            }
+            "rejectPlainCLookalikes" => builder.reject_plain_c_lookalikes(value),
+            "stripTopLevelConstOnValueArgs" => builder.strip_top_level_const_on_value_args(value),
+            "prettyAnonymousNamespaces" => builder.pretty_anonymous_namespaces(value),
+            "lenient" => builder.lenient(value),
+            "demangleNestedGlobalKeyedSymbols" => {
+                builder.demangle_nested_global_keyed_symbols(value)
+            }
+            "demangleVtableOffsets" => builder.demangle_vtable_offsets(value),
+            "fixArrayOfReferences" => builder.fix_array_of_references(value),
+            "describeStaticInitFunctions" => builder.describe_static_init_functions(value),
+            _ => return Err(JsError::new(&format!("Unknown option `{name}`"))),
+        };
+    }
+
+    Ok(builder.build())
+}
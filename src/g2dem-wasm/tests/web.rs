@@ -0,0 +1,147 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+//! Run with `wasm-pack test --node`.
+
+#![cfg(target_arch = "wasm32")]
+
+use g2dem_wasm::{
+    demangle_batch_js, demangle_batch_with_options_js, demangle_js, demangle_with_options_js,
+    features_js,
+};
+use serde::Deserialize;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+struct BatchEntry {
+    input: String,
+    output: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FeatureEntry {
+    name: String,
+    default_g2dem: bool,
+}
+
+fn options(json: &str) -> JsValue {
+    js_sys::JSON::parse(json).unwrap()
+}
+
+fn error_message(err: JsValue) -> String {
+    err.dyn_into::<js_sys::Error>().unwrap().message().into()
+}
+
+#[wasm_bindgen_test]
+fn test_demangle_js() {
+    assert_eq!(
+        demangle_js("Printf__7ConsolePce", "g2dem").as_deref(),
+        Some("Console::Printf(char *, ...)")
+    );
+    assert_eq!(
+        demangle_js("Printf__7ConsolePce", "c++filt").as_deref(),
+        Some("Console::Printf(char *,...)")
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_demangle_js_failure() {
+    assert_eq!(demangle_js("not_mangled", "g2dem"), None);
+    assert_eq!(demangle_js("Printf__7ConsolePce", "gnu-v3"), None);
+}
+
+#[wasm_bindgen_test]
+fn test_demangle_with_options_js() {
+    let demangled = demangle_with_options_js(
+        "Printf__7ConsolePce",
+        "c++filt",
+        options(r#"{"ellipsisEmitSpaceAfterComma": true}"#),
+    );
+    assert_eq!(
+        demangled.ok().as_deref(),
+        Some("Console::Printf(char *, ...)")
+    );
+
+    let demangled = demangle_with_options_js("Printf__7ConsolePce", "c++filt", JsValue::UNDEFINED);
+    assert_eq!(
+        demangled.ok().as_deref(),
+        Some("Console::Printf(char *,...)")
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_demangle_with_options_js_errors() {
+    let err = demangle_with_options_js("not_mangled", "g2dem", JsValue::UNDEFINED).unwrap_err();
+    assert_eq!(error_message(err.into()), "NotMangled");
+
+    let err =
+        demangle_with_options_js("Printf__7ConsolePce", "gnu-v3", JsValue::UNDEFINED).unwrap_err();
+    assert!(error_message(err.into()).contains("gnu-v3"));
+
+    let err = demangle_with_options_js(
+        "Printf__7ConsolePce",
+        "g2dem",
+        options(r#"{"notAnOption": true}"#),
+    )
+    .unwrap_err();
+    assert!(error_message(err.into()).contains("notAnOption"));
+}
+
+#[wasm_bindgen_test]
+fn test_demangle_batch_js() {
+    let batch = demangle_batch_js("Printf__7ConsolePce\nnot_mangled", "g2dem").unwrap();
+    let entries: Vec<BatchEntry> = serde_wasm_bindgen::from_value(batch).unwrap();
+
+    assert_eq!(
+        entries,
+        [
+            BatchEntry {
+                input: "Printf__7ConsolePce".into(),
+                output: Some("Console::Printf(char *, ...)".into()),
+                error: None,
+            },
+            BatchEntry {
+                input: "not_mangled".into(),
+                output: None,
+                error: Some("NotMangled".into()),
+            },
+        ]
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_demangle_batch_with_options_js() {
+    let batch = demangle_batch_with_options_js(
+        "Printf__7ConsolePce",
+        "c",
+        options(r#"{"ellipsisEmitSpaceAfterComma": true}"#),
+    )
+    .unwrap();
+    let entries: Vec<BatchEntry> = serde_wasm_bindgen::from_value(batch).unwrap();
+
+    assert_eq!(
+        entries,
+        [BatchEntry {
+            input: "Printf__7ConsolePce".into(),
+            output: Some("Console::Printf(char *, ...)".into()),
+            error: None,
+        }]
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_features_js() {
+    let entries: Vec<FeatureEntry> =
+        serde_wasm_bindgen::from_value(features_js().unwrap()).unwrap();
+    assert!(!entries.is_empty());
+
+    // Every listed feature must be accepted as an option.
+    for entry in entries {
+        let json = format!(r#"{{"{}": {}}}"#, entry.name, entry.default_g2dem);
+        let demangled = demangle_with_options_js("Printf__7ConsolePce", "g2dem", options(&json));
+        assert!(demangled.is_ok(), "`{}` is not accepted", entry.name);
+    }
+}
@@ -1,16 +1,17 @@
 /* SPDX-FileCopyrightText: © 2025 Decompollaborate */
 /* SPDX-License-Identifier: MIT OR Apache-2.0 */
 
-use js_sys::{Object, Reflect};
+use gloo::timers::callback::Timeout;
 use rand::seq::IndexedRandom;
-use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsValue;
 use web_sys::HtmlInputElement;
 use yew::events::InputEvent;
 use yew::html::Scope;
 use yew::{html, Component, Context, Html, TargetCast};
 
-use gnuv2_demangle::{demangle, DemangleConfig};
+use gnuv2_demangle::{demangle_highlighted, TokenClass};
 
+mod permalink;
 mod persistent_state;
 mod settings;
 
@@ -22,23 +23,26 @@ pub mod built_info {
     include!(concat!(env!("OUT_DIR"), "/built.rs"));
 }
 
-#[wasm_bindgen]
-extern "C" {
-    #[wasm_bindgen(js_namespace = hljs, js_name = highlight)]
-    fn hljs_highlight(code: &str, options: &JsValue) -> JsValue;
-}
-
 pub enum Msg {
     InputData(String),
     ChangeTheme(Theme),
     ChangeDemanglingStyle(DemanglingStyle),
+    ChangeFlag(&'static str, bool),
+    ResetFlags,
+    UpdatePermalink,
 }
 
 pub struct App {
     user_input: String,
     state: PersistentState,
+    /// Pending update of the URL fragment, so it isn't rewritten on every
+    /// keystroke. Dropping it cancels the update.
+    permalink_timeout: Option<Timeout>,
 }
 
+/// How long to wait after the last change before updating the URL fragment.
+const PERMALINK_DELAY_MS: u32 = 500;
+
 // Feel free to add more examples.
 static EXAMPLES: [&str; 15] = [
     "test__Fv",
@@ -63,8 +67,19 @@ impl Component for App {
     type Properties = ();
 
     fn create(_ctx: &Context<Self>) -> Self {
-        // Choose 3 examples each time.
-        let example =
+        let fragment = gloo::utils::window().location().hash().unwrap_or_default();
+        let permalink = permalink::decode(&fragment);
+
+        let mut state = PersistentState::new();
+        if let Some(style) = permalink.style {
+            state.demangling_style = style;
+        }
+        if let Some(flags) = permalink.flags {
+            state.flags = flags;
+        }
+
+        // Choose 3 examples each time, unless the link already has an input.
+        let user_input = permalink.input.unwrap_or_else(|| {
             EXAMPLES
                 .choose_multiple(&mut rand::rng(), 3)
                 .fold(String::new(), |mut x, y| {
@@ -72,15 +87,17 @@ impl Component for App {
                         x.push('\n');
                     }
                     x + y
-                });
+                })
+        });
 
         Self {
-            user_input: example,
-            state: PersistentState::new(),
+            user_input,
+            state,
+            permalink_timeout: None,
         }
     }
 
-    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
             Msg::InputData(input) => {
                 self.user_input = input;
@@ -91,8 +108,24 @@ impl Component for App {
             Msg::ChangeDemanglingStyle(demangling_style) => {
                 self.state.demangling_style = demangling_style;
             }
+            Msg::ChangeFlag(name, value) => {
+                self.state.flags.set(name, value);
+            }
+            Msg::ResetFlags => {
+                self.state.flags.reset();
+            }
+            Msg::UpdatePermalink => {
+                self.permalink_timeout = None;
+                self.write_permalink();
+                return false;
+            }
         }
 
+        let link = ctx.link().clone();
+        self.permalink_timeout = Some(Timeout::new(PERMALINK_DELAY_MS, move || {
+            link.send_message(Msg::UpdatePermalink);
+        }));
+
         self.state.save();
         true
     }
@@ -115,6 +148,23 @@ impl Component for App {
 }
 
 impl App {
+    /// Put the current input and configuration in the URL fragment, without
+    /// adding an entry to the browser history.
+    fn write_permalink(&self) {
+        let fragment = permalink::encode(
+            &self.user_input,
+            self.state.demangling_style,
+            &self.state.flags,
+        );
+        let url = format!("#{fragment}");
+
+        if let Err(err) =
+            gloo::utils::history().replace_state_with_url(&JsValue::NULL, "", Some(&url))
+        {
+            log::warn!("Failed to update the URL fragment: {err:?}");
+        }
+    }
+
     fn view_header(&self, ctx: &Context<Self>) -> Html {
         let link = ctx.link();
         let label_position = LabelPosition::Left;
@@ -224,19 +274,17 @@ impl App {
 
     fn demangle_input(&self) -> Vec<Html> {
         let mut result = Vec::new();
-        let config = match self.state.demangling_style {
-            DemanglingStyle::G2dem => DemangleConfig::new_g2dem(),
-            DemanglingStyle::Cfilt => DemangleConfig::new_cfilt(),
-        };
+        let config = self.state.flags.config(self.state.demangling_style);
 
         for sym in self.user_input.lines() {
-            let row = match demangle(sym.trim(), &config) {
-                Ok(demangled) => {
-                    let highlighted = highlight_cpp_cod(&demangled).unwrap_or(demangled);
-                    let highlighted_html = Html::from_html_unchecked(highlighted.into());
+            let row = match demangle_highlighted(sym.trim(), &config) {
+                Ok(pieces) => {
+                    let highlighted = pieces.into_iter().map(|(text, class)| {
+                        html! { <span class={token_css_class(class)}>{ text }</span> }
+                    });
                     html! {
                       <tr>
-                        <td class="cod"> { highlighted_html } </td>
+                        <td class="cod"> { for highlighted } </td>
                       </tr>
                     }
                 }
@@ -260,6 +308,11 @@ impl App {
             label_position,
             Msg::ChangeDemanglingStyle,
         );
+        let checkboxes_flags =
+            self.state
+                .flags
+                .gen_checkboxes(link, self.state.demangling_style, Msg::ChangeFlag);
+        let onclick_reset = link.callback(|_| Msg::ResetFlags);
 
         html! {
           <>
@@ -267,34 +320,28 @@ impl App {
             <div class="settings">
               { dropdown_demangling_style }
             </div>
+            <div class="settings">
+              { checkboxes_flags }
+            </div>
+            <button class="settings-button" onclick={onclick_reset}>
+              { "Reset flags to style defaults" }
+            </button>
           </>
         }
     }
 }
 
-fn highlight_cpp_cod(cod: &str) -> Option<String> {
-    let opts = Object::new();
-    // Should be equivalent to
-    // `{ language: 'cpp' }`
-    // https://highlightjs.org/#usage
-    if Reflect::set(
-        &opts,
-        &JsValue::from_str("language"),
-        &JsValue::from_str("cpp"),
-    )
-    .is_err()
-    {
-        return Some(cod.to_string());
+fn token_css_class(class: TokenClass) -> &'static str {
+    match class {
+        TokenClass::Scope => "tok-scope",
+        TokenClass::Name => "tok-name",
+        TokenClass::Operator => "tok-operator",
+        TokenClass::Keyword => "tok-keyword",
+        TokenClass::Type => "tok-type",
+        TokenClass::TemplateArg => "tok-template-arg",
+        TokenClass::Literal => "tok-literal",
+        _ => "tok-punctuation",
     }
-
-    let highlighted = hljs_highlight(cod, &opts.into());
-    Reflect::get(&highlighted, &JsValue::from_str("value"))
-        .ok()
-        .and_then(|x| x.as_string())
-        .map(|x| {
-            // Hacky way to workaround the fact that this cod was not using "monospace" as the font family
-            x.replace(" class=\"hljs-", " class=\"cod hljs-")
-        })
 }
 
 fn main() {
@@ -0,0 +1,178 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+//! Encode the input and the configuration in the URL fragment, like
+//! `#s=<base64>&style=g2dem&cfg=fix_extension_int:0`, so a result can be
+//! shared with a link.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+
+use crate::settings::{DemanglingStyle, DropdownEnum, FlagSettings};
+
+/// Longest fragment produced, since some browsers and chat apps mangle very
+/// long URLs. Past this the input is left out of the link.
+pub const MAX_FRAGMENT_LEN: usize = 2000;
+
+/// What was read from a fragment.
+///
+/// Missing or invalid parts are `None`, so they can keep their usual value.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Permalink {
+    pub input: Option<String>,
+    pub style: Option<DemanglingStyle>,
+    pub flags: Option<FlagSettings>,
+}
+
+/// Build the fragment for the given state, without the leading `#`.
+///
+/// The input is left out if the fragment would get longer than
+/// [`MAX_FRAGMENT_LEN`], so at least the configuration can be shared.
+pub fn encode(input: &str, style: DemanglingStyle, flags: &FlagSettings) -> String {
+    let mut config = format!("style={}", style.id());
+    let overrides: Vec<String> = flags
+        .overrides()
+        .map(|(name, value)| format!("{name}:{}", u8::from(value)))
+        .collect();
+    if !overrides.is_empty() {
+        config.push_str("&cfg=");
+        config.push_str(&overrides.join(","));
+    }
+
+    let symbols = URL_SAFE_NO_PAD.encode(input);
+    if "s=&".len() + symbols.len() + config.len() > MAX_FRAGMENT_LEN {
+        config
+    } else {
+        format!("s={symbols}&{config}")
+    }
+}
+
+/// Read a fragment produced by [`encode`], with or without the leading `#`.
+pub fn decode(fragment: &str) -> Permalink {
+    let fragment = fragment.strip_prefix('#').unwrap_or(fragment);
+    let mut permalink = Permalink::default();
+
+    for (key, value) in fragment.split('&').filter_map(|x| x.split_once('=')) {
+        match key {
+            "s" => permalink.input = decode_input(value),
+            "style" => permalink.style = decode_style(value),
+            "cfg" => permalink.flags = decode_flags(value),
+            // Maybe from a newer version.
+            _ => {}
+        }
+    }
+
+    permalink
+}
+
+fn decode_input(value: &str) -> Option<String> {
+    let bytes = URL_SAFE_NO_PAD.decode(value).ok()?;
+    String::from_utf8(bytes).ok()
+}
+
+fn decode_style(value: &str) -> Option<DemanglingStyle> {
+    DemanglingStyle::array()
+        .iter()
+        .find(|style| style.id() == value)
+        .copied()
+}
+
+fn decode_flags(value: &str) -> Option<FlagSettings> {
+    let mut flags = FlagSettings::default();
+
+    for flag in value.split(',').filter(|x| !x.is_empty()) {
+        let (name, value) = flag.split_once(':')?;
+        let value = match value {
+            "0" => false,
+            "1" => true,
+            _ => return None,
+        };
+        flags.set(name, value);
+    }
+    Some(flags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut flags = FlagSettings::default();
+        flags.set("fix_extension_int", false);
+        flags.set("simplify_stl_containers", true);
+        let input = "SetText__5tNamePCc\n_$_5tName\n__ls__FR7ostreamPCc";
+
+        let fragment = encode(input, DemanglingStyle::Cfilt, &flags);
+        assert_eq!(
+            decode(&format!("#{fragment}")),
+            Permalink {
+                input: Some(input.to_string()),
+                style: Some(DemanglingStyle::Cfilt),
+                flags: Some(flags),
+            }
+        );
+    }
+
+    #[test]
+    fn test_round_trip_without_overrides() {
+        let fragment = encode("test__Fv", DemanglingStyle::G2dem, &FlagSettings::default());
+
+        assert_eq!(fragment, "s=dGVzdF9fRnY&style=g2dem");
+        assert_eq!(
+            decode(&fragment),
+            Permalink {
+                input: Some("test__Fv".to_string()),
+                style: Some(DemanglingStyle::G2dem),
+                flags: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_input_too_large() {
+        let input = "SetText__5tNamePCc\n".repeat(200);
+        let mut flags = FlagSettings::default();
+        flags.set("fix_extension_int", false);
+
+        let fragment = encode(&input, DemanglingStyle::G2dem, &flags);
+        assert!(fragment.len() <= MAX_FRAGMENT_LEN);
+        assert_eq!(
+            decode(&fragment),
+            Permalink {
+                input: None,
+                style: Some(DemanglingStyle::G2dem),
+                flags: Some(flags),
+            }
+        );
+    }
+
+    #[test]
+    fn test_invalid_fragment() {
+        static CASES: [&str; 7] = [
+            "",
+            "#",
+            "#s=!!!",
+            "#s=_w",
+            "#style=gcc3",
+            "#cfg=fix_extension_int",
+            "#cfg=fix_extension_int:2",
+        ];
+
+        for fragment in CASES {
+            assert_eq!(decode(fragment), Permalink::default(), "{fragment:?}");
+        }
+    }
+
+    #[test]
+    fn test_partially_invalid_fragment() {
+        assert_eq!(
+            decode("#s=dGVzdF9fRnY&style=gcc3&future=1"),
+            Permalink {
+                input: Some("test__Fv".to_string()),
+                style: None,
+                flags: None,
+            }
+        );
+    }
+}
@@ -1,6 +1,7 @@
 /* SPDX-FileCopyrightText: © 2025 Decompollaborate */
 /* SPDX-License-Identifier: MIT OR Apache-2.0 */
 
+use gnuv2_demangle::DemangleConfig;
 use serde::{Deserialize, Serialize};
 
 use crate::settings::{DropdownEnum, Storagable};
@@ -16,6 +17,15 @@ pub enum DemanglingStyle {
     Cfilt,
 }
 
+impl DemanglingStyle {
+    pub fn config(&self) -> DemangleConfig {
+        match self {
+            Self::G2dem => DemangleConfig::new_g2dem(),
+            Self::Cfilt => DemangleConfig::new_cfilt(),
+        }
+    }
+}
+
 impl Storagable for DemanglingStyle {
     fn storage_key() -> &'static str {
         KEY
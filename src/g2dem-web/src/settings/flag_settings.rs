@@ -0,0 +1,277 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+use std::collections::BTreeMap;
+
+use gnuv2_demangle::{features, DemangleConfig, DemangleConfigBuilder};
+use serde::{Deserialize, Serialize};
+use web_sys::HtmlInputElement;
+use yew::events::Event;
+use yew::html::Scope;
+use yew::{html, Component, Html, TargetCast};
+
+use crate::settings::{DemanglingStyle, Storagable};
+
+const KEY: &str = "decompollaborate.g2dem-web.state.flags";
+
+/// Individual `DemangleConfig` flags overridden on top of the selected
+/// `DemanglingStyle`.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct FlagSettings {
+    overrides: BTreeMap<String, bool>,
+}
+
+impl Storagable for FlagSettings {
+    fn storage_key() -> &'static str {
+        KEY
+    }
+}
+
+impl FlagSettings {
+    pub fn set(&mut self, name: &str, value: bool) {
+        self.overrides.insert(name.to_string(), value);
+    }
+
+    /// The flags that differ from the selected style, by their field name.
+    pub fn overrides(&self) -> impl Iterator<Item = (&str, bool)> {
+        self.overrides
+            .iter()
+            .map(|(name, value)| (name.as_str(), *value))
+    }
+
+    /// Go back to the values of the selected style.
+    pub fn reset(&mut self) {
+        self.overrides.clear();
+    }
+
+    /// The selected style, with every override applied.
+    pub fn config(&self, style: DemanglingStyle) -> DemangleConfig {
+        let mut builder = DemangleConfigBuilder::from(style.config());
+
+        for (name, value) in &self.overrides {
+            // Unknown flags may come from an older version, so just skip them.
+            if let Some(b) = with_flag(builder, name, *value) {
+                builder = b;
+            }
+        }
+
+        builder.build()
+    }
+
+    pub fn gen_checkboxes<F, M, S>(
+        &self,
+        link: &Scope<S>,
+        style: DemanglingStyle,
+        msgfier: F,
+    ) -> Html
+    where
+        F: Fn(&'static str, bool) -> M + Copy + 'static,
+        S: Component<Message = M>,
+    {
+        let preset = style.config();
+        let config = self.config(style);
+
+        features()
+            .iter()
+            .filter_map(|feature| {
+                let name = feature.name;
+                let checked = flag(&config, name)?;
+                let overridden = checked != flag(&preset, name)?;
+                let onchange = link.batch_callback(move |e: Event| {
+                    let input: HtmlInputElement = e.target_unchecked_into();
+                    Some(msgfier(name, input.checked()))
+                });
+                let label_text = if overridden {
+                    format!("{name} *")
+                } else {
+                    name.to_string()
+                };
+
+                Some(html! {
+                  <div class="settings-label-container-centerer">
+                    <input type="checkbox" id={name} {checked} {onchange} />
+                    <label for={name} class="settings-label-centered">
+                      <span class="tooltip">
+                        { label_text }
+                        <span class="tooltiptext">{ feature.doc }</span>
+                      </span>
+                    </label>
+                  </div>
+                })
+            })
+            .collect()
+    }
+}
+
+/// Read a flag of `config` by its field name.
+fn flag(config: &DemangleConfig, name: &str) -> Option<bool> {
+    let value = match name {
+        "fix_namespaced_global_constructor_bug" => config.fix_namespaced_global_constructor_bug,
+        "fix_array_length_arg" => config.fix_array_length_arg,
+        "fix_array_length_skip_zero" => config.fix_array_length_skip_zero,
+        "demangle_global_keyed_frames" => config.demangle_global_keyed_frames,
+        "ellipsis_emit_space_after_comma" => config.ellipsis_emit_space_after_comma,
+        "fix_extension_int" => config.fix_extension_int,
+        "fix_array_in_return_position" => config.fix_array_in_return_position,
+        "fix_function_pointers_in_template_lists" => config.fix_function_pointers_in_template_lists,
+        "simplify_stl_containers" => config.simplify_stl_containers,
+        "demangle_constructor_variants" => config.demangle_constructor_variants,
+        "emit_enum_casts_in_templates" => config.emit_enum_casts_in_templates,
+        "fix_function_pointer_in_return_position" => config.fix_function_pointer_in_return_position,
+        "emit_plain_restrict" => config.emit_plain_restrict,
+        "demangle_clone_suffix" => config.demangle_clone_suffix,
+        "emit_clone_suffix" => config.emit_clone_suffix,
+        "tolerate_stray_underscores_between_args" => config.tolerate_stray_underscores_between_args,
+        "fix_function_pointer_template_lookbacks" => config.fix_function_pointer_template_lookbacks,
+        "emit_integer_suffixes_in_templates" => config.emit_integer_suffixes_in_templates,
+        "emit_wide_character_literals_in_templates" => {
+            config.emit_wide_character_literals_in_templates
+        }
+        "fix_template_parameter_levels" => config.fix_template_parameter_levels,
+        "complete_truncated_symbols" => config.complete_truncated_symbols,
+        "demangle_global_keyed_template_instantiations" => {
+            config.demangle_global_keyed_template_instantiations
+        }
+        "reject_plain_c_lookalikes" => config.reject_plain_c_lookalikes,
+        "strip_top_level_const_on_value_args" => config.strip_top_level_const_on_value_args,
+        "pretty_anonymous_namespaces" => config.pretty_anonymous_namespaces,
+        "lenient" => config.lenient,
+        "demangle_nested_global_keyed_symbols" => config.demangle_nested_global_keyed_symbols,
+        "demangle_vtable_offsets" => config.demangle_vtable_offsets,
+        "fix_array_of_references" => config.fix_array_of_references,
+        "describe_static_init_functions" => config.describe_static_init_functions,
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Set a flag of `builder` by its field name.
+fn with_flag(
+    builder: DemangleConfigBuilder,
+    name: &str,
+    value: bool,
+) -> Option<DemangleConfigBuilder> {
+    let builder = match name {
+        "fix_namespaced_global_constructor_bug" => {
+            builder.fix_namespaced_global_constructor_bug(value)
+        }
+        "fix_array_length_arg" => builder.fix_array_length_arg(value),
+        "fix_array_length_skip_zero" => builder.fix_array_length_skip_zero(value),
+        "demangle_global_keyed_frames" => builder.demangle_global_keyed_frames(value),
+        "ellipsis_emit_space_after_comma" => builder.ellipsis_emit_space_after_comma(value),
+        "fix_extension_int" => builder.fix_extension_int(value),
+        "fix_array_in_return_position" => builder.fix_array_in_return_position(value),
+        "fix_function_pointers_in_template_lists" => {
+            builder.fix_function_pointers_in_template_lists(value)
+        }
+        "simplify_stl_containers" => builder.simplify_stl_containers(value),
+        "demangle_constructor_variants" => builder.demangle_constructor_variants(value),
+        "emit_enum_casts_in_templates" => builder.emit_enum_casts_in_templates(value),
+        "fix_function_pointer_in_return_position" => {
+            builder.fix_function_pointer_in_return_position(value)
+        }
+        "emit_plain_restrict" => builder.emit_plain_restrict(value),
+        "demangle_clone_suffix" => builder.demangle_clone_suffix(value),
+        "emit_clone_suffix" => builder.emit_clone_suffix(value),
+        "tolerate_stray_underscores_between_args" => {
+            builder.tolerate_stray_underscores_between_args(value)
+        }
+        "fix_function_pointer_template_lookbacks" => {
+            builder.fix_function_pointer_template_lookbacks(value)
+        }
+        "emit_integer_suffixes_in_templates" => builder.emit_integer_suffixes_in_templates(value),
+        "emit_wide_character_literals_in_templates" => {
+            builder.emit_wide_character_literals_in_templates(value)
+        }
+        "fix_template_parameter_levels" => builder.fix_template_parameter_levels(value),
+        "complete_truncated_symbols" => builder.complete_truncated_symbols(value),
+        "demangle_global_keyed_template_instantiations" => {
+            builder.demangle_global_keyed_template_instantiations(value)
+        }
+        "reject_plain_c_lookalikes" => builder.reject_plain_c_lookalikes(value),
+        "strip_top_level_const_on_value_args" => builder.strip_top_level_const_on_value_args(value),
+        "pretty_anonymous_namespaces" => builder.pretty_anonymous_namespaces(value),
+        "lenient" => builder.lenient(value),
+        "demangle_nested_global_keyed_symbols" => {
+            builder.demangle_nested_global_keyed_symbols(value)
+        }
+        "demangle_vtable_offsets" => builder.demangle_vtable_offsets(value),
+        "fix_array_of_references" => builder.fix_array_of_references(value),
+        "describe_static_init_functions" => builder.describe_static_init_functions(value),
+        _ => return None,
+    };
+    Some(builder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_feature_is_settable() {
+        let config = DemangleConfig::new_g2dem();
+
+        for feature in features() {
+            let value = flag(&config, feature.name);
+            assert_eq!(value, Some(feature.default_g2dem), "{}", feature.name);
+
+            let builder = with_flag(
+                DemangleConfigBuilder::from(config),
+                feature.name,
+                !value.unwrap(),
+            );
+            let toggled = builder.map(|x| x.build());
+            assert_eq!(
+                toggled.and_then(|x| flag(&x, feature.name)),
+                Some(!feature.default_g2dem),
+                "{}",
+                feature.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_config_without_overrides() {
+        let flags = FlagSettings::default();
+
+        assert_eq!(
+            flags.config(DemanglingStyle::G2dem),
+            DemangleConfig::new_g2dem()
+        );
+        assert_eq!(
+            flags.config(DemanglingStyle::Cfilt),
+            DemangleConfig::new_cfilt()
+        );
+    }
+
+    #[test]
+    fn test_config_with_overrides() {
+        let mut flags = FlagSettings::default();
+        flags.set("ellipsis_emit_space_after_comma", true);
+        flags.set("fix_array_length_arg", false);
+        flags.set("flag_from_the_future", true);
+
+        let config = flags.config(DemanglingStyle::Cfilt);
+        assert_eq!(
+            config,
+            DemangleConfig::builder()
+                .preset_cfilt()
+                .ellipsis_emit_space_after_comma(true)
+                .build()
+        );
+
+        let config = flags.config(DemanglingStyle::G2dem);
+        assert_eq!(
+            config,
+            DemangleConfig::builder()
+                .fix_array_length_arg(false)
+                .build()
+        );
+
+        flags.reset();
+        assert_eq!(
+            flags.config(DemanglingStyle::Cfilt),
+            DemangleConfig::new_cfilt()
+        );
+    }
+}
@@ -9,9 +9,11 @@ use yew::html::Scope;
 use yew::{html, Component, Html, TargetCast};
 
 mod demangling_style;
+mod flag_settings;
 mod theme;
 
 pub use demangling_style::DemanglingStyle;
+pub use flag_settings::FlagSettings;
 pub use theme::Theme;
 
 pub trait Storagable
@@ -3,10 +3,20 @@
 
 #![doc = include_str!("../README.md")]
 
-use std::io::{self, BufRead};
+use std::{
+    collections::BTreeMap,
+    io::{self, BufRead},
+};
 
 use argp::{FromArgValue, FromArgs};
-use gnuv2_demangle::{demangle, DemangleConfig};
+use gnuv2_demangle::{
+    demangle, demangle_all, CompilerId, DemangleConfig, DemangleConfigBuilder, DemangleError,
+    DemangleErrorKind, OwnedDemangleError, ReturnTypeStyle,
+};
+
+mod side_by_side;
+
+use side_by_side::Columns;
 
 pub mod built_info {
     // The file has been placed there by the build script.
@@ -26,6 +36,44 @@ struct Args {
     #[argp(option, short = 'm', default = "Mode::default()")]
     mode: Mode,
 
+    /// Use the quirks of a known compiler instead of a demangling flavor. Valid values: {"ee-gcc-2.9-990721", "ee-gcc-2.96", "gcc-2.95.3-sn", "gcc-2.96-redhat", "generic"}.
+    #[argp(option)]
+    compiler: Option<Compiler>,
+
+    /// Print demangling statistics to stderr after processing every symbol. Valid values: {"summary", "verbose"}.
+    ///
+    /// "verbose" also lists every kind of error found, with an example symbol for each one.
+    #[argp(option)]
+    report: Option<ReportMode>,
+
+    /// Leave out the parameter list of functions and methods, like `tName::SetText`.
+    #[argp(switch)]
+    strip_params: bool,
+
+    /// Where to place the return type of templated functions. Valid values: {"leading", "omit", "trailing"}. Defaults to "leading".
+    #[argp(option, default = "ReturnType::default()")]
+    return_type: ReturnType,
+
+    /// Leave out the template arguments of the classes functions and methods belong to, like `List::Insert`.
+    #[argp(switch)]
+    strip_scope_template_args: bool,
+
+    /// Demangle the symbols using every core, printing them in the same order once all of them are done.
+    ///
+    /// When reading from stdin the whole input is read before demangling anything.
+    #[argp(switch)]
+    parallel: bool,
+
+    /// Print each symbol next to its demangling, in two columns. Symbols that fail to demangle show the kind of the error instead.
+    ///
+    /// The mangled column fits the longest symbol, up to 60 characters, when every symbol is known beforehand, like when passed as arguments or with `--parallel`. Otherwise it defaults to 40 characters.
+    #[argp(switch)]
+    side_by_side: bool,
+
+    /// Width of the mangled column of `--side-by-side`.
+    #[argp(option)]
+    width: Option<usize>,
+
     /// Print current version information and exit.
     #[argp(switch, short = 'V')]
     version: bool,
@@ -51,6 +99,171 @@ impl FromArgValue for Mode {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Compiler {
+    EeGcc29_990721,
+    EeGcc296,
+    Gcc295SnSystems,
+    Gcc296RedHat,
+    GenericGnu2,
+}
+
+impl FromArgValue for Compiler {
+    fn from_arg_value(value: &std::ffi::OsStr) -> Result<Self, String> {
+        const ERROR: &str = "Valid options are: `ee-gcc-2.9-990721`, `ee-gcc-2.96`, `gcc-2.95.3-sn`, `gcc-2.96-redhat` and `generic`";
+
+        let value = value.to_str().ok_or_else(|| ERROR.to_string())?;
+        match value {
+            "ee-gcc-2.9-990721" => Ok(Self::EeGcc29_990721),
+            "ee-gcc-2.96" => Ok(Self::EeGcc296),
+            "gcc-2.95.3-sn" => Ok(Self::Gcc295SnSystems),
+            "gcc-2.96-redhat" => Ok(Self::Gcc296RedHat),
+            "generic" => Ok(Self::GenericGnu2),
+            _ => Err(ERROR.to_string()),
+        }
+    }
+}
+
+impl From<Compiler> for CompilerId {
+    fn from(value: Compiler) -> Self {
+        match value {
+            Compiler::EeGcc29_990721 => Self::EeGcc29_990721,
+            Compiler::EeGcc296 => Self::EeGcc296,
+            Compiler::Gcc295SnSystems => Self::Gcc295SnSystems,
+            Compiler::Gcc296RedHat => Self::Gcc296RedHat,
+            Compiler::GenericGnu2 => Self::GenericGnu2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+enum ReturnType {
+    #[default]
+    Leading,
+    Omit,
+    Trailing,
+}
+
+impl FromArgValue for ReturnType {
+    fn from_arg_value(value: &std::ffi::OsStr) -> Result<Self, String> {
+        const ERROR: &str = "Valid options are: `leading`, `omit` and `trailing`";
+
+        let value = value.to_str().ok_or_else(|| ERROR.to_string())?;
+        match value {
+            "leading" => Ok(Self::Leading),
+            "omit" => Ok(Self::Omit),
+            "trailing" => Ok(Self::Trailing),
+            _ => Err(ERROR.to_string()),
+        }
+    }
+}
+
+impl From<ReturnType> for ReturnTypeStyle {
+    fn from(value: ReturnType) -> Self {
+        match value {
+            ReturnType::Leading => Self::CfiltLeading,
+            ReturnType::Omit => Self::Omit,
+            ReturnType::Trailing => Self::TrailingArrow,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum ReportMode {
+    Summary,
+    Verbose,
+}
+
+impl FromArgValue for ReportMode {
+    fn from_arg_value(value: &std::ffi::OsStr) -> Result<Self, String> {
+        const ERROR: &str = "Valid options are: `summary` and `verbose`";
+
+        let value = value.to_str().ok_or_else(|| ERROR.to_string())?;
+        match value {
+            "summary" => Ok(Self::Summary),
+            "verbose" => Ok(Self::Verbose),
+            _ => Err(ERROR.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Report {
+    total: usize,
+    demangled: usize,
+    not_mangled: usize,
+    failed: usize,
+    /// The first symbol that failed with each error variant, and how many
+    /// symbols failed with it, grouped by the kind of the error.
+    errors:
+        BTreeMap<DemangleErrorKind, BTreeMap<&'static str, (String, OwnedDemangleError, usize)>>,
+}
+
+impl Report {
+    fn add(&mut self, sym: &str, result: &Result<String, DemangleError>) {
+        self.total += 1;
+        match result {
+            Ok(_) => self.demangled += 1,
+            Err(DemangleError::NotMangled) => self.not_mangled += 1,
+            Err(e) => {
+                self.failed += 1;
+                self.errors
+                    .entry(e.kind())
+                    .or_default()
+                    .entry(e.kind_name())
+                    .or_insert_with(|| (sym.to_string(), e.into_owned(), 0))
+                    .2 += 1;
+            }
+        }
+    }
+
+    fn print(&self, mode: ReportMode) {
+        eprintln!("Total symbols: {}", self.total);
+        eprintln!("Demangled: {}", self.demangled);
+        eprintln!("Returned as-is (not mangled): {}", self.not_mangled);
+        eprintln!("Failed with an error: {}", self.failed);
+
+        if mode == ReportMode::Verbose && !self.errors.is_empty() {
+            eprintln!();
+            eprintln!("Errors:");
+            for (kind, variants) in &self.errors {
+                let total: usize = variants.values().map(|(_, _, count)| count).sum();
+                eprintln!("  {kind:?} ({total}):");
+                for (name, (example, error, count)) in variants {
+                    eprintln!("    {name} ({count}): {example}");
+                    eprintln!("      {error}");
+                }
+            }
+        }
+    }
+}
+
+fn demangle_and_print(
+    sym: &str,
+    config: &DemangleConfig,
+    columns: Option<&Columns>,
+    report: &mut Report,
+) {
+    print_result(sym, &demangle(sym, config), columns, report);
+}
+
+fn print_result(
+    sym: &str,
+    result: &Result<String, DemangleError>,
+    columns: Option<&Columns>,
+    report: &mut Report,
+) {
+    if let Some(columns) = columns {
+        println!("{}", columns.format_row(sym, result));
+    } else if let Ok(demangled) = &result {
+        println!("{demangled}");
+    } else {
+        println!("{sym}");
+    }
+
+    report.add(sym, result);
+}
+
 fn show_version() {
     let (dirty, hash_short) = if built_info::GIT_DIRTY == Some(true) {
         let hash_short = built_info::GIT_COMMIT_HASH_SHORT.unwrap_or("");
@@ -100,28 +313,57 @@ fn main() {
         return;
     }
 
-    let config = match args.mode {
-        Mode::G2dem => DemangleConfig::new_g2dem(),
-        Mode::Cfilt => DemangleConfig::new_cfilt(),
+    let config = match (args.compiler, args.mode) {
+        (Some(compiler), _) => DemangleConfig::for_compiler(compiler.into()),
+        (None, Mode::G2dem) => DemangleConfig::new_g2dem(),
+        (None, Mode::Cfilt) => DemangleConfig::new_cfilt(),
     };
+    let config = DemangleConfigBuilder::from(config)
+        .omit_parameter_list(args.strip_params)
+        .return_type_style(args.return_type.into())
+        .omit_template_args_in_scope(args.strip_scope_template_args)
+        .build();
+
+    let mut report = Report::default();
+
+    if args.parallel {
+        let lines: Vec<String> = if args.syms.is_empty() {
+            io::stdin()
+                .lock()
+                .lines()
+                .collect::<Result<_, _>>()
+                .expect("Error reading from stdin")
+        } else {
+            args.syms
+        };
+        let syms: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let columns = args
+            .side_by_side
+            .then(|| Columns::new(Some(&syms), args.width));
+
+        for (sym, result) in syms.iter().zip(demangle_all(&syms, &config)) {
+            print_result(sym, &result, columns.as_ref(), &mut report);
+        }
+    } else if args.syms.is_empty() {
+        let columns = args.side_by_side.then(|| Columns::new(None, args.width));
 
-    if args.syms.is_empty() {
         for line in io::stdin().lock().lines() {
             let line = line.expect("Error reading from stdin");
 
-            if let Ok(demangled) = demangle(&line, &config) {
-                println!("{demangled}");
-            } else {
-                println!("{line}");
-            }
+            demangle_and_print(&line, &config, columns.as_ref(), &mut report);
         }
     } else {
-        for mangled in args.syms {
-            if let Ok(demangled) = demangle(&mangled, &config) {
-                println!("{demangled}");
-            } else {
-                println!("{mangled}");
-            }
+        let syms: Vec<&str> = args.syms.iter().map(String::as_str).collect();
+        let columns = args
+            .side_by_side
+            .then(|| Columns::new(Some(&syms), args.width));
+
+        for mangled in syms {
+            demangle_and_print(mangled, &config, columns.as_ref(), &mut report);
         }
     }
+
+    if let Some(mode) = args.report {
+        report.print(mode);
+    }
 }
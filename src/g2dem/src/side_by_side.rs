@@ -0,0 +1,213 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+//! The two column output of `--side-by-side`, with the mangled symbols on the
+//! left and their demangling on the right.
+
+use std::borrow::Cow;
+
+use gnuv2_demangle::DemangleError;
+
+/// The width of the mangled column when the symbols are not known beforehand,
+/// like when streaming stdin.
+pub const DEFAULT_MANGLED_WIDTH: usize = 40;
+
+/// The widest the mangled column gets when sized to fit a batch of symbols.
+/// Longer symbols get the demangled column on the next line.
+const MAX_FITTED_WIDTH: usize = 60;
+
+/// Where the demangled column wraps, unless that leaves it too narrow.
+const LINE_WIDTH: usize = 120;
+const MIN_DEMANGLED_WIDTH: usize = 40;
+
+const GAP: &str = "  ";
+/// Extra indentation of the lines of a wrapped demangled symbol.
+const CONTINUATION: &str = "  ";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Columns {
+    mangled_width: usize,
+    demangled_width: usize,
+}
+
+impl Columns {
+    /// `batch` has every symbol that will be printed, if they are known
+    /// beforehand, which sizes the mangled column to fit the longest one, up
+    /// to [`MAX_FITTED_WIDTH`]. Otherwise [`DEFAULT_MANGLED_WIDTH`] is used.
+    ///
+    /// An explicit `width` always wins.
+    pub fn new(batch: Option<&[&str]>, width: Option<usize>) -> Self {
+        let mangled_width = match (width, batch) {
+            (Some(width), _) => width,
+            (None, Some(batch)) => batch
+                .iter()
+                .map(|x| x.len())
+                .max()
+                .unwrap_or(0)
+                .min(MAX_FITTED_WIDTH),
+            (None, None) => DEFAULT_MANGLED_WIDTH,
+        };
+        let demangled_width = LINE_WIDTH
+            .saturating_sub(mangled_width + GAP.len())
+            .max(MIN_DEMANGLED_WIDTH);
+
+        Self {
+            mangled_width,
+            demangled_width,
+        }
+    }
+
+    /// The lines of a single symbol, without a trailing newline.
+    ///
+    /// Symbols too long for the mangled column get the demangled one on the
+    /// next line.
+    pub fn format_row(&self, sym: &str, result: &Result<String, DemangleError>) -> String {
+        let right = right_column(result);
+        let indent = " ".repeat(self.mangled_width) + GAP;
+
+        let mut out = String::new();
+        let mut lines = wrap(&right, self.demangled_width, CONTINUATION.len()).into_iter();
+        let first = lines.next().unwrap_or_default();
+        if sym.len() > self.mangled_width {
+            out.push_str(sym);
+            out.push('\n');
+            out.push_str(&indent);
+        } else {
+            out.push_str(&format!("{sym:<0$}{GAP}", self.mangled_width));
+        }
+        out.push_str(first);
+
+        for line in lines {
+            out.push('\n');
+            out.push_str(&indent);
+            out.push_str(CONTINUATION);
+            out.push_str(line);
+        }
+        out
+    }
+}
+
+fn right_column<'a>(result: &'a Result<String, DemangleError>) -> Cow<'a, str> {
+    match result {
+        Ok(demangled) => Cow::from(demangled),
+        Err(e) => Cow::from(format!("error: {:?}", e.kind())),
+    }
+}
+
+/// Split `text` into lines of at most `width` characters, with the lines
+/// after the first one `continuation` characters narrower.
+///
+/// Lines are broken at the last space, `::`, `(` or `<` that fits. Names too
+/// long for a line are split wherever they reach its end.
+fn wrap(text: &str, width: usize, continuation: usize) -> Vec<&str> {
+    let continuation_width = width.saturating_sub(continuation).max(1);
+
+    let mut lines = Vec::new();
+    let mut rest = text;
+    let mut width = width.max(1);
+    while rest.len() > width {
+        // The latest place to break the line at, skipping the space itself.
+        let (end, skip) = (1..=width)
+            .rev()
+            .find_map(|i| {
+                let line = &rest[..i];
+                if rest[i..].starts_with(' ') {
+                    Some((i, 1))
+                } else if line.ends_with(['(', '<']) || line.ends_with("::") {
+                    Some((i, 0))
+                } else {
+                    None
+                }
+            })
+            .unwrap_or((width, 0));
+        let (line, next) = (&rest[..end], &rest[end + skip..]);
+        lines.push(line);
+        rest = next;
+        width = continuation_width;
+    }
+    lines.push(rest);
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_width_of_a_batch() {
+        let batch = ["f__Fi", "SetText__5tNamePCc", "main"];
+
+        let columns = Columns::new(Some(&batch), None);
+        assert_eq!(columns.mangled_width, 18);
+        assert_eq!(columns.demangled_width, 100);
+
+        assert_eq!(Columns::new(Some(&[]), None).mangled_width, 0);
+        let long = "f".repeat(100);
+        let columns = Columns::new(Some(&[&long, "f__Fi"]), None);
+        assert_eq!(columns.mangled_width, MAX_FITTED_WIDTH);
+        assert_eq!(Columns::new(Some(&batch), Some(8)).mangled_width, 8);
+    }
+
+    #[test]
+    fn test_width_of_a_stream() {
+        let columns = Columns::new(None, None);
+        assert_eq!(columns.mangled_width, DEFAULT_MANGLED_WIDTH);
+        assert_eq!(Columns::new(None, Some(8)).mangled_width, 8);
+
+        // Never narrower than the minimum, even for huge symbols.
+        let columns = Columns::new(None, Some(200));
+        assert_eq!(columns.demangled_width, MIN_DEMANGLED_WIDTH);
+    }
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(wrap("", 10, 2), [""]);
+        assert_eq!(wrap("f(int)", 10, 2), ["f(int)"]);
+        assert_eq!(
+            wrap("f(int, char, float)", 10, 2),
+            ["f(int,", "char,", "float)"]
+        );
+        // Continuation lines are narrower.
+        assert_eq!(wrap("aaaa bbbb cccc", 9, 2), ["aaaa bbbb", "cccc"]);
+        assert_eq!(wrap("aaaa bbbb cccc", 9, 5), ["aaaa bbbb", "cccc"]);
+        assert_eq!(wrap("aaaa bbb ccc", 8, 4), ["aaaa bbb", "ccc"]);
+        assert_eq!(wrap("aaaa bb cc dd", 7, 2), ["aaaa bb", "cc dd"]);
+        // Names are split after scopes and brackets, or anywhere if needed.
+        assert_eq!(wrap("Foo::Bar::Baz", 10, 0), ["Foo::Bar::", "Baz"]);
+        assert_eq!(wrap("List<Foo::Bar>", 10, 0), ["List<Foo::", "Bar>"]);
+        assert_eq!(wrap("f(Foo::Bar)", 8, 0), ["f(Foo::", "Bar)"]);
+        assert_eq!(wrap("abcdefghij", 4, 1), ["abcd", "efg", "hij"]);
+    }
+
+    #[test]
+    fn test_format_row() {
+        let columns = Columns {
+            mangled_width: 10,
+            demangled_width: 30,
+        };
+
+        assert_eq!(
+            columns.format_row("f__Fi", &Ok("f(int)".to_string())),
+            "f__Fi       f(int)"
+        );
+        assert_eq!(
+            columns.format_row(
+                "f__FiN31",
+                &Err(DemangleError::InvalidRepeatingArgument("N31"))
+            ),
+            "f__FiN31    error: MalformedArguments"
+        );
+        assert_eq!(
+            columns.format_row(
+                "f__3Fooicfd",
+                &Ok("Foo::f(int, char, float, double)".to_string())
+            ),
+            "f__3Fooicfd\n            Foo::f(int, char, float,\n              double)"
+        );
+        assert_eq!(
+            columns.format_row("f__Ficfd", &Ok("f(int, char, float, double)".to_string())),
+            "f__Ficfd    f(int, char, float, double)"
+        );
+    }
+}
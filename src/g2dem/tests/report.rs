@@ -0,0 +1,173 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+const MIXED_SYMBOLS: &str = include_str!("fixtures/mixed_symbols.txt");
+
+fn run_g2dem(args: &[&str], stdin: &str) -> (String, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_g2dem"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to run g2dem");
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    (
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+#[test]
+fn test_report_summary() {
+    let (stdout, stderr) = run_g2dem(&["--report", "summary"], MIXED_SYMBOLS);
+
+    assert_eq!(stdout.lines().count(), MIXED_SYMBOLS.lines().count());
+    assert_eq!(
+        stderr,
+        "Total symbols: 10
+Demangled: 4
+Returned as-is (not mangled): 3
+Failed with an error: 3
+"
+    );
+}
+
+#[test]
+fn test_report_verbose() {
+    let (_stdout, stderr) = run_g2dem(&["--report", "verbose"], MIXED_SYMBOLS);
+
+    assert_eq!(
+        stderr,
+        "Total symbols: 10
+Demangled: 4
+Returned as-is (not mangled): 3
+Failed with an error: 3
+
+Errors:
+  MalformedArguments (2):
+    InvalidRepeatingArgument (2): f__FiN31
+      InvalidRepeatingArgument(\"N31\")
+  MalformedSpecial (1):
+    InvalidDeltaOnThunk (1): __thunk_x_foo__3Bar
+      InvalidDeltaOnThunk(\"x_foo__3Bar\")
+"
+    );
+}
+
+#[test]
+fn test_no_report() {
+    let (stdout, stderr) = run_g2dem(&[], MIXED_SYMBOLS);
+
+    assert_eq!(stdout.lines().next(), Some("main"));
+    assert_eq!(stderr, "");
+}
+
+#[test]
+fn test_output_shaping() {
+    let symbols = "Insert__t4List1Z5tNameRC5tName\nSwap__H1Zi_RX01T0_v\n";
+
+    let (stdout, _stderr) = run_g2dem(&["--strip-params"], symbols);
+    assert_eq!(stdout, "List<tName>::Insert\nvoid Swap<int>\n");
+
+    let (stdout, _stderr) = run_g2dem(&["--return-type", "omit"], symbols);
+    assert_eq!(
+        stdout,
+        "List<tName>::Insert(tName const &)\nSwap<int>(int &, int &)\n"
+    );
+
+    let (stdout, _stderr) = run_g2dem(&["--return-type", "trailing"], symbols);
+    assert_eq!(
+        stdout,
+        "List<tName>::Insert(tName const &)\nSwap<int>(int &, int &) -> void\n"
+    );
+
+    let (stdout, _stderr) = run_g2dem(
+        &[
+            "--strip-params",
+            "--return-type",
+            "omit",
+            "--strip-scope-template-args",
+        ],
+        symbols,
+    );
+    assert_eq!(stdout, "List::Insert\nSwap<int>\n");
+}
+
+#[test]
+fn test_parallel() {
+    let (stdout, stderr) = run_g2dem(&["--report", "verbose"], MIXED_SYMBOLS);
+    let (parallel_stdout, parallel_stderr) =
+        run_g2dem(&["--report", "verbose", "--parallel"], MIXED_SYMBOLS);
+
+    assert_eq!(parallel_stdout, stdout);
+    assert_eq!(parallel_stderr, stderr);
+}
+
+#[test]
+fn test_compiler() {
+    let symbols = "SetTime__5TimerUJ\n__5tName2i\n";
+
+    let (stdout, _stderr) = run_g2dem(&[], symbols);
+    assert_eq!(
+        stdout,
+        "SetTime__5TimerUJ\ntName::tName(int) [not-in-charge]\n"
+    );
+
+    let (stdout, _stderr) = run_g2dem(&["--compiler", "gcc-2.95.3-sn"], symbols);
+    assert_eq!(stdout, "Timer::SetTime(unsigned __int64)\n__5tName2i\n");
+}
+
+#[test]
+fn test_side_by_side() {
+    let symbols = "SetText__5tNamePCc\nf__FiN31\nmain\n";
+
+    // The whole batch is known, so the column fits the longest symbol.
+    let (stdout, _stderr) = run_g2dem(
+        &["--side-by-side", "SetText__5tNamePCc", "f__FiN31", "main"],
+        "",
+    );
+    assert_eq!(
+        stdout,
+        "\
+SetText__5tNamePCc  tName::SetText(char const *)
+f__FiN31            error: MalformedArguments
+main                error: NotMangled
+"
+    );
+    let (parallel_stdout, _stderr) = run_g2dem(&["--side-by-side", "--parallel"], symbols);
+    assert_eq!(parallel_stdout, stdout);
+
+    // Streaming stdin can't know how long the symbols are.
+    let (stdout, _stderr) = run_g2dem(&["--side-by-side"], symbols);
+    assert_eq!(
+        stdout.lines().next(),
+        Some(format!("{:<40}  tName::SetText(char const *)", "SetText__5tNamePCc").as_str())
+    );
+
+    let (stdout, _stderr) = run_g2dem(&["--side-by-side", "--width", "10"], symbols);
+    assert_eq!(
+        stdout,
+        "\
+SetText__5tNamePCc
+            tName::SetText(char const *)
+f__FiN31    error: MalformedArguments
+main        error: NotMangled
+"
+    );
+}
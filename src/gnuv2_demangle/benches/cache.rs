@@ -0,0 +1,103 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+//! Compare `CachedDemangler` with different capacities against plain
+//! `demangle` calls, over a Zipf-distributed sample of the mangled lists used
+//! by the snapshot tests. Profilers see a few hot symbols most of the time,
+//! which is what this tries to mimic.
+//!
+//! ```bash
+//! cargo bench -p gnuv2_demangle --features cache --bench cache
+//! ```
+
+#[path = "../tests/testutil/mod.rs"]
+mod testutil;
+
+use std::{hint::black_box, num::NonZeroUsize};
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+
+use gnuv2_demangle::{CachedDemangler, DemangleConfig};
+
+use testutil::MANGLED_LISTS;
+
+const SAMPLES: usize = 100_000;
+const ZIPF_EXPONENT: f64 = 1.1;
+const CAPACITIES: [usize; 3] = [64, 1024, 16384];
+
+/// A xorshift generator, good enough for picking symbols.
+struct Rng(u64);
+
+impl Rng {
+    fn next_f64(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Pick `SAMPLES` symbols, the `k`th one being picked with a probability
+/// proportional to `1 / k^ZIPF_EXPONENT`.
+fn zipf_samples<'s>(syms: &[&'s str]) -> Vec<&'s str> {
+    let mut cumulative = Vec::with_capacity(syms.len());
+    let mut total = 0.0;
+    for k in 1..=syms.len() {
+        total += 1.0 / (k as f64).powf(ZIPF_EXPONENT);
+        cumulative.push(total);
+    }
+
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    (0..SAMPLES)
+        .map(|_| {
+            let x = rng.next_f64() * total;
+            let k = cumulative.partition_point(|&c| c < x);
+            syms[k.min(syms.len() - 1)]
+        })
+        .collect()
+}
+
+fn bench_cache(c: &mut Criterion) {
+    let config = DemangleConfig::new();
+    let syms: Vec<&str> = MANGLED_LISTS
+        .iter()
+        .flat_map(|(_, contents)| contents.lines())
+        .collect();
+    let samples = zipf_samples(&syms);
+
+    println!("{} samples of {} symbols", samples.len(), syms.len());
+    for capacity in CAPACITIES {
+        let mut demangler = CachedDemangler::new(config, NonZeroUsize::new(capacity).unwrap());
+        for sym in &samples {
+            demangler.demangle_cached(sym);
+        }
+        let hit_rate = demangler.hits() as f64 / samples.len() as f64 * 100.0;
+        println!("capacity {capacity:>6}  {hit_rate:.1}% hits");
+    }
+
+    let mut group = c.benchmark_group("cache");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(samples.len() as u64));
+    group.bench_function("uncached", |b| {
+        b.iter(|| testutil::demangle_each(&samples, &config))
+    });
+    for capacity in CAPACITIES {
+        // Start every run with an empty cache, like a profiler that was just
+        // opened.
+        group.bench_function(BenchmarkId::new("capacity", capacity), |b| {
+            b.iter_batched_ref(
+                || CachedDemangler::new(config, NonZeroUsize::new(capacity).unwrap()),
+                |demangler| {
+                    for sym in &samples {
+                        black_box(demangler.demangle_cached(black_box(sym)));
+                    }
+                },
+                BatchSize::PerIteration,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_cache);
+criterion_main!(benches);
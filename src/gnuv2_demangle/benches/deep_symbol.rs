@@ -0,0 +1,41 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+//! Micro-benchmark for deeply namespaced symbols with long argument lists.
+//!
+//! ```bash
+//! cargo bench -p gnuv2_demangle --bench deep_symbol
+//! ```
+
+#[path = "../tests/testutil/mod.rs"]
+mod testutil;
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use gnuv2_demangle::demangle;
+
+const SYMBOLS: [&str; 3] = [
+    "Method__Q55Outer6Middlet4List1Zi5Inner4LeafPCcRCQ25Outer6MiddleiT1T2fdPCcRC5InnerUlsT3T4PA9_A4_fRCt4List1Zt4Pair2ZiZPCcPFiPCc_vN32",
+    "Insert__Q35Outert4Dict2Z5tNameZt4List1Zi4NodeRCt4Dict2Z5tNameZt4List1ZiPA3_A7_iT1N22",
+    "CreateRoadBlock__12AICopManagerP8IPursuitiP8IVehiclePQ43UTL11Collectionst11ListableSet4Z8IVehiclei10Z12eVehicleListUi10_4List",
+];
+
+fn bench_deep_symbol(c: &mut Criterion) {
+    let mut group = c.benchmark_group("deep_symbol");
+    for (preset, config) in testutil::presets() {
+        for (i, sym) in SYMBOLS.into_iter().enumerate() {
+            // Make sure we are measuring the happy path.
+            demangle(sym, &config).expect("Benchmark symbols must demangle");
+
+            group.bench_with_input(BenchmarkId::new(preset, i), sym, |b, sym| {
+                b.iter(|| demangle(black_box(sym), &config).ok())
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_deep_symbol);
+criterion_main!(benches);
@@ -0,0 +1,58 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+//! Compare `demangle_in` with an arena that is reset after every batch
+//! against plain `demangle` calls, over 100k symbols taken from the mangled
+//! lists used by the snapshot tests.
+//!
+//! ```bash
+//! cargo bench -p gnuv2_demangle --features allocator-api2 --bench demangle_in
+//! ```
+
+#[path = "../tests/testutil/mod.rs"]
+mod testutil;
+
+use std::hint::black_box;
+
+use bumpalo::Bump;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+use gnuv2_demangle::{demangle_in, DemangleConfig};
+
+use testutil::MANGLED_LISTS;
+
+const SYMBOLS: usize = 100_000;
+/// How many symbols are demangled before resetting the arena.
+const BATCH: usize = 1000;
+
+fn bench_demangle_in(c: &mut Criterion) {
+    let config = DemangleConfig::new();
+    let syms: Vec<&str> = MANGLED_LISTS
+        .iter()
+        .flat_map(|(_, contents)| contents.lines())
+        .cycle()
+        .take(SYMBOLS)
+        .collect();
+
+    let mut group = c.benchmark_group("demangle_in");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(syms.len() as u64));
+    group.bench_function("global", |b| {
+        b.iter(|| testutil::demangle_each(&syms, &config))
+    });
+    group.bench_function("bump", |b| {
+        let mut bump = Bump::new();
+        b.iter(|| {
+            for batch in syms.chunks(BATCH) {
+                for sym in batch {
+                    black_box(demangle_in(black_box(sym), &config, &bump).ok());
+                }
+                bump.reset();
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_demangle_in);
+criterion_main!(benches);
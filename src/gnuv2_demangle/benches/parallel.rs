@@ -0,0 +1,51 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+//! Compare `demangle_all` with different amounts of threads against a plain
+//! loop over the mangled lists used by the snapshot tests.
+//!
+//! ```bash
+//! cargo bench -p gnuv2_demangle --features parallel --bench parallel
+//! ```
+
+#[path = "../tests/testutil/mod.rs"]
+mod testutil;
+
+use std::{hint::black_box, thread};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use gnuv2_demangle::{demangle_all, DemangleConfig};
+
+use testutil::MANGLED_LISTS;
+
+fn bench_parallel(c: &mut Criterion) {
+    let config = DemangleConfig::new();
+    let syms: Vec<&str> = MANGLED_LISTS
+        .iter()
+        .flat_map(|(_, contents)| contents.lines())
+        .collect();
+    let max_threads = thread::available_parallelism().map_or(1, |x| x.get());
+
+    let mut group = c.benchmark_group("parallel");
+    group.throughput(Throughput::Elements(syms.len() as u64));
+    group.bench_function("sequential", |b| {
+        b.iter(|| testutil::demangle_each(&syms, &config))
+    });
+
+    let mut threads = 1;
+    while threads <= max_threads {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        group.bench_function(BenchmarkId::new("threads", threads), |b| {
+            b.iter(|| pool.install(|| demangle_all(black_box(&syms), &config)))
+        });
+        threads *= 2;
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_parallel);
+criterion_main!(benches);
@@ -0,0 +1,45 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+//! Compare `demangle_scope_only` against a full `demangle` over the mangled
+//! lists used by the snapshot tests.
+//!
+//! ```bash
+//! cargo bench -p gnuv2_demangle --bench scope_only
+//! ```
+
+#[path = "../tests/testutil/mod.rs"]
+mod testutil;
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+use gnuv2_demangle::{demangle_scope_only, DemangleConfig};
+
+use testutil::MANGLED_LISTS;
+
+fn bench_scope_only(c: &mut Criterion) {
+    let config = DemangleConfig::new();
+
+    for (name, contents) in MANGLED_LISTS {
+        let syms: Vec<&str> = contents.lines().collect();
+
+        let mut group = c.benchmark_group(format!("scope_only/{name}"));
+        group.throughput(Throughput::Elements(syms.len() as u64));
+        group.bench_function("demangle", |b| {
+            b.iter(|| testutil::demangle_each(&syms, &config))
+        });
+        group.bench_function("scope_only", |b| {
+            b.iter(|| {
+                for sym in &syms {
+                    black_box(demangle_scope_only(black_box(sym), &config).ok());
+                }
+            })
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_scope_only);
+criterion_main!(benches);
@@ -0,0 +1,42 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+//! Throughput of the mangled lists used by the snapshot tests, of a few
+//! pathological symbols and of rejecting symbols that are not mangled, in
+//! both presets.
+//!
+//! ```bash
+//! cargo bench -p gnuv2_demangle --bench throughput
+//! ```
+//!
+//! See `tests/throughput.rs` for checking against `benches/baseline.txt` and
+//! for updating it.
+
+#[path = "../tests/testutil/mod.rs"]
+mod testutil;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+use gnuv2_demangle::{demangle, DemangleError};
+
+fn bench_throughput(c: &mut Criterion) {
+    for (_, config) in testutil::presets() {
+        // Make sure we are measuring the paths we think we are.
+        for (_, sym) in testutil::PATHOLOGICAL {
+            demangle(sym, &config).expect("Pathological symbols must demangle");
+        }
+        for sym in testutil::NOT_MANGLED {
+            assert_eq!(demangle(sym, &config), Err(DemangleError::NotMangled));
+        }
+    }
+
+    let mut group = c.benchmark_group("throughput");
+    for benchmark in testutil::benchmarks() {
+        group.throughput(Throughput::Elements(benchmark.symbols.len() as u64));
+        group.bench_function(&benchmark.name, |b| b.iter(|| benchmark.demangle_each()));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_throughput);
+criterion_main!(benches);
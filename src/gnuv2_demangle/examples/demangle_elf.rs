@@ -0,0 +1,152 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+//! Demangle every symbol of an ELF file and summarize the failures.
+//!
+//! ```bash
+//! cargo run -p gnuv2_demangle --example demangle_elf -- path/to/file.o
+//! ```
+//!
+//! Defaults to `tests/fixtures/gnuv2_symbols.o` if no path is given.
+//!
+//! Meant as a template for tools that demangle lots of symbols at once:
+//! symbols that are not mangled are passed through without allocating, and
+//! failures are counted by their [`DemangleError::kind`].
+
+use std::{borrow::Cow, collections::BTreeMap, fs, process::ExitCode};
+
+use gnuv2_demangle::{demangle, DemangleConfig, DemangleError, DemangleErrorKind};
+use object::{Object, ObjectSymbol};
+
+const DEFAULT_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/gnuv2_symbols.o"
+);
+
+/// Demangle `sym`, borrowing it as-is if it is not mangled at all, like the
+/// symbol of a C function.
+fn demangle_cow<'s>(
+    sym: &'s str,
+    config: &DemangleConfig,
+) -> Result<Cow<'s, str>, DemangleError<'s>> {
+    match demangle(sym, config) {
+        Ok(demangled) => Ok(Cow::Owned(demangled)),
+        Err(DemangleError::NotMangled) => Ok(Cow::Borrowed(sym)),
+        Err(err) => Err(err),
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Stats {
+    total: usize,
+    not_mangled: usize,
+    failures_by_kind: BTreeMap<DemangleErrorKind, usize>,
+}
+
+/// A symbol of the file, with what it demangled to.
+struct Symbol<'d> {
+    name: &'d str,
+    demangled: Result<Cow<'d, str>, DemangleError<'d>>,
+}
+
+/// Demangle the named symbols of an object file, in the order of its symbol
+/// table.
+fn demangle_symbols<'d>(
+    data: &'d [u8],
+    config: &DemangleConfig,
+) -> Result<(Vec<Symbol<'d>>, Stats), object::Error> {
+    let file = object::File::parse(data)?;
+
+    let mut symbols = Vec::new();
+    let mut stats = Stats::default();
+    for symbol in file.symbols() {
+        let name = symbol.name()?;
+        if name.is_empty() {
+            continue;
+        }
+
+        let demangled = demangle_cow(name, config);
+        stats.total += 1;
+        match &demangled {
+            Ok(Cow::Borrowed(_)) => stats.not_mangled += 1,
+            Ok(Cow::Owned(_)) => {}
+            Err(err) => *stats.failures_by_kind.entry(err.kind()).or_default() += 1,
+        }
+        symbols.push(Symbol { name, demangled });
+    }
+
+    Ok((symbols, stats))
+}
+
+fn run() -> Result<(), String> {
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_PATH.to_string());
+    let data = fs::read(&path).map_err(|err| format!("Can't read `{path}`: {err}"))?;
+
+    let config = DemangleConfig::new();
+    let (symbols, stats) =
+        demangle_symbols(&data, &config).map_err(|err| format!("Can't parse `{path}`: {err}"))?;
+
+    for symbol in &symbols {
+        match &symbol.demangled {
+            Ok(demangled) => println!("{demangled}"),
+            Err(err) => println!("{}  # {}", symbol.name, err.kind_name()),
+        }
+    }
+
+    println!();
+    let failed: usize = stats.failures_by_kind.values().sum();
+    println!(
+        "{} symbols, {} not mangled, {failed} failed",
+        stats.total, stats.not_mangled
+    );
+    for (kind, count) in &stats.failures_by_kind {
+        println!("  {kind:?}: {count}");
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[test]
+fn test_demangle_fixture() {
+    let data = fs::read(DEFAULT_PATH).unwrap();
+    let (symbols, stats) = demangle_symbols(&data, &DemangleConfig::new()).unwrap();
+
+    assert_eq!(
+        stats,
+        Stats {
+            total: 9,
+            not_mangled: 1,
+            failures_by_kind: BTreeMap::from([(DemangleErrorKind::MalformedArguments, 1)]),
+        }
+    );
+
+    let demangled: Vec<_> = symbols
+        .iter()
+        .filter_map(|x| x.demangled.as_deref().ok())
+        .collect();
+    assert_eq!(
+        demangled,
+        [
+            "main",
+            "tName::SetText(char const *)",
+            "tName::tName(char const *)",
+            "Console::Printf(char *, ...)",
+            "List<int>::Insert(int const &)",
+            "operator<<(ostream &, char const *)",
+            "global constructors keyed to tName::SetText(char const *)",
+            "tName virtual table",
+        ]
+    );
+}
@@ -0,0 +1,155 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+//! Demangle a list of symbols, one per line, with both the c++filt and g2dem
+//! presets and summarize the failures.
+//!
+//! ```bash
+//! cargo run -p gnuv2_demangle --features test-utils --example run_corpus -- \
+//!     symbols.txt --report new_report.txt --previous old_report.txt
+//! ```
+//!
+//! - `--report <path>`: Write the full report, which can be passed as
+//!   `--previous` on a later run.
+//! - `--previous <path>`: Print every symbol whose output changed since that
+//!   report.
+//! - `--thresholds <path>`: The maximum failure percentage allowed for each
+//!   preset. Defaults to `tests/corpus_thresholds.txt`.
+//!
+//! Exits with an error if the failure rate of any preset goes past its
+//! threshold.
+
+use std::{fs, path::PathBuf, process::ExitCode};
+
+use gnuv2_demangle::corpus::{CorpusPreset, CorpusReport};
+
+const DEFAULT_THRESHOLDS: &str =
+    concat!(env!("CARGO_MANIFEST_DIR"), "/tests/corpus_thresholds.txt");
+
+struct Args {
+    corpora: Vec<PathBuf>,
+    report: Option<PathBuf>,
+    previous: Option<PathBuf>,
+    thresholds: PathBuf,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        corpora: Vec::new(),
+        report: None,
+        previous: None,
+        thresholds: PathBuf::from(DEFAULT_THRESHOLDS),
+    };
+
+    let mut iter = std::env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        let mut value = || iter.next().ok_or(format!("Missing value for `{arg}`"));
+        match arg.as_str() {
+            "--report" => args.report = Some(value()?.into()),
+            "--previous" => args.previous = Some(value()?.into()),
+            "--thresholds" => args.thresholds = value()?.into(),
+            _ if arg.starts_with("--") => return Err(format!("Unknown option `{arg}`")),
+            _ => args.corpora.push(arg.into()),
+        }
+    }
+
+    if args.corpora.is_empty() {
+        return Err(String::from(
+            "Usage: run_corpus <symbols.txt>... [--report <path>] [--previous <path>] [--thresholds <path>]",
+        ));
+    }
+    Ok(args)
+}
+
+/// Lines like `cfilt 2.5`, where the number is the maximum failure
+/// percentage. `#` starts a comment.
+fn parse_thresholds(contents: &str) -> Result<Vec<(CorpusPreset, f64)>, String> {
+    let mut thresholds = Vec::new();
+
+    for line in contents.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let threshold = line.split_once(' ').and_then(|(preset, rate)| {
+            Some((CorpusPreset::from_name(preset)?, rate.trim().parse().ok()?))
+        });
+        thresholds.push(threshold.ok_or(format!("Invalid threshold `{line}`"))?);
+    }
+
+    Ok(thresholds)
+}
+
+fn read(path: &PathBuf) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|err| format!("Can't read `{}`: {err}", path.display()))
+}
+
+fn run() -> Result<bool, String> {
+    let args = parse_args()?;
+
+    let mut contents = String::new();
+    for path in &args.corpora {
+        contents.push_str(&read(path)?);
+        contents.push('\n');
+    }
+    let report = CorpusReport::demangle_lines(&contents);
+
+    if let Some(path) = &args.previous {
+        let previous = CorpusReport::parse(&read(path)?)
+            .map_err(|line| format!("Malformed report `{}` at line {line}", path.display()))?;
+
+        let changes = report.diff(&previous);
+        for change in &changes {
+            println!("{change}");
+        }
+        let regressions = changes.iter().filter(|x| x.is_regression()).count();
+        println!(
+            "{} changes since the previous report, {regressions} of them are new failures",
+            changes.len()
+        );
+        println!();
+    }
+
+    if let Some(path) = &args.report {
+        fs::write(path, report.to_string())
+            .map_err(|err| format!("Can't write `{}`: {err}", path.display()))?;
+    }
+
+    let thresholds = parse_thresholds(&read(&args.thresholds)?)?;
+    let mut passed = true;
+    for preset in CorpusPreset::ALL {
+        let stats = report.stats(preset);
+        let rate = stats.failure_rate();
+        println!(
+            "{}: {} symbols, {} not mangled, {} failed ({rate:.2}%)",
+            preset.name(),
+            stats.total,
+            stats.not_mangled,
+            stats.failed
+        );
+        for (kind, count) in &stats.failures_by_kind {
+            println!("  {kind}: {count}");
+        }
+
+        for (_, max_rate) in thresholds.iter().filter(|(x, _)| *x == preset) {
+            if rate > *max_rate {
+                println!("  Failure rate is past the threshold of {max_rate:.2}%");
+                passed = false;
+            }
+        }
+    }
+
+    Ok(passed)
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,296 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    num::NonZeroUsize,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use alloc::{boxed::Box, vec::Vec};
+
+use crate::{demangle, DemangleConfig};
+
+const NIL: usize = usize::MAX;
+
+#[derive(Debug)]
+struct Entry {
+    key: u64,
+    fingerprint: u64,
+    sym: Box<str>,
+    demangled: Option<Arc<str>>,
+    /// The entry used right before this one, or [`NIL`].
+    newer: usize,
+    /// The entry used right after this one, or [`NIL`].
+    older: usize,
+}
+
+/// Demangle symbols, remembering the most recently used ones.
+///
+/// Useful for profilers and similar tools that demangle the same few symbols
+/// over and over. Once `capacity` symbols are cached, the least recently
+/// used one is forgotten to make room for the new one.
+///
+/// Symbols that fail to demangle are cached too, as [`None`].
+///
+/// The cache key includes a fingerprint of the [`DemangleConfig`], so
+/// changing it with [`set_config`](CachedDemangler::set_config) never returns
+/// a symbol demangled with the previous config.
+///
+/// Use [`SyncCachedDemangler`] to share the cache between threads.
+///
+/// # Examples
+///
+/// ```
+/// use std::num::NonZeroUsize;
+///
+/// use gnuv2_demangle::{CachedDemangler, DemangleConfig};
+///
+/// let capacity = NonZeroUsize::new(1024).unwrap();
+/// let mut demangler = CachedDemangler::new(DemangleConfig::new(), capacity);
+///
+/// for _ in 0..3 {
+///     assert_eq!(
+///         demangler.demangle_cached("SetText__5tNamePCc").as_deref(),
+///         Some("tName::SetText(char const *)")
+///     );
+/// }
+/// assert_eq!(demangler.demangle_cached("main"), None);
+///
+/// assert_eq!(demangler.hits(), 2);
+/// assert_eq!(demangler.misses(), 2);
+/// ```
+#[derive(Debug)]
+pub struct CachedDemangler {
+    config: DemangleConfig,
+    fingerprint: u64,
+    capacity: NonZeroUsize,
+    index: HashMap<u64, usize>,
+    entries: Vec<Entry>,
+    /// The most recently used entry.
+    newest: usize,
+    /// The least recently used entry, the next one to be forgotten.
+    oldest: usize,
+    hits: u64,
+    misses: u64,
+}
+
+impl CachedDemangler {
+    /// Create an empty cache that remembers up to `capacity` symbols.
+    #[must_use]
+    pub fn new(config: DemangleConfig, capacity: NonZeroUsize) -> Self {
+        Self {
+            config,
+            fingerprint: fingerprint(&config),
+            capacity,
+            index: HashMap::new(),
+            entries: Vec::new(),
+            newest: NIL,
+            oldest: NIL,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Demangle `sym`, reusing the previous result if it is still cached.
+    ///
+    /// Returns [`None`] if `sym` can't be demangled.
+    pub fn demangle_cached(&mut self, sym: &str) -> Option<Arc<str>> {
+        let key = key(self.fingerprint, sym);
+
+        if let Some(&i) = self.index.get(&key) {
+            let entry = &self.entries[i];
+            if entry.fingerprint == self.fingerprint && *entry.sym == *sym {
+                self.hits += 1;
+                self.detach(i);
+                self.attach(i);
+                return self.entries[i].demangled.clone();
+            }
+        }
+
+        self.misses += 1;
+        let entry = Entry {
+            key,
+            fingerprint: self.fingerprint,
+            sym: Box::from(sym),
+            demangled: demangle(sym, &self.config).ok().map(Arc::from),
+            newer: NIL,
+            older: NIL,
+        };
+        let i = match self.index.get(&key) {
+            // Another symbol with the same key, replace it.
+            Some(&i) => {
+                self.detach(i);
+                self.entries[i] = entry;
+                i
+            }
+            None if self.entries.len() < self.capacity.get() => {
+                self.entries.push(entry);
+                self.entries.len() - 1
+            }
+            None => {
+                let i = self.oldest;
+                self.detach(i);
+                self.index.remove(&self.entries[i].key);
+                self.entries[i] = entry;
+                i
+            }
+        };
+        self.index.insert(key, i);
+        self.attach(i);
+
+        self.entries[i].demangled.clone()
+    }
+
+    /// The config used for demangling.
+    #[must_use]
+    pub fn config(&self) -> &DemangleConfig {
+        &self.config
+    }
+
+    /// Use a different config for the next symbols.
+    ///
+    /// Symbols cached with the previous config are not returned anymore, but
+    /// they are kept in case the config is changed back.
+    pub fn set_config(&mut self, config: DemangleConfig) {
+        self.config = config;
+        self.fingerprint = fingerprint(&config);
+    }
+
+    /// How many symbols can be cached at most.
+    #[must_use]
+    pub fn capacity(&self) -> NonZeroUsize {
+        self.capacity
+    }
+
+    /// How many symbols are currently cached.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no symbol has been cached yet.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// How many calls to [`demangle_cached`](CachedDemangler::demangle_cached)
+    /// reused a cached result.
+    #[must_use]
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// How many calls to [`demangle_cached`](CachedDemangler::demangle_cached)
+    /// had to demangle the symbol.
+    #[must_use]
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Forget every cached symbol. The hit and miss counts are kept.
+    pub fn clear(&mut self) {
+        self.index.clear();
+        self.entries.clear();
+        self.newest = NIL;
+        self.oldest = NIL;
+    }
+
+    /// Unlink the entry `i` from the recently used list.
+    fn detach(&mut self, i: usize) {
+        let Entry { newer, older, .. } = self.entries[i];
+
+        if newer == NIL {
+            self.newest = older;
+        } else {
+            self.entries[newer].older = older;
+        }
+        if older == NIL {
+            self.oldest = newer;
+        } else {
+            self.entries[older].newer = newer;
+        }
+    }
+
+    /// Link the entry `i` as the most recently used one.
+    fn attach(&mut self, i: usize) {
+        self.entries[i].newer = NIL;
+        self.entries[i].older = self.newest;
+
+        if self.newest == NIL {
+            self.oldest = i;
+        } else {
+            self.entries[self.newest].newer = i;
+        }
+        self.newest = i;
+    }
+}
+
+/// A [`CachedDemangler`] that can be shared between threads.
+///
+/// Every call locks the whole cache, so heavily multithreaded users may want
+/// a [`CachedDemangler`] per thread instead.
+///
+/// # Examples
+///
+/// ```
+/// use std::{num::NonZeroUsize, thread};
+///
+/// use gnuv2_demangle::{DemangleConfig, SyncCachedDemangler};
+///
+/// let capacity = NonZeroUsize::new(1024).unwrap();
+/// let demangler = SyncCachedDemangler::new(DemangleConfig::new(), capacity);
+///
+/// thread::scope(|s| {
+///     for _ in 0..4 {
+///         s.spawn(|| {
+///             assert_eq!(
+///                 demangler.demangle_cached("SetText__5tNamePCc").as_deref(),
+///                 Some("tName::SetText(char const *)")
+///             );
+///         });
+///     }
+/// });
+/// assert_eq!(demangler.lock().misses(), 1);
+/// ```
+#[derive(Debug)]
+pub struct SyncCachedDemangler {
+    inner: Mutex<CachedDemangler>,
+}
+
+impl SyncCachedDemangler {
+    /// Create an empty cache that remembers up to `capacity` symbols.
+    #[must_use]
+    pub fn new(config: DemangleConfig, capacity: NonZeroUsize) -> Self {
+        Self {
+            inner: Mutex::new(CachedDemangler::new(config, capacity)),
+        }
+    }
+
+    /// Same as [`CachedDemangler::demangle_cached`].
+    pub fn demangle_cached(&self, sym: &str) -> Option<Arc<str>> {
+        self.lock().demangle_cached(sym)
+    }
+
+    /// Lock the cache, to change its config or check its stats.
+    pub fn lock(&self) -> MutexGuard<'_, CachedDemangler> {
+        // The cache is always left in a consistent state, even if a thread
+        // panics while holding the lock.
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+fn fingerprint(config: &DemangleConfig) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    config.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn key(fingerprint: u64, sym: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    fingerprint.hash(&mut hasher);
+    sym.hash(&mut hasher);
+    hasher.finish()
+}
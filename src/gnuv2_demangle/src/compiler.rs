@@ -0,0 +1,187 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+use crate::{demangle_with_info, Construct, DemangleConfig};
+
+/// A known GNU V2-era compiler, with its own mangling quirks.
+///
+/// See [`DemangleConfig::for_compiler`] and [`CompilerId::detect`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum CompilerId {
+    /// EE GCC 2.9 build 990721, from the early PS2 SDKs.
+    ///
+    /// Repeated arguments are emitted as chains of lookbacks, like the
+    /// `T0T0` of `Debug_Assert__FPcT0T0i`.
+    EeGcc29_990721,
+    /// EE GCC 2.96 build 001003-1, from later PS2 SDKs.
+    ///
+    /// Repeated arguments are emitted as repeats, like the `N20` of
+    /// `Debug_Assert__FPcN20i`.
+    EeGcc296,
+    /// EE GCC 2.95.3 as shipped by SN Systems (ProDG).
+    ///
+    /// Uses `.` instead of `$` as the separator of special symbols, like
+    /// `_._5tName`, and may emit vendor type codes and stray `_` between
+    /// the arguments of a function.
+    Gcc295SnSystems,
+    /// RedHat's GCC 2.96.
+    ///
+    /// May emit constructor variant markers, like the `2` of `__5tName2i`.
+    Gcc296RedHat,
+    /// Any other GNU V2 compiler.
+    GenericGnu2,
+}
+
+impl CompilerId {
+    /// Guess which compiler emitted a list of symbols.
+    ///
+    /// This is a heuristic based on the quirks found on the symbols, so it
+    /// works better the more symbols are passed. A whole symbol table is
+    /// best. Symbols that don't demangle are ignored.
+    ///
+    /// Returns `None` if none of the symbols could be demangled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gnuv2_demangle::CompilerId;
+    ///
+    /// let syms = ["Debug_Assert__FPcT0T0i", "SetText__5tNamePCc"];
+    /// assert_eq!(
+    ///     CompilerId::detect(syms.into_iter()),
+    ///     Some(CompilerId::EeGcc29_990721)
+    /// );
+    ///
+    /// let syms = ["Debug_Assert__FPcN20i", "SetText__5tNamePCc"];
+    /// assert_eq!(
+    ///     CompilerId::detect(syms.into_iter()),
+    ///     Some(CompilerId::EeGcc296)
+    /// );
+    ///
+    /// assert_eq!(CompilerId::detect(["main"].into_iter()), None);
+    /// ```
+    #[must_use]
+    pub fn detect<'s>(symbols: impl Iterator<Item = &'s str>) -> Option<CompilerId> {
+        // Recognize every quirk, so the symbols that have them get counted.
+        let mut config = DemangleConfig::new_g2dem();
+        config.vendor_type_map = Some(sn_systems_types);
+        config.tolerate_stray_underscores_between_args = true;
+        config.demangle_constructor_variants = true;
+
+        let mut demangled = false;
+        let mut constructor_variants = false;
+        let mut sn_systems = false;
+        let mut repeats = false;
+        let mut lookback_chains = false;
+
+        for sym in symbols {
+            let (result, info) = demangle_with_info(sym, &config);
+            if result.is_err() {
+                continue;
+            }
+            demangled = true;
+
+            constructor_variants |= info.saw(Construct::ConstructorVariant);
+            sn_systems |= info.saw(Construct::VendorType)
+                || info.saw(Construct::StrayUnderscore)
+                || (!info.saw(Construct::CloneSuffix) && sym.contains('.'));
+            repeats |= info.saw(Construct::Repeat);
+            lookback_chains |= has_lookback_chain(sym);
+        }
+
+        if !demangled {
+            None
+        } else if constructor_variants {
+            Some(CompilerId::Gcc296RedHat)
+        } else if sn_systems {
+            Some(CompilerId::Gcc295SnSystems)
+        } else if repeats {
+            // Newer compilers still emit some chains, but older ones never
+            // emit repeats.
+            Some(CompilerId::EeGcc296)
+        } else if lookback_chains {
+            Some(CompilerId::EeGcc29_990721)
+        } else {
+            Some(CompilerId::GenericGnu2)
+        }
+    }
+}
+
+impl DemangleConfig {
+    /// The [`new_g2dem`](DemangleConfig::new_g2dem) configuration, with the
+    /// quirks of `compiler` turned on and the ones of other compilers turned
+    /// off.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, CompilerId, DemangleConfig, DemangleError};
+    ///
+    /// let config = DemangleConfig::for_compiler(CompilerId::Gcc296RedHat);
+    /// let demangled = demangle("__5tName2i", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("tName::tName(int) [not-in-charge]")
+    /// );
+    ///
+    /// let config = DemangleConfig::for_compiler(CompilerId::EeGcc296);
+    /// let demangled = demangle("__5tName2i", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Err(&DemangleError::ConstructorVariantMarker("2i"))
+    /// );
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn for_compiler(compiler: CompilerId) -> Self {
+        let mut config = Self::new_g2dem();
+
+        match compiler {
+            CompilerId::EeGcc29_990721 | CompilerId::EeGcc296 => {
+                config.demangle_constructor_variants = false;
+            }
+            CompilerId::Gcc295SnSystems => {
+                config.demangle_constructor_variants = false;
+                config.vendor_type_map = Some(sn_systems_types);
+                config.tolerate_stray_underscores_between_args = true;
+            }
+            CompilerId::Gcc296RedHat => {
+                config.demangle_constructor_variants = true;
+            }
+            CompilerId::GenericGnu2 => {}
+        }
+
+        config
+    }
+}
+
+/// The vendor type codes of SN Systems' compilers.
+fn sn_systems_types(code: char, _rest: &str) -> Option<(usize, &'static str)> {
+    match code {
+        'J' => Some((0, "__int64")),
+        _ => None,
+    }
+}
+
+/// Whether `sym` has the same lookback twice in a row, like `T0T0`, which
+/// newer compilers emit as a repeat instead.
+fn has_lookback_chain(sym: &str) -> bool {
+    let mut lookbacks = sym.split('T').skip(1).map(|x| {
+        let digits = x.bytes().take_while(u8::is_ascii_digit).count();
+        (&x[..digits], digits == x.len())
+    });
+
+    let Some(mut previous) = lookbacks.next() else {
+        return false;
+    };
+    for current in lookbacks {
+        // `previous` must be right before the `T` of `current`.
+        let (index, whole) = previous;
+        if whole && !index.is_empty() && current.0 == index {
+            return true;
+        }
+        previous = current;
+    }
+    false
+}
@@ -0,0 +1,384 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+use alloc::{borrow::Cow, vec::Vec};
+
+use crate::{demangle_info::Context, DemangleConfig, DemangleError};
+
+use crate::{
+    dem::{demangle_custom_name, demangle_operator_name, CvQualifiers},
+    dem_arg::{demangle_argument, ArrayPosition, DemangledArg},
+    dem_arg_list::ArgVec,
+    dem_namespace::{demangle_namespace_count, skip_digit_separator},
+    dem_template::demangle_template_and_types,
+    extract::find_invalid_char,
+    remainer::{Remaining, StrParsing},
+    str_cutter::StrCutter,
+};
+
+/// The pieces that make the name of a demangled symbol, without its argument
+/// list.
+///
+/// See [`demangle_components`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Components<'s> {
+    scope: Vec<ScopeSegment<'s>>,
+    leaf: Cow<'s, str>,
+    leaf_kind: LeafKind,
+    qualifiers: CvQualifiers,
+}
+
+impl<'s> Components<'s> {
+    /// Every scope containing the symbol, outermost first.
+    ///
+    /// Free functions have an empty scope.
+    #[must_use]
+    pub fn scope(&self) -> &[ScopeSegment<'s>] {
+        &self.scope
+    }
+
+    /// The name of the symbol itself, like a function name or
+    /// `operator==`.
+    ///
+    /// The name of constructors and destructors is the class name, without
+    /// the `~`.
+    #[must_use]
+    pub fn leaf(&self) -> &str {
+        &self.leaf
+    }
+
+    /// What kind of name the leaf is.
+    #[must_use]
+    pub fn leaf_kind(&self) -> LeafKind {
+        self.leaf_kind
+    }
+
+    /// The cv-qualifiers of a method, like `const`.
+    pub(crate) fn qualifiers(&self) -> CvQualifiers {
+        self.qualifiers
+    }
+}
+
+/// A single scope of a symbol, like a namespace or a class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ScopeSegment<'s> {
+    name: &'s str,
+    kind: ScopeKind,
+    template_args: Option<&'s str>,
+}
+
+impl<'s> ScopeSegment<'s> {
+    /// The name of this scope, excluding any template argument.
+    #[must_use]
+    pub fn name(&self) -> &'s str {
+        self.name
+    }
+
+    /// What kind of scope this is.
+    #[must_use]
+    pub fn kind(&self) -> ScopeKind {
+        self.kind
+    }
+
+    /// The still mangled template arguments of a
+    /// [`ScopeKind::TemplateInstance`], i.e. `Zi` for `t4List1Zi`.
+    #[must_use]
+    pub fn template_args(&self) -> Option<&'s str> {
+        self.template_args
+    }
+}
+
+/// See [`ScopeSegment::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum ScopeKind {
+    /// An enclosing scope.
+    ///
+    /// The GNU V2 mangling does not tell apart namespaces from enclosing
+    /// classes, so this may be either.
+    Namespace,
+    /// The innermost scope the symbol belongs to.
+    ///
+    /// With [`DemangleConfig::annotate_ambiguous_scope`] this is only used
+    /// when the mangling proves the scope is a class.
+    Class,
+    /// An instance of a templated class.
+    TemplateInstance,
+    /// The innermost scope the symbol belongs to, which may be either a
+    /// class or a namespace.
+    ///
+    /// Only used with [`DemangleConfig::annotate_ambiguous_scope`].
+    Ambiguous,
+}
+
+/// See [`Components::leaf_kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum LeafKind {
+    /// A plain function or method.
+    Function,
+    /// An overloaded operator, including cast operators.
+    Operator,
+    Constructor,
+    Destructor,
+}
+
+/// Split a symbol into its scopes and its name, without producing the whole
+/// demangled string.
+///
+/// Only functions, methods, operators, constructors and destructors are
+/// supported. The argument list is not validated, use [`demangle`] for that.
+///
+/// [`demangle`]: crate::demangle
+///
+/// # Examples
+///
+/// ```
+/// use gnuv2_demangle::{demangle_components, DemangleConfig, LeafKind, ScopeKind};
+///
+/// let config = DemangleConfig::new();
+///
+/// let components = demangle_components("Insert__t4List1Z5tNamei", &config).unwrap();
+/// let scope = components.scope();
+/// assert_eq!(scope.len(), 1);
+/// assert_eq!(scope[0].name(), "List");
+/// assert_eq!(scope[0].kind(), ScopeKind::TemplateInstance);
+/// assert_eq!(scope[0].template_args(), Some("Z5tName"));
+/// assert_eq!(components.leaf(), "Insert");
+/// assert_eq!(components.leaf_kind(), LeafKind::Function);
+/// ```
+pub fn demangle_components<'s>(
+    sym: &'s str,
+    config: &DemangleConfig,
+) -> Result<Components<'s>, DemangleError<'s>> {
+    let config = &Context::new(config);
+    if !sym.is_ascii() {
+        return Err(DemangleError::NonAscii);
+    }
+    if let Some((c, position)) = find_invalid_char(sym) {
+        return Err(DemangleError::InvalidCharacter(c, position));
+    }
+    let cplus_marker = sym.chars().find(|x| *x == '.').unwrap_or('$');
+
+    if let Some(s) = sym.c_strip_prefix_3chars('_', cplus_marker, '_') {
+        let (r, scope) = components_scope(config, s, true)?;
+        if !r.is_empty() {
+            return Err(DemangleError::TrailingDataOnDestructor(r));
+        }
+        let leaf = scope.last().map(|x| x.name).unwrap_or_default();
+        Ok(Components {
+            scope,
+            leaf: Cow::from(leaf),
+            leaf_kind: LeafKind::Destructor,
+            qualifiers: CvQualifiers::default(),
+        })
+    } else if let Some(s) = sym.strip_prefix("__") {
+        components_special(config, s, sym)
+    } else {
+        components_function(config, sym)
+    }
+}
+
+fn components_special<'s>(
+    config: &Context,
+    s: &'s str,
+    full_sym: &'s str,
+) -> Result<Components<'s>, DemangleError<'s>> {
+    if s.starts_with("tf")
+        || s.starts_with("ti")
+        || s.starts_with("thunk_")
+        || s.strip_prefix('H')
+            .is_some_and(|r| r.starts_with(|c: char| c.is_ascii_digit()))
+    {
+        return Err(DemangleError::UnsupportedSymbolKindOnComponents(full_sym));
+    }
+    // Same as `demangle_special`, plain C symbols like `__throw` are not
+    // templated or namespaced constructors.
+    let is_constructor = s.starts_with(|c| matches!(c, '1'..='9'))
+        || s.strip_prefix('t')
+            .is_some_and(|r| r.starts_with(|c: char| c.is_ascii_digit()))
+        || s.strip_prefix('Q')
+            .is_some_and(|r| r.starts_with(|c: char| c == '_' || c.is_ascii_digit()));
+    if is_constructor {
+        let (_r, scope) = components_scope(config, s, true)?;
+        let leaf = scope.last().map(|x| x.name).unwrap_or_default();
+        return Ok(Components {
+            scope,
+            leaf: Cow::from(leaf),
+            leaf_kind: LeafKind::Constructor,
+            qualifiers: CvQualifiers::default(),
+        });
+    }
+
+    let end_index = s.find("__").ok_or(DemangleError::NotMangled)?;
+    let op = &s[..end_index];
+    let remaining = &s[end_index + 2..];
+
+    // Cast operators can only be methods.
+    let mut is_method = false;
+    let leaf = if let Some(operator) = demangle_operator_name(op) {
+        Cow::from(operator)
+    } else if op.starts_with("op") && remaining.starts_with('H') {
+        return Err(DemangleError::UnsupportedSymbolKindOnComponents(full_sym));
+    } else if let Some(cast) = op.strip_prefix("op") {
+        let (_r, DemangledArg::Plain(typ, array_qualifiers)) = demangle_argument(
+            config,
+            cast,
+            &ArgVec::new(config, None),
+            &ArgVec::new(config, None),
+            true,
+        )?
+        else {
+            return Err(DemangleError::UnrecognizedSpecialMethod(op));
+        };
+        is_method = true;
+        let typ = match array_qualifiers.as_option() {
+            Some(arr) => arr.qualify(&typ, ArrayPosition::CastTarget, config.type_pointer_spacing),
+            None => typ,
+        };
+        Cow::from(format!("operator {typ}"))
+    } else {
+        // Maybe a plain function starting with `__`.
+        return components_function(config, full_sym);
+    };
+
+    let (scope, qualifiers) = if remaining.starts_with('F') {
+        (Vec::new(), CvQualifiers::default())
+    } else if remaining.starts_with('H') {
+        return Err(DemangleError::UnsupportedSymbolKindOnComponents(full_sym));
+    } else {
+        let Remaining { r, d: qualifiers } = CvQualifiers::parse(remaining);
+        let scope = components_scope(config, r, is_method || !qualifiers.is_empty())?.1;
+        (scope, qualifiers)
+    };
+
+    Ok(Components {
+        scope,
+        leaf,
+        leaf_kind: LeafKind::Operator,
+        qualifiers,
+    })
+}
+
+fn components_function<'s>(
+    config: &Context,
+    sym: &'s str,
+) -> Result<Components<'s>, DemangleError<'s>> {
+    let Some((name, the_rest, c)) = sym.c_split2_r_starts_with("__", |c| {
+        matches!(c, 'F' | '1'..='9' | 'C' | 'V' | 'u' | 't' | 'H' | 'Q')
+    }) else {
+        return Err(DemangleError::NotMangled);
+    };
+
+    let (scope, qualifiers) = match c {
+        'F' => (Vec::new(), CvQualifiers::default()),
+        'H' => return Err(DemangleError::UnsupportedSymbolKindOnComponents(sym)),
+        _ => {
+            let Remaining { r, d: qualifiers } = CvQualifiers::parse(the_rest);
+            let scope = components_scope(config, r, !qualifiers.is_empty())?.1;
+            (scope, qualifiers)
+        }
+    };
+
+    Ok(Components {
+        scope,
+        leaf: Cow::from(name),
+        leaf_kind: LeafKind::Function,
+        qualifiers,
+    })
+}
+
+/// `is_class` tells whether the symbol is known to be a method, so its
+/// innermost scope is a class.
+fn components_scope<'s>(
+    config: &Context,
+    s: &'s str,
+    is_class: bool,
+) -> Result<(&'s str, Vec<ScopeSegment<'s>>), DemangleError<'s>> {
+    let mut scope = Vec::new();
+
+    let remaining = if let Some(q_less) = s.strip_prefix('Q') {
+        let Remaining { r, d: count } = demangle_namespace_count(config, q_less)?;
+        let mut remaining = r;
+        // Same as `demangle_namespaces`, later segments may reference the
+        // template arguments of the previous ones.
+        let mut outer_template_args = ArgVec::new(config, None);
+        let mut after_digit = false;
+
+        for _ in 0..count.get() {
+            let (r, segment, ends_with_digit) = components_segment(
+                config,
+                skip_digit_separator(remaining, after_digit),
+                &mut outer_template_args,
+            )?;
+            scope.push(segment);
+            remaining = r;
+            after_digit = ends_with_digit;
+        }
+        remaining
+    } else {
+        let (r, segment, _) = components_segment(config, s, &mut ArgVec::new(config, None))?;
+        scope.push(segment);
+        r
+    };
+
+    if let Some(last) = scope.last_mut() {
+        if last.kind == ScopeKind::Namespace {
+            last.kind = if is_class || !config.annotate_ambiguous_scope {
+                ScopeKind::Class
+            } else {
+                ScopeKind::Ambiguous
+            };
+        }
+    }
+
+    Ok((remaining, scope))
+}
+
+/// Also tells whether the segment ends in a digit, see
+/// `skip_digit_separator`.
+fn components_segment<'s>(
+    config: &Context,
+    s: &'s str,
+    outer_template_args: &mut ArgVec,
+) -> Result<(&'s str, ScopeSegment<'s>, bool), DemangleError<'s>> {
+    if let Some(templated) = s.strip_prefix('t') {
+        let Remaining { r: args, d: name } = demangle_custom_name(
+            config,
+            templated,
+            DemangleError::InvalidCustomNameOnTemplate,
+        )?;
+        let (r, _template, _typ, types) =
+            demangle_template_and_types(config, templated, outer_template_args, true)?;
+        outer_template_args.extend_resolved(&types);
+
+        // Skip the template count
+        let Some(Remaining { r: args, d: _count }) = args.p_digit() else {
+            return Err(DemangleError::InvalidTemplateCount(args));
+        };
+        let template_args = &args[..args.len() - r.len()];
+
+        Ok((
+            r,
+            ScopeSegment {
+                name,
+                kind: ScopeKind::TemplateInstance,
+                template_args: Some(template_args),
+            },
+            types.ends_with_digit,
+        ))
+    } else {
+        let Remaining { r, d: name } =
+            demangle_custom_name(config, s, DemangleError::InvalidCustomNameOnNamespace)?;
+
+        Ok((
+            r,
+            ScopeSegment {
+                name,
+                kind: ScopeKind::Namespace,
+                template_args: None,
+            },
+            name.ends_with(|c: char| c.is_ascii_digit()),
+        ))
+    }
+}
@@ -0,0 +1,343 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+//! Utilities for checking the demangler against lists of symbols.
+//!
+//! A corpus is a plain text file with a mangled symbol per line. Demangling
+//! it produces a [`CorpusReport`], which can be written to a file and parsed
+//! back later to see what changed between two runs.
+//!
+//! The `run_corpus` example wraps this module into a command line tool.
+
+use core::fmt;
+
+use alloc::{
+    collections::{btree_map::Entry, BTreeMap},
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::{demangle, DemangleConfig, DemangleError};
+
+const NOT_MANGLED: &str = DemangleError::NotMangled.kind_name();
+
+/// The [`DemangleConfig`] presets checked by a [`CorpusReport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CorpusPreset {
+    /// [`DemangleConfig::new_cfilt`].
+    Cfilt,
+    /// [`DemangleConfig::new_g2dem`].
+    G2dem,
+}
+
+impl CorpusPreset {
+    /// Every preset, in the order they are reported.
+    pub const ALL: [Self; 2] = [Self::Cfilt, Self::G2dem];
+
+    /// The name of the preset, as used in reports.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Cfilt => "cfilt",
+            Self::G2dem => "g2dem",
+        }
+    }
+
+    /// Parse the name returned by [`CorpusPreset::name`].
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|x| x.name() == name)
+    }
+
+    /// The config of this preset.
+    #[must_use]
+    pub const fn config(self) -> DemangleConfig {
+        match self {
+            Self::Cfilt => DemangleConfig::new_cfilt(),
+            Self::G2dem => DemangleConfig::new_g2dem(),
+        }
+    }
+}
+
+/// What happened when demangling a single symbol.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CorpusOutcome {
+    /// The demangled symbol.
+    Demangled(String),
+    /// The symbol failed to demangle, with the
+    /// [`kind_name`](crate::DemangleError::kind_name) of the error.
+    Failed(String),
+}
+
+impl CorpusOutcome {
+    fn parse(s: &str) -> Option<Self> {
+        if let Some(demangled) = s.strip_prefix("ok:") {
+            Some(Self::Demangled(demangled.to_string()))
+        } else {
+            s.strip_prefix("err:")
+                .map(|kind| Self::Failed(kind.to_string()))
+        }
+    }
+}
+
+impl fmt::Display for CorpusOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Demangled(demangled) => write!(f, "ok:{demangled}"),
+            Self::Failed(kind) => write!(f, "err:{kind}"),
+        }
+    }
+}
+
+/// A symbol of a corpus, demangled with every [`CorpusPreset`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CorpusEntry {
+    /// The mangled symbol.
+    pub symbol: String,
+    /// The outcome with [`CorpusPreset::Cfilt`].
+    pub cfilt: CorpusOutcome,
+    /// The outcome with [`CorpusPreset::G2dem`].
+    pub g2dem: CorpusOutcome,
+}
+
+impl CorpusEntry {
+    /// The outcome with the given preset.
+    #[must_use]
+    pub const fn outcome(&self, preset: CorpusPreset) -> &CorpusOutcome {
+        match preset {
+            CorpusPreset::Cfilt => &self.cfilt,
+            CorpusPreset::G2dem => &self.g2dem,
+        }
+    }
+}
+
+/// The counts of a [`CorpusReport`] for a single preset.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CorpusStats {
+    /// How many symbols were demangled.
+    pub total: usize,
+    /// How many of them are not mangled at all, like the symbols of C
+    /// functions. Those are not counted as failures.
+    pub not_mangled: usize,
+    /// How many of the mangled symbols failed.
+    pub failed: usize,
+    /// How many failed with each kind of error.
+    pub failures_by_kind: BTreeMap<String, usize>,
+}
+
+impl CorpusStats {
+    /// The percentage of mangled symbols that failed, from 0 to 100.
+    #[must_use]
+    pub fn failure_rate(&self) -> f64 {
+        let mangled = self.total - self.not_mangled;
+        if mangled == 0 {
+            0.0
+        } else {
+            self.failed as f64 * 100.0 / mangled as f64
+        }
+    }
+}
+
+/// A symbol whose outcome changed between two reports.
+///
+/// See [`CorpusReport::diff`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CorpusChange<'r> {
+    /// The mangled symbol.
+    pub symbol: &'r str,
+    /// The preset whose outcome changed.
+    pub preset: CorpusPreset,
+    /// The outcome on the older report.
+    pub previous: &'r CorpusOutcome,
+    /// The outcome on the newer report.
+    pub current: &'r CorpusOutcome,
+}
+
+impl CorpusChange<'_> {
+    /// Whether the symbol used to demangle but now it fails.
+    #[must_use]
+    pub const fn is_regression(&self) -> bool {
+        matches!(
+            (self.previous, self.current),
+            (CorpusOutcome::Demangled(_), CorpusOutcome::Failed(_))
+        )
+    }
+}
+
+impl fmt::Display for CorpusChange<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} {}", self.preset.name(), self.symbol)?;
+        writeln!(f, "  - {}", self.previous)?;
+        write!(f, "  + {}", self.current)
+    }
+}
+
+/// The outcome of demangling every symbol of a corpus.
+///
+/// The [`Display`](fmt::Display) implementation writes the report in a line
+/// oriented format that can be read back with [`CorpusReport::parse`].
+///
+/// # Examples
+///
+/// ```
+/// use gnuv2_demangle::corpus::{CorpusPreset, CorpusReport};
+///
+/// let report = CorpusReport::demangle_lines("SetText__5tNamePCc\nfoo__FiN31\nmain\n");
+/// let stats = report.stats(CorpusPreset::G2dem);
+/// assert_eq!(stats.total, 3);
+/// assert_eq!(stats.not_mangled, 1);
+/// assert_eq!(stats.failed, 1);
+/// assert_eq!(stats.failures_by_kind["InvalidRepeatingArgument"], 1);
+///
+/// let parsed = CorpusReport::parse(&report.to_string());
+/// assert_eq!(parsed, Ok(report));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CorpusReport {
+    /// Every symbol, in the order of the corpus.
+    pub entries: Vec<CorpusEntry>,
+}
+
+impl CorpusReport {
+    /// Demangle every line of `contents`. Empty lines are skipped.
+    #[must_use]
+    pub fn demangle_lines(contents: &str) -> Self {
+        let cfilt = CorpusPreset::Cfilt.config();
+        let g2dem = CorpusPreset::G2dem.config();
+        let outcome = |sym, config| match demangle(sym, config) {
+            Ok(demangled) => CorpusOutcome::Demangled(demangled),
+            Err(err) => CorpusOutcome::Failed(err.kind_name().to_string()),
+        };
+
+        let entries = contents
+            .lines()
+            .map(|line| line.trim_end_matches('\r'))
+            .filter(|line| !line.is_empty())
+            .map(|symbol| CorpusEntry {
+                symbol: symbol.to_string(),
+                cfilt: outcome(symbol, &cfilt),
+                g2dem: outcome(symbol, &g2dem),
+            })
+            .collect();
+
+        Self { entries }
+    }
+
+    /// Read back a report written by the [`Display`](fmt::Display)
+    /// implementation.
+    ///
+    /// Returns the 1-based number of the first malformed line on failure.
+    pub fn parse(report: &str) -> Result<Self, usize> {
+        let mut entries = Vec::new();
+
+        for (i, line) in report.lines().enumerate() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut columns = line.split('\t');
+            let (Some(symbol), Some(cfilt), Some(g2dem), None) = (
+                columns.next(),
+                columns.next().and_then(CorpusOutcome::parse),
+                columns.next().and_then(CorpusOutcome::parse),
+                columns.next(),
+            ) else {
+                return Err(i + 1);
+            };
+
+            entries.push(CorpusEntry {
+                symbol: symbol.to_string(),
+                cfilt,
+                g2dem,
+            });
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Count the failures of a preset.
+    #[must_use]
+    pub fn stats(&self, preset: CorpusPreset) -> CorpusStats {
+        let mut stats = CorpusStats {
+            total: self.entries.len(),
+            ..CorpusStats::default()
+        };
+
+        for entry in &self.entries {
+            match entry.outcome(preset) {
+                CorpusOutcome::Demangled(_) => {}
+                CorpusOutcome::Failed(kind) if kind == NOT_MANGLED => stats.not_mangled += 1,
+                CorpusOutcome::Failed(kind) => {
+                    stats.failed += 1;
+                    *stats.failures_by_kind.entry(kind.clone()).or_default() += 1;
+                }
+            }
+        }
+
+        stats
+    }
+
+    /// List the symbols whose outcome changed since `previous`, for every
+    /// preset.
+    ///
+    /// Symbols missing from either report are ignored. If a symbol is
+    /// repeated then only its first appearance is compared.
+    #[must_use]
+    pub fn diff<'r>(&'r self, previous: &'r Self) -> Vec<CorpusChange<'r>> {
+        let mut old = BTreeMap::new();
+        for entry in &previous.entries {
+            if let Entry::Vacant(vacant) = old.entry(entry.symbol.as_str()) {
+                vacant.insert(entry);
+            }
+        }
+
+        let mut changes = Vec::new();
+        for entry in &self.entries {
+            let Some(old_entry) = old.remove(entry.symbol.as_str()) else {
+                continue;
+            };
+
+            for preset in CorpusPreset::ALL {
+                let previous = old_entry.outcome(preset);
+                let current = entry.outcome(preset);
+                if previous != current {
+                    changes.push(CorpusChange {
+                        symbol: &entry.symbol,
+                        preset,
+                        previous,
+                        current,
+                    });
+                }
+            }
+        }
+
+        changes
+    }
+}
+
+impl fmt::Display for CorpusReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# gnuv2_demangle {} corpus report", crate::VERSION)?;
+        for preset in CorpusPreset::ALL {
+            let stats = self.stats(preset);
+            writeln!(
+                f,
+                "# {}: {} symbols, {} not mangled, {} failed ({:.2}%)",
+                preset.name(),
+                stats.total,
+                stats.not_mangled,
+                stats.failed,
+                stats.failure_rate()
+            )?;
+            for (kind, count) in &stats.failures_by_kind {
+                writeln!(f, "#   {kind}: {count}")?;
+            }
+        }
+
+        for entry in &self.entries {
+            writeln!(f, "{}\t{}\t{}", entry.symbol, entry.cfilt, entry.g2dem)?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,11 +1,17 @@
 /* SPDX-FileCopyrightText: © 2025 Decompollaborate */
 /* SPDX-License-Identifier: MIT OR Apache-2.0 */
 
-use crate::DemangleError;
+use alloc::string::String;
+
+use crate::{
+    demangle_info::{Construct, Context},
+    DemangleError,
+};
 
 use crate::remainer::{Remaining, StrParsing};
 
 pub(crate) fn demangle_custom_name<'s, F>(
+    config: &Context,
     s: &'s str,
     err: F,
 ) -> Result<Remaining<'s, &'s str>, DemangleError<'s>>
@@ -17,14 +23,184 @@ where
     if r.len() < length {
         Err(err(s))
     } else {
-        Ok(Remaining::split_at(r, length))
+        split_custom_name(config, s, r, length)
+    }
+}
+
+/// Same as [`demangle_custom_name`], but a name running past the end of `s`
+/// is reported as [`DemangleError::LikelyTruncated`].
+///
+/// Only meant for places where `s` is the rest of the symbol, since running
+/// out of it there means the symbol was cut short, usually by the linker or
+/// the object format.
+pub(crate) fn demangle_trailing_custom_name<'s, F>(
+    config: &Context,
+    s: &'s str,
+    err: F,
+) -> Result<Remaining<'s, &'s str>, DemangleError<'s>>
+where
+    F: Fn(&'s str) -> DemangleError<'s>,
+{
+    let Remaining { r, d: length } = s.p_number().ok_or_else(|| err(s))?;
+
+    if r.len() < length {
+        Err(DemangleError::LikelyTruncated(s, length - r.len()))
+    } else {
+        split_custom_name(config, s, r, length)
     }
 }
 
-pub(crate) fn demangle_method_qualifier(s: &str) -> Remaining<'_, &str> {
-    if let Some(remaining) = s.strip_prefix('C') {
-        Remaining::new(remaining, " const")
+/// Take the name of `length` characters at the start of `r`, which comes
+/// right after the length in `s`.
+///
+/// Some old compilers emit unnamed structs as names of length zero, like the
+/// `0` of `Get__Q23foo0`, which are only accepted by
+/// [`DemangleConfig::lenient`](crate::DemangleConfig::lenient).
+fn split_custom_name<'s>(
+    config: &Context,
+    s: &'s str,
+    r: &'s str,
+    length: usize,
+) -> Result<Remaining<'s, &'s str>, DemangleError<'s>> {
+    if length != 0 {
+        return Ok(Remaining::split_at(r, length));
+    }
+
+    config.saw(Construct::Malformed);
+    if config.lenient {
+        Ok(Remaining::new(r, "<unnamed>"))
     } else {
-        Remaining::new(s, "")
+        Err(DemangleError::EmptyCustomName(s))
+    }
+}
+
+/// A run of `C` (`const`), `V` (`volatile`) and `u` (`__restrict`)
+/// qualifiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub(crate) struct CvQualifiers {
+    pub(crate) is_const: bool,
+    pub(crate) is_volatile: bool,
+    pub(crate) is_restrict: bool,
+}
+
+impl CvQualifiers {
+    pub(crate) fn parse(s: &str) -> Remaining<'_, Self> {
+        let mut cv = Self::default();
+        let mut remaining = s;
+
+        while let Some(Remaining { r, d: c }) = remaining.p_first() {
+            match c {
+                'C' => cv.is_const = true,
+                'V' => cv.is_volatile = true,
+                'u' => cv.is_restrict = true,
+                _ => break,
+            }
+            remaining = r;
+        }
+
+        Remaining::new(remaining, cv)
+    }
+
+    pub(crate) fn is_empty(self) -> bool {
+        self == Self::default()
     }
+
+    /// The qualifiers as words, always in `const volatile __restrict` order
+    /// regardless of the mangled order.
+    pub(crate) fn words(self, config: &Context) -> impl Iterator<Item = &'static str> {
+        if self.is_restrict {
+            config.saw(Construct::Restrict);
+        }
+        let restrict = if config.emit_plain_restrict {
+            "restrict"
+        } else {
+            "__restrict"
+        };
+
+        [
+            (self.is_const, "const"),
+            (self.is_volatile, "volatile"),
+            (self.is_restrict, restrict),
+        ]
+        .into_iter()
+        .filter_map(|(present, word)| present.then_some(word))
+    }
+}
+
+/// Parse the cv-qualifiers of a method, like `C` for `const` methods.
+pub(crate) fn demangle_method_qualifier<'s>(config: &Context, s: &'s str) -> Remaining<'s, String> {
+    let Remaining { r, d: cv } = CvQualifiers::parse(s);
+
+    Remaining::new(r, cv.words(config).map(|x| format!(" {x}")).collect())
+}
+
+/// Map the mangled name of an overloaded operator to its C++ spelling.
+///
+/// Cast operators (`op<type>`) are not handled here.
+pub(crate) fn demangle_operator_name(op: &str) -> Option<&'static str> {
+    let name = match op {
+        // Memory
+        "nw" => "operator new",
+        "dl" => "operator delete",
+        "vn" => "operator new []",
+        "vd" => "operator delete []",
+
+        // Comparison
+        "eq" => "operator==",
+        "ne" => "operator!=",
+        "lt" => "operator<",
+        "gt" => "operator>",
+        "le" => "operator<=",
+        "ge" => "operator>=",
+
+        // Assignment
+        "as" => "operator=",
+        "apl" => "operator+=",
+        "ami" => "operator-=",
+        "aml" => "operator*=",
+        "adv" => "operator/=",
+        "amd" => "operator%=",
+        "aer" => "operator^=",
+        "aad" => "operator&=",
+        "aor" => "operator|=",
+        "als" => "operator<<=",
+        "ars" => "operator>>=",
+
+        // Bitwise
+        "er" => "operator^",
+        "ad" => "operator&",
+        "or" => "operator|",
+        "ls" => "operator<<",
+        "rs" => "operator>>",
+        "co" => "operator~",
+
+        // Increment/Decrement
+        "pp" => "operator++",
+        "mm" => "operator--",
+
+        // Logical
+        "aa" => "operator&&",
+        "oo" => "operator||",
+        "nt" => "operator!",
+
+        // Member access
+        "vc" => "operator[]",
+        "rf" => "operator->",
+        "rm" => "operator->*",
+
+        // Arithmetic
+        "pl" => "operator+",
+        "mi" => "operator-",
+        "ml" => "operator*",
+        "dv" => "operator/",
+        "md" => "operator%",
+
+        // Other
+        "cl" => "operator()",
+        "cm" => "operator, ",
+
+        _ => return None,
+    };
+
+    Some(name)
 }
@@ -1,7 +1,7 @@
 /* SPDX-FileCopyrightText: © 2025 Decompollaborate */
 /* SPDX-License-Identifier: MIT OR Apache-2.0 */
 
-use core::fmt;
+use core::fmt::{self, Write as _};
 use core::num::NonZeroUsize;
 
 use alloc::{
@@ -9,15 +9,25 @@ use alloc::{
     string::{String, ToString},
 };
 
+use crate::{
+    demangle_info::{Construct, Context},
+    scratch::ScratchString,
+    spans::SpanKind,
+    DemangleError, TypePointerSpacing,
+};
 use crate::{option_display::OptionDisplay, str_cutter::StrCutter};
-use crate::{DemangleConfig, DemangleError};
 
 use crate::{
-    dem::demangle_custom_name,
-    dem_arg_list::{demangle_argument_list_impl, ArgVec},
+    dem::{
+        demangle_custom_name, demangle_method_qualifier, demangle_trailing_custom_name,
+        CvQualifiers,
+    },
+    dem_arg_list::{demangle_argument_list_into, ArgVec},
     dem_namespace::demangle_namespaces,
-    dem_template::demangle_template,
+    dem_template::{demangle_template, template_arg_at_depth},
+    markup::{CutMarks, Token},
     remainer::{Remaining, StrParsing},
+    spacing::{needs_space, needs_space_in_declarator, push_spaced, respace_qualifiers},
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -29,12 +39,34 @@ pub(crate) enum DemangledArg {
     Ellipsis,
 }
 
+impl DemangledArg {
+    /// The type as it is shown on its own, like a return type.
+    ///
+    /// Repeats are left empty, since they need the list they refer to.
+    pub(crate) fn type_string(&self, spacing: Option<TypePointerSpacing>) -> String {
+        match self {
+            Self::Plain(plain, array_qualifiers) => match array_qualifiers.as_option() {
+                Some(arr) => arr.qualify(plain, ArrayPosition::Argument, spacing),
+                None => plain.clone(),
+            },
+            Self::FunctionPointer(function_pointer) => function_pointer.to_string(),
+            Self::MethodPointer(method_pointer) => method_pointer.to_string(),
+            Self::Repeat { .. } => String::new(),
+            Self::Ellipsis => String::from("..."),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub(crate) struct FunctionPointer {
     pub(crate) return_type: String,
     pub(crate) array_qualifiers: OptionDisplay<ArrayQualifiers>,
     pub(crate) post_qualifiers: String,
     pub(crate) args: String,
+    pub(crate) spacing: Option<TypePointerSpacing>,
+    /// See `DemangleConfig::argument_list_parens`.
+    pub(crate) parens: bool,
+    pub(crate) cuts: CutMarks,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -44,7 +76,14 @@ pub(crate) struct MethodPointer {
     pub(crate) class: String, // TODO: `&'s str` instead? should be easy, i think...
     pub(crate) post_qualifiers: String,
     pub(crate) args: String,
-    pub(crate) is_const_method: bool,
+    /// The cv-qualifiers of the method, like ` const`.
+    pub(crate) method_qualifiers: String,
+    pub(crate) spacing: Option<TypePointerSpacing>,
+    /// See `DemangleConfig::argument_list_parens`.
+    pub(crate) parens: bool,
+    /// See `DemangleConfig::scope_separator`.
+    pub(crate) scope_separator: &'static str,
+    pub(crate) cuts: CutMarks,
 }
 
 impl fmt::Display for FunctionPointer {
@@ -54,6 +93,9 @@ impl fmt::Display for FunctionPointer {
             array_qualifiers,
             post_qualifiers,
             args,
+            spacing,
+            parens,
+            cuts,
         } = self;
 
         let array_qualifiers = array_qualifiers.as_option();
@@ -70,17 +112,18 @@ impl fmt::Display for FunctionPointer {
             write!(f, " ")?;
             wrote_space = true;
             if !arr.inner_post_qualifiers.is_empty() {
-                write!(f, "({}", arr.inner_post_qualifiers)?;
+                write!(f, "{}({}", cuts.open(), arr.inner_post_qualifiers)?;
             }
         }
-        if !return_type.ends_with(['*', '&']) && !wrote_space {
+        if !wrote_space && needs_space_in_declarator(*spacing, return_type, "(") {
             write!(f, " ")?;
         }
-        write!(f, "({})", post_qualifiers.trim_matches(' '))?;
-        write!(f, "({args})")?;
+        let (open, close) = (cuts.open(), cuts.close());
+        write!(f, "{open}({}{close})", post_qualifiers.trim_matches(' '))?;
+        write!(f, "{}", ParameterList::new(args, *parens, *cuts))?;
         if let Some(arr) = array_qualifiers {
             if !arr.inner_post_qualifiers.is_empty() {
-                write!(f, ")")?;
+                write!(f, "{})", cuts.close())?;
             }
             write!(f, "{}", arr.arrays)?;
         }
@@ -96,7 +139,11 @@ impl fmt::Display for MethodPointer {
             class,
             post_qualifiers,
             args,
-            is_const_method,
+            method_qualifiers,
+            spacing,
+            parens,
+            scope_separator,
+            cuts,
         } = self;
 
         let array_qualifiers = array_qualifiers.as_option();
@@ -113,20 +160,23 @@ impl fmt::Display for MethodPointer {
             write!(f, " ")?;
             wrote_space = true;
             if !arr.inner_post_qualifiers.is_empty() {
-                write!(f, "({}", arr.inner_post_qualifiers)?;
+                write!(f, "{}({}", cuts.open(), arr.inner_post_qualifiers)?;
             }
         }
-        if !return_type.ends_with(['*', '&']) && !wrote_space {
+        if !wrote_space && needs_space_in_declarator(*spacing, return_type, "(") {
             write!(f, " ")?;
         }
-        write!(f, "({}::{})", class, post_qualifiers.trim_matches(' '))?;
-        write!(f, "({args})")?;
-        if *is_const_method {
-            write!(f, " const")?;
-        }
+        let (open, close) = (cuts.open(), cuts.close());
+        write!(
+            f,
+            "{open}({class}{scope_separator}{}{close})",
+            post_qualifiers.trim_matches(' ')
+        )?;
+        write!(f, "{}", ParameterList::new(args, *parens, *cuts))?;
+        write!(f, "{method_qualifiers}")?;
         if let Some(arr) = array_qualifiers {
             if !arr.inner_post_qualifiers.is_empty() {
-                write!(f, ")")?;
+                write!(f, "{})", cuts.close())?;
             }
             write!(f, "{}", arr.arrays)?;
         }
@@ -134,6 +184,35 @@ impl fmt::Display for MethodPointer {
     }
 }
 
+/// The parameters of a function, as they go after its name or declarator, see
+/// `DemangleConfig::argument_list_parens`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct ParameterList<'a> {
+    args: &'a str,
+    parens: bool,
+    cuts: CutMarks,
+}
+
+impl<'a> ParameterList<'a> {
+    pub(crate) fn new(args: &'a str, parens: bool, cuts: CutMarks) -> Self {
+        Self { args, parens, cuts }
+    }
+}
+
+impl fmt::Display for ParameterList<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ParameterList { args, parens, cuts } = self;
+
+        if *parens {
+            write!(f, "{}({args}{})", cuts.open(), cuts.close())
+        } else if !args.is_empty() {
+            write!(f, " {args}")
+        } else {
+            Ok(())
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 enum Signedness {
     No,
@@ -141,13 +220,15 @@ enum Signedness {
     Unsigned,
 }
 
-impl fmt::Display for Signedness {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::No => Ok(()),
-            Self::Signed => write!(f, "signed "),
-            Self::Unsigned => write!(f, "unsigned "),
-        }
+impl Signedness {
+    /// The word going before the type, along with its space.
+    fn prefix(self, config: &Context) -> String {
+        let word = match self {
+            Self::No => return String::new(),
+            Self::Signed => "signed",
+            Self::Unsigned => "unsigned",
+        };
+        format!("{} ", config.tokens().mark(Token::Type, word))
     }
 }
 
@@ -158,113 +239,240 @@ pub(crate) struct ArrayQualifiers {
     pub(crate) arrays: String,
 }
 
-impl fmt::Display for ArrayQualifiers {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, " ")?;
+/// Where a type with [`ArrayQualifiers`] is written, see
+/// [`ArrayQualifiers::qualify`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum ArrayPosition<'a> {
+    /// An argument, or any other type on its own, like `int (*)[4]` or the
+    /// one of `float [3] type_info node`.
+    Argument,
+    /// The type of a cast operator, like the `int (*)[4]` of
+    /// `operator int (*)[4]`. Written the same as an argument.
+    CastTarget,
+    /// The return type of the function `name`, which goes inside of the
+    /// array declarator, like `int (*f<int>(int))[4]`. The declarator gets
+    /// the given cut marks, since `name` may be cut.
+    Return(&'a str, CutMarks),
+}
 
-        if !self.inner_post_qualifiers.is_empty() {
-            // Only add parenthesis if there are post_qualifiers, like a
-            // pointer.
-            // Arrays without being decaying to pointers can happen in, for
-            // example, templated functions.
-            write!(f, "({})", self.inner_post_qualifiers)?;
+impl ArrayQualifiers {
+    /// Write `typ` qualified by these array qualifiers, as it goes in
+    /// `position`.
+    pub(crate) fn qualify(
+        &self,
+        typ: &str,
+        position: ArrayPosition<'_>,
+        spacing: Option<TypePointerSpacing>,
+    ) -> String {
+        let ArrayQualifiers {
+            inner_post_qualifiers,
+            arrays,
+        } = self;
+        let mut out = String::from(typ);
+
+        match position {
+            ArrayPosition::Argument | ArrayPosition::CastTarget => {
+                // Only add parenthesis if there are post_qualifiers, like a
+                // pointer.
+                // Arrays without being decaying to pointers can happen in, for
+                // example, templated functions.
+                if inner_post_qualifiers.is_empty() {
+                    push_spaced(&mut out, spacing, arrays);
+                } else {
+                    push_spaced(
+                        &mut out,
+                        spacing,
+                        &format!("({inner_post_qualifiers}){arrays}"),
+                    );
+                }
+            }
+            ArrayPosition::Return(name, cuts) => {
+                push_spaced(&mut out, spacing, "(");
+                // The mark goes right before the `(`, after its space.
+                out.insert_str(out.len() - 1, cuts.open());
+                out.push_str(inner_post_qualifiers);
+                out.push_str(name);
+                out.push_str(cuts.close());
+                out.push(')');
+                out.push_str(arrays);
+            }
         }
 
-        write!(f, "{}", self.arrays)
+        out
     }
 }
 
 pub(crate) fn demangle_argument<'s>(
-    config: &DemangleConfig,
+    config: &Context,
     full_args: &'s str,
     parsed_arguments: &ArgVec,
     template_args: &ArgVec,
     allow_array_fixup: bool,
 ) -> Result<(&'s str, DemangledArg), DemangleError<'s>> {
-    if let Some(demangled) = demangle_qualifierless_arg(config, full_args)? {
-        return Ok(demangled);
-    }
-
-    let Remaining {
-        r: args,
-        d: (sign, post_qualifiers),
-    } = demangle_arg_qualifiers(full_args)?;
-    let Remaining {
-        r: args,
-        d: (sign, post_qualifiers, array_qualifiers),
-    } = demangle_array_pseudo_qualifier(config, args, sign, post_qualifiers, allow_array_fixup)?;
+    config.trace("argument", full_args, || {
+        if let Some(demangled) = demangle_qualifierless_arg(config, full_args)? {
+            return Ok(demangled);
+        }
 
-    if let Some(s) = args.strip_prefix('F') {
-        let (r, fp) = demangle_function_pointer_arg(
-            config,
-            s,
-            template_args,
-            sign,
-            post_qualifiers,
-            array_qualifiers,
-            allow_array_fixup,
-        )?;
-        Ok((r, DemangledArg::FunctionPointer(fp)))
-    } else if let Some(r) = args.strip_prefix('M') {
-        let (r, mp) = demangle_method_pointer_arg(
-            config,
-            r,
-            full_args,
-            template_args,
-            sign,
-            post_qualifiers,
-            array_qualifiers,
-            allow_array_fixup,
-        )?;
-        Ok((r, DemangledArg::MethodPointer(mp)))
-    } else if let Some(r) = args.strip_prefix('O') {
-        let (r, mp) = demangle_object_pointer_arg(
+        let Remaining {
+            r: args,
+            d: (sign, post_qualifiers),
+        } = demangle_arg_qualifiers(config, full_args)?;
+        let Remaining {
+            r: args,
+            d: (sign, post_qualifiers, array_qualifiers),
+        } = demangle_array_pseudo_qualifier(
             config,
-            r,
-            full_args,
-            template_args,
+            args,
             sign,
             post_qualifiers,
-            array_qualifiers,
             allow_array_fixup,
         )?;
-        Ok((r, DemangledArg::Plain(mp, None.into())))
-    } else {
-        // 'G' is used for classes, structs and unions, so we must make sure we
-        // don't parse a primitive type next, otherwise this is not properly
-        // mangled.
-        let (args, must_be_class_like) = args.c_maybe_strip_prefix('G');
 
-        let Remaining {
-            r,
-            d: (is_class_like, typ, sign),
-        } = demangle_arg_type(
+        if let Some(s) = args.strip_prefix('F') {
+            let (r, fp) = demangle_function_pointer_arg(
+                config,
+                s,
+                parsed_arguments,
+                template_args,
+                sign,
+                post_qualifiers,
+                array_qualifiers,
+                allow_array_fixup,
+            )?;
+            Ok((r, DemangledArg::FunctionPointer(fp)))
+        } else if let Some(r) = args.strip_prefix('M') {
+            let (r, mp) = demangle_method_pointer_arg(
+                config,
+                r,
+                full_args,
+                parsed_arguments,
+                template_args,
+                sign,
+                post_qualifiers,
+                array_qualifiers,
+                allow_array_fixup,
+            )?;
+            Ok((r, DemangledArg::MethodPointer(mp)))
+        } else if let Some(r) = args.strip_prefix('O') {
+            let (r, mp) = demangle_object_pointer_arg(
+                config,
+                r,
+                full_args,
+                template_args,
+                sign,
+                post_qualifiers,
+                array_qualifiers,
+                allow_array_fixup,
+            )?;
+            Ok((r, DemangledArg::Plain(mp, None.into())))
+        } else if let Some(lookback) = demangle_pointer_template_lookback(
             config,
             args,
-            sign,
-            parsed_arguments,
             template_args,
-            allow_array_fixup,
-        )?;
+            sign,
+            &post_qualifiers,
+            &array_qualifiers,
+        )? {
+            Ok(lookback)
+        } else {
+            // 'G' is used for classes, structs and unions, so we must make sure we
+            // don't parse a primitive type next, otherwise this is not properly
+            // mangled.
+            let (args, must_be_class_like) = args.c_maybe_strip_prefix('G');
 
-        if must_be_class_like && !is_class_like {
-            return Err(DemangleError::PrimitiveInsteadOfClass(full_args));
+            let Remaining {
+                r,
+                d: (is_class_like, typ, sign),
+            } = demangle_arg_type(
+                config,
+                args,
+                sign,
+                parsed_arguments,
+                template_args,
+                allow_array_fixup,
+            )?;
+
+            if must_be_class_like && !is_class_like {
+                return Err(DemangleError::PrimitiveInsteadOfClass(full_args));
+            }
+
+            let mut out = format!("{}{typ}", sign.prefix(config));
+            push_post_qualifiers(config, &mut out, &post_qualifiers);
+
+            Ok((r, DemangledArg::Plain(out, array_qualifiers)))
         }
+    })
+}
 
-        let out = format!(
-            "{}{}{}{}",
-            sign,
-            typ,
-            if !post_qualifiers.is_empty() { " " } else { "" },
-            post_qualifiers.trim_matches(' ')
-        );
+/// Demangle a `const` argument which is neither an array nor any kind of
+/// pointer to function or member, along with the same argument without that
+/// `const`.
+///
+/// For example, both `Foo *const` and `Foo *` for `CP3Foo`.
+///
+/// Returns `None` for anything else, which [`demangle_argument`] must handle.
+pub(crate) fn demangle_const_argument<'s>(
+    config: &Context,
+    full_args: &'s str,
+    parsed_arguments: &ArgVec,
+    template_args: &ArgVec,
+    allow_array_fixup: bool,
+) -> Result<Option<Remaining<'s, (String, String)>>, DemangleError<'s>> {
+    let Some(unqualified_args) = full_args.strip_prefix('C') else {
+        return Ok(None);
+    };
 
-        Ok((r, DemangledArg::Plain(out, array_qualifiers)))
+    let Remaining {
+        r: args,
+        d: (sign, post_qualifiers),
+    } = demangle_arg_qualifiers(config, full_args)?;
+    let Remaining {
+        d: (_, unqualified_post_qualifiers),
+        ..
+    } = demangle_arg_qualifiers(config, unqualified_args)?;
+
+    if args.starts_with(['A', 'F', 'M', 'O', 'X']) {
+        return Ok(None);
     }
+
+    let (args, must_be_class_like) = args.c_maybe_strip_prefix('G');
+    let Remaining {
+        r,
+        d: (is_class_like, typ, sign),
+    } = demangle_arg_type(
+        config,
+        args,
+        sign,
+        parsed_arguments,
+        template_args,
+        allow_array_fixup,
+    )?;
+
+    if must_be_class_like && !is_class_like {
+        return Err(DemangleError::PrimitiveInsteadOfClass(full_args));
+    }
+
+    let mut out = format!("{}{typ}", sign.prefix(config));
+    let mut unqualified = out.clone();
+    push_post_qualifiers(config, &mut out, &post_qualifiers);
+    push_post_qualifiers(config, &mut unqualified, &unqualified_post_qualifiers);
+
+    Ok(Some(Remaining::new(r, (out, unqualified))))
+}
+
+/// Write the qualifiers made by [`demangle_arg_qualifiers`] after the type
+/// they apply to, like the `*const` of `char *const`.
+pub(crate) fn push_post_qualifiers(config: &Context, out: &mut String, post_qualifiers: &str) {
+    if post_qualifiers.contains(['*', '&']) {
+        config.saw(Construct::Declarator);
+    }
+    let spacing = config.type_pointer_spacing;
+    push_spaced(out, spacing, &respace_qualifiers(spacing, post_qualifiers));
 }
 
 fn demangle_arg_type<'s, 'pa, 't, 'out>(
-    config: &DemangleConfig,
+    config: &Context,
     args: &'s str,
     mut sign: Signedness,
     parsed_arguments: &'pa ArgVec,
@@ -281,54 +489,60 @@ where
         .next()
         .ok_or(DemangleError::RanOutOfArguments)?;
 
+    let builtin = |typ| config.tokens().marked(Token::Type, typ);
     let (args, is_class_like, typ) = match c {
-        'c' => (&args[1..], false, Cow::from("char")),
-        's' => (&args[1..], false, Cow::from("short")),
-        'i' => (&args[1..], false, Cow::from("int")),
-        'l' => (&args[1..], false, Cow::from("long")),
-        'x' => (&args[1..], false, Cow::from("long long")),
-        'f' => (&args[1..], false, Cow::from("float")),
-        'd' => (&args[1..], false, Cow::from("double")),
-        'r' => (&args[1..], false, Cow::from("long double")),
-        'b' => (&args[1..], false, Cow::from("bool")),
-        'w' => (&args[1..], false, Cow::from("wchar_t")),
-        'v' => (&args[1..], false, Cow::from("void")),
+        'c' => (&args[1..], false, builtin("char")),
+        's' => (&args[1..], false, builtin("short")),
+        'i' => (&args[1..], false, builtin("int")),
+        'l' => (&args[1..], false, builtin("long")),
+        'x' => (&args[1..], false, builtin("long long")),
+        'f' => (&args[1..], false, builtin("float")),
+        'd' => (&args[1..], false, builtin("double")),
+        'r' => (&args[1..], false, builtin("long double")),
+        'b' => (&args[1..], false, builtin("bool")),
+        'w' => (&args[1..], false, builtin("wchar_t")),
+        'v' => (&args[1..], false, builtin("void")),
         'I' => {
             let Remaining { r, d: bitwidth } = args[1..].p_hex_number().ok_or(
                 DemangleError::MissingBitwidthForExtensionInteger(&args[1..]),
             )?;
-            let typ = match bitwidth {
-                128 => {
-                    // g++ does not like the `int128_t` type, but it recognizes
-                    // `__int128_t` and `__uint128_t` just fine, so we emit
-                    // instead.
-                    // Also `unsigned __int128_t` doesn't make sense. Some g++
-                    // versions kinda recognizes it, but it mangles the symbol
-                    // as `unsigned int`, so it seems more like a bug than an
-                    // actual feature.
-                    if config.fix_extension_int {
-                        if sign == Signedness::Unsigned {
-                            sign = Signedness::No;
-                            "__uint128_t"
-                        } else {
-                            "__int128_t"
-                        }
-                    } else {
-                        "int128_t"
-                    }
-                }
-                _ => {
-                    return Err(DemangleError::InvalidBitwidthForExtensionInteger(
-                        args, bitwidth,
-                    ));
-                }
+            if !matches!(bitwidth, 8 | 16 | 32 | 64 | 128) {
+                return Err(DemangleError::InvalidBitwidthForExtensionInteger(
+                    args, bitwidth,
+                ));
+            }
+            config.saw(Construct::ExtensionInt);
+            let typ = if config.fix_extension_int {
+                // g++ does not like the `int128_t` type, but it recognizes
+                // `__int128_t` and `__uint128_t` just fine, so we emit
+                // instead. The other widths use the `<stdint.h>` names.
+                // Also `unsigned __int128_t` doesn't make sense. Some g++
+                // versions kinda recognizes it, but it mangles the symbol
+                // as `unsigned int`, so it seems more like a bug than an
+                // actual feature.
+                // The same goes for `signed`, `__int128_t` already is.
+                let is_unsigned = sign == Signedness::Unsigned;
+                sign = Signedness::No;
+                let prefix = match (bitwidth, is_unsigned) {
+                    (128, false) => "__",
+                    (128, true) => "__u",
+                    (_, false) => "",
+                    (_, true) => "u",
+                };
+                format!("{prefix}int{bitwidth}_t")
+            } else {
+                format!("int{bitwidth}_t")
             };
+            let typ = config.tokens().marked(Token::Type, &typ).into_owned();
             (r, false, Cow::from(typ))
         }
         '1'..='9' => {
-            let Remaining { r, d: class_name } =
-                demangle_custom_name(args, DemangleError::InvalidCustomNameOnArgument)?;
-            (r, true, Cow::from(class_name))
+            let Remaining { r, d: class_name } = demangle_trailing_custom_name(
+                config,
+                args,
+                DemangleError::InvalidCustomNameOnArgument,
+            )?;
+            (r, true, config.tokens().marked(Token::Type, class_name))
         }
         'Q' => {
             let (remaining, namespaces, _trailing_namespace) =
@@ -337,12 +551,12 @@ where
         }
         'T' => {
             // Remembered type / look back
-            let Remaining { r, d: lookback } = args[1..]
-                .p_number_maybe_multi_digit()
+            let Remaining { r, d: lookback } = config
+                .lookback_index(&args[1..])
                 .ok_or(DemangleError::InvalidLookbackCount(args))?;
 
             let referenced_arg = parsed_arguments
-                .get(lookback)
+                .get_lookback(lookback, args)?
                 .ok_or(DemangleError::LookbackCountTooBig(args, lookback))?;
 
             (r, false, Cow::from(referenced_arg))
@@ -355,30 +569,40 @@ where
         }
         'X' => {
             // Index into type of templated function
-            let args = &args[1..];
-            let Remaining { r, d: index } = if let Some(r) = args.strip_prefix('_') {
-                r.p_number_maybe_multi_digit()
+            let Remaining {
+                r,
+                d: (index, level),
+            } = demangle_template_lookback(&args[1..])?;
+
+            let t = if uses_template_level(config, template_args, level) {
+                let t = template_args
+                    .get_template(index, level)
+                    .ok_or(DemangleError::InvalidNumber1OnXArgument(r, level))?;
+                Cow::from(t)
+            } else if let Some(t) = template_arg_at_depth(config, template_args, index)
+                .and_then(|arg| arg.get(0).map(String::from))
+            {
+                Cow::from(t)
             } else {
-                args.p_digit()
-            }
-            .ok_or(DemangleError::InvalidValueForIndexOnXArgument(args))?;
-
-            let Some(Remaining { r, d: number1 }) = r.p_digit() else {
-                return Err(DemangleError::InvalidValueForNumber1OnXArgument(r));
+                let t = template_args
+                    .get(index)
+                    .ok_or(DemangleError::IndexTooBigForXArgument(r, index))?;
+                Cow::from(t)
             };
-            // TODO: what is this number?
-            if number1 != 1 && number1 != 0 {
-                return Err(DemangleError::InvalidNumber1OnXArgument(r, number1));
-            }
 
-            let Some(t) = template_args.get(index) else {
-                return Err(DemangleError::IndexTooBigForXArgument(r, index));
-            };
-
-            (r, false, Cow::from(t))
+            (r, false, t)
         }
         _ => {
-            return Err(DemangleError::UnknownType(c, args));
+            let rest = &args[c.len_utf8()..];
+            config.saw(Construct::VendorType);
+            let Some((r, typ)) = config
+                .vendor_type_map
+                .and_then(|map| map(c, rest))
+                .and_then(|(len, typ)| Some((rest.get(len..)?, typ)))
+            else {
+                return Err(DemangleError::UnknownType(c, args));
+            };
+            (r, false, config.tokens().marked(Token::Type, typ))
         }
     };
 
@@ -386,31 +610,137 @@ where
 }
 
 /// Handles any arg that can't be qualified
+/// The index and the level of a `X` lookback, with the `X` already stripped.
+fn demangle_template_lookback(
+    args: &str,
+) -> Result<Remaining<'_, (usize, usize)>, DemangleError<'_>> {
+    let Remaining { r, d: index } = if let Some(r) = args.strip_prefix('_') {
+        r.p_number_maybe_multi_digit()
+    } else {
+        args.p_digit()
+    }
+    .ok_or(DemangleError::InvalidValueForIndexOnXArgument(args))?;
+
+    let Some(Remaining { r, d: level }) = r.p_digit() else {
+        return Err(DemangleError::InvalidValueForNumber1OnXArgument(r));
+    };
+
+    Ok(Remaining::new(r, (index, level)))
+}
+
+/// Whether a `X` lookback with the given level has to be resolved with
+/// [`ArgVec::get_template`] instead of on the template list of the function
+/// itself, like c++filt does.
+fn uses_template_level(config: &Context, template_args: &ArgVec, level: usize) -> bool {
+    let scoped = level >= 2 || (level == 1 && template_args.has_enclosing());
+    if scoped {
+        config.saw(Construct::TemplateParameterLevel);
+    }
+    scoped && config.fix_template_parameter_levels
+}
+
+/// A qualified `X` lookback to a function or method pointer, like the `PX01`
+/// of `call__H1ZPFi_v_PX01_v`.
+///
+/// The qualifiers of the lookback go inside the declarator of the pointer, so
+/// it becomes `void (**)(int)` instead of `void (*)(int) *`.
+///
+/// Returns `None` if the lookback has to be handled as any other type.
+fn demangle_pointer_template_lookback<'s>(
+    config: &Context,
+    args: &'s str,
+    template_args: &ArgVec,
+    sign: Signedness,
+    post_qualifiers: &str,
+    array_qualifiers: &OptionDisplay<ArrayQualifiers>,
+) -> Result<Option<(&'s str, DemangledArg)>, DemangleError<'s>> {
+    let Some(args) = args.strip_prefix('X') else {
+        return Ok(None);
+    };
+    if post_qualifiers.is_empty()
+        || sign != Signedness::No
+        || array_qualifiers.as_option().is_some()
+    {
+        return Ok(None);
+    }
+
+    let Remaining {
+        r,
+        d: (index, level),
+    } = demangle_template_lookback(args)?;
+    if uses_template_level(config, template_args, level) {
+        return Ok(None);
+    }
+    let at_depth = template_arg_at_depth(config, template_args, index);
+    let pointer = match &at_depth {
+        Some(arg) => arg.get_pointer(0),
+        None => template_args.get_pointer(index),
+    };
+    let Some(pointer) = pointer else {
+        return Ok(None);
+    };
+    config.saw(Construct::FunctionPointerLookback);
+    if !config.fix_function_pointer_template_lookbacks {
+        return Ok(None);
+    }
+
+    // Pointers with a more complex declarator, like arrays of function
+    // pointers, are left as they are.
+    let requalify = |inner: &str| {
+        (!inner.contains(['(', '['])).then(|| {
+            respace_qualifiers(
+                config.type_pointer_spacing,
+                &format!("{inner}{post_qualifiers}"),
+            )
+        })
+    };
+    let arg = match pointer {
+        DemangledArg::FunctionPointer(fp) => {
+            let Some(post_qualifiers) = requalify(&fp.post_qualifiers) else {
+                return Ok(None);
+            };
+            DemangledArg::FunctionPointer(FunctionPointer {
+                post_qualifiers,
+                ..fp.clone()
+            })
+        }
+        DemangledArg::MethodPointer(mp) => {
+            let Some(post_qualifiers) = requalify(&mp.post_qualifiers) else {
+                return Ok(None);
+            };
+            DemangledArg::MethodPointer(MethodPointer {
+                post_qualifiers,
+                ..mp.clone()
+            })
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some((r, arg)))
+}
+
 fn demangle_qualifierless_arg<'s>(
-    _config: &DemangleConfig,
+    config: &Context,
     full_args: &'s str,
 ) -> Result<Option<(&'s str, DemangledArg)>, DemangleError<'s>> {
-    #[expect(clippy::manual_map)]
     let maybe_demangled = if let Some(repeater) = full_args.strip_prefix('N') {
-        let remaining = repeater;
-        let Remaining {
-            r: remaining,
-            d: count,
-        } = remaining
-            .p_number_maybe_multi_digit()
+        // A multi-digit count is terminated by an underscore, but the
+        // underscore may actually be the end of a function pointer argument
+        // list, like `N21_`. Retry with a single digit count if the index
+        // can't be parsed.
+        let (remaining, count, index) = demangle_repeat(config, repeater, true)
+            .or_else(|| demangle_repeat(config, repeater, false))
             .ok_or(DemangleError::InvalidRepeatingArgument(full_args))?;
         let count =
             NonZeroUsize::new(count).ok_or(DemangleError::InvalidRepeatingArgument(full_args))?;
-
-        let Remaining {
-            r: remaining,
-            d: index,
-        } = remaining
-            .p_number_maybe_multi_digit()
-            .ok_or(DemangleError::InvalidRepeatingArgument(full_args))?;
+        config.saw(Construct::Repeat);
+        if count.get() > config.max_repeat_count {
+            return Err(DemangleError::RepeatCountTooLarge(full_args, count.get()));
+        }
 
         Some((remaining, DemangledArg::Repeat { count, index }))
     } else if let Some(remaining) = full_args.strip_prefix('e') {
+        config.saw(Construct::Ellipsis);
         Some((remaining, DemangledArg::Ellipsis))
     } else {
         None
@@ -419,102 +749,189 @@ fn demangle_qualifierless_arg<'s>(
     Ok(maybe_demangled)
 }
 
+/// Parse the count and the index of a `N` repeat, returning them in that
+/// order.
+fn demangle_repeat<'s>(
+    config: &Context,
+    repeater: &'s str,
+    multi_digit_count: bool,
+) -> Option<(&'s str, usize, usize)> {
+    let Remaining {
+        r: remaining,
+        d: count,
+    } = if multi_digit_count {
+        repeater.p_number_maybe_multi_digit()?
+    } else {
+        repeater.p_digit()?
+    };
+
+    let Remaining {
+        r: remaining,
+        d: index,
+    } = config.lookback_index(remaining)?;
+
+    Some((remaining, count, index))
+}
+
 /// Function pointer/reference
+///
+/// The argument list of the function pointer can look back into
+/// `parsed_arguments`, the list containing the function pointer.
+// TODO: fix too_many_arguments
+#[expect(clippy::too_many_arguments)]
 fn demangle_function_pointer_arg<'s>(
-    config: &DemangleConfig,
+    config: &Context,
     s: &'s str,
+    parsed_arguments: &ArgVec,
     template_args: &ArgVec,
     sign: Signedness,
-    post_qualifiers: String,
+    post_qualifiers: ScratchString,
     array_qualifiers: OptionDisplay<ArrayQualifiers>,
     allow_array_fixup: bool,
 ) -> Result<(&'s str, FunctionPointer), DemangleError<'s>> {
-    let (r, func_args) =
-        demangle_argument_list_impl(config, s, None, template_args, true, allow_array_fixup)?;
-    let Some(r) = r.strip_prefix('_') else {
-        return Err(DemangleError::MissingReturnTypeForFunctionPointer(r));
-    };
+    config.trace("function pointer", s, || {
+        let (r, func_args) = demangle_argument_list_into(
+            config,
+            s,
+            ArgVec::with_parent(config, parsed_arguments),
+            template_args,
+            true,
+            allow_array_fixup,
+        )?;
+        let Some(r) = r.strip_prefix('_') else {
+            return Err(DemangleError::MissingReturnTypeForFunctionPointer(r));
+        };
 
-    let (r, return_type) =
-        demangle_argument(config, r, &func_args, template_args, allow_array_fixup)?;
+        if r.is_empty() {
+            return Err(DemangleError::MissingReturnTypeForFunctionPointer(r));
+        }
 
-    let fp = match return_type {
-        DemangledArg::Plain(plain, array_qualifiers) => FunctionPointer {
-            return_type: format!("{sign}{plain}"),
-            array_qualifiers,
-            post_qualifiers,
-            args: func_args.join(),
-        },
-        DemangledArg::FunctionPointer(function_pointer) => {
-            let FunctionPointer {
-                return_type: sub_return_type,
-                array_qualifiers: sub_array_qualifiers,
-                post_qualifiers: sub_post_qualifiers,
-                args: sub_args,
-            } = function_pointer;
-            let func_args = func_args.join();
-            FunctionPointer {
-                return_type: sub_return_type,
-                array_qualifiers: sub_array_qualifiers,
-                // This is kinda hacky, but it seems to work...
-                post_qualifiers: format!(
-                    "{sign}{post_qualifiers}({sub_post_qualifiers})({func_args}){array_qualifiers}",
-                ),
-                args: sub_args,
+        let ret = r;
+        let (r, return_type) =
+            demangle_argument(config, ret, &func_args, template_args, allow_array_fixup)?;
+        config.span(SpanKind::ReturnType, ret, r, || {
+            return_type.type_string(config.type_pointer_spacing)
+        });
+
+        config.saw(Construct::Declarator);
+        let spacing = config.type_pointer_spacing;
+        let post_qualifiers = respace_qualifiers(spacing, &post_qualifiers);
+        // Arrays of function pointers, like `void (*(*)[4])(int)`, have the array
+        // part inside the declarator.
+        let post_qualifiers = if let Some(arr) = array_qualifiers.as_option() {
+            if arr.inner_post_qualifiers.is_empty() {
+                format!("{post_qualifiers}{}", arr.arrays)
+            } else {
+                let space = if needs_space_in_declarator(spacing, &post_qualifiers, "(") {
+                    " "
+                } else {
+                    ""
+                };
+                format!(
+                    "{post_qualifiers}{space}({}){}",
+                    arr.inner_post_qualifiers, arr.arrays
+                )
             }
-        }
-        DemangledArg::MethodPointer(method_pointer) => {
-            // Copied from the FunctionPointer block. Untested
-            let MethodPointer {
-                return_type: sub_return_type,
-                array_qualifiers: sub_array_qualifiers,
-                class,
-                post_qualifiers: sub_post_qualifiers,
-                args: sub_args,
-                is_const_method,
-            } = method_pointer;
-            let func_args = func_args.join();
-            let const_qualifier = if is_const_method { " const" } else { "" };
-            FunctionPointer {
-                return_type: sub_return_type,
-                array_qualifiers: sub_array_qualifiers,
-                post_qualifiers: format!(
-                    "{sign}{post_qualifiers}({class}::{sub_post_qualifiers})({func_args}){const_qualifier}{array_qualifiers}",
-                ),
-                args: sub_args,
+        } else {
+            post_qualifiers
+        };
+
+        let (open, close) = (config.cuts().open(), config.cuts().close());
+        let fp = match return_type {
+            DemangledArg::Plain(plain, array_qualifiers) => FunctionPointer {
+                return_type: format!("{}{plain}", sign.prefix(config)),
+                array_qualifiers,
+                post_qualifiers,
+                args: func_args.join()?,
+                spacing,
+                parens: config.argument_list_parens,
+                cuts: config.cuts(),
+            },
+            DemangledArg::FunctionPointer(function_pointer) => {
+                let FunctionPointer {
+                    return_type: sub_return_type,
+                    array_qualifiers: sub_array_qualifiers,
+                    post_qualifiers: sub_post_qualifiers,
+                    args: sub_args,
+                    spacing: _,
+                    parens: _,
+                    cuts: _,
+                } = function_pointer;
+                let func_args = func_args.join()?;
+                FunctionPointer {
+                    return_type: sub_return_type,
+                    array_qualifiers: sub_array_qualifiers,
+                    // This is kinda hacky, but it seems to work...
+                    // Our own declarator goes inside the one of the returned
+                    // function pointer.
+                    post_qualifiers: format!(
+                        "{}{}{open}({}{close}){}",
+                        sign.prefix(config),
+                        sub_post_qualifiers.trim_end(),
+                        post_qualifiers.trim_end(),
+                        ParameterList::new(&func_args, config.argument_list_parens, config.cuts()),
+                    ),
+                    args: sub_args,
+                    spacing,
+                    parens: config.argument_list_parens,
+                    cuts: config.cuts(),
+                }
             }
-        }
-        DemangledArg::Repeat { .. } | DemangledArg::Ellipsis => {
-            return Err(DemangleError::InvalidReturnTypeForFunctionPointer(r))
-        }
-    };
+            DemangledArg::MethodPointer(method_pointer) => {
+                // Copied from the FunctionPointer block. Untested
+                let MethodPointer {
+                    return_type: sub_return_type,
+                    array_qualifiers: sub_array_qualifiers,
+                    class,
+                    post_qualifiers: sub_post_qualifiers,
+                    args: sub_args,
+                    method_qualifiers,
+                    spacing: _,
+                    parens: _,
+                    scope_separator: _,
+                    cuts: _,
+                } = method_pointer;
+                let func_args = func_args.join()?;
+                FunctionPointer {
+                    return_type: sub_return_type,
+                    array_qualifiers: sub_array_qualifiers,
+                    post_qualifiers: format!(
+                        "{}{class}{}{}{open}({}{close}){}{method_qualifiers}",
+                        sign.prefix(config),
+                        config.scope_separator,
+                        sub_post_qualifiers.trim_end(),
+                        post_qualifiers.trim_end(),
+                        ParameterList::new(&func_args, config.argument_list_parens, config.cuts()),
+                    ),
+                    args: sub_args,
+                    spacing,
+                    parens: config.argument_list_parens,
+                    cuts: config.cuts(),
+                }
+            }
+            DemangledArg::Repeat { .. } | DemangledArg::Ellipsis => {
+                return Err(DemangleError::InvalidReturnTypeForFunctionPointer(r))
+            }
+        };
 
-    Ok((r, fp))
+        Ok((r, fp))
+    })
 }
 
-/// Method pointer
-// TODO: fix too_many_arguments
-#[expect(clippy::too_many_arguments)]
-fn demangle_method_pointer_arg<'s>(
-    config: &DemangleConfig,
+/// The class of a method pointer or an object member pointer.
+pub(crate) fn demangle_member_pointer_class<'s, F>(
+    config: &Context,
     s: &'s str,
-    full_args: &'s str,
     template_args: &ArgVec,
-    sign: Signedness,
-    post_qualifiers: String,
-    array_qualifiers: OptionDisplay<ArrayQualifiers>,
     allow_array_fixup: bool,
-) -> Result<(&'s str, MethodPointer), DemangleError<'s>> {
-    if sign != Signedness::No || !post_qualifiers.chars().all(|c| c == '*') {
-        // The only qualifer valid for this seems to be pointer (`*`), not
-        // even references (`&`) seem to be valid C++
-        return Err(DemangleError::InvalidQualifierForMethodMemberArg(full_args));
-    }
-
-    let (r, class_name) = if s.starts_with(|c| matches!(c, '1'..='9')) {
-        let Remaining { r, d: class_name } =
-            demangle_custom_name(s, DemangleError::InvalidClassNameOnMethodArgument)?;
-        (r, Cow::from(class_name))
+    err: F,
+) -> Result<(&'s str, Cow<'s, str>), DemangleError<'s>>
+where
+    F: Fn(&'s str) -> DemangleError<'s>,
+{
+    if s.starts_with(|c| matches!(c, '1'..='9')) {
+        let Remaining { r, d: class_name } = demangle_custom_name(config, s, err)?;
+        Ok((r, config.tokens().marked(Token::Type, class_name)))
     } else {
         let (r, DemangledArg::Plain(class_name, array_qualifiers)) = demangle_argument(
             config,
@@ -524,175 +941,253 @@ fn demangle_method_pointer_arg<'s>(
             allow_array_fixup,
         )?
         else {
-            return Err(DemangleError::InvalidClassNameOnMethodArgument(s));
+            return Err(err(s));
         };
         if array_qualifiers.is_some() {
-            return Err(DemangleError::InvalidClassNameOnMethodArgument(s));
+            return Err(err(s));
         }
 
-        (r, Cow::from(class_name))
-    };
+        Ok((r, Cow::from(class_name)))
+    }
+}
 
-    let (r, is_const_method) = r.c_maybe_strip_prefix('C');
-    if let Some(func_pointer) = r.strip_prefix('F') {
-        let r = {
-            // First argument should be a pointer to the class name.
-            // We can't do much about it, besides use it check the mangled name
-            // is valid.
-
-            // It has to be a pointer
-            let Some(r) = func_pointer.strip_prefix('P') else {
-                return Err(DemangleError::MethodPointerNotHavingAPointerFirst(
-                    func_pointer,
-                ));
-            };
+/// Method pointer
+// TODO: fix too_many_arguments
+#[expect(clippy::too_many_arguments)]
+fn demangle_method_pointer_arg<'s>(
+    config: &Context,
+    s: &'s str,
+    full_args: &'s str,
+    parsed_arguments: &ArgVec,
+    template_args: &ArgVec,
+    sign: Signedness,
+    post_qualifiers: ScratchString,
+    array_qualifiers: OptionDisplay<ArrayQualifiers>,
+    allow_array_fixup: bool,
+) -> Result<(&'s str, MethodPointer), DemangleError<'s>> {
+    config.trace("method pointer", s, || {
+        if sign != Signedness::No || !is_valid_member_pointer_qualifiers(&post_qualifiers) {
+            return Err(DemangleError::InvalidQualifierForMethodMemberArg(full_args));
+        }
 
-            // Possibly `const`, but only if the class is const.
-            let r = if is_const_method {
-                r.strip_prefix('C')
-                    .ok_or(DemangleError::MethodPointerMissingConstness(func_pointer))?
-            } else {
+        let (r, class_name) = demangle_member_pointer_class(
+            config,
+            s,
+            template_args,
+            allow_array_fixup,
+            DemangleError::InvalidClassNameOnMethodArgument,
+        )?;
+        let mangled_class_name = &s[..s.len() - r.len()];
+
+        let Remaining {
+            r: after_cv,
+            d: method_qualifiers,
+        } = demangle_method_qualifier(config, r);
+        let mangled_method_cv = &r[..r.len() - after_cv.len()];
+        let r = after_cv;
+        if let Some(func_pointer) = r.strip_prefix('F') {
+            let r = {
+                // First argument should be a pointer to the class name.
+                // We can't do much about it, besides use it check the mangled name
+                // is valid.
+
+                // It has to be a pointer
+                let Some(r) = func_pointer.strip_prefix('P') else {
+                    return Err(DemangleError::MethodPointerNotHavingAPointerFirst(
+                        func_pointer,
+                    ));
+                };
+
+                // Possibly `const` or `volatile`, but only if the method is.
+                let r = r
+                    .strip_prefix(mangled_method_cv)
+                    .ok_or(DemangleError::MethodPointerMissingConstness(func_pointer))?;
+
+                // The class may be a lookback to one of the outer arguments,
+                // like `PT0`, so those have to be visible here.
+                let class_arg = r;
+                let (r, DemangledArg::Plain(class_name_again, array_qualifiers)) =
+                    demangle_argument(
+                        config,
+                        class_arg,
+                        parsed_arguments,
+                        template_args,
+                        allow_array_fixup,
+                    )?
+                else {
+                    return Err(DemangleError::MissingFirstClassArgumentForMethodMemberArg(
+                        func_pointer,
+                    ));
+                };
+                // Compare the mangled names first, the rendered ones may not match
+                // when the class contains lookbacks, like `t7Wrapper1ZX01`.
+                let mangled_class_name_again = &class_arg[..class_arg.len() - r.len()];
+                if mangled_class_name != mangled_class_name_again && class_name != class_name_again
+                {
+                    return Err(DemangleError::MethodPointerWrongClassName(func_pointer));
+                }
+                if array_qualifiers.is_some() {
+                    return Err(DemangleError::MethodPointerClassNameAsArray(func_pointer));
+                }
                 r
             };
 
-            let (r, DemangledArg::Plain(class_name_again, array_qualifiers)) = demangle_argument(
+            let (r, fp) = demangle_function_pointer_arg(
                 config,
                 r,
-                &ArgVec::new(config, None),
+                parsed_arguments,
                 template_args,
+                sign,
+                post_qualifiers,
+                array_qualifiers,
                 allow_array_fixup,
-            )?
-            else {
-                return Err(DemangleError::MissingFirstClassArgumentForMethodMemberArg(
-                    func_pointer,
-                ));
+            )?;
+            let FunctionPointer {
+                return_type,
+                array_qualifiers,
+                post_qualifiers,
+                args,
+                spacing,
+                parens,
+                cuts,
+            } = fp;
+
+            let arg = MethodPointer {
+                return_type,
+                array_qualifiers,
+                class: config.tokens().scope(&class_name).into_owned(),
+                post_qualifiers,
+                args,
+                method_qualifiers,
+                spacing,
+                parens,
+                scope_separator: config.scope_separator,
+                cuts,
             };
-            if class_name != class_name_again {
-                return Err(DemangleError::MethodPointerWrongClassName(func_pointer));
-            }
-            if array_qualifiers.is_some() {
-                return Err(DemangleError::MethodPointerClassNameAsArray(func_pointer));
-            }
-            r
-        };
-
-        let (r, fp) = demangle_function_pointer_arg(
-            config,
-            r,
-            template_args,
-            sign,
-            post_qualifiers,
-            array_qualifiers,
-            allow_array_fixup,
-        )?;
-        let FunctionPointer {
-            return_type,
-            array_qualifiers,
-            post_qualifiers,
-            args,
-        } = fp;
-
-        let arg = MethodPointer {
-            return_type,
-            array_qualifiers,
-            class: class_name.to_string(),
-            post_qualifiers,
-            args,
-            is_const_method,
-        };
-        Ok((r, arg))
-    } else {
-        // What else could this be?
-        Err(DemangleError::UnknownMethodMemberArgKind(r))
-    }
+            Ok((r, arg))
+        } else {
+            // What else could this be?
+            Err(DemangleError::UnknownMethodMemberArgKind(r))
+        }
+    })
 }
 
 /// Object pointer
 // TODO: fix too_many_arguments
 #[expect(clippy::too_many_arguments)]
 fn demangle_object_pointer_arg<'s>(
-    config: &DemangleConfig,
+    config: &Context,
     s: &'s str,
     full_args: &'s str,
     template_args: &ArgVec,
     sign: Signedness,
-    post_qualifiers: String,
+    post_qualifiers: ScratchString,
     array_qualifiers: OptionDisplay<ArrayQualifiers>,
     allow_array_fixup: bool,
 ) -> Result<(&'s str, String), DemangleError<'s>> {
-    if sign != Signedness::No
-        || !post_qualifiers.chars().all(|c| c == '*')
-        || array_qualifiers.is_some()
-    {
-        // The only qualifer valid for this seems to be pointer (`*`), not
-        // even references (`&`) seem to be valid C++
-        return Err(DemangleError::InvalidQualifierForObjectMemberArg(full_args));
-    }
+    config.trace("member pointer", s, || {
+        if sign != Signedness::No
+            || !is_valid_member_pointer_qualifiers(&post_qualifiers)
+            || array_qualifiers.is_some()
+        {
+            return Err(DemangleError::InvalidQualifierForObjectMemberArg(full_args));
+        }
 
-    let (r, class_name) = if s.starts_with(|c| matches!(c, '1'..='9')) {
-        let Remaining { r, d: class_name } =
-            demangle_custom_name(s, DemangleError::InvalidClassNameOnObjectMemberArgument)?;
-        (r, Cow::from(class_name))
-    } else {
-        let (r, DemangledArg::Plain(class_name, array_qualifiers)) = demangle_argument(
+        let (r, class_name) = demangle_member_pointer_class(
             config,
             s,
+            template_args,
+            allow_array_fixup,
+            DemangleError::InvalidClassNameOnObjectMemberArgument,
+        )?;
+
+        let Some(r) = r.strip_prefix('_').filter(|r| !r.is_empty()) else {
+            return Err(DemangleError::MissingTypeForObjectMemberPointer(r));
+        };
+
+        let (r, DemangledArg::Plain(member_type, arr)) = demangle_argument(
+            config,
+            r,
             &ArgVec::new(config, None),
             template_args,
             allow_array_fixup,
         )?
         else {
-            return Err(DemangleError::InvalidClassNameOnObjectMemberArgument(s));
+            return Err(DemangleError::InvalidTypeForObjectMemberPointer(full_args));
         };
-        if array_qualifiers.is_some() {
-            return Err(DemangleError::InvalidClassNameOnObjectMemberArgument(s));
-        }
-
-        (r, Cow::from(class_name))
-    };
-
-    let Some(r) = r.strip_prefix('_') else {
-        return Err(DemangleError::MissingTypeForObjectMemberPointer(r));
-    };
-
-    let (r, DemangledArg::Plain(member_type, arr)) = demangle_argument(
-        config,
-        r,
-        &ArgVec::new(config, None),
-        template_args,
-        allow_array_fixup,
-    )?
-    else {
-        return Err(DemangleError::InvalidTypeForObjectMemberPointer(full_args));
-    };
 
-    // Arrays makes everything harder.
-    let mut arg = member_type;
-    arg.push(' ');
-    if let Some(arr) = arr.as_option() {
-        if !arr.inner_post_qualifiers.is_empty() {
-            arg.push('(');
-            arg.push_str(&arr.inner_post_qualifiers);
+        // Arrays makes everything harder.
+        config.saw(Construct::Declarator);
+        let spacing = config.type_pointer_spacing;
+        let mut arg = member_type;
+        if needs_space(spacing, &arg, "(") {
+            arg.push(' ');
         }
-    }
-    arg += &format!("({class_name}::{post_qualifiers})");
-    if let Some(arr) = arr.as_option() {
-        if !arr.inner_post_qualifiers.is_empty() {
-            arg.push(')');
+        let (open, close) = (config.cuts().open(), config.cuts().close());
+        if let Some(arr) = arr.as_option() {
+            if !arr.inner_post_qualifiers.is_empty() {
+                arg.push_str(open);
+                arg.push('(');
+                arg.push_str(&arr.inner_post_qualifiers);
+            }
         }
-        arg.push_str(&arr.arrays);
+        arg += &format!(
+            "{open}({}{}{}{close})",
+            config.tokens().scope(&class_name),
+            config.scope_separator,
+            respace_qualifiers(spacing, &post_qualifiers)
+        );
+        if let Some(arr) = arr.as_option() {
+            if !arr.inner_post_qualifiers.is_empty() {
+                arg.push_str(close);
+                arg.push(')');
+            }
+            arg.push_str(&arr.arrays);
+        }
+
+        Ok((r, arg))
+    })
+}
+
+/// Member pointers must be pointers first (`*`), which then can be qualified
+/// further, like `Foo::*const &`. A reference can only be the outermost
+/// qualifier.
+///
+/// No qualifiers at all is fine too, since template lists use the bare
+/// member type.
+fn is_valid_member_pointer_qualifiers(post_qualifiers: &str) -> bool {
+    if post_qualifiers.is_empty() {
+        return true;
     }
 
-    Ok((r, arg))
+    let post_qualifiers = post_qualifiers.trim_end();
+    let without_reference = post_qualifiers.strip_suffix('&').unwrap_or(post_qualifiers);
+
+    post_qualifiers.starts_with('*') && !without_reference.contains('&')
 }
 
-fn demangle_arg_qualifiers<'s>(
+fn demangle_arg_qualifiers<'a, 's>(
+    config: &Context<'a>,
     s: &'s str,
-) -> Result<Remaining<'s, (Signedness, String)>, DemangleError<'s>> {
+) -> Result<Remaining<'s, (Signedness, ScratchString<'a>)>, DemangleError<'s>> {
     let mut remaining = s;
-    let mut post_qualifiers = String::new();
+    let mut post_qualifiers = config.scratch_string();
 
     while !remaining.is_empty() {
+        // Qualifiers of the same level are always emitted in the same order,
+        // so both `PCVc` and `PVCc` are `char const volatile *`.
+        let Remaining { r, d: cv } = CvQualifiers::parse(remaining);
+        if !cv.is_empty() {
+            let mut words = config.scratch_string();
+            for word in cv.words(config) {
+                words.push_str(word);
+                words.push(' ');
+            }
+            post_qualifiers.insert_str(0, &words);
+            remaining = r;
+            continue;
+        }
+
         let Remaining { r, d: c } = remaining
             .p_first()
             .ok_or(DemangleError::RanOutOfArguments)?;
@@ -700,8 +1195,6 @@ fn demangle_arg_qualifiers<'s>(
         match c {
             'P' => post_qualifiers.insert(0, '*'),
             'R' => post_qualifiers.insert(0, '&'),
-            'C' => post_qualifiers.insert_str(0, "const "),
-            'V' => post_qualifiers.insert_str(0, "volatile "),
             _ => break,
         }
 
@@ -724,14 +1217,23 @@ fn demangle_arg_qualifiers<'s>(
 
 // `allow_array_fixup` exists because array sizes are not always messed up.
 // As far as I know, array sizes are correct only on templated functions.
-fn demangle_array_pseudo_qualifier<'s>(
-    config: &DemangleConfig,
+fn demangle_array_pseudo_qualifier<'a, 's>(
+    config: &Context<'a>,
     s: &'s str,
     mut sign: Signedness,
-    mut post_qualifiers: String,
+    mut post_qualifiers: ScratchString<'a>,
     allow_array_fixup: bool,
-) -> Result<Remaining<'s, (Signedness, String, OptionDisplay<ArrayQualifiers>)>, DemangleError<'s>>
-{
+) -> Result<
+    Remaining<
+        's,
+        (
+            Signedness,
+            ScratchString<'a>,
+            OptionDisplay<ArrayQualifiers>,
+        ),
+    >,
+    DemangleError<'s>,
+> {
     if !s.starts_with('A') {
         return Ok(Remaining::new(s, (sign, post_qualifiers, None.into())));
     }
@@ -745,38 +1247,73 @@ fn demangle_array_pseudo_qualifier<'s>(
         // Avoid stuff like "signed signed"
         return Err(DemangleError::PrevQualifiersInInvalidPostioniAtArrayArgument(s));
     }
-    array_qualifiers.inner_post_qualifiers = post_qualifiers;
+    // cv-qualifiers applied directly to an array actually qualify its
+    // elements, so `PCA41_i` is the same as `PA41_Ci`: `int const (*)[42]`.
+    // Those qualifiers are the leading ones, since the innermost qualifier
+    // is the first one in the string.
+    let (array_cv, inner_post_qualifiers) = split_leading_cv_qualifiers(config, &post_qualifiers);
+    array_qualifiers.inner_post_qualifiers = inner_post_qualifiers.trim_end().to_string();
 
     let mut args = s;
+    let mut dimensions = 0;
     while let Some(remaining) = args.strip_prefix('A') {
+        config.saw(Construct::ArrayArgument);
+        dimensions += 1;
+        if dimensions > config.max_array_dimensions {
+            return Err(DemangleError::TooManyArrayDimensions(s));
+        }
         let Some(Remaining {
             r: remaining,
             d: array_length,
         }) = remaining.p_number()
         else {
+            // The digits are there, they just don't fit.
+            if remaining.starts_with(|c: char| c.is_ascii_digit()) {
+                return Err(DemangleError::ArrayLengthOverflow(args));
+            }
             return Err(DemangleError::InvalidArraySize(remaining));
         };
         let Some(remaining) = remaining.strip_prefix('_') else {
             return Err(DemangleError::MalformedArrayArgumment(remaining));
         };
 
-        let array_length = if config.fix_array_length_arg && allow_array_fixup {
-            array_length + 1
+        let skip_fixup = config.fix_array_length_skip_zero && array_length == 0;
+        let array_length = if config.fix_array_length_arg && allow_array_fixup && !skip_fixup {
+            array_length
+                .checked_add(1)
+                .ok_or(DemangleError::ArrayLengthOverflow(args))?
         } else {
             array_length
         };
 
-        array_qualifiers
-            .arrays
-            .push_str(&format!("[{array_length}]"));
+        let array_length = array_length.to_string();
+        let array_length = config.tokens().mark(Token::Literal, &array_length);
+        // Writing into a `String` never fails.
+        let _ = write!(array_qualifiers.arrays, "[{array_length}]");
         args = remaining;
     }
 
     let Remaining {
         r,
-        d: (sign_other, post),
-    } = demangle_arg_qualifiers(args)?;
+        d: (sign_other, mut post),
+    } = demangle_arg_qualifiers(config, args)?;
     sign = sign_other;
+
+    // Arrays of references can't exist, so `A9_Ri` is actually the reference
+    // to an array `RA9_i`: `int (&)[10]`.
+    // The outermost element qualifier is the last one of the string.
+    if post.ends_with('&')
+        && array_qualifiers.inner_post_qualifiers.is_empty()
+        && array_cv.is_empty()
+    {
+        config.saw(Construct::ArrayOfReferences);
+        if config.fix_array_of_references {
+            array_qualifiers.inner_post_qualifiers.push('&');
+            post.pop();
+        }
+    }
+    // The array qualifiers are applied on top of the element ones.
+    post.push_str(&array_cv);
     post_qualifiers = post;
 
     Ok(Remaining::new(
@@ -784,3 +1321,36 @@ fn demangle_array_pseudo_qualifier<'s>(
         (sign, post_qualifiers, Some(array_qualifiers).into()),
     ))
 }
+
+/// The cv-qualifier words, in the order they are emitted.
+const CV_WORDS: [&str; 4] = ["const ", "volatile ", "__restrict ", "restrict "];
+
+/// Split the leading `const `/`volatile `/`__restrict ` words of a qualifier
+/// string.
+///
+/// The words are returned in the order of [`CV_WORDS`], so both `PCVA41_i`
+/// and `PVCA41_i` are `int const volatile (*)[42]`.
+fn split_leading_cv_qualifiers<'a, 'p>(
+    config: &Context<'a>,
+    post_qualifiers: &'p str,
+) -> (ScratchString<'a>, &'p str) {
+    let mut remaining = post_qualifiers;
+    let mut found = [false; CV_WORDS.len()];
+
+    while let Some((i, r)) = CV_WORDS
+        .iter()
+        .enumerate()
+        .find_map(|(i, word)| Some((i, remaining.strip_prefix(word)?)))
+    {
+        found[i] = true;
+        remaining = r;
+    }
+
+    let mut cv = config.scratch_string();
+    for (word, found) in CV_WORDS.iter().zip(found) {
+        if found {
+            cv.push_str(word);
+        }
+    }
+    (cv, remaining)
+}
@@ -1,27 +1,103 @@
 /* SPDX-FileCopyrightText: © 2025 Decompollaborate */
 /* SPDX-License-Identifier: MIT OR Apache-2.0 */
 
+use core::ops::Range;
+
 use alloc::{
     string::{String, ToString},
     vec::Vec,
 };
 
-use crate::{DemangleConfig, DemangleError};
+use crate::{
+    demangle_info::{Construct, Context},
+    scratch::ScratchVec,
+    spans::SpanKind,
+    DemangleError,
+};
 
-use crate::dem_arg::{demangle_argument, DemangledArg};
+use crate::{
+    dem_arg::{demangle_argument, ArrayPosition, DemangledArg},
+    dem_template::{close_template_list, open_template_list},
+    markup::{is_unmarked, Token},
+    stl_simplify::StlArg,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 enum ProcessedArg {
     Plain(String),
-    Lookback { index: usize },
+    /// A function or method pointer, with the parsed pointer kept around so
+    /// qualified lookbacks to it can be placed inside its declarator.
+    Pointer(String, DemangledArg),
+    /// A template argument as seen by
+    /// [`simplify_stl_template`](crate::stl_simplify::simplify_stl_template).
+    Stl(StlArg),
+    Lookback {
+        index: usize,
+    },
+    Ellipsis,
+}
+
+/// What an index of an [`ArgVec`] refers to, see [`ArgVec::lookup`].
+enum Referenced<'a> {
+    Type(&'a str),
+    /// A `void` argument, which is only valid on its own.
+    Void(&'a str),
     Ellipsis,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct ArgVec<'c, 'ns> {
-    config: &'c DemangleConfig,
+    config: &'c Context<'c>,
     namespace: Option<&'ns str>,
-    args: Vec<ProcessedArg>,
+    args: ScratchVec<'c, ProcessedArg>,
+
+    /// The argument list containing this one, if this is the argument list
+    /// of a function pointer.
+    ///
+    /// Lookbacks past the end of this list are resolved on the parent list,
+    /// mimicking c++filt.
+    parent: Option<&'ns ArgVec<'ns, 'ns>>,
+
+    /// The template arguments of the templated classes containing a
+    /// templated method, innermost first.
+    ///
+    /// Only set on the template arguments of the method, see
+    /// [`ArgVec::get_template`].
+    enclosing: ScratchVec<'c, ScratchVec<'c, String>>,
+
+    /// Whether an argument running past the end of the symbol ends the list
+    /// with a `<truncated>` marker instead of failing, if
+    /// `DemangleConfig::complete_truncated_symbols` is turned on.
+    ///
+    /// Only set on the argument list of the symbol itself, since nested
+    /// lists, like the ones of function pointers, still need to be closed.
+    complete_truncated: bool,
+
+    /// What the arguments of this list are reported as, see
+    /// [`demangle_with_spans`](crate::demangle_with_spans).
+    pub(crate) span_kind: SpanKind,
+
+    /// Whether the encoding of the last argument of this template list ends
+    /// in a digit, like the `Ui10` of `t3Foo1Ui10` or the `Z6UCrc32` of
+    /// `t3Foo1Z6UCrc32`.
+    pub(crate) ends_with_digit: bool,
+
+    /// Whether this template list is written as `<...>`, since it is nested
+    /// deeper than `DemangleConfig::max_template_depth`.
+    pub(crate) elided: bool,
+
+    /// How many template lists this template list is nested in, counting
+    /// itself, and whether arrays were fixed up in it.
+    ///
+    /// Along with `sources`, it allows writing an argument again at the depth
+    /// of a `X` lookback to it, see
+    /// [`template_arg_at_depth`](crate::dem_template::template_arg_at_depth).
+    pub(crate) depth: usize,
+    pub(crate) allow_array_fixup: bool,
+
+    /// The part of the symbol each argument of this template list was parsed
+    /// from, see [`ArgVec::push_source`].
+    sources: ScratchVec<'c, &'ns str>,
 
     /// !HACK(c++filt): Allows to avoid emitting an space between a comma and
     /// the ellipsis.
@@ -32,35 +108,170 @@ pub(crate) struct ArgVec<'c, 'ns> {
 }
 
 impl<'c, 'ns> ArgVec<'c, 'ns> {
-    pub(crate) fn new(config: &'c DemangleConfig, namespace: Option<&'ns str>) -> Self {
+    pub(crate) fn new(config: &'c Context, namespace: Option<&'ns str>) -> Self {
         Self {
             config,
             namespace,
-            args: Vec::new(),
+            args: config.scratch_vec(),
+            parent: None,
+            enclosing: config.scratch_vec(),
+            complete_truncated: false,
+            span_kind: SpanKind::Argument,
+            ends_with_digit: false,
+            elided: false,
+            depth: 0,
+            allow_array_fixup: false,
+            sources: config.scratch_vec(),
             trailing_ellipsis: false,
         }
     }
 
-    pub(crate) fn get(&self, mut index: usize) -> Option<&str> {
-        if let Some(namespace) = self.namespace {
-            if index == 0 {
-                return Some(namespace);
-            } else {
-                index -= 1;
-            }
+    /// Create the argument list of a function pointer, which can look back
+    /// into the arguments of `parent`.
+    pub(crate) fn with_parent(config: &'c Context, parent: &'ns ArgVec<'ns, 'ns>) -> Self {
+        Self {
+            parent: Some(parent),
+            ..Self::new(config, None)
         }
+    }
+
+    /// Map an argument index into a position of `self.args`.
+    ///
+    /// When there's a namespace it is the implicit first argument, so it
+    /// takes the index 0 and every other argument gets shifted by one.
+    /// `None` means the index refers to the namespace itself.
+    fn position_of(&self, index: usize) -> Option<usize> {
+        if self.namespace.is_some() {
+            index.checked_sub(1)
+        } else {
+            Some(index)
+        }
+    }
 
+    pub(crate) fn get(&self, index: usize) -> Option<&str> {
+        match self.lookup(index)? {
+            Referenced::Type(typ) | Referenced::Void(typ) => Some(typ),
+            Referenced::Ellipsis => Some("..."),
+        }
+    }
+
+    /// Same as [`ArgVec::get`], for the `T` lookback or `N` repeat `s`.
+    ///
+    /// Those can't refer to an ellipsis, nor to a `void` argument, since it
+    /// can only be the single argument of a function.
+    pub(crate) fn get_lookback<'s>(
+        &self,
+        index: usize,
+        s: &'s str,
+    ) -> Result<Option<&str>, DemangleError<'s>> {
+        match self.lookup(index) {
+            Some(Referenced::Type(typ)) => Ok(Some(typ)),
+            Some(Referenced::Void(_)) => Err(DemangleError::LookbackToVoid(s)),
+            Some(Referenced::Ellipsis) => Err(DemangleError::LookbackToEllipsis(s)),
+            None => Ok(None),
+        }
+    }
+
+    fn lookup(&self, mut index: usize) -> Option<Referenced<'_>> {
         loop {
-            let arg = self.args.get(index)?;
+            let Some(position) = self.position_of(index) else {
+                break self.namespace.map(Referenced::Type);
+            };
+            let Some(arg) = self.args.get(position) else {
+                break self.parent.and_then(|parent| parent.lookup(index));
+            };
             match arg {
-                ProcessedArg::Plain(p) => break Some(p),
+                // Template arguments may be `void` just fine.
+                ProcessedArg::Plain(p)
+                    if is_unmarked(p, "void") && self.span_kind != SpanKind::TemplateArgument =>
+                {
+                    break Some(Referenced::Void(p))
+                }
+                ProcessedArg::Plain(p) | ProcessedArg::Pointer(p, _) => {
+                    break Some(Referenced::Type(p))
+                }
+                ProcessedArg::Stl(arg) => break Some(Referenced::Type(&arg.text)),
                 ProcessedArg::Lookback { index: i } => {
                     if *i >= index {
                         break None;
                     }
                     index = *i;
                 }
-                ProcessedArg::Ellipsis => break Some("..."),
+                ProcessedArg::Ellipsis => break Some(Referenced::Ellipsis),
+            }
+        }
+    }
+
+    /// Remember the template arguments of the templated classes containing
+    /// this templated method, outermost first.
+    pub(crate) fn set_enclosing(&mut self, mut enclosing: ScratchVec<'c, ScratchVec<'c, String>>) {
+        enclosing.reverse();
+        self.enclosing = enclosing;
+    }
+
+    /// The arguments of this template list, as one of the levels given to
+    /// [`ArgVec::set_enclosing`].
+    pub(crate) fn level(&self) -> ScratchVec<'c, String> {
+        let mut level = self.config.scratch_vec();
+        level.extend(self.resolve().into_iter().map(String::from));
+        level
+    }
+
+    /// Whether this templated method belongs to a templated class.
+    pub(crate) fn has_enclosing(&self) -> bool {
+        !self.enclosing.is_empty()
+    }
+
+    /// Resolve the `X` lookback to the argument `index` of the template
+    /// list at `level`.
+    ///
+    /// The level 0 is the template list of the method itself, the level 1
+    /// the one of the class containing it, and so on. Since functions and
+    /// methods of plain classes have a single template list, the level 1
+    /// refers to their own one.
+    pub(crate) fn get_template(&self, index: usize, level: usize) -> Option<&str> {
+        if level == 0 || (level == 1 && self.enclosing.is_empty()) {
+            return self.get(index);
+        }
+        self.enclosing
+            .get(level - 1)?
+            .get(index)
+            .map(String::as_str)
+    }
+
+    /// Same as [`ArgVec::get`], but only for function and method pointers,
+    /// returning the parsed pointer instead of its string.
+    pub(crate) fn get_pointer(&self, mut index: usize) -> Option<&DemangledArg> {
+        loop {
+            let position = self.position_of(index)?;
+            let Some(arg) = self.args.get(position) else {
+                break self.parent.and_then(|parent| parent.get_pointer(index));
+            };
+            match arg {
+                ProcessedArg::Pointer(_, pointer) => break Some(pointer),
+                ProcessedArg::Lookback { index: i } if *i < index => index = *i,
+                _ => break None,
+            }
+        }
+    }
+
+    /// Remember that the arguments pushed since the last call were parsed
+    /// from `source`.
+    pub(crate) fn push_source(&mut self, source: &'ns str) {
+        let missing = self.args.len().saturating_sub(self.sources.len());
+        self.sources
+            .extend(core::iter::repeat(source).take(missing));
+    }
+
+    /// The part of the symbol the argument `index` was parsed from, see
+    /// [`ArgVec::push_source`].
+    pub(crate) fn source(&self, mut index: usize) -> Option<&'ns str> {
+        loop {
+            let position = self.position_of(index)?;
+            match self.args.get(position)? {
+                ProcessedArg::Lookback { index: i } if *i < index => index = *i,
+                ProcessedArg::Lookback { .. } | ProcessedArg::Ellipsis => break None,
+                _ => break self.sources.get(position).copied(),
             }
         }
     }
@@ -73,34 +284,63 @@ impl<'c, 'ns> ArgVec<'c, 'ns> {
         allow_data_after_ellipsis: bool,
     ) -> Result<bool, DemangleError<'s>> {
         let mut found_end = false;
+        self.config.reserve(&mut self.args, 1)?;
 
         // Map the external `DemangledArg` representation to our `ProcessedArg`
         // internal one.
         let arg = match arg {
-            DemangledArg::Plain(plain, array_qualifiers) => {
-                ProcessedArg::Plain(format!("{plain}{array_qualifiers}"))
+            DemangledArg::Plain(mut plain, array_qualifiers) => {
+                if let Some(arr) = array_qualifiers.as_option() {
+                    plain = arr.qualify(
+                        &plain,
+                        ArrayPosition::Argument,
+                        self.config.type_pointer_spacing,
+                    );
+                }
+                self.span(s, remaining, &plain);
+                ProcessedArg::Plain(plain)
             }
             DemangledArg::FunctionPointer(function_pointer) => {
-                ProcessedArg::Plain(function_pointer.to_string())
+                let text = function_pointer.to_string();
+                self.span(s, remaining, &text);
+                ProcessedArg::Pointer(text, DemangledArg::FunctionPointer(function_pointer))
             }
             DemangledArg::MethodPointer(method_pointer) => {
-                ProcessedArg::Plain(method_pointer.to_string())
+                let text = method_pointer.to_string();
+                self.span(s, remaining, &text);
+                ProcessedArg::Pointer(text, DemangledArg::MethodPointer(method_pointer))
             }
             DemangledArg::Repeat { count, index } => {
+                self.config.reserve(&mut self.args, count.get())?;
                 // Check the index is in-bounds
-                if self.namespace.is_some() {
-                    if index == 0 {
-                        // we're grood
-                    } else {
-                        let i = index - 1;
-                        if i >= self.args.len() {
-                            return Err(DemangleError::InvalidRepeatingArgument(s));
-                        }
+                if self
+                    .position_of(index)
+                    .is_some_and(|position| position >= self.args.len())
+                {
+                    // Maybe it refers to an argument of the parent list.
+                    let referenced = match self.parent {
+                        Some(parent) => parent.get_lookback(index, s)?,
+                        None => None,
+                    };
+                    let Some(referenced) = referenced else {
+                        return Err(DemangleError::InvalidRepeatingArgument(s));
+                    };
+                    let referenced = referenced.to_string();
+                    for _ in 0..count.get() {
+                        self.span(s, remaining, &referenced);
+                    }
+                    for _ in 0..count.get() - 1 {
+                        self.args.push(ProcessedArg::Plain(referenced.clone()));
                     }
-                } else if index >= self.args.len() {
-                    return Err(DemangleError::InvalidRepeatingArgument(s));
+                    self.args.push(ProcessedArg::Plain(referenced));
+                    return Ok(found_end);
                 }
 
+                if let Some(referenced) = self.get_lookback(index, s)? {
+                    for _ in 0..count.get() {
+                        self.span(s, remaining, referenced);
+                    }
+                }
                 for _ in 0..count.get() - 1 {
                     self.args.push(ProcessedArg::Lookback { index });
                 }
@@ -111,6 +351,7 @@ impl<'c, 'ns> ArgVec<'c, 'ns> {
                     return Err(DemangleError::TrailingDataAfterEllipsis(remaining));
                 }
                 found_end = true;
+                self.span(s, remaining, "...");
                 if !self.config.ellipsis_emit_space_after_comma {
                     self.trailing_ellipsis = true;
                     return Ok(found_end);
@@ -122,86 +363,397 @@ impl<'c, 'ns> ArgVec<'c, 'ns> {
         Ok(found_end)
     }
 
-    pub(crate) fn join(self) -> String {
+    /// Same as [`ArgVec::push`], for a template argument parsed for
+    /// [`simplify_stl_template`](crate::stl_simplify::simplify_stl_template).
+    pub(crate) fn push_stl<'s>(
+        &mut self,
+        arg: StlArg,
+        s: &'s str,
+        remaining: &'s str,
+    ) -> Result<(), DemangleError<'s>> {
+        self.config.reserve(&mut self.args, 1)?;
+        self.span(s, remaining, &arg.text);
+        self.args.push(ProcessedArg::Stl(arg));
+        Ok(())
+    }
+
+    /// Record the argument parsed from `s` until `remaining`.
+    fn span(&self, s: &str, remaining: &str, text: &str) {
+        self.config
+            .span(self.span_kind, s, remaining, || String::from(text));
+    }
+
+    pub(crate) fn join<'s>(&self) -> Result<String, DemangleError<'s>> {
+        let mut out = String::new();
+        self.join_into(&mut out)?;
+        Ok(out)
+    }
+
+    /// Same as [`ArgVec::join`], but appending to an existing string.
+    pub(crate) fn join_into<'s>(&self, out: &mut String) -> Result<(), DemangleError<'s>> {
+        let args = self.resolve();
+
+        // Reserve everything upfront, including the separators and the
+        // ellipsis.
+        let total_len = args.iter().map(|arg| arg.len()).sum::<usize>()
+            + args.len().saturating_sub(1) * ", ".len()
+            + if self.trailing_ellipsis {
+                ",...".len()
+            } else {
+                0
+            };
+        self.config.reserve(out, total_len)?;
+
+        let separator = self.config.cuts().separator();
+        for (i, arg) in args.iter().enumerate() {
+            if i != 0 {
+                out.push_str(separator);
+                out.push_str(", ");
+            }
+            out.push_str(arg);
+        }
+        if self.trailing_ellipsis {
+            // !HACK(c++filt): Special case to mimic c++filt, since it doesn't
+            // !use an space between the comma and the ellipsis.
+            if !args.is_empty() {
+                out.push_str(separator);
+                out.push(',');
+            }
+            out.push_str("...");
+        }
+
+        Ok(())
+    }
+
+    /// Write this template list after the name of its template, like the
+    /// `<int, 4>` of `Foo<int, 4>`.
+    pub(crate) fn push_template_list<'s>(&self, out: &mut String) -> Result<(), DemangleError<'s>> {
+        open_template_list(self.config, out);
+        if self.elided {
+            out.push_str("...");
+        } else {
+            let start = out.len();
+            self.join_into(out)?;
+            self.config.tokens().template_args(out, start);
+        }
+        close_template_list(self.config, out);
+        Ok(())
+    }
+
+    /// Append every argument from `other`, with its lookbacks already
+    /// resolved.
+    pub(crate) fn extend_resolved(&mut self, other: &ArgVec) {
+        self.args.extend(
+            other
+                .resolve()
+                .into_iter()
+                .map(|arg| ProcessedArg::Plain(arg.to_string())),
+        );
+    }
+
+    /// Lists every argument, with lookbacks already replaced by the argument
+    /// they reference.
+    ///
+    /// The trailing ellipsis hack is not included.
+    pub(crate) fn resolve(&self) -> Vec<&str> {
         let mut args = Vec::with_capacity(self.args.len());
 
         for arg in &self.args {
             match arg {
-                ProcessedArg::Plain(plain) => args.push(plain.as_str()),
+                ProcessedArg::Plain(plain) | ProcessedArg::Pointer(plain, _) => {
+                    args.push(plain.as_str())
+                }
+                ProcessedArg::Stl(arg) => args.push(arg.text.as_str()),
                 ProcessedArg::Lookback { index } => {
-                    let arg = if let Some(namespace) = self.namespace {
-                        if *index == 0 {
-                            namespace
-                        } else {
-                            args.get(*index - 1)
-                                .expect("Indices were verified when pushing the arguments")
-                        }
-                    } else {
-                        args.get(*index)
-                            .expect("Indices were verified when pushing the arguments")
-                    };
+                    let arg = match self.position_of(*index) {
+                        None => self.namespace,
+                        Some(position) => args.get(position).copied(),
+                    }
+                    .expect("Indices were verified when pushing the arguments");
                     args.push(arg);
                 }
                 ProcessedArg::Ellipsis => args.push("..."),
             }
         }
 
-        let mut out = args.join(", ");
-        if self.trailing_ellipsis {
-            // !HACK(c++filt): Special case to mimic c++filt, since it doesn't
-            // !use an space between the comma and the ellipsis.
-            if !out.is_empty() {
-                out.push(',');
-            }
-            out.push_str("...");
+        args
+    }
+
+    /// Same as [`ArgVec::resolve`], but as
+    /// [`simplify_stl_template`](crate::stl_simplify::simplify_stl_template)
+    /// sees the arguments.
+    pub(crate) fn resolve_stl(&self) -> Vec<StlArg> {
+        let mut args: Vec<StlArg> = Vec::with_capacity(self.args.len());
+
+        for arg in &self.args {
+            let arg = match arg {
+                ProcessedArg::Plain(plain) | ProcessedArg::Pointer(plain, _) => {
+                    StlArg::other(plain.clone())
+                }
+                ProcessedArg::Stl(arg) => arg.clone(),
+                ProcessedArg::Lookback { index } => match self.position_of(*index) {
+                    None => self
+                        .namespace
+                        .map(|namespace| StlArg::other(namespace.into())),
+                    Some(position) => args.get(position).cloned(),
+                }
+                .expect("Indices were verified when pushing the arguments"),
+                ProcessedArg::Ellipsis => StlArg::other(String::from("...")),
+            };
+            args.push(arg);
         }
-        out
+
+        args
     }
 }
 
 pub(crate) fn demangle_argument_list<'s>(
-    config: &DemangleConfig,
+    config: &Context,
     args: &'s str,
     namespace: Option<&str>,
     template_args: &ArgVec,
     allow_array_fixup: bool,
+    complete_truncated: bool,
 ) -> Result<String, DemangleError<'s>> {
-    let (remaining, argument_list) = demangle_argument_list_impl(
+    let mut arguments = ArgVec::new(config, namespace);
+    arguments.complete_truncated = complete_truncated;
+    let (mut remaining, mut argument_list) = demangle_argument_list_into(
         config,
         args,
-        namespace,
+        arguments,
         template_args,
         false,
         allow_array_fixup,
     )?;
 
+    if config.tolerate_stray_underscores_between_args {
+        // Only a single `_` followed by more arguments, so the repeaters can
+        // still refer to the arguments before it.
+        while let Some(r) = remaining
+            .strip_prefix('_')
+            .filter(|r| !r.is_empty() && !r.starts_with('_'))
+        {
+            config.saw(Construct::StrayUnderscore);
+            (remaining, argument_list) = demangle_argument_list_into(
+                config,
+                r,
+                argument_list,
+                template_args,
+                false,
+                allow_array_fixup,
+            )?;
+        }
+    }
+
+    if remaining == "_" {
+        config.saw(Construct::Malformed);
+        if config.lenient {
+            // Nothing but the `_`, like `Id__3Foo_`, is the same as an empty
+            // argument list.
+            let argument_list = argument_list.join()?;
+            if argument_list.is_empty() {
+                return Ok(config.tokens().marked(Token::Type, "void").into_owned());
+            }
+            return Ok(argument_list);
+        }
+    }
+
     if !remaining.is_empty() {
         return Err(DemangleError::TrailingDataAfterArgumentList(remaining));
     }
 
-    Ok(argument_list.join())
+    argument_list.join()
+}
+
+/// Skip the `C` of an argument passed by value, like the one of `C3Foo`,
+/// since the `const` of the parameter itself is not part of the function
+/// type.
+///
+/// Only classes and primitives are taken as passed by value, since the
+/// `const` of things like `CA10_i` or `CT0` may not apply to the parameter
+/// itself.
+fn strip_top_level_const<'s>(config: &Context, arg: &'s str) -> &'s str {
+    let Some(r) = arg.strip_prefix('C') else {
+        return arg;
+    };
+    let typ = r.trim_start_matches(['V', 'u', 'S', 'U']);
+    if typ.starts_with(['P', 'R', 'A', 'F', 'M', 'O', 'T', 'N', 'X', 'C']) {
+        return arg;
+    }
+
+    config.saw(Construct::TopLevelConst);
+    if config.strip_top_level_const_on_value_args {
+        r
+    } else {
+        arg
+    }
 }
 
 pub(crate) fn demangle_argument_list_impl<'c, 's, 'ns>(
-    config: &'c DemangleConfig,
-    mut args: &'s str,
+    config: &'c Context,
+    args: &'s str,
     namespace: Option<&'ns str>,
     template_args: &ArgVec,
     allow_data_after_ellipsis: bool,
     allow_array_fixup: bool,
 ) -> Result<(&'s str, ArgVec<'c, 'ns>), DemangleError<'s>> {
-    let mut arguments = ArgVec::new(config, namespace);
+    demangle_argument_list_into(
+        config,
+        args,
+        ArgVec::new(config, namespace),
+        template_args,
+        allow_data_after_ellipsis,
+        allow_array_fixup,
+    )
+}
+
+/// How many greedy readings of ambiguous indices may be pending at once in an
+/// argument list, since each of them keeps a copy of the arguments before it.
+const MAX_PENDING_GREEDY: usize = 32;
+
+/// A greedy reading of an ambiguous index, which is kept as long as the rest
+/// of the argument list can be parsed after it.
+struct PendingGreedy<'c, 's, 'ns> {
+    /// Where the argument with the ambiguous index starts.
+    args: &'s str,
+    /// The arguments before it.
+    arguments: ArgVec<'c, 'ns>,
+    /// The first reading of the argument, used if the greedy one fails.
+    parsed: Result<(&'s str, DemangledArg), DemangleError<'s>>,
+    /// The spans of the first reading. The ones of the greedy reading start
+    /// where these end.
+    marks: Range<usize>,
+}
+
+/// Same as [`demangle_argument_list_impl`], but pushing into an already
+/// existing `arguments` list.
+pub(crate) fn demangle_argument_list_into<'c, 's, 'ns>(
+    config: &'c Context,
+    mut args: &'s str,
+    mut arguments: ArgVec<'c, 'ns>,
+    template_args: &ArgVec,
+    allow_data_after_ellipsis: bool,
+    allow_array_fixup: bool,
+) -> Result<(&'s str, ArgVec<'c, 'ns>), DemangleError<'s>> {
+    config.trace("argument list", args, || {
+        // Backtracking is done with an explicit stack instead of recursing
+        // into the rest of the list, so long lists don't need a deep stack.
+        let mut pending = config.scratch_vec();
+        let mut first_reading = None;
+
+        loop {
+            match demangle_arguments_until_end(
+                config,
+                args,
+                arguments,
+                first_reading.take(),
+                &mut pending,
+                template_args,
+                allow_data_after_ellipsis,
+                allow_array_fixup,
+            ) {
+                Ok(parsed) => {
+                    // The spans of the first readings are not used. The last
+                    // ones go first, so the marks of the rest stay valid.
+                    for greedy in pending.iter().rev() {
+                        config.discard_spans(greedy.marks.clone());
+                    }
+                    return Ok(parsed);
+                }
+                Err(e) => {
+                    let Some(greedy) = pending.pop() else {
+                        return Err(e);
+                    };
+                    config.discard_spans(greedy.marks.end..config.span_mark());
+                    config.backtrack();
+                    args = greedy.args;
+                    arguments = greedy.arguments;
+                    first_reading = Some(greedy.parsed);
+                }
+            }
+        }
+    })
+}
 
+/// Parse arguments until the end of the list, trying the greedy reading of
+/// ambiguous indices first and leaving a [`PendingGreedy`] to come back to if
+/// the rest of the list fails.
+///
+/// `first_reading` is used instead of parsing the first argument.
+#[expect(clippy::too_many_arguments)]
+fn demangle_arguments_until_end<'c, 's, 'ns>(
+    config: &'c Context,
+    mut args: &'s str,
+    mut arguments: ArgVec<'c, 'ns>,
+    mut first_reading: Option<Result<(&'s str, DemangledArg), DemangleError<'s>>>,
+    pending: &mut ScratchVec<'c, PendingGreedy<'c, 's, 'ns>>,
+    template_args: &ArgVec,
+    allow_data_after_ellipsis: bool,
+    allow_array_fixup: bool,
+) -> Result<(&'s str, ArgVec<'c, 'ns>), DemangleError<'s>> {
     while !args.is_empty() && !args.starts_with('_') {
         let old_args = args;
-        let (remaining, b) = demangle_argument(
-            config,
-            old_args,
-            &arguments,
-            template_args,
-            allow_array_fixup,
-        )?;
+        let parsed = if let Some(parsed) = first_reading.take() {
+            parsed
+        } else {
+            let arg = strip_top_level_const(config, old_args);
+            let mark = config.span_mark();
+            let parsed = config.attempt(|| {
+                demangle_argument(config, arg, &arguments, template_args, allow_array_fixup)
+            });
 
+            // Prefer reading an ambiguous index greedily, like `T10` as the
+            // lookback 10, as long as the rest of the list can be parsed.
+            if config.take_ambiguous_index() && pending.len() < MAX_PENDING_GREEDY {
+                let greedy_mark = config.span_mark();
+                let greedy = config.with_greedy_indices(|| {
+                    demangle_argument(config, arg, &arguments, template_args, allow_array_fixup)
+                });
+                if let Ok((remaining, b)) = greedy {
+                    let mut greedy_arguments = arguments.clone();
+                    if let Ok(found_end) =
+                        greedy_arguments.push(b, old_args, remaining, allow_data_after_ellipsis)
+                    {
+                        pending.push(PendingGreedy {
+                            args: old_args,
+                            arguments,
+                            parsed,
+                            marks: mark..greedy_mark,
+                        });
+                        arguments = greedy_arguments;
+                        args = remaining;
+                        if found_end {
+                            break;
+                        }
+                        continue;
+                    }
+                }
+                config.discard_spans(greedy_mark..config.span_mark());
+                config.backtrack();
+            }
+
+            parsed
+        };
+
+        let (remaining, b) = match parsed {
+            Ok(parsed) => parsed,
+            // A symbol ending right after the length of a name is left
+            // alone, since that length may have been cut too, or not be a
+            // length at all, like the marker of a constructor variant.
+            Err(e @ DemangleError::LikelyTruncated(name, _))
+                if arguments.complete_truncated && !name.bytes().all(|c| c.is_ascii_digit()) =>
+            {
+                config.saw(Construct::Truncated);
+                if !config.complete_truncated_symbols {
+                    return Err(e);
+                }
+                arguments
+                    .args
+                    .push(ProcessedArg::Plain("<truncated>".to_string()));
+                return Ok(("", arguments));
+            }
+            Err(e) => return Err(e),
+        };
         args = remaining;
         if arguments.push(b, old_args, remaining, allow_data_after_ellipsis)? {
             break;
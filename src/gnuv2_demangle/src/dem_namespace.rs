@@ -1,26 +1,95 @@
 /* SPDX-FileCopyrightText: © 2025 Decompollaborate */
 /* SPDX-License-Identifier: MIT OR Apache-2.0 */
 
-use core::num::NonZeroUsize;
+use core::{marker::PhantomData, num::NonZeroUsize};
 
 use alloc::{borrow::Cow, string::String};
 
-use crate::{DemangleConfig, DemangleError};
+use crate::{
+    demangle_info::{Construct, Context},
+    markup::Token,
+    scratch::ScratchVec,
+    spans::SpanKind,
+    DemangleError,
+};
 
 use crate::{
-    dem::demangle_custom_name,
+    dem::demangle_trailing_custom_name,
     dem_arg_list::ArgVec,
-    dem_template::demangle_template,
+    dem_template::demangle_template_and_types,
     remainer::{Remaining, StrParsing},
 };
 
 // 'Q' must be stripped already
 pub(crate) fn demangle_namespaces<'s>(
-    config: &DemangleConfig,
+    config: &Context,
     s: &'s str,
     template_args: &ArgVec,
     allow_array_fixup: bool,
 ) -> Result<(&'s str, String, &'s str), DemangleError<'s>> {
+    config.trace("namespaces", s, || {
+        let Remaining {
+            r,
+            d: namespace_count,
+        } = demangle_namespace_count(config, s)?;
+
+        let scope = demangle_namespaces_impl(
+            config,
+            r,
+            namespace_count,
+            template_args,
+            allow_array_fixup,
+            false,
+        )?;
+        Ok((scope.remaining, scope.namespaces, scope.trailing_namespace))
+    })
+}
+
+/// The namespaces of a function or method, see [`demangle_scope`].
+pub(crate) struct Scope<'c, 's> {
+    pub(crate) remaining: &'s str,
+    pub(crate) namespaces: String,
+    /// Same as `namespaces`, but without the template arguments of its
+    /// components, like `Map::Node` for `Map<int, int>::Node`.
+    pub(crate) bare: String,
+    pub(crate) trailing_namespace: &'s str,
+    /// The template arguments of each templated component, outermost first.
+    pub(crate) levels: ScratchVec<'c, ScratchVec<'c, String>>,
+    /// The allocator of `levels`, which is only kept track of with the
+    /// `allocator-api2` feature.
+    _alloc: PhantomData<&'c ()>,
+}
+
+/// Same as [`demangle_namespaces`], but keeping everything a function or
+/// method may need from its scope.
+pub(crate) fn demangle_scope<'c, 's>(
+    config: &'c Context,
+    s: &'s str,
+    template_args: &ArgVec,
+    allow_array_fixup: bool,
+) -> Result<Scope<'c, 's>, DemangleError<'s>> {
+    config.trace("namespaces", s, || {
+        let Remaining {
+            r,
+            d: namespace_count,
+        } = demangle_namespace_count(config, s)?;
+
+        demangle_namespaces_impl(
+            config,
+            r,
+            namespace_count,
+            template_args,
+            allow_array_fixup,
+            true,
+        )
+    })
+}
+
+// 'Q' must be stripped already
+pub(crate) fn demangle_namespace_count<'s>(
+    config: &Context,
+    s: &'s str,
+) -> Result<Remaining<'s, NonZeroUsize>, DemangleError<'s>> {
     let Remaining {
         r,
         d: namespace_count,
@@ -30,52 +99,150 @@ pub(crate) fn demangle_namespaces<'s>(
             r.strip_prefix('_').map(|new_r| Remaining::new(new_r, d))
         })
     } else {
+        // An underscore may follow a single digit count too, i.e.
+        // `Q2_17_GLOBAL_.N.foo_cc3Bar`, like c++filt accepts.
         s.p_digit()
+            .map(|Remaining { r, d }| Remaining::new(r.strip_prefix('_').unwrap_or(r), d))
     }
     .ok_or(DemangleError::InvalidNamespaceCount(s))?;
 
-    let namespace_count =
-        NonZeroUsize::new(namespace_count).ok_or(DemangleError::InvalidNamespaceCount(s))?;
+    // Some buggy compilers emit `Q0` in front of a plain name.
+    let namespace_count = match NonZeroUsize::new(namespace_count) {
+        Some(namespace_count) => namespace_count,
+        None => {
+            config.saw(Construct::Malformed);
+            if !config.lenient {
+                return Err(DemangleError::InvalidNamespaceCount(s));
+            }
+            NonZeroUsize::MIN
+        }
+    };
 
-    demangle_namespaces_impl(config, r, namespace_count, template_args, allow_array_fixup)
+    Ok(Remaining::new(r, namespace_count))
 }
 
-fn demangle_namespaces_impl<'s>(
-    config: &DemangleConfig,
+/// `is_scope` tells whether these are the namespaces of a function or method,
+/// which get a [`SpanKind::Scope`] span for each of them.
+fn demangle_namespaces_impl<'c, 's>(
+    config: &'c Context,
     s: &'s str,
     namespace_count: NonZeroUsize,
     template_args: &ArgVec,
     allow_array_fixup: bool,
-) -> Result<(&'s str, String, &'s str), DemangleError<'s>> {
+    is_scope: bool,
+) -> Result<Scope<'c, 's>, DemangleError<'s>> {
+    // The demangled namespaces are usually shorter than the mangled symbol,
+    // so use it as an estimate to avoid reallocating.
     let mut namespaces = String::new();
+    config.reserve(&mut namespaces, s.len())?;
+    let mut bare = String::new();
     let mut remaining = s;
     let mut trailing_type = "";
+    // Template arguments from the templated components seen so far, so
+    // nested templates can reference the ones from their enclosing classes,
+    // i.e. `Q2t5Outer1i4t5Inner1iY01` -> `Outer<4>::Inner<4>`.
+    let mut outer_template_args: Option<ArgVec> = None;
+    let mut levels = config.scratch_vec();
+    let mut after_digit = false;
 
-    for _i in 0..namespace_count.get() {
-        if !namespaces.is_empty() {
-            namespaces.push_str("::");
-        }
+    for i in 0..namespace_count.get() {
+        remaining = skip_digit_separator(remaining, after_digit);
 
-        // Sometimes there's a trailing underscore after a number.
-        // Not sure if this is the correct way to handle this, but at least it
-        // doesn't seem to break anything else.
-        // i.e. CreateRoadBlock__12AICopManagerP8IPursuitiP8IVehiclePQ43UTL11Collectionst11ListableSet4Z8IVehiclei10Z12eVehicleListUi10_4List
-        remaining = remaining.trim_start_matches('_');
-
-        let (r, n) = if let Some(temp) = remaining.strip_prefix('t') {
-            let (r, template, typ) =
-                demangle_template(config, temp, template_args, allow_array_fixup)?;
+        let (r, n, bare_name) = if let Some(temp) = remaining.strip_prefix('t') {
+            let (r, template, typ, types) = demangle_template_and_types(
+                config,
+                temp,
+                outer_template_args.as_ref().unwrap_or(template_args),
+                allow_array_fixup,
+            )?;
+            outer_template_args
+                .get_or_insert_with(|| ArgVec::new(config, None))
+                .extend_resolved(&types);
+            levels.push(types.level());
             trailing_type = typ;
-            (r, Cow::from(template))
+            after_digit = types.ends_with_digit;
+            (
+                r,
+                Cow::from(template),
+                config.tokens().marked(Token::Type, typ),
+            )
         } else {
-            let Remaining { r, d: ns } =
-                demangle_custom_name(remaining, DemangleError::InvalidCustomNameOnNamespace)?;
+            let Remaining { r, d: ns } = demangle_trailing_custom_name(
+                config,
+                remaining,
+                DemangleError::InvalidCustomNameOnNamespace,
+            )?;
             trailing_type = ns;
-            (r, Cow::from(ns))
+            after_digit = ns.ends_with(|c: char| c.is_ascii_digit());
+            let ns = config
+                .tokens()
+                .marked(Token::Type, anonymous_namespace(config, ns));
+            (r, ns.clone(), ns)
         };
+        if is_scope {
+            config.span(SpanKind::Scope, remaining, r, || n.clone().into_owned());
+        }
         remaining = r;
-        namespaces.push_str(&n);
+        // Every component but the last is the scope of the next one.
+        if i + 1 == namespace_count.get() {
+            namespaces.push_str(&n);
+            bare.push_str(&bare_name);
+        } else {
+            namespaces.push_str(&config.tokens().scope(&n));
+            namespaces.push_str(config.scope_separator);
+            bare.push_str(&config.tokens().scope(&bare_name));
+            bare.push_str(config.scope_separator);
+        }
     }
 
-    Ok((remaining, namespaces, trailing_type))
+    Ok(Scope {
+        remaining,
+        namespaces,
+        bare,
+        trailing_namespace: trailing_type,
+        levels,
+        _alloc: PhantomData,
+    })
+}
+
+/// Skip the `_` some compilers emit between a namespace component ending in a
+/// digit and the next one, which starts with the digits of its length, so
+/// both numbers don't merge.
+///
+/// i.e. the `10_4List` of
+/// `CreateRoadBlock__12AICopManagerP8IPursuitiP8IVehiclePQ43UTL11Collectionst11ListableSet4Z8IVehiclei10Z12eVehicleListUi10_4List`,
+/// the `Z6UCrc32_9Prototype` of
+/// `_Q43UTL3COMt7Factory3ZiZ11InputDeviceZ6UCrc32_9Prototype.mHead` or the
+/// `b.0_19TotalNumInfractions` of the local class
+/// `_._Q313FEPlayerCarDB42GetTotalNumInfractions__13FEPlayerCarDBb.0_19TotalNumInfractions`.
+///
+/// Not every compiler emits it, like `Q216IRadMoviePlayer25State`.
+pub(crate) fn skip_digit_separator(s: &str, after_digit: bool) -> &str {
+    match s.strip_prefix('_') {
+        Some(r) if after_digit && r.starts_with(|c: char| c.is_ascii_digit()) => r,
+        _ => s,
+    }
+}
+
+/// Recognize the names g++ generates for anonymous namespaces, like
+/// `_GLOBAL_.N.foo_cc`, `_GLOBAL_$N$foo_cc` or `__N_foo_cc`.
+fn is_anonymous_namespace(name: &str) -> bool {
+    if let Some(r) = name.strip_prefix("_GLOBAL_") {
+        matches!(r.as_bytes(), [marker @ (b'.' | b'$'), b'N', other, ..] if marker == other)
+    } else {
+        name.starts_with("__N_")
+    }
+}
+
+fn anonymous_namespace<'s>(config: &Context, name: &'s str) -> &'s str {
+    if !is_anonymous_namespace(name) {
+        return name;
+    }
+
+    config.saw(Construct::AnonymousNamespace);
+    if config.pretty_anonymous_namespaces {
+        "(anonymous namespace)"
+    } else {
+        name
+    }
 }
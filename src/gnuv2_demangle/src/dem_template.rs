@@ -5,300 +5,745 @@ use core::num::NonZeroUsize;
 
 use alloc::{
     borrow::Cow,
+    boxed::Box,
     string::{String, ToString},
 };
 
-use crate::{dem_arg::FunctionPointer, str_cutter::StrCutter, DemangleConfig, DemangleError};
+use crate::{
+    dem_arg::FunctionPointer,
+    demangle_info::{Construct, Context},
+    markup::{last_unmarked, Token},
+    spans::SpanKind,
+    str_cutter::StrCutter,
+    DemangleError,
+};
 
 use crate::{
-    dem::demangle_custom_name,
-    dem_arg::{demangle_argument, DemangledArg},
+    dem::{demangle_custom_name, demangle_trailing_custom_name},
+    dem_arg::{
+        demangle_argument, demangle_const_argument, demangle_member_pointer_class,
+        push_post_qualifiers, DemangledArg, ParameterList,
+    },
     dem_arg_list::ArgVec,
-    dem_namespace::demangle_namespaces,
+    dem_namespace::demangle_scope,
     remainer::{Remaining, StrParsing},
+    stl_simplify::{is_stl_container, simplify_stl_template, Simplified, StlArg},
 };
 
 pub(crate) fn demangle_template<'s>(
-    config: &DemangleConfig,
+    config: &Context,
     s: &'s str,
     template_args: &ArgVec,
     allow_array_fixup: bool,
 ) -> Result<(&'s str, String, &'s str), DemangleError<'s>> {
-    let Remaining { r, d: class_name } =
-        demangle_custom_name(s, DemangleError::InvalidCustomNameOnTemplate)?;
-    let Some(Remaining {
-        r: remaining,
-        d: digit,
-    }) = r.p_digit()
-    else {
-        return Err(DemangleError::InvalidTemplateCount(r));
-    };
-    let digit = NonZeroUsize::new(digit).ok_or(DemangleError::TemplateReturnCountIsZero(r))?;
+    let (r, template, class_name, _types) =
+        demangle_template_and_types(config, s, template_args, allow_array_fixup)?;
 
-    let (remaining, types) =
-        demangle_template_types_impl(config, remaining, digit, template_args, allow_array_fixup)?;
+    Ok((r, template, class_name))
+}
 
-    let templated = types.join();
-    let template = if templated.ends_with('>') {
-        format!("{}<{} >", class_name, templated)
-    } else {
-        format!("{}<{}>", class_name, templated)
-    };
-    Ok((remaining, template, class_name))
+/// Same as `demangle_template`, but also returns the list of template
+/// arguments, so they can be referenced by something else.
+pub(crate) fn demangle_template_and_types<'c, 's>(
+    config: &'c Context,
+    s: &'s str,
+    template_args: &ArgVec,
+    allow_array_fixup: bool,
+) -> Result<(&'s str, String, &'s str, ArgVec<'c, 's>), DemangleError<'s>> {
+    config.trace("template", s, || {
+        let Remaining { r, d: class_name } =
+            demangle_trailing_custom_name(config, s, DemangleError::InvalidCustomNameOnTemplate)?;
+        let Some(Remaining {
+            r: remaining,
+            d: digit,
+        }) = template_count(config, r)
+        else {
+            return Err(DemangleError::InvalidTemplateCount(r));
+        };
+        let digit = NonZeroUsize::new(digit).ok_or(DemangleError::TemplateReturnCountIsZero(r))?;
+
+        let (remaining, types) = demangle_template_types_impl(
+            config,
+            remaining,
+            digit,
+            template_args,
+            true,
+            allow_array_fixup,
+        )?;
+
+        if is_stl_container(class_name) {
+            config.saw(Construct::StlContainer);
+        }
+        if config.simplify_stl_containers {
+            let args = types.resolve_stl();
+            if let Some(Simplified { name, args }) = simplify_stl_template(class_name, &args) {
+                let mut template = config.tokens().marked(Token::Type, name).into_owned();
+                if !args.is_empty() {
+                    open_template_list(config, &mut template);
+                    if types.elided {
+                        template.push_str("...");
+                    } else {
+                        let start = template.len();
+                        template.push_str(&args.join(&format!("{}, ", config.cuts().separator())));
+                        config.tokens().template_args(&mut template, start);
+                    }
+                    close_template_list(config, &mut template);
+                }
+                return Ok((remaining, template, class_name, types));
+            }
+        }
+
+        let mut template = String::with_capacity(class_name.len() + "< >".len());
+        template.push_str(&config.tokens().marked(Token::Type, class_name));
+        types.push_template_list(&mut template)?;
+        Ok((remaining, template, class_name, types))
+    })
+}
+
+/// Start the template list of `out`, the name of a template, with the
+/// brackets from `DemangleConfig::template_brackets`.
+pub(crate) fn open_template_list(config: &Context, out: &mut String) {
+    let (open, _) = config.template_brackets;
+    // Avoid things like `operator<<int>`
+    if open == '<' && last_unmarked(out) == Some('<') {
+        out.push(' ');
+    }
+    out.push_str(config.cuts().open());
+    out.push(open);
 }
 
+/// End the template list of `out`, see [`open_template_list`].
+pub(crate) fn close_template_list(config: &Context, out: &mut String) {
+    let (_, close) = config.template_brackets;
+    // Avoid writing `>>`
+    if close == '>' && last_unmarked(out) == Some('>') {
+        out.push(' ');
+    }
+    out.push_str(config.cuts().close());
+    out.push(close);
+}
+
+/// The namespace of a templated function, both with and without the template
+/// arguments of its components, and the name of its last component, like the
+/// `Bar` of `Foo::Bar<int>`.
+pub(crate) type TemplatedFunctionScope<'s> = (Cow<'s, str>, Cow<'s, str>, &'s str);
+
 pub(crate) fn demangle_template_with_return_type<'c, 's>(
-    config: &'c DemangleConfig,
+    config: &'c Context,
     s: &'s str,
     allow_array_fixup: bool,
-) -> Result<(&'s str, ArgVec<'c, 's>, Option<Cow<'s, str>>), DemangleError<'s>> {
-    let Some(Remaining { r, d: digit }) = s.p_digit() else {
-        return Err(DemangleError::InvalidTemplateReturnCount(s));
-    };
-    let digit = NonZeroUsize::new(digit).ok_or(DemangleError::TemplateReturnCountIsZero(s))?;
-
-    let (r, types) = demangle_template_types_impl(
-        config,
-        r,
-        digit,
-        &ArgVec::new(config, None),
-        allow_array_fixup,
-    )?;
+) -> Result<(&'s str, ArgVec<'c, 's>, Option<TemplatedFunctionScope<'s>>), DemangleError<'s>> {
+    config.trace("templated function", s, || {
+        let Some(Remaining { r, d: digit }) = template_count(config, s) else {
+            return Err(DemangleError::InvalidTemplateReturnCount(s));
+        };
+        let digit = NonZeroUsize::new(digit).ok_or(DemangleError::TemplateReturnCountIsZero(s))?;
 
-    let Some(r) = r.strip_prefix('_') else {
-        return Err(DemangleError::MalformedTemplateWithReturnType(r));
-    };
-    let (r, namespaces) = if let Some(q_less) = r.strip_prefix('Q') {
-        let (r, namespaces, _trailing_namespace) = demangle_namespaces(
+        let (r, mut types) = demangle_template_types_impl(
             config,
-            q_less,
+            r,
+            digit,
             &ArgVec::new(config, None),
+            false,
             allow_array_fixup,
         )?;
 
-        (r, Some(Cow::from(namespaces)))
-    } else if r.starts_with(|c| matches!(c, '1'..='9')) {
-        let Remaining { r, d: namespace } =
-            demangle_custom_name(r, DemangleError::InvalidNamespaceOnTemplatedFunction)?.d_as_cow();
-        (r, Some(namespace))
-    } else {
-        (r, None)
+        let Some(r) = r.strip_prefix('_') else {
+            return Err(DemangleError::MalformedTemplateWithReturnType(r));
+        };
+        let (r, namespaces) = if let Some(q_less) = r.strip_prefix('Q') {
+            let scope = demangle_scope(
+                config,
+                q_less,
+                &ArgVec::new(config, None),
+                allow_array_fixup,
+            )?;
+            types.set_enclosing(scope.levels);
+
+            (
+                scope.remaining,
+                Some((
+                    Cow::from(scope.namespaces),
+                    Cow::from(scope.bare),
+                    scope.trailing_namespace,
+                )),
+            )
+        } else if r.starts_with(|c| matches!(c, '1'..='9')) {
+            let Remaining {
+                r: after,
+                d: namespace,
+            } = demangle_custom_name(
+                config,
+                r,
+                DemangleError::InvalidNamespaceOnTemplatedFunction,
+            )?;
+            config.span(SpanKind::Scope, r, after, || String::from(namespace));
+            let marked = config.tokens().marked(Token::Type, namespace);
+            (after, Some((marked.clone(), marked, namespace)))
+        } else {
+            (r, None)
+        };
+
+        Ok((r, types, namespaces))
+    })
+}
+
+/// Parse the amount of arguments of a template.
+///
+/// It is usually a single digit, but counts of more than one digit followed
+/// by an underscore are used by some compilers, like the `12_` of
+/// `t5Tuple12_Zi...`. Those are only read as such as an alternative reading,
+/// since `12_` may as well be a count of 1 followed by a value.
+fn template_count<'s>(config: &Context, s: &'s str) -> Option<Remaining<'s, usize>> {
+    let single = s.p_digit()?;
+    let Some(multi) = s.p_number_maybe_multi_digit() else {
+        return Some(single);
     };
 
-    Ok((r, types, namespaces))
+    if multi.r.len() != single.r.len() && config.alternative_template_reading() {
+        Some(multi)
+    } else {
+        Some(single)
+    }
 }
 
+// `allow_pointer_terminator` must be `false` for templated functions, since
+// there the `_` after the template list separates the return type.
 fn demangle_template_types_impl<'c, 's>(
-    config: &'c DemangleConfig,
+    config: &'c Context,
+    s: &'s str,
+    count: NonZeroUsize,
+    template_args: &ArgVec,
+    allow_pointer_terminator: bool,
+    allow_array_fixup: bool,
+) -> Result<(&'s str, ArgVec<'c, 's>), DemangleError<'s>> {
+    config.in_template_list(|| {
+        demangle_template_types_list(
+            config,
+            s,
+            count,
+            template_args,
+            allow_pointer_terminator,
+            allow_array_fixup,
+        )
+    })
+}
+
+fn demangle_template_types_list<'c, 's>(
+    config: &'c Context,
     s: &'s str,
     count: NonZeroUsize,
     template_args: &ArgVec,
+    allow_pointer_terminator: bool,
     allow_array_fixup: bool,
 ) -> Result<(&'s str, ArgVec<'c, 's>), DemangleError<'s>> {
     let mut remaining = s;
     let mut types = ArgVec::new(config, None);
+    types.span_kind = SpanKind::TemplateArgument;
+    types.elided = config.is_too_deep();
+    types.depth = config.template_depth();
+    types.allow_array_fixup = allow_array_fixup;
 
     for _i in 0..count.get() {
-        let (r, arg, allow_data_after_ellipsis) = if let Some(r) = remaining.strip_prefix('Z') {
-            // typename / class
-            let (r, arg) = demangle_argument(config, r, &types, template_args, allow_array_fixup)?;
-            (r, arg, true)
-        } else {
-            // value
-            let Remaining { r, d: arg } =
-                demangle_templated_value(config, remaining, template_args, allow_array_fixup)?;
-            (r, arg, false)
-        };
-        types.push(arg, remaining, r, allow_data_after_ellipsis)?;
+        let (r, element) = demangle_template_element(
+            config,
+            remaining,
+            &types,
+            template_args,
+            allow_pointer_terminator,
+            allow_array_fixup,
+        )?;
+        let source = &remaining[..remaining.len() - r.len()];
+        types.ends_with_digit = source.ends_with(|c: char| c.is_ascii_digit());
+        push_template_element(&mut types, element, remaining, r)?;
+        types.push_source(source);
         remaining = r;
     }
 
     Ok((remaining, types))
 }
 
-fn demangle_templated_value<'s>(
-    config: &DemangleConfig,
-    s: &'s str,
+/// A single argument of a template list, see [`demangle_template_element`].
+enum TemplateElement {
+    Stl(StlArg),
+    /// Along with whether it may be followed by more data if it is an
+    /// ellipsis.
+    Arg(DemangledArg, bool),
+}
+
+/// Parse the next argument of the template list `types`.
+fn demangle_template_element<'s>(
+    config: &Context,
+    remaining: &'s str,
+    types: &ArgVec,
     template_args: &ArgVec,
+    allow_pointer_terminator: bool,
     allow_array_fixup: bool,
-) -> Result<Remaining<'s, DemangledArg>, DemangleError<'s>> {
-    let mut r = s;
-    let mut is_pointer = false;
-    let mut is_reference = false;
-
-    // Skip over any known qualifier
-    while !r.is_empty() {
-        let c = r.chars().next().ok_or(DemangleError::RanOutOfArguments)?;
-
-        match c {
-            // '*'
-            'P' => is_pointer = true,
-            // '&'
-            'R' => is_reference = true,
-            // "const"
-            'C' => {}
-            // "signed" | "unsigned"
-            'S' | 'U' => {}
-            _ => break,
+) -> Result<(&'s str, TemplateElement), DemangleError<'s>> {
+    let stl_arg = match remaining.strip_prefix('Z') {
+        Some(r) if config.simplify_stl_containers => {
+            demangle_stl_argument(config, r, types, template_args, allow_array_fixup)?
         }
+        _ => None,
+    };
+    if let Some(Remaining { r, d: arg }) = stl_arg {
+        return Ok((r, TemplateElement::Stl(arg)));
+    }
+
+    let (r, arg, allow_data_after_ellipsis) = if let Some(r) = remaining.strip_prefix('Z') {
+        // typename / class
+        let (r, arg) = demangle_argument(config, r, types, template_args, allow_array_fixup)?;
+
+        // Function pointers in class template lists may be followed by an
+        // extra underscore, i.e. `t7Functor1ZPFi_i_`. Consume it here so
+        // it doesn't get mistaken as part of the enclosing symbol.
+        let r = match arg {
+            DemangledArg::FunctionPointer(..) | DemangledArg::MethodPointer(..)
+                if allow_pointer_terminator =>
+            {
+                r.strip_prefix('_').unwrap_or(r)
+            }
+            _ => r,
+        };
+        (r, arg, true)
+    } else if let Some((r, arg)) =
+        class_missing_type_marker(config, remaining, types, template_args, allow_array_fixup)
+    {
+        // A counted name is usually the enum type of a value, but some
+        // compilers omit the `Z` of class arguments.
+        (r, arg, true)
+    } else {
+        // value
+        let Remaining { r, d: arg } =
+            demangle_templated_value(config, remaining, template_args, allow_array_fixup)?;
+        (r, arg, false)
+    };
+    Ok((r, TemplateElement::Arg(arg, allow_data_after_ellipsis)))
+}
 
-        r = &r[1..];
+fn push_template_element<'s>(
+    types: &mut ArgVec,
+    element: TemplateElement,
+    s: &'s str,
+    remaining: &'s str,
+) -> Result<(), DemangleError<'s>> {
+    match element {
+        TemplateElement::Stl(arg) => types.push_stl(arg, s, remaining),
+        TemplateElement::Arg(arg, allow_data_after_ellipsis) => types
+            .push(arg, s, remaining, allow_data_after_ellipsis)
+            .map(|_| ()),
     }
+}
 
-    let (remaining, arg) = if is_pointer || is_reference {
-        let (aux, demangled_arg) = demangle_argument(
-            config,
-            r,
-            &ArgVec::new(config, None),
-            &ArgVec::new(config, None),
-            allow_array_fixup,
-        )?;
+/// Parse the argument `index` of the template list `types` again, if the
+/// template list being parsed now is nested at a different depth than it, so
+/// a `X` lookback to it gets its template lists written up to
+/// `DemangleConfig::max_template_depth` from where it is used.
+///
+/// The argument is the single one of the returned list.
+pub(crate) fn template_arg_at_depth<'c, 's>(
+    config: &'c Context,
+    types: &ArgVec<'_, 's>,
+    index: usize,
+) -> Option<ArgVec<'c, 's>> {
+    if config.max_template_depth.is_none() || types.depth == config.template_depth() {
+        return None;
+    }
+    let source = types.source(index)?;
 
-        let (aux, t) = match demangled_arg {
-            DemangledArg::Plain(_arg, _array_qualifiers) => {
-                let Remaining { r: aux, d: symbol } =
-                    demangle_custom_name(aux, DemangleError::InvalidSymbolNameOnTemplateType)?;
-                let ampersand = if is_pointer { "&" } else { "" };
-                let t = format!("{ampersand}{symbol}");
-                (aux, t)
-            }
-            DemangledArg::FunctionPointer(function_pointer) => {
-                // Function pointers as types in template lists
-
-                let FunctionPointer {
-                    return_type,
-                    array_qualifiers: _,
-                    post_qualifiers: _,
-                    args,
-                } = function_pointer;
-
-                let Remaining { r: aux, d: symbol } =
-                    demangle_custom_name(aux, DemangleError::InvalidSymbolNameOnTemplateType)?;
-
-                // TODO: check `_mangled_args` demangles to `args`
-                let Some((actual_sym, _mangled_args)) = symbol.c_split2("__F") else {
-                    return Err(DemangleError::InvalidFunctionPointerTypeInTemplatedList(
-                        r, symbol,
-                    ));
-                };
-
-                let ampersand = if is_pointer { "&" } else { "" };
-                let t = if config.fix_function_pointers_in_template_lists {
-                    if is_pointer {
-                        format!("({return_type}(*)({args})) {ampersand}{actual_sym}")
-                    } else {
-                        format!("({return_type}(&)({args})) {ampersand}{actual_sym}")
-                    }
-                } else {
-                    format!("{ampersand}{actual_sym}({args})")
-                };
-                (aux, t)
-            }
-            DemangledArg::MethodPointer(..)
-            | DemangledArg::Repeat { .. }
-            | DemangledArg::Ellipsis => {
-                return Err(DemangleError::InvalidTemplatedPointerReferenceValue(r))
-            }
+    let mut arg = ArgVec::new(config, None);
+    arg.span_kind = SpanKind::TemplateArgument;
+    // The spans were recorded when it was parsed the first time.
+    let start = config.span_mark();
+    let parsed = demangle_template_element(
+        config,
+        source,
+        types,
+        &ArgVec::new(config, None),
+        true,
+        types.allow_array_fixup,
+    )
+    .and_then(|(r, element)| push_template_element(&mut arg, element, source, r));
+    config.discard_spans(start..config.span_mark());
+
+    parsed.ok().map(|()| arg)
+}
+
+/// Read a class template argument the way
+/// [`simplify_stl_template`] needs to see it, if it is a template or a `const`
+/// type.
+///
+/// Returns `None` for anything else, which is read as a plain argument.
+fn demangle_stl_argument<'s>(
+    config: &Context,
+    s: &'s str,
+    types: &ArgVec,
+    template_args: &ArgVec,
+    allow_array_fixup: bool,
+) -> Result<Option<Remaining<'s, StlArg>>, DemangleError<'s>> {
+    let unqualified = s.strip_prefix('C');
+
+    if let Some(t) = unqualified.unwrap_or(s).strip_prefix('t') {
+        let (r, text, class_name, args) =
+            demangle_template_and_types(config, t, template_args, allow_array_fixup)?;
+        let template = StlArg {
+            text,
+            unqualified: None,
+            template: Some((String::from(class_name), args.resolve_stl())),
         };
+        if unqualified.is_none() {
+            return Ok(Some(Remaining::new(r, template)));
+        }
 
-        (aux, DemangledArg::Plain(t, None.into()))
-    } else {
-        let remaining = r;
-        let Remaining { r, d: c } = remaining
-            .p_first()
-            .ok_or(DemangleError::RanOutOfArguments)?;
-
-        // Add a way to make clear which type is being used.
-        match c {
-            // "char" | "wchar_t"
-            'c' | 'w' => {
-                let Remaining { r, d: number } = r
-                    .p_number()
-                    .ok_or(DemangleError::InvalidTemplatedNumberForCharacterValue(r))?;
-                let demangled_char = char::from_u32(
-                    number
-                        .try_into()
-                        .map_err(|_| DemangleError::InvalidTemplatedCharacterValue(r, number))?,
-                )
-                .ok_or(DemangleError::InvalidTemplatedCharacterValue(r, number))?;
-                let t = format!("'{demangled_char}'");
-                (r, DemangledArg::Plain(t, None.into()))
+        // Same as `demangle_argument` does for a `const` class.
+        let mut text = template.text.clone();
+        push_post_qualifiers(config, &mut text, "const ");
+        let arg = StlArg {
+            unqualified: Some(Box::new(template)),
+            ..StlArg::other(text)
+        };
+        return Ok(Some(Remaining::new(r, arg)));
+    }
+
+    let Some(Remaining {
+        r,
+        d: (text, unqualified),
+    }) = demangle_const_argument(config, s, types, template_args, allow_array_fixup)?
+    else {
+        return Ok(None);
+    };
+    let arg = StlArg {
+        unqualified: Some(Box::new(StlArg::other(unqualified))),
+        ..StlArg::other(text)
+    };
+    Ok(Some(Remaining::new(r, arg)))
+}
+
+/// Read a template argument starting with a counted name, like `5tName`, as a
+/// class without its `Z`, if using the alternative reading of ambiguous
+/// template arguments.
+fn class_missing_type_marker<'s>(
+    config: &Context,
+    s: &'s str,
+    types: &ArgVec,
+    template_args: &ArgVec,
+    allow_array_fixup: bool,
+) -> Option<(&'s str, DemangledArg)> {
+    if !s.starts_with(|c| matches!(c, '1'..='9')) || !config.alternative_template_reading() {
+        return None;
+    }
+
+    config
+        .attempt(|| demangle_argument(config, s, types, template_args, allow_array_fixup))
+        .ok()
+}
+
+fn demangle_templated_value<'s>(
+    config: &Context,
+    s: &'s str,
+    template_args: &ArgVec,
+    allow_array_fixup: bool,
+) -> Result<Remaining<'s, DemangledArg>, DemangleError<'s>> {
+    config.trace("template value", s, || {
+        let mut r = s;
+        let mut is_pointer = false;
+        let mut is_reference = false;
+        let mut is_unsigned = false;
+
+        // Skip over any known qualifier
+        while !r.is_empty() {
+            let c = r.chars().next().ok_or(DemangleError::RanOutOfArguments)?;
+
+            match c {
+                // '*'
+                'P' => is_pointer = true,
+                // '&'
+                'R' => is_reference = true,
+                // "const" | "volatile" | "__restrict"
+                'C' | 'V' | 'u' => {}
+                // "signed"
+                'S' => {}
+                // "unsigned"
+                'U' => is_unsigned = true,
+                _ => break,
             }
-            // "short" | "int" | "long" | "long long"
-            's' | 'i' | 'l' | 'x' => {
-                if let Some(r) = r.strip_prefix('Y') {
-                    // Y01 -> Use value at index 0 from the template list. No
-                    // idea about the second digit
-
-                    // TODO: what happens if the index is larger than 9?
-                    let Some(Remaining { r, d: index }) = r.p_digit() else {
-                        return Err(DemangleError::MissingLookbackIndexForTemplatedValue(s));
-                    };
-                    let Some(Remaining { r, d: digit1 }) = r.p_digit() else {
-                        return Err(DemangleError::MissingLookbackSecondDigitForTemplatedValue(
-                            s,
+
+            r = &r[1..];
+        }
+
+        let qualifiers = &s[..s.len() - r.len()];
+        let (remaining, arg) = if qualifiers.ends_with('P') && r.starts_with(['O', 'M']) {
+            // Keep the `P` so the member type gets parsed as a pointer.
+            let pointer = &s[qualifiers.len() - 1..];
+            demangle_templated_member_pointer_value(config, pointer, allow_array_fixup)?
+        } else if is_pointer || is_reference {
+            let (aux, demangled_arg) = demangle_argument(
+                config,
+                r,
+                &ArgVec::new(config, None),
+                &ArgVec::new(config, None),
+                allow_array_fixup,
+            )?;
+
+            let (aux, t) = match demangled_arg {
+                DemangledArg::Plain(_arg, _array_qualifiers) => {
+                    let Remaining { r: aux, d: symbol } = demangle_custom_name(
+                        config,
+                        aux,
+                        DemangleError::InvalidSymbolNameOnTemplateType,
+                    )?;
+                    let ampersand = if is_pointer { "&" } else { "" };
+                    let symbol = config.tokens().mark(Token::Name, symbol);
+                    let t = format!("{ampersand}{symbol}");
+                    (aux, t)
+                }
+                DemangledArg::FunctionPointer(function_pointer) => {
+                    // Function pointers as types in template lists
+
+                    let FunctionPointer {
+                        return_type,
+                        array_qualifiers: _,
+                        post_qualifiers: _,
+                        args,
+                        spacing: _,
+                        parens,
+                        cuts,
+                    } = function_pointer;
+
+                    let Remaining { r: aux, d: symbol } = demangle_custom_name(
+                        config,
+                        aux,
+                        DemangleError::InvalidSymbolNameOnTemplateType,
+                    )?;
+
+                    // TODO: check `_mangled_args` demangles to `args`
+                    let Some((actual_sym, _mangled_args)) = symbol.c_split2("__F") else {
+                        return Err(DemangleError::InvalidFunctionPointerTypeInTemplatedList(
+                            r, symbol,
                         ));
                     };
-                    if digit1 != 1 {
-                        return Err(DemangleError::InvalidLookbackSecondDigitForTemplatedValue(
-                            s, digit1,
-                        ));
-                    }
 
-                    let Some(templated_value) = template_args.get(index) else {
-                        return Err(DemangleError::IndexTooBigForYArgument(s, index));
+                    let ampersand = if is_pointer { "&" } else { "" };
+                    let actual_sym = config.tokens().mark(Token::Name, actual_sym);
+                    config.saw(Construct::FunctionPointerInTemplate);
+                    let args = ParameterList::new(&args, parens, cuts);
+                    let t = if config.fix_function_pointers_in_template_lists {
+                        let declarator = if is_pointer { "(*)" } else { "(&)" };
+                        let (open, close) = (cuts.open(), cuts.close());
+                        format!("{open}({return_type}{declarator}{args}{close}) {ampersand}{actual_sym}")
+                    } else {
+                        format!("{ampersand}{actual_sym}{args}")
+                    };
+                    (aux, t)
+                }
+                DemangledArg::MethodPointer(..)
+                | DemangledArg::Repeat { .. }
+                | DemangledArg::Ellipsis => {
+                    return Err(DemangleError::InvalidTemplatedPointerReferenceValue(r))
+                }
+            };
+
+            (aux, DemangledArg::Plain(t, None.into()))
+        } else {
+            let remaining = r;
+            let Remaining { r, d: c } = remaining
+                .p_first()
+                .ok_or(DemangleError::RanOutOfArguments)?;
+
+            // Add a way to make clear which type is being used.
+            match c {
+                // "char" | "wchar_t"
+                'c' | 'w' => {
+                    if let Some(r) = r.strip_prefix('Y') {
+                        templated_value_lookback(s, r, template_args)?
+                    } else {
+                        let Remaining { r, d: number } = r
+                            .p_number()
+                            .ok_or(DemangleError::InvalidTemplatedNumberForCharacterValue(r))?;
+                        let t = if c == 'w' {
+                            config.saw(Construct::WideCharacterInTemplate);
+                            if config.emit_wide_character_literals_in_templates {
+                                wide_character_literal(number)
+                            } else {
+                                format!("'{}'", character_value(r, number)?)
+                            }
+                        } else {
+                            format!("'{}'", character_value(r, number)?)
+                        };
+                        let t = config.tokens().marked(Token::Literal, &t).into_owned();
+                        (r, DemangledArg::Plain(t, None.into()))
+                    }
+                }
+                // "short" | "int" | "long" | "long long"
+                's' | 'i' | 'l' | 'x' => {
+                    if let Some(r) = r.strip_prefix('Y') {
+                        templated_value_lookback(s, r, template_args)?
+                    } else {
+                        let (r, negative) = r.c_maybe_strip_prefix('m');
+                        let Remaining { r, d: number } = if let Some(r) = r.strip_prefix('_') {
+                            r.p_number_maybe_multi_digit()
+                                .ok_or(DemangleError::InvalidValueForIntegralTemplated(r))?
+                        } else {
+                            r.p_number()
+                                .ok_or(DemangleError::InvalidValueForIntegralTemplated(r))?
+                        };
+                        let sign = if negative { "-" } else { "" };
+                        config.saw(Construct::IntegerInTemplate);
+                        let suffix = if config.emit_integer_suffixes_in_templates {
+                            match (c, is_unsigned) {
+                                ('s', _) | ('i', false) => "",
+                                ('i', true) => "u",
+                                ('l', false) => "l",
+                                ('l', true) => "ul",
+                                ('x', false) => "ll",
+                                _ => "ull",
+                            }
+                        } else {
+                            ""
+                        };
+                        let t = format!("{sign}{number}{suffix}");
+                        let t = config.tokens().marked(Token::Literal, &t).into_owned();
+                        (r, DemangledArg::Plain(t, None.into()))
+                    }
+                }
+                // 'f' => {}, // "float"
+                // 'd' => {}, // "double"
+                // 'r' => {}, // "long double"
+                // "bool"
+                'b' => {
+                    let value = match r.chars().next() {
+                        Some('1') => "true",
+                        Some('0') => "false",
+                        _ => return Err(DemangleError::InvalidTemplatedBoolean(r)),
                     };
-                    (
-                        r,
-                        DemangledArg::Plain(templated_value.to_string(), None.into()),
-                    )
-                } else {
+                    let t = config.tokens().marked(Token::Literal, value).into_owned();
+                    (&r[1..], DemangledArg::Plain(t, None.into()))
+                }
+                '1'..='9' => {
+                    // enum
+                    let Remaining { r, d: enum_name } = demangle_custom_name(
+                        config,
+                        remaining,
+                        DemangleError::InvalidEnumNameForTemplatedValue,
+                    )?;
+
                     let (r, negative) = r.c_maybe_strip_prefix('m');
-                    let Remaining { r, d: number } = if let Some(r) = r.strip_prefix('_') {
-                        r.p_number_maybe_multi_digit()
-                            .ok_or(DemangleError::InvalidValueForIntegralTemplated(r))?
+                    let Remaining { r, d: number } = r
+                        .p_number()
+                        .ok_or(DemangleError::InvalidValueForIntegralTemplated(r))?;
+                    let sign = if negative { "-" } else { "" };
+                    config.saw(Construct::EnumInTemplate);
+                    let value = format!("{sign}{number}");
+                    let value = config.tokens().mark(Token::Literal, &value);
+                    let t = if config.emit_enum_casts_in_templates {
+                        let enum_name = config.tokens().mark(Token::Type, enum_name);
+                        format!("({enum_name}){value}")
                     } else {
-                        r.p_number()
-                            .ok_or(DemangleError::InvalidValueForIntegralTemplated(r))?
+                        value.to_string()
                     };
-                    let t = format!("{}{}", if negative { "-" } else { "" }, number);
                     (r, DemangledArg::Plain(t, None.into()))
                 }
+                _ => return Err(DemangleError::InvalidTypeValueForTemplated(c, r)),
             }
-            // 'f' => {}, // "float"
-            // 'd' => {}, // "double"
-            // 'r' => {}, // "long double"
-            // "bool"
-            'b' => match r.chars().next() {
-                Some('1') => (
-                    &r[1..],
-                    DemangledArg::Plain("true".to_string(), None.into()),
-                ),
-                Some('0') => (
-                    &r[1..],
-                    DemangledArg::Plain("false".to_string(), None.into()),
-                ),
-                _ => return Err(DemangleError::InvalidTemplatedBoolean(r)),
-            },
-            '1'..='9' => {
-                // enum
-                let Remaining { r, d: _enum_name } = demangle_custom_name(
-                    remaining,
-                    DemangleError::InvalidEnumNameForTemplatedValue,
-                )?;
-
-                // TODO: <(SomeEnum)0> is valid c++, try to use it somehow.
-
-                let (r, negative) = r.c_maybe_strip_prefix('m');
-                let Remaining { r, d: number } = r
-                    .p_number()
-                    .ok_or(DemangleError::InvalidValueForIntegralTemplated(r))?;
-                let t = format!("{}{}", if negative { "-" } else { "" }, number);
-                (r, DemangledArg::Plain(t, None.into()))
+        };
+
+        Ok(Remaining::new(remaining, arg))
+    })
+}
+
+/// Y01 -> Use value at index 0 from the template list.
+///
+/// The second digit is the template level, like on `X` lookbacks. It is
+/// ignored like c++filt does, since the template list already includes the
+/// arguments of the enclosing templates.
+///
+/// Any rendering particular to the type of the value, like the `L` prefix of
+/// `wchar_t` values, is carried over since the already demangled value is
+/// reused.
+fn templated_value_lookback<'s>(
+    s: &'s str,
+    r: &'s str,
+    template_args: &ArgVec,
+) -> Result<(&'s str, DemangledArg), DemangleError<'s>> {
+    // TODO: what happens if the index is larger than 9?
+    let Some(Remaining { r, d: index }) = r.p_digit() else {
+        return Err(DemangleError::MissingLookbackIndexForTemplatedValue(s));
+    };
+    let Some(Remaining { r, d: _level }) = r.p_digit() else {
+        return Err(DemangleError::MissingLookbackSecondDigitForTemplatedValue(
+            s,
+        ));
+    };
+
+    let Some(templated_value) = template_args.get(index) else {
+        return Err(DemangleError::IndexTooBigForYArgument(s, index));
+    };
+    Ok((
+        r,
+        DemangledArg::Plain(templated_value.to_string(), None.into()),
+    ))
+}
+
+fn character_value(r: &str, number: usize) -> Result<char, DemangleError<'_>> {
+    let value = number
+        .try_into()
+        .map_err(|_| DemangleError::InvalidTemplatedCharacterValue(r, number))?;
+    char::from_u32(value).ok_or(DemangleError::InvalidTemplatedCharacterValue(r, number))
+}
+
+/// Characters outside of the Basic Multilingual Plane don't fit on a 16 bits
+/// `wchar_t`, so they are spelled as an universal character name, and values
+/// that aren't characters at all, like surrogates, as a hex escape.
+fn wide_character_literal(number: usize) -> String {
+    match u32::try_from(number).ok().and_then(char::from_u32) {
+        Some(c) if u32::from(c) <= 0xFFFF => format!("L'{c}'"),
+        Some(c) => format!("L'\\U{:08X}'", u32::from(c)),
+        None => format!("L'\\x{number:x}'"),
+    }
+}
+
+/// Pointers to data members (`PO`) and to methods (`PM`).
+fn demangle_templated_member_pointer_value<'s>(
+    config: &Context,
+    s: &'s str,
+    allow_array_fixup: bool,
+) -> Result<(&'s str, DemangledArg), DemangleError<'s>> {
+    let (_, class_name) = demangle_member_pointer_class(
+        config,
+        &s[2..],
+        &ArgVec::new(config, None),
+        allow_array_fixup,
+        DemangleError::InvalidTemplatedPointerReferenceValue,
+    )?;
+
+    let (r, member_type) = demangle_argument(
+        config,
+        s,
+        &ArgVec::new(config, None),
+        &ArgVec::new(config, None),
+        allow_array_fixup,
+    )?;
+
+    let Remaining { r, d: symbol } =
+        demangle_custom_name(config, r, DemangleError::InvalidSymbolNameOnTemplateType)?;
+
+    // Methods may use their full mangled name, i.e. `method__3Foo`.
+    let member = symbol.c_split2("__").map_or(symbol, |(name, _)| name);
+
+    let separator = config.scope_separator;
+    let class_name = config.tokens().scope(&class_name);
+    let member = config.tokens().mark(Token::Name, member);
+    let t = match member_type {
+        DemangledArg::MethodPointer(method_pointer) => {
+            config.saw(Construct::FunctionPointerInTemplate);
+            if config.fix_function_pointers_in_template_lists {
+                let (open, close) = (config.cuts().open(), config.cuts().close());
+                format!("{open}({method_pointer}{close}) &{class_name}{separator}{member}")
+            } else {
+                format!("&{class_name}{separator}{member}")
             }
-            _ => return Err(DemangleError::InvalidTypeValueForTemplated(c, r)),
         }
+        _ => format!("&{class_name}{separator}{member}"),
     };
-
-    Ok(Remaining::new(remaining, arg))
+    Ok((r, DemangledArg::Plain(t, None.into())))
 }
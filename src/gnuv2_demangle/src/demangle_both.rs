@@ -0,0 +1,89 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+use alloc::{string::String, vec::Vec};
+
+use crate::{
+    demangle, demangle_with_info, features, features::set_feature, Construct, DemangleConfig,
+    DemangleError, DemangleInfo,
+};
+
+/// The output of [`demangle_both`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub struct BothOutputs {
+    /// Demangled with [`DemangleConfig::new_cfilt`].
+    pub cfilt: String,
+    /// Demangled with [`DemangleConfig::new_g2dem`].
+    pub g2dem: String,
+    /// The settings of [`DemangleConfig`] that change the c++filt output of
+    /// this symbol when set to their g2dem value, in declaration order.
+    pub changed_by: Vec<&'static str>,
+    /// The constructs found by either demangling.
+    pub info: DemangleInfo,
+}
+
+/// Demangle `sym` with both [`DemangleConfig::new_cfilt`] and
+/// [`DemangleConfig::new_g2dem`], and find which settings make them differ.
+///
+/// Each setting that differs between both presets is set to its g2dem value on
+/// top of the c++filt preset, one at a time. The symbol is only demangled
+/// again if it has the construct the setting cares about, see
+/// [`FeatureDesc::construct`](crate::FeatureDesc::construct).
+///
+/// Fails if either preset fails to demangle the symbol.
+///
+/// # Examples
+///
+/// ```
+/// use gnuv2_demangle::demangle_both;
+///
+/// let both = demangle_both("foo__FPA3_iPCce").unwrap();
+/// assert_eq!(both.cfilt, "foo(int (*)[3], char const *,...)");
+/// assert_eq!(both.g2dem, "foo(int (*)[4], char const *, ...)");
+/// assert_eq!(
+///     both.changed_by,
+///     ["fix_array_length_arg", "ellipsis_emit_space_after_comma"]
+/// );
+/// ```
+pub fn demangle_both(sym: &str) -> Result<BothOutputs, DemangleError<'_>> {
+    let cfilt_config = DemangleConfig::new_cfilt();
+    let g2dem_config = DemangleConfig::new_g2dem();
+
+    let (cfilt, cfilt_info) = demangle_with_info(sym, &cfilt_config);
+    let (g2dem, g2dem_info) = demangle_with_info(sym, &g2dem_config);
+    let cfilt = cfilt?;
+    let g2dem = g2dem?;
+    let info = cfilt_info.union(g2dem_info);
+
+    let changes_output = |config: &DemangleConfig| demangle(sym, config).as_ref() != Ok(&cfilt);
+
+    let mut changed_by = Vec::new();
+    for feature in features() {
+        if feature.default_cfilt == feature.default_g2dem || !info.saw(feature.construct) {
+            continue;
+        }
+
+        let mut config = cfilt_config;
+        set_feature(&mut config, feature.name, feature.default_g2dem);
+        if changes_output(&config) {
+            changed_by.push(feature.name);
+        }
+    }
+
+    // Not a flag, so it is not listed by `features`.
+    if info.saw(Construct::Declarator) {
+        let mut config = cfilt_config;
+        config.type_pointer_spacing = g2dem_config.type_pointer_spacing;
+        if changes_output(&config) {
+            changed_by.push("type_pointer_spacing");
+        }
+    }
+
+    Ok(BothOutputs {
+        cfilt,
+        g2dem,
+        changed_by,
+        info,
+    })
+}
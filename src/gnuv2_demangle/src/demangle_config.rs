@@ -1,12 +1,20 @@
 /* SPDX-FileCopyrightText: © 2025 Decompollaborate */
 /* SPDX-License-Identifier: MIT OR Apache-2.0 */
 
+use crate::DemangleConfigBuilder;
+
 /// Tweak how a symbol should be disassembled.
 ///
 /// The constructors provide sensible defaults, so there's usually no need to
 /// override each option.
 ///
 /// Refer to each option to see what it does and examples.
+///
+/// Use [`DemangleConfig::builder`] to tweak a few options in a
+/// forward-compatible way.
+// Comparing `vendor_type_map` by address is good enough to tell configs
+// apart.
+#[allow(unknown_lints, unpredictable_function_pointer_comparisons)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[non_exhaustive]
 pub struct DemangleConfig {
@@ -91,6 +99,90 @@ pub struct DemangleConfig {
     /// ```
     pub fix_array_length_arg: bool,
 
+    /// Don't add 1 to the length of `A0_` arrays when
+    /// [`fix_array_length_arg`] is turned on.
+    ///
+    /// A mangled `A0_` length is an edge case, since bumping it produces a
+    /// `[1]` array even if the original symbol may have used a zero-length
+    /// or flexible array. This setting keeps the length of those arrays as
+    /// `[0]` while still fixing every other length.
+    ///
+    /// This setting does nothing if [`fix_array_length_arg`] is turned off.
+    ///
+    /// [`fix_array_length_arg`]: DemangleConfig::fix_array_length_arg
+    ///
+    /// # Examples
+    ///
+    /// Turning off this setting:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.fix_array_length_arg = true;
+    /// config.fix_array_length_skip_zero = false;
+    ///
+    /// let demangled = demangle("zero_array__FPA0_A5_i", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("zero_array(int (*)[1][6])")
+    /// );
+    /// ```
+    ///
+    /// The setting turned on:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.fix_array_length_arg = true;
+    /// config.fix_array_length_skip_zero = true;
+    ///
+    /// let demangled = demangle("zero_array__FPA0_A5_i", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("zero_array(int (*)[0][6])")
+    /// );
+    /// ```
+    pub fix_array_length_skip_zero: bool,
+
+    /// Demangle arrays of references, like the `A9_Ri` of `f__FA9_Ri`, as
+    /// references to arrays.
+    ///
+    /// Arrays of references are not valid C++, but some compiler revisions
+    /// mangle a reference to an array argument, like `int (&)[10]`, with the
+    /// reference after the array instead of before it (`A9_Ri` instead of
+    /// `RA9_i`).
+    ///
+    /// c++filt demangles them as arrays of references.
+    ///
+    /// # Examples
+    ///
+    /// Turning off this setting (mimicking c++filt behavior):
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.fix_array_of_references = false;
+    ///
+    /// let demangled = demangle("sum__FA9_Ri", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("sum(int &[10])"));
+    /// ```
+    ///
+    /// The setting turned on:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.fix_array_of_references = true;
+    ///
+    /// let demangled = demangle("sum__FA9_Ri", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("sum(int (&)[10])"));
+    /// ```
+    pub fix_array_of_references: bool,
+
     /// Recognize and demangle symbols prefixed by `_GLOBAL_$F$`.
     ///
     /// c++filt does not recognizes this prefix, so it tries to demangle it as
@@ -145,6 +237,319 @@ pub struct DemangleConfig {
     /// ```
     pub demangle_global_keyed_frames: bool,
 
+    /// Recognize and demangle symbols prefixed by `_GLOBAL_$T$`.
+    ///
+    /// Some GCC 2.x toolchains emit these next to explicit template
+    /// instantiations. c++filt does not recognize this prefix either, so it
+    /// tries to demangle it as other mangled kinds.
+    ///
+    /// When turned on, the symbol gets demangled the same way `_GLOBAL_$I$`
+    /// and `_GLOBAL_$D$` are demangled, but the words "template
+    /// instantiations" are used instead of "constructors" or "destructors".
+    ///
+    /// # Examples
+    ///
+    /// Turning off this setting (mimicking c++filt behavior):
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.demangle_global_keyed_template_instantiations = false;
+    ///
+    /// let demangled = demangle("_GLOBAL_$T$Insert__t4List1ZiRCi", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("List<int>::_GLOBAL_$T$Insert(int const &)")
+    /// );
+    /// let demangled = demangle("_GLOBAL_$T$gErrFileName", &config);
+    /// assert!(demangled.is_err());
+    /// ```
+    ///
+    /// The setting turned on:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.demangle_global_keyed_template_instantiations = true;
+    ///
+    /// let demangled = demangle("_GLOBAL_$T$Insert__t4List1ZiRCi", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("global template instantiations keyed to List<int>::Insert(int const &)")
+    /// );
+    /// ```
+    pub demangle_global_keyed_template_instantiations: bool,
+
+    /// Demangle a `_GLOBAL_` keyed symbol whose payload is itself a keyed
+    /// symbol, like `_GLOBAL_$I$_GLOBAL_$D$gSomething`.
+    ///
+    /// Compilers don't emit these, but tools that concatenate keys may.
+    /// c++filt only recognizes the outer key, so the payload is shown as-is
+    /// if it has no arguments, or demangled as a plain function otherwise.
+    ///
+    /// When turned on, a single level of nesting is demangled, using the
+    /// same separator as the outer key. The payload of the inner key is
+    /// never checked for another key, so this can't recurse any further.
+    ///
+    /// # Examples
+    ///
+    /// Turning off this setting (mimicking c++filt behavior):
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.demangle_nested_global_keyed_symbols = false;
+    ///
+    /// let demangled = demangle("_GLOBAL_$I$_GLOBAL_$D$gSomething", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("global constructors keyed to _GLOBAL_$D$gSomething")
+    /// );
+    /// ```
+    ///
+    /// The setting turned on:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.demangle_nested_global_keyed_symbols = true;
+    ///
+    /// let demangled = demangle("_GLOBAL_$I$_GLOBAL_$D$gSomething", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("global constructors keyed to global destructors keyed to gSomething")
+    /// );
+    /// ```
+    pub demangle_nested_global_keyed_symbols: bool,
+
+    /// Describe `_GLOBAL_$I$` and `_GLOBAL_$D$` symbols keyed to the function
+    /// g++ generates to initialize and destroy the static objects of a file,
+    /// `__static_initialization_and_destruction_0__Fii`, instead of showing
+    /// that function as-is.
+    ///
+    /// Symbols for a specific `init_priority` have the priority right before
+    /// the `__F`, like `__static_initialization_and_destruction_0_65535__Fii`.
+    ///
+    /// # Examples
+    ///
+    /// Turning off this setting (mimicking c++filt behavior):
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.describe_static_init_functions = false;
+    ///
+    /// let demangled = demangle(
+    ///     "_GLOBAL_$I$__static_initialization_and_destruction_0_65535__Fii",
+    ///     &config,
+    /// );
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("global constructors keyed to __static_initialization_and_destruction_0_65535(int, int)")
+    /// );
+    /// ```
+    ///
+    /// The setting turned on:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.describe_static_init_functions = true;
+    ///
+    /// let demangled = demangle(
+    ///     "_GLOBAL_$I$__static_initialization_and_destruction_0_65535__Fii",
+    ///     &config,
+    /// );
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("static initialization and destruction (priority 65535) for global constructors")
+    /// );
+    /// let demangled = demangle(
+    ///     "_GLOBAL_$D$__static_initialization_and_destruction_0__Fii",
+    ///     &config,
+    /// );
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("static initialization and destruction for global destructors")
+    /// );
+    /// ```
+    pub describe_static_init_functions: bool,
+
+    /// Demangle the base class offset some targets add to the secondary
+    /// virtual tables of classes with multiple inheritance, like the `$8` of
+    /// `_vt$7Derived$4Base$8` or the `_8` of `_8_vt$7Derived$4Base`.
+    ///
+    /// c++filt doesn't recognize the offset, so it fails to demangle these
+    /// symbols.
+    ///
+    /// A component made only of digits is only an offset if it is the last
+    /// one, since a counted class name would need something after the
+    /// digits. Trailing offsets are only recognized with `$` as the
+    /// separator, since `_vt.5tName.4` is a clone of `_vt.5tName`.
+    ///
+    /// # Examples
+    ///
+    /// Turning off this setting (mimicking c++filt behavior):
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.demangle_vtable_offsets = false;
+    ///
+    /// assert!(demangle("_vt$7Derived$4Base$8", &config).is_err());
+    /// ```
+    ///
+    /// The setting turned on:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.demangle_vtable_offsets = true;
+    ///
+    /// let demangled = demangle("_vt$7Derived$4Base$8", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("Derived::Base virtual table (at offset 8)")
+    /// );
+    /// let demangled = demangle("_8_vt$7Derived$4Base", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("Derived::Base virtual table (at offset 8)")
+    /// );
+    /// ```
+    pub demangle_vtable_offsets: bool,
+
+    /// Don't demangle C symbols that happen to look like methods without
+    /// arguments, like `crc32__8_compute`, returning
+    /// [`DemangleError::NotMangled`] instead.
+    ///
+    /// A symbol is taken as plain C if the name before the `__` only has
+    /// lowercase letters, digits and underscores, the class name starts with
+    /// an underscore followed by a lowercase letter or a digit, and there are
+    /// no arguments after it, or its length goes past the end of the symbol.
+    /// Symbols like `md5__4impl` are still ambiguous and get demangled as
+    /// methods.
+    ///
+    /// c++filt demangles all of them as methods.
+    ///
+    /// [`DemangleError::NotMangled`]: crate::DemangleError::NotMangled
+    ///
+    /// # Examples
+    ///
+    /// Turning off this setting (mimicking c++filt behavior):
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.reject_plain_c_lookalikes = false;
+    ///
+    /// let demangled = demangle("crc32__8_compute", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("_compute::crc32(void)"));
+    /// ```
+    ///
+    /// The setting turned on:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig, DemangleError};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.reject_plain_c_lookalikes = true;
+    ///
+    /// let demangled = demangle("crc32__8_compute", &config);
+    /// assert_eq!(demangled, Err(DemangleError::NotMangled));
+    ///
+    /// let demangled = demangle("init__7filebuf", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("filebuf::init(void)"));
+    /// ```
+    pub reject_plain_c_lookalikes: bool,
+
+    /// Drop the `const` of parameters passed by value, like the one of
+    /// `f__FC3Foo`.
+    ///
+    /// A `const` on the parameter itself is not part of the function type, so
+    /// `void f(const Foo x)` is the same function as `void f(Foo x)`. The
+    /// `const` behind a pointer or a reference, like the one of `f__FPC3Foo`,
+    /// is kept. Repeated arguments referring to a stripped one are stripped
+    /// too.
+    ///
+    /// c++filt emits the `const`.
+    ///
+    /// # Examples
+    ///
+    /// Turning off this setting (mimicking c++filt behavior):
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.strip_top_level_const_on_value_args = false;
+    ///
+    /// let demangled = demangle("f__FC3FooT0", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("f(Foo const, Foo const)"));
+    /// ```
+    ///
+    /// The setting turned on:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.strip_top_level_const_on_value_args = true;
+    ///
+    /// let demangled = demangle("f__FC3FooT0", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("f(Foo, Foo)"));
+    ///
+    /// let demangled = demangle("f__FPC3Foo", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("f(Foo const *)"));
+    /// ```
+    pub strip_top_level_const_on_value_args: bool,
+
+    /// Emit the namespaces generated for anonymous namespaces as
+    /// `(anonymous namespace)`, like modern tools do.
+    ///
+    /// g++ names anonymous namespaces after the translation unit, using names
+    /// like `_GLOBAL_.N.foo_cc`, `_GLOBAL_$N$foo_cc` or `__N_foo_cc`, depending
+    /// on the release. Those names are recognized as namespace components of
+    /// qualified names, like `Q2_17_GLOBAL_.N.foo_cc3Bar`.
+    ///
+    /// c++filt emits the generated name.
+    ///
+    /// # Examples
+    ///
+    /// Turning off this setting (mimicking c++filt behavior):
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.pretty_anonymous_namespaces = false;
+    ///
+    /// let demangled = demangle("Id__Q2_17_GLOBAL_.N.foo_cc3Bar", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("_GLOBAL_.N.foo_cc::Bar::Id(void)"));
+    /// ```
+    ///
+    /// The setting turned on:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.pretty_anonymous_namespaces = true;
+    ///
+    /// let demangled = demangle("Id__Q2_17_GLOBAL_.N.foo_cc3Bar", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("(anonymous namespace)::Bar::Id(void)"));
+    /// ```
+    pub pretty_anonymous_namespaces: bool,
+
     /// Emit an space between a comma and an ellipsis (`...`) in the argument
     /// list.
     ///
@@ -189,6 +594,10 @@ pub struct DemangleConfig {
     /// The former is valid syntax in g++ for this GNU integer extension type,
     /// while the latter is the syntax used by c++filt, but not accepted by g++.
     ///
+    /// Extension integers of other widths, like the `I40` of some vendor
+    /// compilers, are emitted as `int64_t` and `uint64_t` instead of `int64_t`
+    /// and `unsigned int64_t`.
+    ///
     /// This is just another c++filt compatibility setting.
     ///
     /// # Examples
@@ -315,18 +724,1145 @@ pub struct DemangleConfig {
     /// );
     /// ```
     pub fix_function_pointers_in_template_lists: bool,
-}
 
-impl DemangleConfig {
-    /// The default configuration.
-    #[must_use]
-    #[inline]
-    #[track_caller]
+    /// Collapse the well-known SGI-STL templates used by GCC 2.9x into their
+    /// user-facing names.
+    ///
+    /// The following patterns are recognized:
+    ///
+    /// - `vector<T, allocator<T> >` (also `list` and `deque`) as `vector<T>`.
+    /// - `basic_string<char, string_char_traits<char>, __default_alloc_template<...> >`
+    ///   as `string`.
+    /// - `map<K, V, less<K>, allocator<V> >` and
+    ///   `_Rb_tree<K, pair<K const, V>, _Select1st<pair<K const, V> >, less<K>, allocator<V> >`
+    ///   as `map<K, V>`.
+    ///
+    /// Nested templates are simplified too. Templates that don't match any of
+    /// those exact patterns, like the ones using a custom allocator, are left
+    /// untouched.
+    ///
+    /// This setting is turned off on every preset, since the output no longer
+    /// matches the real instantiated names.
+    ///
+    /// # Examples
+    ///
+    /// Turning off this setting:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.simplify_stl_containers = false;
+    ///
+    /// let demangled = demangle("size__Ct6vector2ZiZt9allocator1Zi", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("vector<int, allocator<int> >::size(void) const")
+    /// );
+    /// ```
+    ///
+    /// The setting turned on:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.simplify_stl_containers = true;
+    ///
+    /// let demangled = demangle("size__Ct6vector2ZiZt9allocator1Zi", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("vector<int>::size(void) const")
+    /// );
+    /// ```
+    pub simplify_stl_containers: bool,
+
+    /// Recognize the constructor variant marker emitted by some hybrid
+    /// toolchains, like RedHat's GCC 2.96.
+    ///
+    /// Those toolchains may emit a single digit between the class name of a
+    /// constructor and its argument list, to tell apart the in-charge (`1`)
+    /// and not-in-charge (`2`) variants of it. c++filt does not know about
+    /// this marker and tries to parse it as the length of a class name
+    /// argument.
+    ///
+    /// When turned on, the marker is skipped and an annotation is appended
+    /// to the demangled constructor. The marker is only recognized if the
+    /// argument list can't be demangled otherwise, so symbols where the digit
+    /// is the length of a class name argument keep working.
+    ///
+    /// When turned off, those symbols fail with
+    /// [`DemangleError::ConstructorVariantMarker`].
+    ///
+    /// [`DemangleError::ConstructorVariantMarker`]: crate::DemangleError::ConstructorVariantMarker
+    ///
+    /// # Examples
+    ///
+    /// Turning off this setting (mimicking c++filt behavior):
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig, DemangleError};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.demangle_constructor_variants = false;
+    ///
+    /// let demangled = demangle("__5tName2i", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Err(&DemangleError::ConstructorVariantMarker("2i"))
+    /// );
+    /// ```
+    ///
+    /// The setting turned on:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.demangle_constructor_variants = true;
+    ///
+    /// let demangled = demangle("__5tName2i", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("tName::tName(int) [not-in-charge]")
+    /// );
+    /// ```
+    pub demangle_constructor_variants: bool,
+
+    /// Emit the enum type of enum values used as template arguments, as a
+    /// cast.
+    ///
+    /// The mangled symbol includes the enum type, but c++filt only emits the
+    /// numeric value, making it impossible to know which enumerator was used
+    /// just from the demangled symbol.
+    ///
+    /// This setting applies to every kind of symbol involving the templated
+    /// class, like its methods, its virtual table or its type info, so all of
+    /// them are demangled consistently.
+    ///
+    /// # Examples
+    ///
+    /// Turning off this setting (mimicking c++filt behavior):
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.emit_enum_casts_in_templates = false;
+    ///
+    /// let demangled = demangle("Pop__t14CAutoTransform121G3DTRANSFORMSTATETYPE0", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("CAutoTransform<0>::Pop(void)")
+    /// );
+    /// ```
+    ///
+    /// The setting turned on:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.emit_enum_casts_in_templates = true;
+    ///
+    /// let demangled = demangle("Pop__t14CAutoTransform121G3DTRANSFORMSTATETYPE0", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("CAutoTransform<(G3DTRANSFORMSTATETYPE)0>::Pop(void)")
+    /// );
+    /// ```
+    pub emit_enum_casts_in_templates: bool,
+
+    /// If enabled, emit proper syntax for function pointers and method
+    /// pointers as return types in templated functions.
+    ///
+    /// Disabling this option make it mimic the c++filt behavior for function
+    /// pointers in return position, which is not valid C++ but is simpler to
+    /// read.
+    ///
+    /// # Examples
+    ///
+    /// Turning off this setting (mimicking c++filt behavior):
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.fix_function_pointer_in_return_position = false;
+    ///
+    /// let demangled = demangle("make_handler__H1Zi_X01_PFiX01_v", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("void (*)(int, int) make_handler<int>(int)")
+    /// );
+    /// ```
+    ///
+    /// The setting turned on:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.fix_function_pointer_in_return_position = true;
+    ///
+    /// let demangled = demangle("make_handler__H1Zi_X01_PFiX01_v", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("void (*make_handler<int>(int))(int, int)")
+    /// );
+    /// ```
+    pub fix_function_pointer_in_return_position: bool,
+
+    /// If enabled, emit the `u` qualifier as the C99 `restrict` keyword
+    /// instead of the `__restrict` GNU extension.
+    ///
+    /// `restrict` is not a C++ keyword, so c++filt always uses the
+    /// `__restrict` spelling.
+    ///
+    /// # Examples
+    ///
+    /// Turning off this setting (mimicking c++filt behavior):
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.emit_plain_restrict = false;
+    ///
+    /// let demangled = demangle("copy__FuPcuPCc", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("copy(char *__restrict, char const *__restrict)")
+    /// );
+    /// ```
+    ///
+    /// The setting turned on:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.emit_plain_restrict = true;
+    ///
+    /// let demangled = demangle("copy__FuPcuPCc", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("copy(char *restrict, char const *restrict)")
+    /// );
+    /// ```
+    pub emit_plain_restrict: bool,
+
+    /// GCC may add a `.<number>` discriminator to the end of a symbol, for
+    /// example when collapsing duplicated template instantiations from the
+    /// template repository (`-frepo`). If enabled, a symbol that fails to
+    /// demangle because of such suffix gets demangled without it.
+    ///
+    /// Class names may legitimately contain a `.<number>`, so the suffix is
+    /// only stripped if the whole symbol can't be demangled. If the symbol
+    /// still can't be demangled without the suffix then that error is
+    /// returned instead.
+    ///
+    /// See [`DemangleConfig::emit_clone_suffix`] for how the suffix gets
+    /// emitted.
+    ///
+    /// # Examples
+    ///
+    /// Turning off this setting:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig, DemangleError};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.demangle_clone_suffix = false;
+    ///
+    /// let demangled = demangle("SetText__5tNamePCc.1", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Err(&DemangleError::UnknownType('.', ".1"))
+    /// );
+    /// ```
+    ///
+    /// The setting turned on (c++filt behavior):
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.demangle_clone_suffix = true;
+    ///
+    /// let demangled = demangle("SetText__5tNamePCc.1", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("tName::SetText(char const *) [clone .1]")
+    /// );
+    /// ```
+    pub demangle_clone_suffix: bool,
+
+    /// If enabled, append the `.<number>` discriminator stripped by
+    /// [`DemangleConfig::demangle_clone_suffix`] as `[clone .<number>]`,
+    /// like modern c++filt does for the clones of Itanium symbols.
+    ///
+    /// c++filt silently drops the discriminator of GNU V2 symbols.
+    ///
+    /// # Examples
+    ///
+    /// Turning off this setting (mimicking c++filt behavior):
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.emit_clone_suffix = false;
+    ///
+    /// let demangled = demangle("SetText__5tNamePCc.23", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("tName::SetText(char const *)")
+    /// );
+    /// ```
+    ///
+    /// The setting turned on:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.emit_clone_suffix = true;
+    ///
+    /// let demangled = demangle("SetText__5tNamePCc.23", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("tName::SetText(char const *) [clone .23]")
+    /// );
+    /// ```
+    pub emit_clone_suffix: bool,
+
+    /// If enabled, skip a lone `_` found between the arguments of a
+    /// function, instead of failing to demangle it.
+    ///
+    /// Some old vendor compilers emit an errant `_` between the argument
+    /// groups of a function, like `Debug_Assert__FPcN20_i`. Such `_` can't
+    /// be told apart from the separators of the return type of templated
+    /// functions or the end of a function pointer, so it is only skipped at
+    /// the top level argument list, when the symbol would fail otherwise.
+    ///
+    /// # Examples
+    ///
+    /// Turning off this setting (mimicking c++filt behavior):
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig, DemangleError};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.tolerate_stray_underscores_between_args = false;
+    ///
+    /// let demangled = demangle("Debug_Assert__FPcN20_i", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Err(&DemangleError::TrailingDataAfterArgumentList("_i"))
+    /// );
+    /// ```
+    ///
+    /// The setting turned on:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.tolerate_stray_underscores_between_args = true;
+    ///
+    /// let demangled = demangle("Debug_Assert__FPcN20_i", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("Debug_Assert(char *, char *, char *, int)")
+    /// );
+    /// ```
+    pub tolerate_stray_underscores_between_args: bool,
+
+    /// If enabled, accept some malformed constructs emitted by buggy
+    /// compilers, instead of failing to demangle the symbol.
+    ///
+    /// None of these are valid GNU V2 mangling, so this is only meant for
+    /// corpora produced by known broken toolchains. The accepted constructs
+    /// are:
+    ///
+    /// - A namespace count of zero, like the `Q0` of `Id__Q03Foo`, which is
+    ///   read as a single name, like `Foo::Id(void)`.
+    /// - A lone `_` at the very end of an argument list, like the one of
+    ///   `Id__3Fooi_`, which is skipped.
+    /// - A name of length zero, like the last one of `Get__Q23foo0`, emitted
+    ///   by some compilers for unnamed structs. It is shown as `<unnamed>`,
+    ///   like `foo::<unnamed>::Get(void)`.
+    ///
+    /// Repeated method qualifiers, like the `CC` of `Id__CC3Foo`, are always
+    /// collapsed into a single one, regardless of this setting.
+    ///
+    /// c++filt fails to demangle these symbols.
+    ///
+    /// # Examples
+    ///
+    /// Turning off this setting (mimicking c++filt behavior):
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig, DemangleError};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.lenient = false;
+    ///
+    /// let demangled = demangle("Id__Q03Foo", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Err(&DemangleError::InvalidNamespaceCount("03Foo"))
+    /// );
+    ///
+    /// let demangled = demangle("Id__3Fooi_", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Err(&DemangleError::TrailingDataAfterArgumentList("_"))
+    /// );
+    ///
+    /// let demangled = demangle("Get__Q23foo0", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Err(&DemangleError::EmptyCustomName("0"))
+    /// );
+    /// ```
+    ///
+    /// The setting turned on:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.lenient = true;
+    ///
+    /// let demangled = demangle("Id__Q03Foo", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("Foo::Id(void)"));
+    ///
+    /// let demangled = demangle("Id__3Fooi_", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("Foo::Id(int)"));
+    ///
+    /// let demangled = demangle("Get__Q23foo0", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("foo::<unnamed>::Get(void)"));
+    /// ```
+    pub lenient: bool,
+
+    /// If enabled, emit proper syntax for pointers and references to function
+    /// pointers and method pointers used as template parameters of templated
+    /// functions.
+    ///
+    /// c++filt appends the qualifiers after the whole function pointer,
+    /// which is not valid C++.
+    ///
+    /// # Examples
+    ///
+    /// Turning off this setting (mimicking c++filt behavior):
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.fix_function_pointer_template_lookbacks = false;
+    ///
+    /// let demangled = demangle("call__H1ZPM6WidgetFP6Widgeti_v_PX01_v", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("void call<void (Widget::*)(int)>(void (Widget::*)(int) *)")
+    /// );
+    /// ```
+    ///
+    /// The setting turned on:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.fix_function_pointer_template_lookbacks = true;
+    ///
+    /// let demangled = demangle("call__H1ZPM6WidgetFP6Widgeti_v_PX01_v", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("void call<void (Widget::*)(int)>(void (Widget::**)(int))")
+    /// );
+    /// ```
+    pub fix_function_pointer_template_lookbacks: bool,
+
+    /// Emit the literal suffix of integer values in template lists, like
+    /// `1u` or `1ll`.
+    ///
+    /// c++filt prints every integer value as a plain number, so template
+    /// instances that only differ on the type of an integer parameter, like
+    /// `Foo<1>` and `Foo<1u>`, demangle the same.
+    ///
+    /// The suffix depends on the type: none for `int` and `short`, `u` for
+    /// `unsigned int`, `l` for `long`, `ul` for `unsigned long`, `ll` for
+    /// `long long` and `ull` for `unsigned long long`.
+    ///
+    /// # Examples
+    ///
+    /// Turning off this setting (mimicking c++filt behavior):
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.emit_integer_suffixes_in_templates = false;
+    ///
+    /// let demangled = demangle("Get__t3Foo1Ui1", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("Foo<1>::Get(void)"));
+    /// let demangled = demangle("Get__t3Foo1Ux1", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("Foo<1>::Get(void)"));
+    /// ```
+    ///
+    /// The setting turned on:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.emit_integer_suffixes_in_templates = true;
+    ///
+    /// let demangled = demangle("Get__t3Foo1Ui1", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("Foo<1u>::Get(void)"));
+    /// let demangled = demangle("Get__t3Foo1Ux1", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("Foo<1ull>::Get(void)"));
+    /// ```
+    pub emit_integer_suffixes_in_templates: bool,
+
+    /// Emit `wchar_t` values in template lists as wide character literals,
+    /// like `L'a'`, instead of plain character literals.
+    ///
+    /// Without this setting a `wchar_t` value is indistinguishable from a
+    /// `char` value of the same number, even if they are different
+    /// instantiations. Values outside of the Basic Multilingual Plane are
+    /// emitted as a `\U` escape and values that are not valid characters as a
+    /// `\x` escape, like `L'\U0001F600'` and `L'\xd800'`.
+    ///
+    /// # Examples
+    ///
+    /// Turning off this setting (mimicking c++filt behavior):
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.emit_wide_character_literals_in_templates = false;
+    ///
+    /// let demangled = demangle("Get__t3Foo2c97w97", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("Foo<'a', 'a'>::Get(void)"));
+    /// ```
+    ///
+    /// The setting turned on:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.emit_wide_character_literals_in_templates = true;
+    ///
+    /// let demangled = demangle("Get__t3Foo2c97w97", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("Foo<'a', L'a'>::Get(void)"));
+    /// let demangled = demangle("Get__t3Foo1w128512", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("Foo<L'\\U0001F600'>::Get(void)"));
+    /// ```
+    pub emit_wide_character_literals_in_templates: bool,
+
+    /// Resolve `X` lookbacks of templated methods of templated classes
+    /// against the template list their level refers to.
+    ///
+    /// The second digit of a `X` lookback, like the `2` of `X02`, is the
+    /// level of the template list it refers to: 0 is the template list of
+    /// the method itself, 1 the one of the class containing it, and so on.
+    /// Functions and methods of plain classes only have their own template
+    /// list, which they refer to with the level 1.
+    ///
+    /// c++filt ignores the level and always uses the template list of the
+    /// method.
+    ///
+    /// # Examples
+    ///
+    /// Turning off this setting (mimicking c++filt behavior):
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.fix_template_parameter_levels = false;
+    ///
+    /// let demangled = demangle("Get__H1Zf_t4Pair2ZiZdX00X01X11_v", &config);
+    /// assert_eq!(
+    ///     demangled,
+    ///     Err(gnuv2_demangle::DemangleError::IndexTooBigForXArgument("_v", 1))
+    /// );
+    /// ```
+    ///
+    /// The setting turned on:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.fix_template_parameter_levels = true;
+    ///
+    /// let demangled = demangle("Get__H1Zf_t4Pair2ZiZdX00X01X11_v", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("void Pair<int, double>::Get<float>(float, int, double)")
+    /// );
+    /// ```
+    pub fix_template_parameter_levels: bool,
+
+    /// Demangle the arguments of a truncated symbol up to where it was cut,
+    /// ending the argument list with a `<truncated>` marker.
+    ///
+    /// Some linkers and object formats limit the length of symbol names, so
+    /// long symbols may get cut in the middle of their argument list. A name
+    /// that runs past the end of the symbol is reported as
+    /// [`DemangleError::LikelyTruncated`], along with how many bytes are
+    /// missing from it.
+    ///
+    /// When turned on, the arguments parsed before the truncated one are kept
+    /// and the marker takes the place of the rest of them. Symbols truncated
+    /// before their argument list still fail.
+    ///
+    /// # Examples
+    ///
+    /// Turning off this setting (mimicking c++filt behavior):
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig, DemangleError};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.complete_truncated_symbols = false;
+    ///
+    /// let demangled = demangle("Collide__3SimP8SimStateiP15Collisi", &config);
+    /// assert_eq!(
+    ///     demangled,
+    ///     Err(DemangleError::LikelyTruncated("15Collisi", 8))
+    /// );
+    /// ```
+    ///
+    /// The setting turned on:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.complete_truncated_symbols = true;
+    ///
+    /// let demangled = demangle("Collide__3SimP8SimStateiP15Collisi", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("Sim::Collide(SimState *, int, <truncated>)")
+    /// );
+    /// ```
+    ///
+    /// [`DemangleError::LikelyTruncated`]: crate::DemangleError::LikelyTruncated
+    pub complete_truncated_symbols: bool,
+
+    /// Report the scope of a symbol as [`ScopeKind::Ambiguous`] on
+    /// [`demangle_components`] when the mangling can't tell whether it is a
+    /// class or a namespace.
+    ///
+    /// A function inside a single namespace and a method of a class without
+    /// namespace are mangled the same, so `a_function__4smolfffi` may be
+    /// either `smol::a_function` the free function or a method of the class
+    /// `smol`. Constructors, destructors, cast operators and `const` or
+    /// `volatile` methods are always reported as [`ScopeKind::Class`].
+    ///
+    /// This setting does not change the output of [`demangle`].
+    ///
+    /// [`ScopeKind::Ambiguous`]: crate::ScopeKind::Ambiguous
+    /// [`ScopeKind::Class`]: crate::ScopeKind::Class
+    /// [`demangle_components`]: crate::demangle_components
+    /// [`demangle`]: crate::demangle
+    ///
+    /// # Examples
+    ///
+    /// Turning off this setting (mimicking c++filt behavior):
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle_components, DemangleConfig, ScopeKind};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.annotate_ambiguous_scope = false;
+    ///
+    /// let components = demangle_components("a_function__4smolfffi", &config).unwrap();
+    /// assert_eq!(components.scope()[0].kind(), ScopeKind::Class);
+    /// ```
+    ///
+    /// The setting turned on:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle_components, DemangleConfig, ScopeKind};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.annotate_ambiguous_scope = true;
+    ///
+    /// let components = demangle_components("a_function__4smolfffi", &config).unwrap();
+    /// assert_eq!(components.scope()[0].kind(), ScopeKind::Ambiguous);
+    ///
+    /// let components = demangle_components("GetText__C5tName", &config).unwrap();
+    /// assert_eq!(components.scope()[0].kind(), ScopeKind::Class);
+    /// ```
+    pub annotate_ambiguous_scope: bool,
+
+    /// Leave out the parameter list of functions and methods, along with the
+    /// `const` or `volatile` qualifiers of methods.
+    ///
+    /// Useful for showing a short name, like on stack traces. Operators,
+    /// constructors and destructors keep their names, like `operator==` or
+    /// `~tName`.
+    ///
+    /// # Examples
+    ///
+    /// Turning off this setting:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.omit_parameter_list = false;
+    ///
+    /// let demangled = demangle("GetText__C5tNamei", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("tName::GetText(int) const"));
+    /// ```
+    ///
+    /// The setting turned on:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.omit_parameter_list = true;
+    ///
+    /// let demangled = demangle("GetText__C5tNamei", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("tName::GetText"));
+    /// let demangled = demangle("_$_5tName", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("tName::~tName"));
+    /// ```
+    pub omit_parameter_list: bool,
+
+    /// Where to place the return type of templated functions.
+    ///
+    /// Templated functions are the only ones with their return type mangled,
+    /// so [`ReturnTypeStyle::Omit`] makes them look like the rest of the
+    /// functions.
+    ///
+    /// See [`ReturnTypeStyle`] for the available styles.
+    ///
+    /// The return type of function pointers in arguments is not affected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig, ReturnTypeStyle};
+    ///
+    /// let mut config = DemangleConfig::new();
+    ///
+    /// config.return_type_style = ReturnTypeStyle::CfiltLeading;
+    /// let demangled = demangle("Swap__H1Zi_RX01T0_v", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("void Swap<int>(int &, int &)"));
+    ///
+    /// config.return_type_style = ReturnTypeStyle::Omit;
+    /// let demangled = demangle("Swap__H1Zi_RX01T0_v", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("Swap<int>(int &, int &)"));
+    ///
+    /// config.return_type_style = ReturnTypeStyle::TrailingArrow;
+    /// let demangled = demangle("Swap__H1Zi_RX01T0_v", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("Swap<int>(int &, int &) -> void")
+    /// );
+    /// ```
+    pub return_type_style: ReturnTypeStyle,
+
+    /// Leave out the template arguments of the classes a function or method
+    /// belongs to.
+    ///
+    /// The template arguments of the parameters and of templated functions
+    /// themselves are kept.
+    ///
+    /// # Examples
+    ///
+    /// Turning off this setting:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.omit_template_args_in_scope = false;
+    ///
+    /// let demangled = demangle("Insert__t4List1Z5tNameRC5tName", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("List<tName>::Insert(tName const &)")
+    /// );
+    /// ```
+    ///
+    /// The setting turned on:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.omit_template_args_in_scope = true;
+    ///
+    /// let demangled = demangle("Insert__t4List1Z5tNameRC5tName", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("List::Insert(tName const &)"));
+    /// ```
+    pub omit_template_args_in_scope: bool,
+
+    /// How to place the spaces around the `*` and `&` of pointers and
+    /// references.
+    ///
+    /// `None` mimics c++filt, which is inconsistent about it. It always
+    /// separates a type from its declarator, which produces outputs like
+    /// `char * * get<char *>(void)` when the type already ends in a pointer.
+    ///
+    /// See [`TypePointerSpacing`] for the available styles.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig, TypePointerSpacing};
+    ///
+    /// let mut config = DemangleConfig::new();
+    ///
+    /// config.type_pointer_spacing = None;
+    /// let demangled = demangle("get__H1ZPc_PCc_PX01", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("char * * get<char *>(char const *)")
+    /// );
+    ///
+    /// config.type_pointer_spacing = Some(TypePointerSpacing::BeforeStar);
+    /// let demangled = demangle("get__H1ZPc_PCc_PX01", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("char **get<char *>(char const *)")
+    /// );
+    ///
+    /// config.type_pointer_spacing = Some(TypePointerSpacing::AfterType);
+    /// let demangled = demangle("get__H1ZPc_PCc_PX01", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("char** get<char*>(char const*)")
+    /// );
+    ///
+    /// config.type_pointer_spacing = Some(TypePointerSpacing::Compact);
+    /// let demangled = demangle("get__H1ZPc_PCc_PX01", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("char**get<char*>(char const*)")
+    /// );
+    /// ```
+    pub type_pointer_spacing: Option<TypePointerSpacing>,
+
+    /// Put between the scopes of a name, like the namespaces and classes a
+    /// function belongs to, including the class of method pointers.
+    ///
+    /// Handy for feeding the output to tools for other languages, which may
+    /// choke on `::`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.scope_separator = ".";
+    ///
+    /// let demangled = demangle("AddPair__Q33sim16CollisionManager4AreaPQ23sim15CollisionObjectT1", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("sim.CollisionManager.Area.AddPair(sim.CollisionObject *, sim.CollisionObject *)")
+    /// );
+    /// ```
+    pub scope_separator: &'static str,
+
+    /// The opening and closing brackets of template lists.
+    ///
+    /// The space c++filt puts between two closing `>`, to avoid writing a
+    /// `>>`, is only kept for angle brackets. Operator names, like
+    /// `operator<<`, are never changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// let sym = "__ls__FR7ostreamRCt4List1Zt4List1Zi";
+    ///
+    /// config.template_brackets = ('<', '>');
+    /// let demangled = demangle(sym, &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("operator<<(ostream &, List<List<int> > const &)")
+    /// );
+    ///
+    /// config.template_brackets = ('[', ']');
+    /// let demangled = demangle(sym, &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("operator<<(ostream &, List[List[int]] const &)")
+    /// );
+    /// ```
+    pub template_brackets: (char, char),
+
+    /// Wrap the parameter list of functions and methods in parentheses.
+    ///
+    /// Turning it off writes the parameters right after the name, separated
+    /// by a space, and the same goes for the parameters of function and
+    /// method pointers, like `void (*) int`. Use [`omit_parameter_list`] to
+    /// leave the list out instead.
+    ///
+    /// [`omit_parameter_list`]: DemangleConfig::omit_parameter_list
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    ///
+    /// config.argument_list_parens = true;
+    /// let demangled = demangle("GetText__C5tNamei", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("tName::GetText(int) const"));
+    ///
+    /// config.argument_list_parens = false;
+    /// let demangled = demangle("GetText__C5tNamei", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("tName::GetText int const"));
+    ///
+    /// let demangled = demangle("Sort__FPFPCvPCv_i", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("Sort int (*) void const *, void const *"));
+    /// ```
+    pub argument_list_parens: bool,
+
+    /// Resolve type codes that are not part of the GNU V2 mangling, like the
+    /// ones emitted by some vendor forks of g++ (i.e. ProDG / SN Systems).
+    ///
+    /// The callback is only used for type codes this crate does not know
+    /// about. See [`VendorTypeMap`] for its parameters.
+    ///
+    /// Leaving this as `None` makes those type codes fail to demangle with
+    /// [`DemangleError::UnknownType`].
+    ///
+    /// [`DemangleError::UnknownType`]: crate::DemangleError::UnknownType
+    ///
+    /// # Examples
+    ///
+    /// Without a mapping:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig, DemangleError};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.vendor_type_map = None;
+    ///
+    /// let demangled = demangle("SetTime__5TimerUJ", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Err(&DemangleError::UnknownType('J', "J"))
+    /// );
+    /// ```
+    ///
+    /// With a mapping:
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// fn sn_types(code: char, _rest: &str) -> Option<(usize, &'static str)> {
+    ///     match code {
+    ///         'J' => Some((0, "__int64")),
+    ///         _ => None,
+    ///     }
+    /// }
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.vendor_type_map = Some(sn_types);
+    ///
+    /// let demangled = demangle("SetTime__5TimerUJ", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("Timer::SetTime(unsigned __int64)")
+    /// );
+    /// ```
+    pub vendor_type_map: Option<VendorTypeMap>,
+
+    /// The biggest count allowed on a repeated argument (`N`).
+    ///
+    /// Each repetition is expanded in the output, so a malicious symbol like
+    /// `f__FiN999999999999_0` could otherwise exhaust the memory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig, DemangleError};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.max_repeat_count = 2;
+    ///
+    /// let demangled = demangle("f__FiN20", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("f(int, int, int)"));
+    ///
+    /// let demangled = demangle("f__FiN30", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Err(&DemangleError::RepeatCountTooLarge("N30", 3))
+    /// );
+    /// ```
+    pub max_repeat_count: usize,
+
+    /// The biggest amount of dimensions allowed on a single array argument.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig, DemangleError};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.max_array_dimensions = 2;
+    ///
+    /// let demangled = demangle("f__FPA1_A1_i", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("f(int (*)[2][2])"));
+    ///
+    /// let demangled = demangle("f__FPA1_A1_A1_i", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Err(&DemangleError::TooManyArrayDimensions("A1_A1_A1_i"))
+    /// );
+    /// ```
+    pub max_array_dimensions: usize,
+
+    /// Replace the arguments of templates nested deeper than this with `...`.
+    ///
+    /// Useful to keep names of STL-heavy symbols short enough to be shown on
+    /// a table. `None` doesn't limit the depth.
+    ///
+    /// The depth is the one the template is written at, so the parameters of
+    /// a templated function that refer to its template arguments (`X01`) are
+    /// limited from where they are, not from its template list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// let sym = "find__t8_Rb_tree5ZUiZt4pair2ZCUiZiZt10_Select1st1Zt4pair2ZCUiZiZt4less1ZUiZt9allocator1ZiRCUi";
+    ///
+    /// config.max_template_depth = Some(1);
+    /// let demangled = demangle(sym, &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("_Rb_tree<unsigned int, pair<...>, _Select1st<...>, less<...>, allocator<...> >::find(unsigned int const &)")
+    /// );
+    ///
+    /// config.max_template_depth = Some(0);
+    /// let demangled = demangle(sym, &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("_Rb_tree<...>::find(unsigned int const &)")
+    /// );
+    /// ```
+    pub max_template_depth: Option<usize>,
+
+    /// Cut the demangled symbol to at most this many characters, ending it
+    /// with `…`.
+    ///
+    /// It is only cut at the start or end of a template argument or a
+    /// parameter, never inside of a name, and the brackets left open are
+    /// closed. If the shortest possible cut is still too long then it is used
+    /// anyway. `None` doesn't limit the length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let mut config = DemangleConfig::new();
+    /// config.max_output_len = Some(40);
+    ///
+    /// let demangled = demangle("AddPair__Q33sim16CollisionManager4AreaPQ23sim15CollisionObjectT1", &config);
+    /// assert_eq!(
+    ///     demangled.as_deref(),
+    ///     Ok("sim::CollisionManager::Area::AddPair(…)")
+    /// );
+    /// ```
+    pub max_output_len: Option<usize>,
+}
+
+/// Callback used by [`DemangleConfig::vendor_type_map`].
+///
+/// It receives the unknown type code and the rest of the mangled string right
+/// after it. It must return how many bytes of that rest are part of the type,
+/// besides the type code itself, and the demangled name of the type. Return
+/// `None` if the type code is not recognized either.
+pub type VendorTypeMap = fn(code: char, rest: &str) -> Option<(usize, &'static str)>;
+
+/// See [`DemangleConfig::return_type_style`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum ReturnTypeStyle {
+    /// Before the name, like c++filt.
+    ///
+    /// `void Swap<int>(int &, int &)`.
+    CfiltLeading,
+    /// Left out.
+    ///
+    /// `Swap<int>(int &, int &)`.
+    Omit,
+    /// After the parameter list, like a C++11 trailing return type.
+    ///
+    /// `Swap<int>(int &, int &) -> void`.
+    TrailingArrow,
+}
+
+/// See [`DemangleConfig::type_pointer_spacing`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum TypePointerSpacing {
+    /// Space before the `*`, but not after it.
+    ///
+    /// `char const *`, `char *const`, `char **get(void)`.
+    BeforeStar,
+    /// Space after the `*`, but not before it.
+    ///
+    /// `char const*`, `char* const`, `char** get(void)`.
+    AfterType,
+    /// No spaces around the `*` at all.
+    ///
+    /// `char const*`, `char*const`, `char**get(void)`.
+    Compact,
+}
+
+impl DemangleConfig {
+    /// The default configuration.
+    #[must_use]
+    #[inline]
+    #[track_caller]
     pub const fn new() -> Self {
         Self::new_g2dem()
     }
 
     /// Use improved output and valid C++ syntax whenever possible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let config = DemangleConfig::new_g2dem();
+    ///
+    /// let demangled = demangle("Printf__7ConsolePce", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("Console::Printf(char *, ...)"));
+    /// let demangled = demangle("_GLOBAL_$F$foo__Fi", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("global frames keyed to foo(int)"));
+    /// let demangled = demangle("func__FI80", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("func(__int128_t)"));
+    /// ```
     #[must_use]
     #[inline]
     #[track_caller]
@@ -334,11 +1870,47 @@ impl DemangleConfig {
         Self {
             fix_namespaced_global_constructor_bug: true,
             fix_array_length_arg: true,
+            fix_array_length_skip_zero: false,
+            fix_array_of_references: true,
             demangle_global_keyed_frames: true,
+            demangle_global_keyed_template_instantiations: true,
+            demangle_nested_global_keyed_symbols: false,
+            describe_static_init_functions: false,
+            demangle_vtable_offsets: true,
+            reject_plain_c_lookalikes: true,
+            strip_top_level_const_on_value_args: false,
+            pretty_anonymous_namespaces: false,
             ellipsis_emit_space_after_comma: true,
             fix_extension_int: true,
             fix_array_in_return_position: true,
             fix_function_pointers_in_template_lists: true,
+            simplify_stl_containers: false,
+            demangle_constructor_variants: true,
+            emit_enum_casts_in_templates: false,
+            fix_function_pointer_in_return_position: true,
+            emit_plain_restrict: false,
+            demangle_clone_suffix: true,
+            emit_clone_suffix: true,
+            tolerate_stray_underscores_between_args: false,
+            lenient: false,
+            fix_function_pointer_template_lookbacks: true,
+            emit_integer_suffixes_in_templates: false,
+            emit_wide_character_literals_in_templates: true,
+            fix_template_parameter_levels: true,
+            complete_truncated_symbols: false,
+            annotate_ambiguous_scope: false,
+            omit_parameter_list: false,
+            return_type_style: ReturnTypeStyle::CfiltLeading,
+            omit_template_args_in_scope: false,
+            type_pointer_spacing: Some(TypePointerSpacing::BeforeStar),
+            scope_separator: "::",
+            template_brackets: ('<', '>'),
+            argument_list_parens: true,
+            vendor_type_map: None,
+            max_repeat_count: 256,
+            max_array_dimensions: 32,
+            max_template_depth: None,
+            max_output_len: None,
         }
     }
 
@@ -346,6 +1918,21 @@ impl DemangleConfig {
     /// be considered c++filt bugs.
     ///
     /// Useful for validating demangling against c++filt.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let config = DemangleConfig::new_cfilt();
+    ///
+    /// let demangled = demangle("Printf__7ConsolePce", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("Console::Printf(char *,...)"));
+    /// let demangled = demangle("_GLOBAL_$F$foo__Fi", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("_GLOBAL_$F$foo(int)"));
+    /// let demangled = demangle("func__FI80", &config);
+    /// assert_eq!(demangled.as_deref(), Ok("func(int128_t)"));
+    /// ```
     #[must_use]
     #[inline]
     #[track_caller]
@@ -353,13 +1940,57 @@ impl DemangleConfig {
         Self {
             fix_namespaced_global_constructor_bug: false,
             fix_array_length_arg: false,
+            fix_array_length_skip_zero: false,
+            fix_array_of_references: false,
             demangle_global_keyed_frames: false,
+            demangle_global_keyed_template_instantiations: false,
+            demangle_nested_global_keyed_symbols: false,
+            describe_static_init_functions: false,
+            demangle_vtable_offsets: false,
+            reject_plain_c_lookalikes: false,
+            strip_top_level_const_on_value_args: false,
+            pretty_anonymous_namespaces: false,
             ellipsis_emit_space_after_comma: false,
             fix_extension_int: false,
             fix_array_in_return_position: false,
             fix_function_pointers_in_template_lists: false,
+            simplify_stl_containers: false,
+            demangle_constructor_variants: false,
+            emit_enum_casts_in_templates: false,
+            fix_function_pointer_in_return_position: false,
+            emit_plain_restrict: false,
+            demangle_clone_suffix: true,
+            emit_clone_suffix: false,
+            tolerate_stray_underscores_between_args: false,
+            lenient: false,
+            fix_function_pointer_template_lookbacks: false,
+            emit_integer_suffixes_in_templates: false,
+            emit_wide_character_literals_in_templates: false,
+            fix_template_parameter_levels: false,
+            complete_truncated_symbols: false,
+            annotate_ambiguous_scope: false,
+            omit_parameter_list: false,
+            return_type_style: ReturnTypeStyle::CfiltLeading,
+            omit_template_args_in_scope: false,
+            type_pointer_spacing: None,
+            scope_separator: "::",
+            template_brackets: ('<', '>'),
+            argument_list_parens: true,
+            vendor_type_map: None,
+            max_repeat_count: 256,
+            max_array_dimensions: 32,
+            max_template_depth: None,
+            max_output_len: None,
         }
     }
+
+    /// Start building a configuration from the default one.
+    ///
+    /// See [`DemangleConfigBuilder`].
+    #[inline]
+    pub const fn builder() -> DemangleConfigBuilder {
+        DemangleConfigBuilder::new()
+    }
 }
 
 impl Default for DemangleConfig {
@@ -0,0 +1,550 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+use crate::{DemangleConfig, ReturnTypeStyle, TypePointerSpacing, VendorTypeMap};
+
+/// Build a [`DemangleConfig`] by chaining setters.
+///
+/// Because [`DemangleConfig`] is `#[non_exhaustive]`, this is the
+/// forward-compatible way of setting only the options that matter to you,
+/// on top of one of the presets.
+///
+/// # Examples
+///
+/// ```
+/// use gnuv2_demangle::{demangle, DemangleConfig};
+///
+/// let config = DemangleConfig::builder()
+///     .preset_cfilt()
+///     .ellipsis_emit_space_after_comma(true)
+///     .build();
+///
+/// let demangled = demangle("Printf__7ConsolePce", &config);
+/// assert_eq!(
+///     demangled.as_deref(),
+///     Ok("Console::Printf(char *, ...)")
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[must_use]
+pub struct DemangleConfigBuilder {
+    config: DemangleConfig,
+}
+
+impl DemangleConfigBuilder {
+    /// Start from the default configuration, see [`DemangleConfig::new`].
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            config: DemangleConfig::new(),
+        }
+    }
+
+    /// Reset every option to the values of [`DemangleConfig::new_g2dem`].
+    #[inline]
+    pub const fn preset_g2dem(self) -> Self {
+        Self {
+            config: DemangleConfig::new_g2dem(),
+        }
+    }
+
+    /// Reset every option to the values of [`DemangleConfig::new_cfilt`].
+    #[inline]
+    pub const fn preset_cfilt(self) -> Self {
+        Self {
+            config: DemangleConfig::new_cfilt(),
+        }
+    }
+
+    /// See [`DemangleConfig::fix_namespaced_global_constructor_bug`].
+    #[inline]
+    pub const fn fix_namespaced_global_constructor_bug(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                fix_namespaced_global_constructor_bug: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::fix_array_length_arg`].
+    #[inline]
+    pub const fn fix_array_length_arg(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                fix_array_length_arg: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::fix_array_length_skip_zero`].
+    #[inline]
+    pub const fn fix_array_length_skip_zero(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                fix_array_length_skip_zero: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::fix_array_of_references`].
+    #[inline]
+    pub const fn fix_array_of_references(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                fix_array_of_references: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::demangle_global_keyed_frames`].
+    #[inline]
+    pub const fn demangle_global_keyed_frames(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                demangle_global_keyed_frames: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::demangle_global_keyed_template_instantiations`].
+    #[inline]
+    pub const fn demangle_global_keyed_template_instantiations(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                demangle_global_keyed_template_instantiations: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::demangle_nested_global_keyed_symbols`].
+    #[inline]
+    pub const fn demangle_nested_global_keyed_symbols(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                demangle_nested_global_keyed_symbols: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::describe_static_init_functions`].
+    #[inline]
+    pub const fn describe_static_init_functions(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                describe_static_init_functions: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::demangle_vtable_offsets`].
+    #[inline]
+    pub const fn demangle_vtable_offsets(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                demangle_vtable_offsets: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::reject_plain_c_lookalikes`].
+    #[inline]
+    pub const fn reject_plain_c_lookalikes(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                reject_plain_c_lookalikes: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::strip_top_level_const_on_value_args`].
+    #[inline]
+    pub const fn strip_top_level_const_on_value_args(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                strip_top_level_const_on_value_args: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::pretty_anonymous_namespaces`].
+    #[inline]
+    pub const fn pretty_anonymous_namespaces(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                pretty_anonymous_namespaces: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::ellipsis_emit_space_after_comma`].
+    #[inline]
+    pub const fn ellipsis_emit_space_after_comma(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                ellipsis_emit_space_after_comma: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::fix_extension_int`].
+    #[inline]
+    pub const fn fix_extension_int(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                fix_extension_int: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::fix_array_in_return_position`].
+    #[inline]
+    pub const fn fix_array_in_return_position(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                fix_array_in_return_position: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::fix_function_pointers_in_template_lists`].
+    #[inline]
+    pub const fn fix_function_pointers_in_template_lists(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                fix_function_pointers_in_template_lists: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::simplify_stl_containers`].
+    #[inline]
+    pub const fn simplify_stl_containers(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                simplify_stl_containers: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::demangle_constructor_variants`].
+    #[inline]
+    pub const fn demangle_constructor_variants(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                demangle_constructor_variants: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::emit_enum_casts_in_templates`].
+    #[inline]
+    pub const fn emit_enum_casts_in_templates(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                emit_enum_casts_in_templates: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::fix_function_pointer_in_return_position`].
+    #[inline]
+    pub const fn fix_function_pointer_in_return_position(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                fix_function_pointer_in_return_position: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::emit_plain_restrict`].
+    #[inline]
+    pub const fn emit_plain_restrict(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                emit_plain_restrict: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::demangle_clone_suffix`].
+    #[inline]
+    pub const fn demangle_clone_suffix(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                demangle_clone_suffix: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::emit_clone_suffix`].
+    #[inline]
+    pub const fn emit_clone_suffix(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                emit_clone_suffix: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::tolerate_stray_underscores_between_args`].
+    #[inline]
+    pub const fn tolerate_stray_underscores_between_args(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                tolerate_stray_underscores_between_args: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::lenient`].
+    #[inline]
+    pub const fn lenient(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                lenient: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::fix_function_pointer_template_lookbacks`].
+    #[inline]
+    pub const fn fix_function_pointer_template_lookbacks(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                fix_function_pointer_template_lookbacks: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::emit_integer_suffixes_in_templates`].
+    #[inline]
+    pub const fn emit_integer_suffixes_in_templates(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                emit_integer_suffixes_in_templates: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::emit_wide_character_literals_in_templates`].
+    #[inline]
+    pub const fn emit_wide_character_literals_in_templates(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                emit_wide_character_literals_in_templates: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::fix_template_parameter_levels`].
+    #[inline]
+    pub const fn fix_template_parameter_levels(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                fix_template_parameter_levels: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::complete_truncated_symbols`].
+    #[inline]
+    pub const fn complete_truncated_symbols(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                complete_truncated_symbols: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::annotate_ambiguous_scope`].
+    #[inline]
+    pub const fn annotate_ambiguous_scope(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                annotate_ambiguous_scope: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::omit_parameter_list`].
+    #[inline]
+    pub const fn omit_parameter_list(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                omit_parameter_list: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::return_type_style`].
+    #[inline]
+    pub const fn return_type_style(self, value: ReturnTypeStyle) -> Self {
+        Self {
+            config: DemangleConfig {
+                return_type_style: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::omit_template_args_in_scope`].
+    #[inline]
+    pub const fn omit_template_args_in_scope(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                omit_template_args_in_scope: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::type_pointer_spacing`].
+    #[inline]
+    pub const fn type_pointer_spacing(self, value: Option<TypePointerSpacing>) -> Self {
+        Self {
+            config: DemangleConfig {
+                type_pointer_spacing: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::scope_separator`].
+    #[inline]
+    pub const fn scope_separator(self, value: &'static str) -> Self {
+        Self {
+            config: DemangleConfig {
+                scope_separator: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::template_brackets`].
+    #[inline]
+    pub const fn template_brackets(self, value: (char, char)) -> Self {
+        Self {
+            config: DemangleConfig {
+                template_brackets: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::argument_list_parens`].
+    #[inline]
+    pub const fn argument_list_parens(self, value: bool) -> Self {
+        Self {
+            config: DemangleConfig {
+                argument_list_parens: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::vendor_type_map`].
+    #[inline]
+    pub const fn vendor_type_map(self, value: Option<VendorTypeMap>) -> Self {
+        Self {
+            config: DemangleConfig {
+                vendor_type_map: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::max_repeat_count`].
+    #[inline]
+    pub const fn max_repeat_count(self, value: usize) -> Self {
+        Self {
+            config: DemangleConfig {
+                max_repeat_count: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::max_array_dimensions`].
+    #[inline]
+    pub const fn max_array_dimensions(self, value: usize) -> Self {
+        Self {
+            config: DemangleConfig {
+                max_array_dimensions: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::max_template_depth`].
+    #[inline]
+    pub const fn max_template_depth(self, value: Option<usize>) -> Self {
+        Self {
+            config: DemangleConfig {
+                max_template_depth: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// See [`DemangleConfig::max_output_len`].
+    #[inline]
+    pub const fn max_output_len(self, value: Option<usize>) -> Self {
+        Self {
+            config: DemangleConfig {
+                max_output_len: value,
+                ..self.config
+            },
+        }
+    }
+
+    /// Finish building the configuration.
+    #[must_use]
+    #[inline]
+    pub const fn build(self) -> DemangleConfig {
+        self.config
+    }
+}
+
+impl Default for DemangleConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<DemangleConfig> for DemangleConfigBuilder {
+    fn from(config: DemangleConfig) -> Self {
+        Self { config }
+    }
+}
@@ -3,6 +3,11 @@
 
 use core::{error, fmt};
 
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+
 /// Information about demangling failure.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[non_exhaustive]
@@ -88,6 +93,471 @@ pub enum DemangleError<'s> {
     InvalidTypeForObjectMemberPointer(&'s str),
     MalformedTemplatedSpecializationInvalidNamespace(&'s str),
     TrailingDataAfterReturnTypeOfTemplatedSpecialization(&'s str),
+    UnsupportedSymbolKindOnComponents(&'s str),
+    InvalidDeltaOnThunk(&'s str),
+    InvalidVcallOffsetOnThunk(&'s str),
+    ConstructorVariantMarker(&'s str),
+    InvalidCharacter(char, usize),
+    ArrayLengthOverflow(&'s str),
+    RepeatCountTooLarge(&'s str, usize),
+    TooManyArrayDimensions(&'s str),
+    LikelyTruncated(&'s str, usize),
+    AllocationFailure,
+    AllTemplateReadingsFailed(&'s str),
+    InvalidTypeName(&'s str),
+    TrailingDataOnTypeName(&'s str),
+    LookbackToEllipsis(&'s str),
+    LookbackToVoid(&'s str),
+    EmptyCustomName(&'s str),
+}
+
+/// The broad category of a [`DemangleError`], without any of its data.
+///
+/// See [`DemangleError::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum DemangleErrorKind {
+    /// The symbol doesn't look like a GNU V2 mangled symbol at all, like the
+    /// symbols of C functions.
+    NotMangled,
+    /// The symbol has non ASCII characters.
+    NonAscii,
+    /// The symbol has characters that can't appear on mangled symbols, like
+    /// spaces.
+    InvalidCharacter,
+    /// The symbol ends before it should, usually because the linker or the
+    /// object format cut it short.
+    Truncated,
+    /// A class, namespace or other counted name is malformed.
+    MalformedName,
+    /// The argument list, or an argument of it, is malformed.
+    MalformedArguments,
+    /// A template, its arguments or its return type are malformed.
+    MalformedTemplate,
+    /// A special symbol, like an operator, a virtual table or a thunk, is
+    /// malformed.
+    MalformedSpecial,
+    /// A limit was reached, either one from the [`DemangleConfig`] or the
+    /// available memory.
+    ///
+    /// [`DemangleConfig`]: crate::DemangleConfig
+    InternalLimit,
+    /// The symbol is valid, but the function used doesn't support it.
+    Unsupported,
+}
+
+impl DemangleError<'_> {
+    /// The name of the variant, without any of its data.
+    ///
+    /// Useful for grouping errors, since the data points into the symbol
+    /// that failed to demangle.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let config = DemangleConfig::new();
+    ///
+    /// let err = demangle("f__FiN31", &config).unwrap_err();
+    /// assert_eq!(err.kind_name(), "InvalidRepeatingArgument");
+    /// ```
+    #[must_use]
+    pub const fn kind_name(&self) -> &'static str {
+        match self {
+            Self::NotMangled => "NotMangled",
+            Self::NonAscii => "NonAscii",
+            Self::TrailingDataOnDestructor(..) => "TrailingDataOnDestructor",
+            Self::InvalidClassNameOnDestructor(..) => "InvalidClassNameOnDestructor",
+            Self::InvalidClassNameOnConstructor(..) => "InvalidClassNameOnConstructor",
+            Self::InvalidClassNameOnOperator(..) => "InvalidClassNameOnOperator",
+            Self::InvalidClassNameOnMethod(..) => "InvalidClassNameOnMethod",
+            Self::InvalidClassNameOnVirtualTable(..) => "InvalidClassNameOnVirtualTable",
+            Self::InvalidNamespaceOnNamespacedGlobal(..) => "InvalidNamespaceOnNamespacedGlobal",
+            Self::InvalidCustomNameOnArgument(..) => "InvalidCustomNameOnArgument",
+            Self::InvalidCustomNameOnNamespace(..) => "InvalidCustomNameOnNamespace",
+            Self::InvalidCustomNameOnTemplate(..) => "InvalidCustomNameOnTemplate",
+            Self::InvalidNamespaceOnTemplatedFunction(..) => "InvalidNamespaceOnTemplatedFunction",
+            Self::InvalidSymbolNameOnTemplateType(..) => "InvalidSymbolNameOnTemplateType",
+            Self::InvalidClassNameOnMethodArgument(..) => "InvalidClassNameOnMethodArgument",
+            Self::UnknownType(..) => "UnknownType",
+            Self::InvalidRepeatingArgument(..) => "InvalidRepeatingArgument",
+            Self::RanOutWhileDemanglingSpecial => "RanOutWhileDemanglingSpecial",
+            Self::RanOutOfArguments => "RanOutOfArguments",
+            Self::FoundDuplicatedPrevQualifierOnArgument(..) => {
+                "FoundDuplicatedPrevQualifierOnArgument"
+            }
+            Self::InvalidSpecialMethod(..) => "InvalidSpecialMethod",
+            Self::UnrecognizedSpecialMethod(..) => "UnrecognizedSpecialMethod",
+            Self::PrimitiveInsteadOfClass(..) => "PrimitiveInsteadOfClass",
+            Self::InvalidNamespaceCount(..) => "InvalidNamespaceCount",
+            Self::InvalidLookbackCount(..) => "InvalidLookbackCount",
+            Self::LookbackCountTooBig(..) => "LookbackCountTooBig",
+            Self::InvalidTypeOnTypeInfoFunction(..) => "InvalidTypeOnTypeInfoFunction",
+            Self::TrailingDataOnTypeInfoFunction(..) => "TrailingDataOnTypeInfoFunction",
+            Self::InvalidTypeOnTypeInfoNode(..) => "InvalidTypeOnTypeInfoNode",
+            Self::TrailingDataOnTypeInfoNode(..) => "TrailingDataOnTypeInfoNode",
+            Self::TrailingDataAfterEllipsis(..) => "TrailingDataAfterEllipsis",
+            Self::InvalidTypeValueForTemplated(..) => "InvalidTypeValueForTemplated",
+            Self::InvalidValueForIntegralTemplated(..) => "InvalidValueForIntegralTemplated",
+            Self::InvalidTemplatedPointerReferenceValue(..) => {
+                "InvalidTemplatedPointerReferenceValue"
+            }
+            Self::InvalidFunctionPointerTypeInTemplatedList(..) => {
+                "InvalidFunctionPointerTypeInTemplatedList"
+            }
+            Self::InvalidTemplatedNumberForCharacterValue(..) => {
+                "InvalidTemplatedNumberForCharacterValue"
+            }
+            Self::InvalidTemplatedCharacterValue(..) => "InvalidTemplatedCharacterValue",
+            Self::InvalidTemplatedBoolean(..) => "InvalidTemplatedBoolean",
+            Self::VTableMissingDollarSeparator(..) => "VTableMissingDollarSeparator",
+            Self::InvalidNamespacedGlobal(..) => "InvalidNamespacedGlobal",
+            Self::TrailingDataOnNamespacedGlobal(..) => "TrailingDataOnNamespacedGlobal",
+            Self::MissingReturnTypeForFunctionPointer(..) => "MissingReturnTypeForFunctionPointer",
+            Self::InvalidReturnTypeForFunctionPointer(..) => "InvalidReturnTypeForFunctionPointer",
+            Self::InvalidGlobalSymKeyed(..) => "InvalidGlobalSymKeyed",
+            Self::InvalidArraySize(..) => "InvalidArraySize",
+            Self::MalformedArrayArgumment(..) => "MalformedArrayArgumment",
+            Self::PrevQualifiersInInvalidPostioniAtArrayArgument(..) => {
+                "PrevQualifiersInInvalidPostioniAtArrayArgument"
+            }
+            Self::MalformedCastOperatorOverload(..) => "MalformedCastOperatorOverload",
+            Self::InvalidTemplateCount(..) => "InvalidTemplateCount",
+            Self::InvalidTemplateReturnCount(..) => "InvalidTemplateReturnCount",
+            Self::TemplateReturnCountIsZero(..) => "TemplateReturnCountIsZero",
+            Self::MalformedTemplateWithReturnType(..) => "MalformedTemplateWithReturnType",
+            Self::InvalidValueForIndexOnXArgument(..) => "InvalidValueForIndexOnXArgument",
+            Self::InvalidValueForNumber1OnXArgument(..) => "InvalidValueForNumber1OnXArgument",
+            Self::InvalidNumber1OnXArgument(..) => "InvalidNumber1OnXArgument",
+            Self::IndexTooBigForXArgument(..) => "IndexTooBigForXArgument",
+            Self::TrailingDataAfterArgumentList(..) => "TrailingDataAfterArgumentList",
+            Self::MalformedTemplateWithReturnTypeMissingReturnType(..) => {
+                "MalformedTemplateWithReturnTypeMissingReturnType"
+            }
+            Self::MalformedTemplateWithReturnTypeMissingMalformedReturnType(..) => {
+                "MalformedTemplateWithReturnTypeMissingMalformedReturnType"
+            }
+            Self::TrailingDataAfterReturnTypeOfMalformedTemplateWithReturnType(..) => {
+                "TrailingDataAfterReturnTypeOfMalformedTemplateWithReturnType"
+            }
+            Self::InvalidQualifierForMethodMemberArg(..) => "InvalidQualifierForMethodMemberArg",
+            Self::MissingFirstClassArgumentForMethodMemberArg(..) => {
+                "MissingFirstClassArgumentForMethodMemberArg"
+            }
+            Self::MethodPointerNotHavingAPointerFirst(..) => "MethodPointerNotHavingAPointerFirst",
+            Self::MethodPointerMissingConstness(..) => "MethodPointerMissingConstness",
+            Self::MethodPointerWrongClassName(..) => "MethodPointerWrongClassName",
+            Self::MethodPointerClassNameAsArray(..) => "MethodPointerClassNameAsArray",
+            Self::UnknownMethodMemberArgKind(..) => "UnknownMethodMemberArgKind",
+            Self::MissingBitwidthForExtensionInteger(..) => "MissingBitwidthForExtensionInteger",
+            Self::InvalidBitwidthForExtensionInteger(..) => "InvalidBitwidthForExtensionInteger",
+            Self::InvalidEnumNameForTemplatedValue(..) => "InvalidEnumNameForTemplatedValue",
+            Self::MissingLookbackIndexForTemplatedValue(..) => {
+                "MissingLookbackIndexForTemplatedValue"
+            }
+            Self::MissingLookbackSecondDigitForTemplatedValue(..) => {
+                "MissingLookbackSecondDigitForTemplatedValue"
+            }
+            Self::InvalidLookbackSecondDigitForTemplatedValue(..) => {
+                "InvalidLookbackSecondDigitForTemplatedValue"
+            }
+            Self::IndexTooBigForYArgument(..) => "IndexTooBigForYArgument",
+            Self::InvalidQualifierForObjectMemberArg(..) => "InvalidQualifierForObjectMemberArg",
+            Self::InvalidClassNameOnObjectMemberArgument(..) => {
+                "InvalidClassNameOnObjectMemberArgument"
+            }
+            Self::MissingTypeForObjectMemberPointer(..) => "MissingTypeForObjectMemberPointer",
+            Self::InvalidTypeForObjectMemberPointer(..) => "InvalidTypeForObjectMemberPointer",
+            Self::MalformedTemplatedSpecializationInvalidNamespace(..) => {
+                "MalformedTemplatedSpecializationInvalidNamespace"
+            }
+            Self::TrailingDataAfterReturnTypeOfTemplatedSpecialization(..) => {
+                "TrailingDataAfterReturnTypeOfTemplatedSpecialization"
+            }
+            Self::UnsupportedSymbolKindOnComponents(..) => "UnsupportedSymbolKindOnComponents",
+            Self::InvalidDeltaOnThunk(..) => "InvalidDeltaOnThunk",
+            Self::InvalidVcallOffsetOnThunk(..) => "InvalidVcallOffsetOnThunk",
+            Self::ConstructorVariantMarker(..) => "ConstructorVariantMarker",
+            Self::InvalidCharacter(..) => "InvalidCharacter",
+            Self::ArrayLengthOverflow(..) => "ArrayLengthOverflow",
+            Self::RepeatCountTooLarge(..) => "RepeatCountTooLarge",
+            Self::TooManyArrayDimensions(..) => "TooManyArrayDimensions",
+            Self::LikelyTruncated(..) => "LikelyTruncated",
+            Self::AllocationFailure => "AllocationFailure",
+            Self::AllTemplateReadingsFailed(..) => "AllTemplateReadingsFailed",
+            Self::InvalidTypeName(..) => "InvalidTypeName",
+            Self::TrailingDataOnTypeName(..) => "TrailingDataOnTypeName",
+            Self::LookbackToEllipsis(..) => "LookbackToEllipsis",
+            Self::LookbackToVoid(..) => "LookbackToVoid",
+            Self::EmptyCustomName(..) => "EmptyCustomName",
+        }
+    }
+
+    /// The broad category of the error, see [`DemangleErrorKind`].
+    ///
+    /// Prefer this over matching on the variants directly when only the
+    /// category matters, since new variants may be added at any time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig, DemangleErrorKind};
+    ///
+    /// let config = DemangleConfig::new();
+    ///
+    /// let err = demangle("f__FiN31", &config).unwrap_err();
+    /// assert_eq!(err.kind(), DemangleErrorKind::MalformedArguments);
+    ///
+    /// let err = demangle("Set__15Collisi", &config).unwrap_err();
+    /// assert_eq!(err.kind(), DemangleErrorKind::Truncated);
+    /// ```
+    #[must_use]
+    pub const fn kind(&self) -> DemangleErrorKind {
+        // Keep this exhaustive, so new variants have to pick a kind.
+        match self {
+            Self::NotMangled => DemangleErrorKind::NotMangled,
+            Self::NonAscii => DemangleErrorKind::NonAscii,
+            Self::InvalidCharacter(..) => DemangleErrorKind::InvalidCharacter,
+            Self::RanOutWhileDemanglingSpecial
+            | Self::RanOutOfArguments
+            | Self::LikelyTruncated(..) => DemangleErrorKind::Truncated,
+            Self::InvalidClassNameOnDestructor(..)
+            | Self::InvalidClassNameOnConstructor(..)
+            | Self::InvalidClassNameOnOperator(..)
+            | Self::InvalidClassNameOnMethod(..)
+            | Self::InvalidClassNameOnVirtualTable(..)
+            | Self::InvalidNamespaceOnNamespacedGlobal(..)
+            | Self::InvalidCustomNameOnArgument(..)
+            | Self::InvalidCustomNameOnNamespace(..)
+            | Self::InvalidCustomNameOnTemplate(..)
+            | Self::InvalidNamespaceOnTemplatedFunction(..)
+            | Self::InvalidClassNameOnMethodArgument(..)
+            | Self::InvalidNamespaceCount(..)
+            | Self::InvalidTypeName(..)
+            | Self::TrailingDataOnTypeName(..)
+            | Self::EmptyCustomName(..) => DemangleErrorKind::MalformedName,
+            Self::UnknownType(..)
+            | Self::InvalidRepeatingArgument(..)
+            | Self::FoundDuplicatedPrevQualifierOnArgument(..)
+            | Self::PrimitiveInsteadOfClass(..)
+            | Self::InvalidLookbackCount(..)
+            | Self::LookbackCountTooBig(..)
+            | Self::LookbackToEllipsis(..)
+            | Self::LookbackToVoid(..)
+            | Self::TrailingDataAfterEllipsis(..)
+            | Self::MissingReturnTypeForFunctionPointer(..)
+            | Self::InvalidReturnTypeForFunctionPointer(..)
+            | Self::InvalidArraySize(..)
+            | Self::MalformedArrayArgumment(..)
+            | Self::PrevQualifiersInInvalidPostioniAtArrayArgument(..)
+            | Self::TrailingDataAfterArgumentList(..)
+            | Self::InvalidQualifierForMethodMemberArg(..)
+            | Self::MissingFirstClassArgumentForMethodMemberArg(..)
+            | Self::MethodPointerNotHavingAPointerFirst(..)
+            | Self::MethodPointerMissingConstness(..)
+            | Self::MethodPointerWrongClassName(..)
+            | Self::MethodPointerClassNameAsArray(..)
+            | Self::UnknownMethodMemberArgKind(..)
+            | Self::MissingBitwidthForExtensionInteger(..)
+            | Self::InvalidBitwidthForExtensionInteger(..)
+            | Self::InvalidQualifierForObjectMemberArg(..)
+            | Self::InvalidClassNameOnObjectMemberArgument(..)
+            | Self::MissingTypeForObjectMemberPointer(..)
+            | Self::InvalidTypeForObjectMemberPointer(..) => DemangleErrorKind::MalformedArguments,
+            Self::InvalidSymbolNameOnTemplateType(..)
+            | Self::InvalidTypeValueForTemplated(..)
+            | Self::InvalidValueForIntegralTemplated(..)
+            | Self::InvalidTemplatedPointerReferenceValue(..)
+            | Self::InvalidFunctionPointerTypeInTemplatedList(..)
+            | Self::InvalidTemplatedNumberForCharacterValue(..)
+            | Self::InvalidTemplatedCharacterValue(..)
+            | Self::InvalidTemplatedBoolean(..)
+            | Self::InvalidTemplateCount(..)
+            | Self::InvalidTemplateReturnCount(..)
+            | Self::TemplateReturnCountIsZero(..)
+            | Self::MalformedTemplateWithReturnType(..)
+            | Self::InvalidValueForIndexOnXArgument(..)
+            | Self::InvalidValueForNumber1OnXArgument(..)
+            | Self::InvalidNumber1OnXArgument(..)
+            | Self::IndexTooBigForXArgument(..)
+            | Self::MalformedTemplateWithReturnTypeMissingReturnType(..)
+            | Self::MalformedTemplateWithReturnTypeMissingMalformedReturnType(..)
+            | Self::TrailingDataAfterReturnTypeOfMalformedTemplateWithReturnType(..)
+            | Self::InvalidEnumNameForTemplatedValue(..)
+            | Self::MissingLookbackIndexForTemplatedValue(..)
+            | Self::MissingLookbackSecondDigitForTemplatedValue(..)
+            | Self::InvalidLookbackSecondDigitForTemplatedValue(..)
+            | Self::IndexTooBigForYArgument(..)
+            | Self::MalformedTemplatedSpecializationInvalidNamespace(..)
+            | Self::TrailingDataAfterReturnTypeOfTemplatedSpecialization(..)
+            | Self::AllTemplateReadingsFailed(..) => DemangleErrorKind::MalformedTemplate,
+            Self::TrailingDataOnDestructor(..)
+            | Self::InvalidSpecialMethod(..)
+            | Self::UnrecognizedSpecialMethod(..)
+            | Self::InvalidTypeOnTypeInfoFunction(..)
+            | Self::TrailingDataOnTypeInfoFunction(..)
+            | Self::InvalidTypeOnTypeInfoNode(..)
+            | Self::TrailingDataOnTypeInfoNode(..)
+            | Self::VTableMissingDollarSeparator(..)
+            | Self::InvalidNamespacedGlobal(..)
+            | Self::TrailingDataOnNamespacedGlobal(..)
+            | Self::InvalidGlobalSymKeyed(..)
+            | Self::MalformedCastOperatorOverload(..)
+            | Self::InvalidDeltaOnThunk(..)
+            | Self::InvalidVcallOffsetOnThunk(..)
+            | Self::ConstructorVariantMarker(..) => DemangleErrorKind::MalformedSpecial,
+            Self::ArrayLengthOverflow(..)
+            | Self::RepeatCountTooLarge(..)
+            | Self::TooManyArrayDimensions(..)
+            | Self::AllocationFailure => DemangleErrorKind::InternalLimit,
+            Self::UnsupportedSymbolKindOnComponents(..) => DemangleErrorKind::Unsupported,
+        }
+    }
+}
+
+impl<'s> DemangleError<'s> {
+    /// The part of the symbol where the demangling failed, if any.
+    ///
+    /// When the error has more than one of them, this is the first one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig};
+    ///
+    /// let config = DemangleConfig::new();
+    ///
+    /// let err = demangle("f__FiN31", &config).unwrap_err();
+    /// assert_eq!(err.context(), Some("N31"));
+    /// ```
+    #[must_use]
+    pub const fn context(&self) -> Option<&'s str> {
+        match *self {
+            Self::TrailingDataOnDestructor(s)
+            | Self::InvalidClassNameOnDestructor(s)
+            | Self::InvalidClassNameOnConstructor(s)
+            | Self::InvalidClassNameOnOperator(s)
+            | Self::InvalidClassNameOnMethod(s)
+            | Self::InvalidClassNameOnVirtualTable(s)
+            | Self::InvalidNamespaceOnNamespacedGlobal(s)
+            | Self::InvalidCustomNameOnArgument(s)
+            | Self::InvalidCustomNameOnNamespace(s)
+            | Self::InvalidCustomNameOnTemplate(s)
+            | Self::InvalidNamespaceOnTemplatedFunction(s)
+            | Self::InvalidSymbolNameOnTemplateType(s)
+            | Self::InvalidClassNameOnMethodArgument(s)
+            | Self::InvalidRepeatingArgument(s)
+            | Self::InvalidSpecialMethod(s)
+            | Self::UnrecognizedSpecialMethod(s)
+            | Self::PrimitiveInsteadOfClass(s)
+            | Self::InvalidNamespaceCount(s)
+            | Self::InvalidLookbackCount(s)
+            | Self::InvalidTypeOnTypeInfoFunction(s)
+            | Self::TrailingDataOnTypeInfoFunction(s)
+            | Self::InvalidTypeOnTypeInfoNode(s)
+            | Self::TrailingDataOnTypeInfoNode(s)
+            | Self::TrailingDataAfterEllipsis(s)
+            | Self::InvalidValueForIntegralTemplated(s)
+            | Self::InvalidTemplatedPointerReferenceValue(s)
+            | Self::InvalidTemplatedNumberForCharacterValue(s)
+            | Self::InvalidTemplatedBoolean(s)
+            | Self::VTableMissingDollarSeparator(s)
+            | Self::TrailingDataOnNamespacedGlobal(s)
+            | Self::MissingReturnTypeForFunctionPointer(s)
+            | Self::InvalidReturnTypeForFunctionPointer(s)
+            | Self::InvalidGlobalSymKeyed(s)
+            | Self::InvalidArraySize(s)
+            | Self::MalformedArrayArgumment(s)
+            | Self::PrevQualifiersInInvalidPostioniAtArrayArgument(s)
+            | Self::MalformedCastOperatorOverload(s)
+            | Self::InvalidTemplateCount(s)
+            | Self::InvalidTemplateReturnCount(s)
+            | Self::TemplateReturnCountIsZero(s)
+            | Self::MalformedTemplateWithReturnType(s)
+            | Self::InvalidValueForIndexOnXArgument(s)
+            | Self::InvalidValueForNumber1OnXArgument(s)
+            | Self::TrailingDataAfterArgumentList(s)
+            | Self::MalformedTemplateWithReturnTypeMissingReturnType(s)
+            | Self::MalformedTemplateWithReturnTypeMissingMalformedReturnType(s)
+            | Self::TrailingDataAfterReturnTypeOfMalformedTemplateWithReturnType(s)
+            | Self::InvalidQualifierForMethodMemberArg(s)
+            | Self::MissingFirstClassArgumentForMethodMemberArg(s)
+            | Self::MethodPointerNotHavingAPointerFirst(s)
+            | Self::MethodPointerMissingConstness(s)
+            | Self::MethodPointerWrongClassName(s)
+            | Self::MethodPointerClassNameAsArray(s)
+            | Self::UnknownMethodMemberArgKind(s)
+            | Self::MissingBitwidthForExtensionInteger(s)
+            | Self::InvalidEnumNameForTemplatedValue(s)
+            | Self::MissingLookbackIndexForTemplatedValue(s)
+            | Self::MissingLookbackSecondDigitForTemplatedValue(s)
+            | Self::InvalidQualifierForObjectMemberArg(s)
+            | Self::InvalidClassNameOnObjectMemberArgument(s)
+            | Self::MissingTypeForObjectMemberPointer(s)
+            | Self::InvalidTypeForObjectMemberPointer(s)
+            | Self::MalformedTemplatedSpecializationInvalidNamespace(s)
+            | Self::TrailingDataAfterReturnTypeOfTemplatedSpecialization(s)
+            | Self::UnsupportedSymbolKindOnComponents(s)
+            | Self::InvalidDeltaOnThunk(s)
+            | Self::InvalidVcallOffsetOnThunk(s)
+            | Self::ConstructorVariantMarker(s)
+            | Self::ArrayLengthOverflow(s)
+            | Self::TooManyArrayDimensions(s)
+            | Self::AllTemplateReadingsFailed(s)
+            | Self::InvalidTypeName(s)
+            | Self::TrailingDataOnTypeName(s)
+            | Self::LookbackToEllipsis(s)
+            | Self::LookbackToVoid(s)
+            | Self::EmptyCustomName(s) => Some(s),
+            Self::UnknownType(_, s) | Self::InvalidTypeValueForTemplated(_, s) => Some(s),
+            Self::FoundDuplicatedPrevQualifierOnArgument(s, ..)
+            | Self::LookbackCountTooBig(s, ..)
+            | Self::InvalidFunctionPointerTypeInTemplatedList(s, ..)
+            | Self::InvalidTemplatedCharacterValue(s, ..)
+            | Self::InvalidNamespacedGlobal(s, ..)
+            | Self::InvalidNumber1OnXArgument(s, ..)
+            | Self::IndexTooBigForXArgument(s, ..)
+            | Self::InvalidBitwidthForExtensionInteger(s, ..)
+            | Self::InvalidLookbackSecondDigitForTemplatedValue(s, ..)
+            | Self::IndexTooBigForYArgument(s, ..)
+            | Self::RepeatCountTooLarge(s, ..)
+            | Self::LikelyTruncated(s, ..) => Some(s),
+            Self::NotMangled
+            | Self::NonAscii
+            | Self::RanOutWhileDemanglingSpecial
+            | Self::RanOutOfArguments
+            | Self::InvalidCharacter(..)
+            | Self::AllocationFailure => None,
+        }
+    }
+
+    /// Copy the error into an [`OwnedDemangleError`], which doesn't borrow
+    /// from the symbol.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gnuv2_demangle::{demangle, DemangleConfig, OwnedDemangleError};
+    ///
+    /// let config = DemangleConfig::new();
+    ///
+    /// let owned: OwnedDemangleError = {
+    ///     let sym = String::from("f__FiN31");
+    ///     demangle(&sym, &config).unwrap_err().into_owned()
+    /// };
+    /// assert_eq!(owned.kind_name(), "InvalidRepeatingArgument");
+    /// assert_eq!(owned.context(), Some("N31"));
+    /// ```
+    #[must_use]
+    pub fn into_owned(self) -> OwnedDemangleError {
+        OwnedDemangleError {
+            kind: self.kind(),
+            kind_name: self.kind_name(),
+            context: self.context().map(ToString::to_string),
+            description: format!("{self:?}"),
+        }
+    }
 }
 
 impl fmt::Display for DemangleError<'_> {
@@ -101,3 +571,52 @@ impl fmt::Display for DemangleError<'_> {
 }
 
 impl error::Error for DemangleError<'_> {}
+
+/// A [`DemangleError`] that doesn't borrow from the symbol.
+///
+/// Useful for keeping errors around after the symbol is gone, like when
+/// reusing a line buffer to demangle a stream of symbols.
+///
+/// Created by [`DemangleError::into_owned`], or by converting with
+/// [`From`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OwnedDemangleError {
+    kind: DemangleErrorKind,
+    kind_name: &'static str,
+    context: Option<String>,
+    description: String,
+}
+
+impl OwnedDemangleError {
+    /// See [`DemangleError::kind`].
+    #[must_use]
+    pub const fn kind(&self) -> DemangleErrorKind {
+        self.kind
+    }
+
+    /// See [`DemangleError::kind_name`].
+    #[must_use]
+    pub const fn kind_name(&self) -> &'static str {
+        self.kind_name
+    }
+
+    /// See [`DemangleError::context`].
+    #[must_use]
+    pub fn context(&self) -> Option<&str> {
+        self.context.as_deref()
+    }
+}
+
+impl From<DemangleError<'_>> for OwnedDemangleError {
+    fn from(value: DemangleError<'_>) -> Self {
+        value.into_owned()
+    }
+}
+
+impl fmt::Display for OwnedDemangleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.description)
+    }
+}
+
+impl error::Error for OwnedDemangleError {}
@@ -0,0 +1,533 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+use core::{cell::Cell, ops::Deref};
+
+use alloc::{string::String, vec::Vec};
+
+use crate::{
+    markup::{unmark, CutMarks, TokenMarks},
+    remainer::{Remaining, StrParsing},
+    scratch::{ScratchAlloc, ScratchString, ScratchVec},
+    spans::{SpanKind, SpanRecorder},
+    DemangleConfig, DemangleError,
+};
+
+/// A construct found while demangling a symbol.
+///
+/// Each construct is the place where one or more settings of
+/// [`DemangleConfig`] get checked, so a symbol without a given construct is
+/// not affected by those settings. See [`DemangleInfo`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum Construct {
+    /// A `_GLOBAL_$I$` or `_GLOBAL_$D$` symbol keyed to a namespaced function.
+    GlobalKeyedNamespaced,
+    /// An array argument, like `int (*)[4]`.
+    ArrayArgument,
+    /// A `_GLOBAL_$F$` symbol.
+    GlobalKeyedFrame,
+    /// A variadic argument list.
+    Ellipsis,
+    /// An extension int, like `__int128_t` or `int64_t`.
+    ExtensionInt,
+    /// An array returned by a templated function.
+    ArrayReturn,
+    /// A function pointer in a template list.
+    FunctionPointerInTemplate,
+    /// An SGI-STL container.
+    StlContainer,
+    /// A constructor variant marker of hybrid toolchains.
+    ConstructorVariant,
+    /// An enum value in a template list.
+    EnumInTemplate,
+    /// A function pointer returned by a templated function.
+    FunctionPointerReturn,
+    /// A `__restrict` qualifier.
+    Restrict,
+    /// A pointer or reference, where [`DemangleConfig::type_pointer_spacing`]
+    /// applies.
+    Declarator,
+    /// A type code unknown to GNU V2, passed to
+    /// [`DemangleConfig::vendor_type_map`].
+    VendorType,
+    /// A repeated argument.
+    Repeat,
+    /// A trailing `.<number>` discriminator.
+    CloneSuffix,
+    /// A lone `_` between the arguments of a function.
+    StrayUnderscore,
+    /// A pointer or reference to a function pointer template parameter.
+    FunctionPointerLookback,
+    /// An integer value in a template list.
+    IntegerInTemplate,
+    /// A `wchar_t` value in a template list.
+    WideCharacterInTemplate,
+    /// A `X` lookback with a level other than the template list of the
+    /// function itself.
+    TemplateParameterLevel,
+    /// An argument list cut short by the end of the symbol.
+    Truncated,
+    /// A `_GLOBAL_$T$` symbol.
+    GlobalKeyedTemplateInstantiation,
+    /// A C symbol with a double underscore that looks like a method without
+    /// arguments, like `crc32__8_compute`.
+    PlainCLookalike,
+    /// A `const` parameter passed by value, like the `C3Foo` of `f__FC3Foo`.
+    TopLevelConst,
+    /// A namespace component generated for an anonymous namespace, like the
+    /// `_17_GLOBAL_.N.foo_cc` of `Id__Q2_17_GLOBAL_.N.foo_cc3Bar`.
+    AnonymousNamespace,
+    /// A malformed construct only accepted by [`DemangleConfig::lenient`],
+    /// like the `Q0` of `Id__Q03Foo`.
+    Malformed,
+    /// A `_GLOBAL_` keyed symbol keyed to another one, like
+    /// `_GLOBAL_$I$_GLOBAL_$D$gSomething`.
+    GlobalKeyedNested,
+    /// The base class offset of a secondary virtual table, like the `$8` of
+    /// `_vt$7Derived$4Base$8`.
+    VTableOffset,
+    /// An array whose elements are references, like the `A9_Ri` of
+    /// `f__FA9_Ri`.
+    ArrayOfReferences,
+    /// A `_GLOBAL_` keyed symbol keyed to the function that initializes and
+    /// destroys the static objects of a file, like
+    /// `_GLOBAL_$I$__static_initialization_and_destruction_0_65535__Fii`.
+    StaticInitFunction,
+}
+
+impl Construct {
+    /// The last construct, which must be kept up to date so the bits of
+    /// [`DemangleInfo`] are checked to be enough.
+    const LAST: Self = Self::StaticInitFunction;
+
+    const fn bit(self) -> u64 {
+        1 << self as u32
+    }
+}
+
+const _: () = assert!((Construct::LAST as u32) < u64::BITS);
+
+/// The constructs found while demangling a symbol.
+///
+/// Returned by [`demangle_with_info`](crate::demangle_with_info).
+///
+/// # Examples
+///
+/// ```
+/// use gnuv2_demangle::{demangle_with_info, Construct, DemangleConfig};
+///
+/// let config = DemangleConfig::new();
+///
+/// let (demangled, info) = demangle_with_info("Printf__7ConsolePce", &config);
+/// assert_eq!(demangled.as_deref(), Ok("Console::Printf(char *, ...)"));
+/// assert!(info.saw(Construct::Ellipsis));
+/// assert!(info.saw(Construct::Declarator));
+/// assert!(!info.saw(Construct::ArrayArgument));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct DemangleInfo {
+    bits: u64,
+}
+
+impl DemangleInfo {
+    /// No constructs at all.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self { bits: 0 }
+    }
+
+    /// Whether `construct` was found.
+    #[must_use]
+    #[inline]
+    pub const fn saw(&self, construct: Construct) -> bool {
+        self.bits & construct.bit() != 0
+    }
+
+    /// Whether no construct was found.
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.bits == 0
+    }
+
+    /// The constructs found by either `self` or `other`.
+    #[must_use]
+    #[inline]
+    pub const fn union(self, other: Self) -> Self {
+        Self {
+            bits: self.bits | other.bits,
+        }
+    }
+
+    #[must_use]
+    pub(crate) const fn with(self, construct: Construct) -> Self {
+        Self {
+            bits: self.bits | construct.bit(),
+        }
+    }
+}
+
+/// How many times the greedy reading of an ambiguous index may fail, so
+/// symbols with many of them don't take exponential time.
+const MAX_BACKTRACKS: usize = 16;
+
+/// The config of a single demangling, plus the constructs found on the way.
+///
+/// Dereferences to the config, so the settings can be read directly.
+#[derive(Debug)]
+pub(crate) struct Context<'a> {
+    config: DemangleConfig,
+    /// Where the buffers used while demangling come from, see
+    /// [`demangle_in`](crate::demangle_in).
+    alloc: ScratchAlloc<'a>,
+    info: Cell<DemangleInfo>,
+    /// Read ambiguous lookback indices greedily, see
+    /// [`Context::lookback_index`].
+    greedy_indices: Cell<bool>,
+    /// Whether an ambiguous lookback index was found since the last call to
+    /// [`Context::take_ambiguous_index`].
+    ambiguous_index: Cell<bool>,
+    /// How many more times the greedy reading of an ambiguous index may fail
+    /// before giving up on it.
+    backtracks_left: Cell<usize>,
+    /// Whether a template count or argument that can be read in more than
+    /// one way was found, see [`Context::alternative_template_reading`].
+    ambiguous_template: Cell<bool>,
+    /// Use the alternative reading of ambiguous template counts and
+    /// arguments, see [`Context::with_alternative_template_readings`].
+    alternative_template_readings: Cell<bool>,
+    /// How many template lists the one being parsed is nested in, counting
+    /// itself, see [`Context::in_template_list`].
+    template_depth: Cell<usize>,
+    /// Whether the places the output may be cut at get marked, see
+    /// [`Context::mark_cuts`].
+    cuts: Cell<bool>,
+    /// Whether the tokens of the output get marked, see
+    /// [`Context::mark_tokens`].
+    tokens: Cell<bool>,
+    #[cfg(feature = "trace")]
+    pub(crate) tracer: Option<crate::trace::Tracer>,
+    /// Records where each part of the output comes from, see
+    /// [`demangle_with_spans`](crate::demangle_with_spans).
+    pub(crate) spans: Option<SpanRecorder>,
+    /// Report allocation failures instead of aborting, see
+    /// [`try_demangle`](crate::try_demangle).
+    #[cfg(feature = "fallible-alloc")]
+    pub(crate) fallible: bool,
+}
+
+impl Context<'static> {
+    pub(crate) const fn new(config: &DemangleConfig) -> Self {
+        Context::new_in(config, ScratchAlloc::GLOBAL)
+    }
+}
+
+impl<'a> Context<'a> {
+    pub(crate) const fn new_in(config: &DemangleConfig, alloc: ScratchAlloc<'a>) -> Self {
+        Self {
+            config: *config,
+            alloc,
+            info: Cell::new(DemangleInfo::new()),
+            greedy_indices: Cell::new(false),
+            ambiguous_index: Cell::new(false),
+            backtracks_left: Cell::new(MAX_BACKTRACKS),
+            ambiguous_template: Cell::new(false),
+            alternative_template_readings: Cell::new(false),
+            template_depth: Cell::new(0),
+            cuts: Cell::new(false),
+            tokens: Cell::new(false),
+            #[cfg(feature = "trace")]
+            tracer: None,
+            spans: None,
+            #[cfg(feature = "fallible-alloc")]
+            fallible: false,
+        }
+    }
+
+    /// An empty buffer from the allocator of this demangling.
+    pub(crate) fn scratch_vec<T>(&self) -> ScratchVec<'a, T> {
+        self.alloc.vec()
+    }
+
+    /// Same as [`Context::scratch_vec`], for strings.
+    pub(crate) fn scratch_string(&self) -> ScratchString<'a> {
+        self.alloc.string()
+    }
+
+    pub(crate) fn saw(&self, construct: Construct) {
+        self.info.set(self.info.get().with(construct));
+    }
+
+    pub(crate) fn info(&self) -> DemangleInfo {
+        self.info.get()
+    }
+
+    /// Forget the constructs and spans found so far, for retrying a failed
+    /// demangling.
+    pub(crate) fn clear_info(&self) {
+        self.info.set(DemangleInfo::new());
+        if let Some(spans) = &self.spans {
+            spans.truncate(0);
+        }
+    }
+
+    /// Mark the places the output may be cut at, for
+    /// [`DemangleConfig::max_output_len`].
+    pub(crate) fn mark_cuts(&self) {
+        self.cuts.set(true);
+    }
+
+    /// The marks to put around template and parameter lists, see
+    /// [`Context::mark_cuts`].
+    pub(crate) fn cuts(&self) -> CutMarks {
+        CutMarks::new(self.cuts.get())
+    }
+
+    /// Mark what each token of the output is, for
+    /// [`demangle_highlighted`](crate::demangle_highlighted).
+    ///
+    /// The places the output may be cut at get marked too, since they tell
+    /// apart the tokens of template lists.
+    #[cfg(feature = "classify-output")]
+    pub(crate) fn mark_tokens(&self) {
+        self.tokens.set(true);
+        self.cuts.set(true);
+    }
+
+    /// The marks to put around the tokens of the output, see
+    /// [`Context::mark_tokens`].
+    pub(crate) fn tokens(&self) -> TokenMarks {
+        TokenMarks::new(self.tokens.get())
+    }
+
+    /// Run `f`, which parses a template list, one template list deeper.
+    pub(crate) fn in_template_list<T>(&self, f: impl FnOnce() -> T) -> T {
+        self.template_depth.set(self.template_depth.get() + 1);
+        let result = f();
+        self.template_depth.set(self.template_depth.get() - 1);
+        result
+    }
+
+    /// How many template lists the one being parsed is nested in, counting
+    /// itself, or 0 outside of them.
+    pub(crate) fn template_depth(&self) -> usize {
+        self.template_depth.get()
+    }
+
+    /// Whether the template list being parsed is nested deeper than
+    /// [`DemangleConfig::max_template_depth`].
+    pub(crate) fn is_too_deep(&self) -> bool {
+        self.max_template_depth
+            .is_some_and(|max_depth| self.template_depth.get() > max_depth)
+    }
+
+    /// Record the part of the output parsed from `s` until `remaining`, if
+    /// asked to.
+    #[inline]
+    pub(crate) fn span(
+        &self,
+        kind: SpanKind,
+        s: &str,
+        remaining: &str,
+        text: impl FnOnce() -> String,
+    ) {
+        if let Some(spans) = &self.spans {
+            let text = text();
+            let text = if self.cuts.get() { unmark(&text) } else { text };
+            spans.record(kind, s, remaining, text);
+        }
+    }
+
+    /// How many spans were recorded so far, see [`Context::discard_spans`].
+    pub(crate) fn span_mark(&self) -> usize {
+        self.spans.as_ref().map_or(0, SpanRecorder::mark)
+    }
+
+    /// Forget the spans recorded between two [`Context::span_mark`]s.
+    pub(crate) fn discard_spans(&self, marks: core::ops::Range<usize>) {
+        if let Some(spans) = &self.spans {
+            spans.discard(marks);
+        }
+    }
+
+    /// Run `f`, forgetting the spans it recorded if it fails.
+    ///
+    /// Must wrap every step whose failure is not propagated, so the spans of
+    /// a discarded reading don't end up mixed with the ones of the final
+    /// reading.
+    #[inline]
+    pub(crate) fn attempt<T, E>(&self, f: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+        let Some(spans) = &self.spans else {
+            return f();
+        };
+
+        let mark = spans.mark();
+        let result = f();
+        if result.is_err() {
+            spans.truncate(mark);
+        }
+        result
+    }
+
+    /// Parse the index of a `T` lookback or a `N` repeat.
+    ///
+    /// An index of more than one digit should be followed by an underscore,
+    /// but some compilers omit it, so `T10` may either be the lookback `T1`
+    /// followed by something starting with `0`, or the lookback `T10`. The
+    /// first reading is used unless parsing greedily, see
+    /// [`Context::with_greedy_indices`].
+    pub(crate) fn lookback_index<'s>(&self, s: &'s str) -> Option<Remaining<'s, usize>> {
+        let short = s.p_number_maybe_multi_digit()?;
+        if short.r.len() + 1 != s.len() || !short.r.starts_with(|c: char| c.is_ascii_digit()) {
+            return Some(short);
+        }
+
+        self.ambiguous_index.set(true);
+        if self.greedy_indices.get() {
+            s.p_number()
+        } else {
+            Some(short)
+        }
+    }
+
+    /// Whether an ambiguous index was found since the last call, and there
+    /// is still budget to try the greedy reading.
+    pub(crate) fn take_ambiguous_index(&self) -> bool {
+        let ambiguous = self.ambiguous_index.replace(false);
+        ambiguous && !self.greedy_indices.get() && self.backtracks_left.get() > 0
+    }
+
+    /// The greedy reading of an ambiguous index failed.
+    pub(crate) fn backtrack(&self) {
+        self.backtracks_left
+            .set(self.backtracks_left.get().saturating_sub(1));
+    }
+
+    /// Run `f` reading every ambiguous index greedily.
+    pub(crate) fn with_greedy_indices<T>(&self, f: impl FnOnce() -> T) -> T {
+        self.greedy_indices.set(true);
+        let result = f();
+        self.greedy_indices.set(false);
+        self.ambiguous_index.set(false);
+        result
+    }
+
+    /// Note that a template count or argument can be read in more than one
+    /// way, and return whether to use the alternative reading.
+    ///
+    /// The first reading is always used on the first attempt, the alternative
+    /// ones are only tried if the whole symbol fails to demangle, see
+    /// [`Context::with_alternative_template_readings`].
+    pub(crate) fn alternative_template_reading(&self) -> bool {
+        self.ambiguous_template.set(true);
+        self.alternative_template_readings.get()
+    }
+
+    /// Whether an ambiguous template count or argument was found since the
+    /// last call, outside of [`Context::with_alternative_template_readings`].
+    pub(crate) fn take_ambiguous_template(&self) -> bool {
+        self.ambiguous_template.replace(false) && !self.alternative_template_readings.get()
+    }
+
+    /// Run `f` using the alternative reading of every ambiguous template count
+    /// and argument.
+    pub(crate) fn with_alternative_template_readings<T>(&self, f: impl FnOnce() -> T) -> T {
+        self.alternative_template_readings.set(true);
+        let result = f();
+        self.alternative_template_readings.set(false);
+        self.ambiguous_template.set(false);
+        result
+    }
+
+    /// Reserve space for `additional` more elements on `buf`.
+    ///
+    /// Fails instead of aborting when demangling with
+    /// [`try_demangle`](crate::try_demangle).
+    #[inline]
+    pub(crate) fn reserve<'s>(
+        &self,
+        buf: &mut impl Reserve,
+        additional: usize,
+    ) -> Result<(), DemangleError<'s>> {
+        #[cfg(feature = "fallible-alloc")]
+        if self.fallible {
+            return buf
+                .try_reserve(additional)
+                .map_err(|()| DemangleError::AllocationFailure);
+        }
+
+        buf.reserve(additional);
+        Ok(())
+    }
+
+    /// Run a step of the parse of `s`, recording it if tracing.
+    #[inline]
+    pub(crate) fn trace<'s, T>(
+        &self,
+        construct: &'static str,
+        s: &'s str,
+        f: impl FnOnce() -> Result<T, DemangleError<'s>>,
+    ) -> Result<T, DemangleError<'s>>
+    where
+        T: crate::trace::Traceable,
+    {
+        #[cfg(feature = "trace")]
+        if let Some(tracer) = &self.tracer {
+            let index = tracer.enter(construct, s);
+            let result = f();
+            tracer.exit(index, &result);
+            return result;
+        }
+
+        let _ = (construct, s);
+        f()
+    }
+}
+
+impl Deref for Context<'_> {
+    type Target = DemangleConfig;
+
+    fn deref(&self) -> &Self::Target {
+        &self.config
+    }
+}
+
+/// The growable buffers [`Context::reserve`] works with.
+pub(crate) trait Reserve {
+    fn reserve(&mut self, additional: usize);
+    #[cfg_attr(not(feature = "fallible-alloc"), expect(dead_code))]
+    fn try_reserve(&mut self, additional: usize) -> Result<(), ()>;
+}
+
+impl Reserve for String {
+    fn reserve(&mut self, additional: usize) {
+        self.reserve(additional);
+    }
+
+    fn try_reserve(&mut self, additional: usize) -> Result<(), ()> {
+        self.try_reserve(additional).map_err(|_| ())
+    }
+}
+
+impl<T> Reserve for Vec<T> {
+    fn reserve(&mut self, additional: usize) {
+        self.reserve(additional);
+    }
+
+    fn try_reserve(&mut self, additional: usize) -> Result<(), ()> {
+        self.try_reserve(additional).map_err(|_| ())
+    }
+}
+
+#[cfg(feature = "allocator-api2")]
+impl<T> Reserve for ScratchVec<'_, T> {
+    fn reserve(&mut self, additional: usize) {
+        self.reserve(additional);
+    }
+
+    fn try_reserve(&mut self, additional: usize) -> Result<(), ()> {
+        self.try_reserve(additional).map_err(|_| ())
+    }
+}
@@ -0,0 +1,70 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+use core::fmt;
+
+use crate::{demangle, DemangleConfig};
+
+/// A symbol that gets demangled lazily, only when it is formatted.
+///
+/// Useful for logging, since nothing is demangled nor allocated if the
+/// message is never formatted. The [`Display`] implementation prints the
+/// symbol as-is if it can't be demangled.
+///
+/// [`Display`]: fmt::Display
+///
+/// # Examples
+///
+/// ```
+/// use gnuv2_demangle::{DemangleConfig, Demangled};
+///
+/// let config = DemangleConfig::new();
+///
+/// let demangled = Demangled::new("Printf__7ConsolePce", &config);
+/// assert_eq!(
+///     format!("Calling {demangled}"),
+///     "Calling Console::Printf(char *, ...)"
+/// );
+///
+/// let not_mangled = Demangled::new("main", &config);
+/// assert_eq!(format!("Calling {not_mangled}"), "Calling main");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Demangled<'s, 'c> {
+    sym: &'s str,
+    config: &'c DemangleConfig,
+}
+
+impl<'s, 'c> Demangled<'s, 'c> {
+    /// Wrap the symbol. This does not do any work by itself.
+    #[must_use]
+    #[inline]
+    pub const fn new(sym: &'s str, config: &'c DemangleConfig) -> Self {
+        Self { sym, config }
+    }
+
+    /// The symbol as it was passed.
+    #[must_use]
+    #[inline]
+    pub const fn sym(&self) -> &'s str {
+        self.sym
+    }
+}
+
+impl fmt::Display for Demangled<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match demangle(self.sym, self.config) {
+            Ok(demangled) => f.write_str(&demangled),
+            Err(_) => f.write_str(self.sym),
+        }
+    }
+}
+
+impl fmt::Debug for Demangled<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Demangled")
+            .field("sym", &self.sym)
+            .field("demangled", &demangle(self.sym, self.config))
+            .finish()
+    }
+}
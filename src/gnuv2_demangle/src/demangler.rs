@@ -7,15 +7,30 @@ use alloc::{
     vec::Vec,
 };
 
-use crate::{DemangleConfig, DemangleError};
+use crate::{
+    demangle_info::{Construct, Context, DemangleInfo},
+    DemangleConfig, DemangleError, ReturnTypeStyle, TypePointerSpacing,
+};
 
 use crate::{
-    dem::{demangle_custom_name, demangle_method_qualifier},
-    dem_arg::{demangle_argument, DemangledArg},
+    dem::{
+        demangle_custom_name, demangle_method_qualifier, demangle_operator_name,
+        demangle_trailing_custom_name,
+    },
+    dem_arg::{
+        demangle_argument, ArrayPosition, DemangledArg, FunctionPointer, MethodPointer,
+        ParameterList,
+    },
     dem_arg_list::{demangle_argument_list, demangle_argument_list_impl, ArgVec},
-    dem_namespace::demangle_namespaces,
-    dem_template::{demangle_template, demangle_template_with_return_type},
-    remainer::Remaining,
+    dem_namespace::demangle_scope,
+    dem_template::{
+        demangle_template, demangle_template_and_types, demangle_template_with_return_type,
+    },
+    extract::find_invalid_char,
+    markup::{truncate, Token},
+    remainer::{Remaining, StrParsing},
+    spacing::{needs_space, needs_space_in_declarator},
+    spans::SpanKind,
     str_cutter::StrCutter,
 };
 
@@ -43,31 +58,196 @@ use crate::{
 /// );
 /// ```
 pub fn demangle<'s>(sym: &'s str, config: &DemangleConfig) -> Result<String, DemangleError<'s>> {
+    demangle_with_info(sym, config).0
+}
+
+/// Demangle a symbol, same as [`demangle`], and also report which constructs
+/// were found on the way.
+///
+/// The constructs are reported even if the demangling fails, up to the point
+/// where it failed.
+///
+/// # Examples
+///
+/// ```
+/// use gnuv2_demangle::{demangle_with_info, Construct, DemangleConfig};
+///
+/// let config = DemangleConfig::new();
+///
+/// let (demangled, info) = demangle_with_info("foo__FPA3_i", &config);
+/// assert_eq!(demangled.as_deref(), Ok("foo(int (*)[4])"));
+/// assert!(info.saw(Construct::ArrayArgument));
+/// assert!(!info.saw(Construct::Ellipsis));
+/// ```
+pub fn demangle_with_info<'s>(
+    sym: &'s str,
+    config: &DemangleConfig,
+) -> (Result<String, DemangleError<'s>>, DemangleInfo) {
+    let config = Context::new(config);
+    let demangled = demangle_checked(sym, &config);
+    (demangled, config.info())
+}
+
+/// Demangle a symbol, same as [`demangle`], but returning
+/// [`DemangleError::AllocationFailure`] instead of aborting when the
+/// biggest allocations fail.
+///
+/// Smaller allocations still abort on failure, so this only makes it
+/// unlikely for a huge symbol to abort the program.
+///
+/// # Examples
+///
+/// ```
+/// use gnuv2_demangle::{try_demangle, DemangleConfig};
+///
+/// let config = DemangleConfig::new();
+///
+/// let demangled = try_demangle("SetText__5tNamePCc", &config);
+/// assert_eq!(demangled.as_deref(), Ok("tName::SetText(char const *)"));
+/// ```
+#[cfg(feature = "fallible-alloc")]
+pub fn try_demangle<'s>(
+    sym: &'s str,
+    config: &DemangleConfig,
+) -> Result<String, DemangleError<'s>> {
+    let mut config = Context::new(config);
+    config.fallible = true;
+    demangle_checked(sym, &config)
+}
+
+/// Demangle a symbol, same as [`demangle`], but taking the argument lists of
+/// functions and templates and the qualifiers of the arguments from `alloc`.
+///
+/// Useful for demangling many symbols with an arena that gets reset between
+/// batches. The rest of the buffers, like the strings of the names and types,
+/// and the returned string itself come from the global allocator.
+///
+/// # Examples
+///
+/// ```
+/// use allocator_api2::alloc::Global;
+/// use gnuv2_demangle::{demangle_in, DemangleConfig};
+///
+/// let config = DemangleConfig::new();
+///
+/// let demangled = demangle_in("SetText__5tNamePCc", &config, Global);
+/// assert_eq!(demangled.as_deref(), Ok("tName::SetText(char const *)"));
+/// ```
+#[cfg(feature = "allocator-api2")]
+pub fn demangle_in<'s, A: allocator_api2::alloc::Allocator>(
+    sym: &'s str,
+    config: &DemangleConfig,
+    alloc: A,
+) -> Result<String, DemangleError<'s>> {
+    let config = Context::new_in(config, crate::scratch::ScratchAlloc::new(&alloc));
+    demangle_checked(sym, &config)
+}
+
+pub(crate) fn demangle_checked<'s>(
+    sym: &'s str,
+    config: &Context,
+) -> Result<String, DemangleError<'s>> {
+    if config.max_output_len.is_some() {
+        config.mark_cuts();
+    }
+    let mut demangled = demangle_unlimited(sym, config)?;
+
+    if config.cuts().is_enabled() {
+        let max_len = config.max_output_len.unwrap_or(usize::MAX);
+        demangled = truncate(&demangled, max_len, config.template_brackets.1);
+    }
+    Ok(demangled)
+}
+
+fn demangle_unlimited<'s>(sym: &'s str, config: &Context) -> Result<String, DemangleError<'s>> {
     if !sym.is_ascii() {
         Err(DemangleError::NonAscii)
+    } else if let Some((c, position)) = find_invalid_char(sym) {
+        Err(DemangleError::InvalidCharacter(c, position))
     } else {
-        // GCC lets users change the default marker ('$') for compatibility
-        // with other toolchains that do not accept '$' in symbol names.
-        let cplus_marker = sym.chars().find(|x| *x == '.').unwrap_or('$');
+        let demangled = demangle_with_marker(sym, config);
+        if demangled.is_ok() || !config.demangle_clone_suffix {
+            return demangled;
+        }
+
+        // A `.<number>` discriminator may be part of a counted class name, so
+        // it is only stripped if the whole symbol failed.
+        let Some((core, number)) = split_clone_suffix(sym) else {
+            return demangled;
+        };
+        config.clear_info();
+        config.saw(Construct::CloneSuffix);
+        // If the rest doesn't demangle either the dot was not a discriminator
+        // after all, so the error of the whole symbol is the meaningful one.
+        let Ok(demangled) = demangle_with_marker(core, config) else {
+            return demangled;
+        };
+        if config.emit_clone_suffix {
+            let number = config.tokens().mark(Token::Literal, number);
+            Ok(format!("{demangled} [clone .{number}]"))
+        } else {
+            Ok(demangled)
+        }
+    }
+}
+
+fn demangle_with_marker<'s>(sym: &'s str, config: &Context) -> Result<String, DemangleError<'s>> {
+    // GCC lets users change the default marker ('$') for compatibility
+    // with other toolchains that do not accept '$' in symbol names.
+    let cplus_marker = sym.chars().find(|x| *x == '.').unwrap_or('$');
+
+    let demangled = demangle_impl(sym, config, cplus_marker, true);
+    let Err(err) = demangled else {
+        return demangled;
+    };
+    if !config.take_ambiguous_template() {
+        return Err(err);
+    }
+
+    // Some template counts and arguments can be read in more than one way,
+    // so try the other reading before giving up.
+    config.clear_info();
+    config
+        .with_alternative_template_readings(|| demangle_impl(sym, config, cplus_marker, true))
+        .map_err(|_| match err.context() {
+            Some(context) => DemangleError::AllTemplateReadingsFailed(context),
+            None => err,
+        })
+}
 
-        demangle_impl(sym, config, cplus_marker, true)
+/// Split a trailing `.<number>` discriminator, like `foo__Fi.1`.
+pub(crate) fn split_clone_suffix(sym: &str) -> Option<(&str, &str)> {
+    let (core, number) = sym.rsplit_once('.')?;
+
+    if core.is_empty() || number.is_empty() || !number.bytes().all(|x| x.is_ascii_digit()) {
+        None
+    } else {
+        Some((core, number))
     }
 }
 
 fn demangle_impl<'s>(
     sym: &'s str,
-    config: &DemangleConfig,
+    config: &Context,
     cplus_marker: char,
     allow_global_sym_keyed: bool,
 ) -> Result<String, DemangleError<'s>> {
+    // Also checked here, since keyed globals call back into this function
+    // with a slice of the symbol.
+    if !sym.is_ascii() {
+        return Err(DemangleError::NonAscii);
+    }
+
     if let Some(s) = sym.c_strip_prefix_3chars('_', cplus_marker, '_') {
         demangle_destructor(config, s)
+    } else if let Some(s) = sym.strip_prefix("__thunk_") {
+        demangle_thunk(config, s, cplus_marker)
     } else if let Some(s) = sym.strip_prefix("__") {
         demangle_special(config, s, sym)
     } else if let Some(s) =
         sym.c_cond_and_strip_prefix_and_char(allow_global_sym_keyed, "_GLOBAL_", cplus_marker)
     {
-        demangle_global_sym_keyed(config, s, cplus_marker, sym)
+        demangle_global_sym_keyed(config, s, cplus_marker, sym, true)
     } else {
         demangle_impl_failables(sym, config, cplus_marker)
     }
@@ -75,7 +255,7 @@ fn demangle_impl<'s>(
 
 fn demangle_impl_failables<'s>(
     sym: &'s str,
-    config: &DemangleConfig,
+    config: &Context,
     cplus_marker: char,
 ) -> Result<String, DemangleError<'s>> {
     // Some of the checks here can overlap and produce false positives, so if
@@ -86,23 +266,32 @@ fn demangle_impl_failables<'s>(
     // Look up for the first appareance of something like `__F`, `__t`, `__H`, etc. and just use that
     let leading_error = if let Some((sym_name, the_rest, c)) = sym
         .c_split2_r_starts_with("__", |c| {
-            matches!(c, 'F' | '1'..='9' | 'C' | 't' | 'H' | 'Q')
+            matches!(c, 'F' | '1'..='9' | 'C' | 'V' | 'u' | 't' | 'H' | 'Q')
         }) {
         // All the cases here should be the same as the match above.
         match c {
-            'F' => match demangle_free_function(config, sym_name, &the_rest[1..]) {
-                Ok(d) => return Ok(d),
-                Err(e) => leading_error.or(Some(e)),
-            },
-            '1'..='9' | 'C' | 't' => match demangle_method(config, sym_name, the_rest) {
-                Ok(d) => return Ok(d),
-                Err(e) => leading_error.or(Some(e)),
-            },
-            'H' => match demangle_templated_function(config, sym_name, &the_rest[1..]) {
+            'F' => {
+                match config.attempt(|| demangle_free_function(config, sym_name, &the_rest[1..])) {
+                    Ok(d) => return Ok(d),
+                    Err(e) => leading_error.or(Some(e)),
+                }
+            }
+            '1'..='9' | 'C' | 'V' | 'u' | 't' => {
+                match config.attempt(|| demangle_method(config, sym_name, the_rest)) {
+                    Ok(d) => return Ok(d),
+                    Err(e) => leading_error.or(Some(e)),
+                }
+            }
+            'H' => match config.attempt(|| {
+                let func_name = config.tokens().marked(Token::Name, sym_name);
+                demangle_templated_function(config, &func_name, None, &the_rest[1..])
+            }) {
                 Ok(d) => return Ok(d),
                 Err(e) => leading_error.or(Some(e)),
             },
-            'Q' => match demangle_namespaced_function(config, sym_name, &the_rest[1..]) {
+            'Q' => match config
+                .attempt(|| demangle_namespaced_function(config, sym_name, &the_rest[1..]))
+            {
                 Ok(d) => return Ok(d),
                 Err(e) => leading_error.or(Some(e)),
             },
@@ -112,8 +301,8 @@ fn demangle_impl_failables<'s>(
         None
     };
 
-    let leading_error = if let Some(sym) = sym.strip_prefix("_vt") {
-        match demangle_virtual_table(config, sym, cplus_marker) {
+    let leading_error = if let Some((offset, sym)) = virtual_table_prefix(config, sym) {
+        match config.attempt(|| demangle_virtual_table(config, sym, cplus_marker, offset)) {
             Ok(d) => return Ok(d),
             Err(e) => leading_error.or(Some(e)),
         }
@@ -122,7 +311,7 @@ fn demangle_impl_failables<'s>(
     };
 
     let leading_error = if let Some((s, name)) = sym.c_split2_char(cplus_marker) {
-        match demangle_namespaced_global(config, s, name) {
+        match config.attempt(|| demangle_namespaced_global(config, sym, cplus_marker, s, name)) {
             Ok(d) => return Ok(d),
             Err(e) => leading_error.or(Some(e)),
         }
@@ -134,35 +323,118 @@ fn demangle_impl_failables<'s>(
     Err(leading_error.unwrap_or(DemangleError::NotMangled))
 }
 
-fn demangle_destructor<'s>(
-    config: &DemangleConfig,
+/// A templated constructor, like the one of
+/// `__H1ZPCc_t6vector2ZcZt9allocator1Zc_X01X01_v`.
+///
+/// `s` must start right after the `H`.
+fn demangle_templated_constructor<'s>(
+    config: &Context,
     s: &'s str,
 ) -> Result<String, DemangleError<'s>> {
+    // Same as `demangle_templated_function`.
+    let allow_array_fixup = true;
+    let (remaining, mut template_args, scope) =
+        demangle_template_with_return_type(config, s, allow_array_fixup)?;
+    let allow_array_fixup = false;
+
+    let (remaining, class_name, bare, name) = if let Some((namespaces, bare, trailing)) = scope {
+        let name = config.tokens().marked(Token::Name, trailing).into_owned();
+        (remaining, namespaces, bare_scope(config, bare), name)
+    } else if let Some(r) = remaining.strip_prefix('t') {
+        let (r, template, typ, types) =
+            demangle_template_and_types(config, r, &ArgVec::new(config, None), allow_array_fixup)?;
+        let mut enclosing = config.scratch_vec();
+        enclosing.push(types.level());
+        template_args.set_enclosing(enclosing);
+        let shown = bare_template_scope(config, typ);
+        scope_span(config, remaining, r, shown.as_deref().unwrap_or(&template));
+
+        let name = config.tokens().marked(Token::Name, typ).into_owned();
+        (r, Cow::from(template), shown, name)
+    } else {
+        return Err(DemangleError::InvalidClassNameOnConstructor(remaining));
+    };
+
+    // Some compilers separate the class from the arguments.
+    let remaining = remaining.strip_prefix('_').unwrap_or(remaining);
+    let (remaining, argument_list) = demangle_argument_list_impl(
+        config,
+        remaining,
+        Some(&class_name),
+        &template_args,
+        false,
+        allow_array_fixup,
+    )?;
+
+    // The return type, if any, is always `void`.
+    if let Some(ret) = remaining.strip_prefix('_') {
+        let (r, return_type) = demangle_argument(
+            config,
+            ret,
+            &ArgVec::new(config, Some(&class_name)),
+            &template_args,
+            allow_array_fixup,
+        )?;
+        if !r.is_empty() {
+            return Err(
+                DemangleError::TrailingDataAfterReturnTypeOfMalformedTemplateWithReturnType(r),
+            );
+        }
+        config.span(SpanKind::ReturnType, ret, "", || {
+            return_type.type_string(config.type_pointer_spacing)
+        });
+    } else if !remaining.is_empty() {
+        return Err(DemangleError::TrailingDataAfterArgumentList(remaining));
+    }
+
+    let mut templated_name = name;
+    template_args.push_template_list(&mut templated_name)?;
+
+    Ok(function_signature(
+        config,
+        Some(bare.as_deref().unwrap_or(&class_name)),
+        &templated_name,
+        &argument_list.join()?,
+        "",
+    ))
+}
+
+fn demangle_destructor<'s>(config: &Context, s: &'s str) -> Result<String, DemangleError<'s>> {
     let allow_array_fixup = true;
 
-    let (r, namespace, typ) = if let Some(s) = s.strip_prefix('t') {
+    let (r, namespace, typ) = if let Some(t) = s.strip_prefix('t') {
         let (r, template, typ) =
-            demangle_template(config, s, &ArgVec::new(config, None), allow_array_fixup)?;
-        (r, Cow::from(template), Cow::from(typ))
+            demangle_template(config, t, &ArgVec::new(config, None), allow_array_fixup)?;
+        let shown = bare_template_scope(config, typ).unwrap_or(Cow::from(template));
+        scope_span(config, s, r, &shown);
+        (r, shown, typ)
     } else if let Some(s) = s.strip_prefix('Q') {
-        let (r, namespaces, trailing_namespace) =
-            demangle_namespaces(config, s, &ArgVec::new(config, None), allow_array_fixup)?;
-        (r, Cow::from(namespaces), Cow::from(trailing_namespace))
+        let scope = demangle_scope(config, s, &ArgVec::new(config, None), allow_array_fixup)?;
+        let shown = bare_scope(config, scope.bare).unwrap_or(Cow::from(scope.namespaces));
+        (scope.remaining, shown, scope.trailing_namespace)
     } else {
         let Remaining { r, d: class_name } =
-            demangle_custom_name(s, DemangleError::InvalidClassNameOnDestructor)?;
-        (r, Cow::from(class_name), Cow::from(class_name))
+            demangle_trailing_custom_name(config, s, DemangleError::InvalidClassNameOnDestructor)?;
+        scope_span(config, s, r, class_name);
+        let namespace = config.tokens().marked(Token::Type, class_name);
+        (r, namespace, class_name)
     };
 
     if r.is_empty() {
-        Ok(format!("{namespace}::~{typ}(void)"))
+        Ok(function_signature(
+            config,
+            Some(&namespace),
+            &config.tokens().marked(Token::Name, &format!("~{typ}")),
+            &config.tokens().marked(Token::Type, "void"),
+            "",
+        ))
     } else {
         Err(DemangleError::TrailingDataOnDestructor(r))
     }
 }
 
 fn demangle_special<'s>(
-    config: &DemangleConfig,
+    config: &Context,
     s: &'s str,
     full_sym: &'s str,
 ) -> Result<String, DemangleError<'s>> {
@@ -172,27 +444,64 @@ fn demangle_special<'s>(
         .next()
         .ok_or(DemangleError::RanOutWhileDemanglingSpecial)?;
 
-    let (remaining, class_name, method_name, suffix) = if matches!(c, '1'..='9') {
+    // Plain C symbols, like `__throw` or `__Qsort`, must not be mistaken for
+    // templated or namespaced constructors.
+    let templated_ctor = s
+        .strip_prefix('t')
+        .filter(|r| r.starts_with(|c: char| c.is_ascii_digit()));
+    let namespaced_ctor = s
+        .strip_prefix('Q')
+        .filter(|r| r.starts_with(|c: char| c == '_' || c.is_ascii_digit()));
+
+    // A member template used as a constructor, which has no name since it is
+    // the class that follows the template arguments.
+    if let Some(templated) = s
+        .strip_prefix('H')
+        .filter(|r| r.starts_with(|c: char| c.is_ascii_digit()))
+    {
+        return demangle_templated_constructor(config, templated);
+    }
+
+    let is_constructor =
+        matches!(c, '1'..='9') || templated_ctor.is_some() || namespaced_ctor.is_some();
+    // The class is kept as-is for the lookbacks of the argument list, even if
+    // it is shown without its template arguments.
+    let (remaining, class_name, shown_class, method_name, suffix) = if matches!(c, '1'..='9') {
         // class constructor
         let Remaining { r, d: class_name } =
-            demangle_custom_name(s, DemangleError::InvalidClassNameOnConstructor)?;
+            demangle_trailing_custom_name(config, s, DemangleError::InvalidClassNameOnConstructor)?;
+        scope_span(config, s, r, class_name);
 
-        (r, Some(Cow::from(class_name)), Cow::from(class_name), "")
+        (
+            r,
+            Some(config.tokens().marked(Token::Type, class_name)),
+            None,
+            config.tokens().marked(Token::Name, class_name),
+            Cow::from(""),
+        )
     } else if let Some(remaining) = s.strip_prefix("tf") {
         return demangle_type_info_function(config, remaining);
     } else if let Some(remaining) = s.strip_prefix("ti") {
         return demangle_type_info_node(config, remaining);
-    } else if let Some(remaining) = s.strip_prefix('t') {
+    } else if let Some(templated) = templated_ctor {
         let (remaining, template, typ) = demangle_template(
             config,
-            remaining,
+            templated,
             &ArgVec::new(config, None),
             allow_array_fixup,
         )?;
+        let shown = bare_template_scope(config, typ);
+        scope_span(config, s, remaining, shown.as_deref().unwrap_or(&template));
 
-        (remaining, Some(Cow::from(template)), Cow::from(typ), "")
-    } else if let Some(q_less) = s.strip_prefix('Q') {
-        let (remaining, namespaces, trailing_namespace) = demangle_namespaces(
+        (
+            remaining,
+            Some(Cow::from(template)),
+            shown,
+            config.tokens().marked(Token::Name, typ),
+            Cow::from(""),
+        )
+    } else if let Some(q_less) = namespaced_ctor {
+        let scope = demangle_scope(
             config,
             q_less,
             &ArgVec::new(config, None),
@@ -200,181 +509,227 @@ fn demangle_special<'s>(
         )?;
 
         (
-            remaining,
-            Some(Cow::from(namespaces)),
-            Cow::from(trailing_namespace),
-            "",
+            scope.remaining,
+            Some(Cow::from(scope.namespaces)),
+            bare_scope(config, scope.bare),
+            config
+                .tokens()
+                .marked(Token::Name, scope.trailing_namespace),
+            Cow::from(""),
         )
     } else {
-        let end_index = s.find("__").ok_or(DemangleError::InvalidSpecialMethod(s))?;
+        // Plain C symbols, like libgcc's `__divdi3`.
+        let end_index = s.find("__").ok_or(DemangleError::NotMangled)?;
         let op = &s[..end_index];
 
         // Skip the underscore
         let remaining = &s[end_index + 2..];
 
-        let method_name = match op {
-            // Memory
-            "nw" => Cow::from("operator new"),
-            "dl" => Cow::from("operator delete"),
-            "vn" => Cow::from("operator new []"),
-            "vd" => Cow::from("operator delete []"),
-
-            // Comparison
-            "eq" => Cow::from("operator=="),
-            "ne" => Cow::from("operator!="),
-            "lt" => Cow::from("operator<"),
-            "gt" => Cow::from("operator>"),
-            "le" => Cow::from("operator<="),
-            "ge" => Cow::from("operator>="),
-
-            // Assignment
-            "as" => Cow::from("operator="),
-            "apl" => Cow::from("operator+="),
-            "ami" => Cow::from("operator-="),
-            "aml" => Cow::from("operator*="),
-            "adv" => Cow::from("operator/="),
-            "amd" => Cow::from("operator%="),
-            "aer" => Cow::from("operator^="),
-            "aad" => Cow::from("operator&="),
-            "aor" => Cow::from("operator|="),
-            "als" => Cow::from("operator<<="),
-            "ars" => Cow::from("operator>>="),
-
-            // Bitwise
-            "er" => Cow::from("operator^"),
-            "ad" => Cow::from("operator&"),
-            "or" => Cow::from("operator|"),
-            "ls" => Cow::from("operator<<"),
-            "rs" => Cow::from("operator>>"),
-            "co" => Cow::from("operator~"),
-
-            // Increment/Decrement
-            "pp" => Cow::from("operator++"),
-            "mm" => Cow::from("operator--"),
-
-            // Logical
-            "aa" => Cow::from("operator&&"),
-            "oo" => Cow::from("operator||"),
-            "nt" => Cow::from("operator!"),
-
-            // Member access
-            "vc" => Cow::from("operator[]"),
-            "rf" => Cow::from("operator->"),
-            "rm" => Cow::from("operator->*"),
-
-            // Arithmetic
-            "pl" => Cow::from("operator+"),
-            "mi" => Cow::from("operator-"),
-            "ml" => Cow::from("operator*"),
-            "dv" => Cow::from("operator/"),
-            "md" => Cow::from("operator%"),
-
-            // Other
-            "cl" => Cow::from("operator()"),
-            "cm" => Cow::from("operator, "),
-
-            _ => {
-                if let Some(cast) = op.strip_prefix("op") {
-                    let (remaining, DemangledArg::Plain(typ, array_qualifiers)) =
-                        demangle_argument(
-                            config,
-                            cast,
-                            &ArgVec::new(config, None),
-                            &ArgVec::new(config, None),
-                            allow_array_fixup,
-                        )?
-                    else {
-                        return Err(DemangleError::UnrecognizedSpecialMethod(op));
-                    };
-                    if !remaining.is_empty() {
-                        return Err(DemangleError::MalformedCastOperatorOverload(remaining));
-                    }
+        let method_name = if let Some(operator) = demangle_operator_name(op) {
+            config.tokens().marked(Token::Operator, operator)
+        } else if let (Some(cast), Some(remaining)) =
+            (op.strip_prefix("op"), remaining.strip_prefix('H'))
+        {
+            // Templated conversion operator. The type may reference the
+            // template arguments, so it has to be demangled after them.
+            return demangle_templated_function(config, "", Some(cast), remaining);
+        } else if let Some(cast) = op.strip_prefix("op") {
+            Cow::from(demangle_cast_operator(
+                config,
+                op,
+                cast,
+                &ArgVec::new(config, None),
+                allow_array_fixup,
+            )?)
+        } else {
+            // This may be a plain function that got confused with a special
+            // symbol, so try to decode as a function instead.
+            // If none of those work then this is likely a plain C symbol,
+            // like `__FRAME_BEGIN__`, which is better left as-is than
+            // demangled as garbage.
+            let as_free_function = || {
+                let (func_name, args) = full_sym.c_split2("__F")?;
+                config
+                    .attempt(|| demangle_free_function(config, func_name, args))
+                    .ok()
+            };
+            let as_method = || {
+                let (incomplete_method_name, class_and_args, _c) = s
+                    .c_split2_r_starts_with("__", |c| {
+                        matches!(c, '1'..='9' | 'C' | 'V' | 'u' | 't')
+                    })?;
+                // split `s` instead of `full_sym` to skip over the first `__`,
+                // if that check passes, then recover the actual method name,
+                // including the initial `__`, by using the length of the
+                // `incomplete_method_name` to slice the `full_sym`.
+                let method_name = &full_sym[..incomplete_method_name.len() + 2];
+                config
+                    .attempt(|| demangle_method(config, method_name, class_and_args))
+                    .ok()
+            };
+            let as_templated_function = || {
+                let (func_name, s) = full_sym.c_split2("__H")?;
+                let func_name = config.tokens().marked(Token::Name, func_name);
+                config
+                    .attempt(|| demangle_templated_function(config, &func_name, None, s))
+                    .ok()
+            };
 
-                    Cow::from(format!("operator {typ}{array_qualifiers}"))
-                } else {
-                    return {
-                        // This may be a plain function that got confused with a
-                        // special symbol, so try to decode as a function instead.
-                        if let Some((func_name, args)) = full_sym.c_split2("__F") {
-                            demangle_free_function(config, func_name, args)
-                        } else if let Some((incomplete_method_name, class_and_args, _c)) =
-                            s.c_split2_r_starts_with("__", |c| matches!(c, '1'..='9' | 'C' | 't'))
-                        {
-                            // split `s` instead of `full_sym` to skip over the
-                            // first `__`,
-                            // if that check passes, then recover the actual
-                            // method name, including the initial `__`, by
-                            // using the length of the `incomplete_method_name`
-                            // to slice the `full_sym`.
-
-                            let method_name = &full_sym[..incomplete_method_name.len() + 2];
-                            demangle_method(config, method_name, class_and_args)
-                        } else if let Some((func_name, s)) = full_sym.c_split2("__H") {
-                            demangle_templated_function(config, func_name, s)
-                        } else {
-                            Err(DemangleError::UnrecognizedSpecialMethod(op))
-                        }
-                    };
-                }
-            }
+            return as_free_function()
+                .or_else(as_method)
+                .or_else(as_templated_function)
+                .ok_or(DemangleError::NotMangled);
         };
 
         if let Some(remaining) = remaining.strip_prefix('F') {
-            (remaining, None, method_name, "")
+            (remaining, None, None, method_name, Cow::from(""))
+        } else if let Some(remaining) = remaining.strip_prefix('H') {
+            // Templated free operator, like `operator==<tName>`
+            return demangle_templated_function(config, &method_name, None, remaining);
         } else {
             let Remaining {
                 r: remaining,
                 d: suffix,
-            } = demangle_method_qualifier(remaining);
+            } = demangle_method_qualifier(config, remaining);
 
-            let (remaining, namespaces) = if let Some(q_less) = remaining.strip_prefix('Q') {
-                let (remaining, namespaces, _trailing_namespace) = demangle_namespaces(
+            let (remaining, namespaces, shown) = if let Some(q_less) = remaining.strip_prefix('Q') {
+                let scope = demangle_scope(
                     config,
                     q_less,
                     &ArgVec::new(config, None),
                     allow_array_fixup,
                 )?;
 
-                (remaining, Cow::from(namespaces))
+                (
+                    scope.remaining,
+                    Cow::from(scope.namespaces),
+                    bare_scope(config, scope.bare),
+                )
             } else if let Some(r) = remaining.strip_prefix('t') {
-                let (remaining, template, _typ) =
+                let (r, template, typ) =
                     demangle_template(config, r, &ArgVec::new(config, None), allow_array_fixup)?;
+                let shown = bare_template_scope(config, typ);
+                scope_span(config, remaining, r, shown.as_deref().unwrap_or(&template));
 
-                (remaining, Cow::from(template))
+                (r, Cow::from(template), shown)
             } else {
-                let Remaining { r, d: class_name } =
-                    demangle_custom_name(remaining, DemangleError::InvalidClassNameOnOperator)?
-                        .d_as_cow();
+                let Remaining { r, d: class_name } = demangle_trailing_custom_name(
+                    config,
+                    remaining,
+                    DemangleError::InvalidClassNameOnOperator,
+                )?;
+                scope_span(config, remaining, r, class_name);
 
-                (r, class_name)
+                let class_name = config.tokens().marked(Token::Type, class_name);
+                (r, class_name, None)
             };
 
-            (remaining, Some(namespaces), method_name, suffix)
+            (
+                remaining,
+                Some(namespaces),
+                shown,
+                method_name,
+                Cow::from(suffix),
+            )
         }
     };
 
-    let argument_list = if remaining.is_empty() {
-        "void"
+    let (argument_list, annotation) = if remaining.is_empty() {
+        (config.tokens().marked(Token::Type, "void"), "")
+    } else {
+        match config.attempt(|| {
+            demangle_argument_list(
+                config,
+                remaining,
+                class_name.as_deref(),
+                &ArgVec::new(config, None),
+                allow_array_fixup,
+                !is_constructor,
+            )
+        }) {
+            Ok(argument_list) => (Cow::from(argument_list), ""),
+            Err(e) if is_constructor => {
+                match config.attempt(|| {
+                    demangle_constructor_variant(config, remaining, class_name.as_deref(), e)
+                }) {
+                    Ok((argument_list, annotation)) => (argument_list, annotation),
+                    // Only complete a truncated constructor once it is known
+                    // to not have a variant marker.
+                    Err(DemangleError::LikelyTruncated(..)) => {
+                        let argument_list = demangle_argument_list(
+                            config,
+                            remaining,
+                            class_name.as_deref(),
+                            &ArgVec::new(config, None),
+                            allow_array_fixup,
+                            true,
+                        )?;
+                        (Cow::from(argument_list), "")
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            Err(e) => return Err(e),
+        }
+    };
+
+    let mut out = function_signature(
+        config,
+        shown_class.or(class_name).as_deref(),
+        &method_name,
+        &argument_list,
+        &suffix,
+    );
+    out.push_str(annotation);
+    Ok(out)
+}
+
+/// Some hybrid toolchains emit a digit between the class name of a
+/// constructor and its argument list, to tell apart its variants.
+///
+/// The digit is only considered a marker if the argument list couldn't be
+/// demangled with it, so `err` is returned if it doesn't look like one.
+fn demangle_constructor_variant<'s>(
+    config: &Context,
+    s: &'s str,
+    class_name: Option<&str>,
+    err: DemangleError<'s>,
+) -> Result<(Cow<'static, str>, &'static str), DemangleError<'s>> {
+    let allow_array_fixup = true;
+
+    let annotation = match s.chars().next() {
+        Some('1') => " [in-charge]",
+        Some('2') => " [not-in-charge]",
+        _ => return Err(err),
+    };
+
+    let args = &s[1..];
+    let argument_list = if args.is_empty() {
+        config.tokens().marked(Token::Type, "void")
     } else {
-        &demangle_argument_list(
+        let Ok(argument_list) = demangle_argument_list(
             config,
-            remaining,
-            class_name.as_deref(),
+            args,
+            class_name,
             &ArgVec::new(config, None),
             allow_array_fixup,
-        )?
+            false,
+        ) else {
+            return Err(err);
+        };
+        Cow::from(argument_list)
     };
 
-    let out = if let Some(class_name) = class_name {
-        format!("{class_name}::{method_name}({argument_list}){suffix}")
-    } else {
-        format!("{method_name}({argument_list}){suffix}")
-    };
-    Ok(out)
+    config.saw(Construct::ConstructorVariant);
+    if !config.demangle_constructor_variants {
+        return Err(DemangleError::ConstructorVariantMarker(s));
+    }
+
+    Ok((argument_list, annotation))
 }
 
 fn demangle_free_function<'s>(
-    config: &DemangleConfig,
+    config: &Context,
     func_name: &'s str,
     args: &'s str,
 ) -> Result<String, DemangleError<'s>> {
@@ -386,13 +741,20 @@ fn demangle_free_function<'s>(
         None,
         &ArgVec::new(config, None),
         allow_array_fixup,
+        true,
     )?;
 
-    Ok(format!("{func_name}({argument_list})"))
+    Ok(function_signature(
+        config,
+        None,
+        &config.tokens().marked(Token::Name, func_name),
+        &argument_list,
+        "",
+    ))
 }
 
 fn demangle_method<'s>(
-    config: &DemangleConfig,
+    config: &Context,
     method_name: &'s str,
     class_and_args: &'s str,
 ) -> Result<String, DemangleError<'s>> {
@@ -400,47 +762,138 @@ fn demangle_method<'s>(
     let Remaining {
         r: remaining,
         d: suffix,
-    } = demangle_method_qualifier(class_and_args);
+    } = demangle_method_qualifier(config, class_and_args);
 
-    let (remaining, namespace) = if let Some(templated) = remaining.strip_prefix('t') {
-        let (remaining, template, _typ) = demangle_template(
+    let (remaining, namespace, shown) = if let Some(templated) = remaining.strip_prefix('t') {
+        let (r, template, typ) = demangle_template(
             config,
             templated,
             &ArgVec::new(config, None),
             allow_array_fixup,
         )?;
+        let shown = bare_template_scope(config, typ);
+        scope_span(config, remaining, r, shown.as_deref().unwrap_or(&template));
 
-        (remaining, Cow::from(template))
+        (r, Cow::from(template), shown)
     } else if let Some(q_less) = remaining.strip_prefix('Q') {
-        let (remaining, namespaces, _trailing_namespace) = demangle_namespaces(
+        let scope = demangle_scope(
             config,
             q_less,
             &ArgVec::new(config, None),
             allow_array_fixup,
         )?;
 
-        (remaining, Cow::from(namespaces))
+        (
+            scope.remaining,
+            Cow::from(scope.namespaces),
+            bare_scope(config, scope.bare),
+        )
     } else {
-        let Remaining { r, d: class_name } =
-            demangle_custom_name(remaining, DemangleError::InvalidClassNameOnMethod)?.d_as_cow();
+        let class_name = demangle_trailing_custom_name(
+            config,
+            remaining,
+            DemangleError::InvalidClassNameOnMethod,
+        );
+        let no_arguments = match &class_name {
+            Ok(Remaining { r, .. }) => r.is_empty(),
+            Err(DemangleError::LikelyTruncated(..)) => true,
+            Err(_) => false,
+        };
+        if no_arguments && suffix.is_empty() && looks_like_plain_c(method_name, remaining) {
+            config.saw(Construct::PlainCLookalike);
+            if config.reject_plain_c_lookalikes {
+                return Err(DemangleError::NotMangled);
+            }
+        }
+        let Remaining { r, d: class_name } = class_name?;
+        scope_span(config, remaining, r, class_name);
 
-        (r, class_name)
+        let class_name = config.tokens().marked(Token::Type, class_name);
+        (r, class_name, None)
     };
 
     let argument_list = if remaining.is_empty() {
-        "void"
+        config.tokens().marked(Token::Type, "void")
     } else {
-        &demangle_argument_list(
+        Cow::from(demangle_argument_list(
             config,
             remaining,
             Some(&namespace),
             &ArgVec::new(config, None),
             allow_array_fixup,
-        )?
+            true,
+        )?)
     };
 
+    Ok(function_signature(
+        config,
+        Some(shown.as_deref().unwrap_or(&namespace)),
+        &config.tokens().marked(Token::Name, method_name),
+        &argument_list,
+        &suffix,
+    ))
+}
+
+/// Check if `method_name` and the class name after it look like the name of
+/// a C function that happens to have a `__` in it, like `crc32__8_compute`.
+///
+/// Class names don't usually start with an underscore followed by a
+/// lowercase letter or a digit, while C names with a `__` often do.
+pub(crate) fn looks_like_plain_c(method_name: &str, class_name: &str) -> bool {
+    let is_c_name = |c: char| matches!(c, 'a'..='z' | '0'..='9' | '_');
+    let mut name = class_name
+        .trim_start_matches(|c: char| c.is_ascii_digit())
+        .chars();
+
+    method_name.chars().all(is_c_name)
+        && name.next() == Some('_')
+        && name
+            .next()
+            .is_some_and(|c| matches!(c, 'a'..='z' | '0'..='9'))
+}
+
+/// The name of a conversion operator, like `operator int`, from the still
+/// mangled type it converts to.
+///
+/// Function and method pointers are spelled as they would be on their own,
+/// like `operator void (*)(int)`. `op` is the part of the symbol reported if
+/// the type is not one a conversion can target.
+fn demangle_cast_operator<'s>(
+    config: &Context,
+    op: &'s str,
+    cast: &'s str,
+    template_args: &ArgVec,
+    allow_array_fixup: bool,
+) -> Result<String, DemangleError<'s>> {
+    let (r, cast_type) = demangle_argument(
+        config,
+        cast,
+        &ArgVec::new(config, None),
+        template_args,
+        allow_array_fixup,
+    )?;
+    if !matches!(
+        cast_type,
+        DemangledArg::Plain(..)
+            | DemangledArg::FunctionPointer(..)
+            | DemangledArg::MethodPointer(..)
+    ) {
+        return Err(DemangleError::UnrecognizedSpecialMethod(op));
+    }
+    if !r.is_empty() {
+        return Err(DemangleError::MalformedCastOperatorOverload(r));
+    }
+
+    let cast_type = match &cast_type {
+        DemangledArg::Plain(typ, array_qualifiers) => match array_qualifiers.as_option() {
+            Some(arr) => arr.qualify(typ, ArrayPosition::CastTarget, config.type_pointer_spacing),
+            None => typ.clone(),
+        },
+        _ => cast_type.type_string(config.type_pointer_spacing),
+    };
     Ok(format!(
-        "{namespace}::{method_name}({argument_list}){suffix}"
+        "{} {cast_type}",
+        config.tokens().mark(Token::Operator, "operator")
     ))
 }
 
@@ -448,44 +901,70 @@ fn demangle_method<'s>(
 ///
 /// A templated method is templated individually, it doesn't matter if the
 /// class it comes from is templated or not.
+/// `cast` is the still mangled type of a templated conversion operator, in
+/// which case `func_name` is ignored.
 fn demangle_templated_function<'s>(
-    config: &DemangleConfig,
-    func_name: &'s str,
+    config: &Context,
+    func_name: &str,
+    cast: Option<&'s str>,
     s: &'s str,
 ) -> Result<String, DemangleError<'s>> {
     // Arrays do need to be fixed up if it appears in the template list, but
     // not in the rest of the definition.
     let allow_array_fixup = true;
-    let (remaining, template_args, typ) =
+    let (remaining, mut template_args, typ) =
         demangle_template_with_return_type(config, s, allow_array_fixup)?;
+
+    let func_name = if let Some(cast) = cast {
+        Cow::from(demangle_cast_operator(
+            config,
+            cast,
+            cast,
+            &template_args,
+            allow_array_fixup,
+        )?)
+    } else {
+        Cow::from(func_name)
+    };
     let allow_array_fixup = false;
 
     let Remaining {
         r: remaining,
         d: suffix,
-    } = demangle_method_qualifier(remaining);
+    } = demangle_method_qualifier(config, remaining);
 
-    let (remaining, typ) = if let Some(typ) = typ {
-        (remaining, Some(typ))
+    let (remaining, typ, bare_typ) = if let Some((typ, bare, _)) = typ {
+        (remaining, Some(typ), bare_scope(config, bare))
     } else if remaining.starts_with(|c| matches!(c, '1'..='9')) {
         let Remaining { r, d: namespace } = demangle_custom_name(
+            config,
             remaining,
             DemangleError::InvalidNamespaceOnTemplatedFunction,
-        )?
-        .d_as_cow();
-        (r, Some(namespace))
+        )?;
+        scope_span(config, remaining, r, namespace);
+        let namespace = config.tokens().marked(Token::Type, namespace);
+        (r, Some(namespace), None)
     } else if let Some(r) = remaining.strip_prefix('t') {
-        let (r, template, _typ) =
-            demangle_template(config, r, &ArgVec::new(config, None), allow_array_fixup)?;
-
-        (r, Some(Cow::from(template)))
+        let (r, template, typ, types) =
+            demangle_template_and_types(config, r, &ArgVec::new(config, None), allow_array_fixup)?;
+        let mut enclosing = config.scratch_vec();
+        enclosing.push(types.level());
+        template_args.set_enclosing(enclosing);
+        let shown = bare_template_scope(config, typ);
+        scope_span(config, remaining, r, shown.as_deref().unwrap_or(&template));
+
+        (r, Some(Cow::from(template)), shown)
     } else if let Some(r) = remaining.strip_prefix('Q') {
-        let (r, namespaces, _trailing_namespace) =
-            demangle_namespaces(config, r, &ArgVec::new(config, None), allow_array_fixup)?;
+        let scope = demangle_scope(config, r, &ArgVec::new(config, None), allow_array_fixup)?;
+        template_args.set_enclosing(scope.levels);
 
-        (r, Some(Cow::from(namespaces)))
+        (
+            scope.remaining,
+            Some(Cow::from(scope.namespaces)),
+            bare_scope(config, scope.bare),
+        )
     } else {
-        (remaining, None)
+        (remaining, None, None)
     };
 
     // - First there's an optional namespace used in template specializations.
@@ -495,19 +974,15 @@ fn demangle_templated_function<'s>(
 
     // Demangle the specialization namespace
     let (remaining, specialization_namespace) = if let Some(r) = remaining.strip_prefix('_') {
-        let (r, DemangledArg::Plain(specialization_namespace, array_qualifiers)) =
-            demangle_argument(
-                config,
-                r,
-                &ArgVec::new(config, typ.as_deref()),
-                &template_args,
-                allow_array_fixup,
-            )?
-        else {
-            return Err(DemangleError::MalformedTemplatedSpecializationInvalidNamespace(r));
-        };
+        let (remaining, specialization_namespace) = demangle_argument(
+            config,
+            r,
+            &ArgVec::new(config, typ.as_deref()),
+            &template_args,
+            allow_array_fixup,
+        )?;
 
-        (r, Some((specialization_namespace, array_qualifiers)))
+        (remaining, Some((r, specialization_namespace)))
     } else {
         (remaining, None)
     };
@@ -523,26 +998,31 @@ fn demangle_templated_function<'s>(
     )?;
 
     // Demangle the return type
-    let (specialization_namespace, return_type, array_qualifiers) =
-        if let Some(r) = remaining.strip_prefix('_') {
-            let (r, DemangledArg::Plain(ret_type, array_qualifiers)) = demangle_argument(
+    let (specialization_namespace, return_type, ret) =
+        if let Some(ret) = remaining.strip_prefix('_') {
+            let (r, return_type) = demangle_argument(
                 config,
-                r,
+                ret,
                 &ArgVec::new(config, typ.as_deref()),
                 &template_args,
                 allow_array_fixup,
-            )?
-            else {
-                return Err(DemangleError::MalformedTemplateWithReturnTypeMissingReturnType(r));
-            };
+            )?;
 
             if !r.is_empty() {
                 return Err(
                     DemangleError::TrailingDataAfterReturnTypeOfMalformedTemplateWithReturnType(r),
                 );
             }
-            (specialization_namespace, ret_type, array_qualifiers)
-        } else if let Some((actual_return_type, array_qualifiers)) = specialization_namespace {
+
+            let specialization_namespace = match specialization_namespace {
+                None => None,
+                Some((_, DemangledArg::Plain(namespace, _array_qualifiers))) => Some(namespace),
+                Some((r, _)) => {
+                    return Err(DemangleError::MalformedTemplatedSpecializationInvalidNamespace(r))
+                }
+            };
+            (specialization_namespace, return_type, ret)
+        } else if let Some((ret, actual_return_type)) = specialization_namespace {
             // If there's no argument list and this symbol is not a template
             // specialization inside a namespace then we mistakenly consumed the
             // return type as the specialization_namespace
@@ -553,133 +1033,272 @@ fn demangle_templated_function<'s>(
                 );
             }
 
-            (None, actual_return_type, array_qualifiers)
+            (None, actual_return_type, ret)
         } else {
             return Err(DemangleError::MalformedTemplateWithReturnTypeMissingReturnType(remaining));
         };
+    // Either way the return type is the rest of the symbol.
+    config.span(SpanKind::ReturnType, ret, "", || {
+        return_type.type_string(config.type_pointer_spacing)
+    });
 
-    let template_args = template_args.join();
-    let formated_template_args = if template_args.ends_with('>') {
-        format!("<{} >", template_args)
-    } else {
-        format!("<{}>", template_args)
-    };
-    let argument_list = argument_list.join();
+    let argument_list = argument_list.join()?;
 
-    let mut out = return_type;
-    if let Some((specialization_namespace, _array_qualifiers)) = specialization_namespace {
-        out.push(' ');
-        out.push_str(&specialization_namespace);
+    // Everything that goes after the return type.
+    let mut templated_name = func_name.into_owned();
+    template_args.push_template_list(&mut templated_name)?;
+    let mut name = function_signature(
+        config,
+        bare_typ.as_deref().or(typ.as_deref()),
+        &templated_name,
+        &argument_list,
+        &suffix,
+    );
+    if let Some(specialization_namespace) = &specialization_namespace {
+        if !matches!(return_type, DemangledArg::Plain(..)) {
+            name = format!("{specialization_namespace} {name}");
+        }
     }
-    if let Some(array_qualifiers) = array_qualifiers.as_option() {
-        if config.fix_array_in_return_position {
-            out.push_str(" (");
-            out.push_str(&array_qualifiers.inner_post_qualifiers);
-        } else {
-            out.push_str(&array_qualifiers.to_string());
-            out.push(' ');
+
+    let out = match (config.return_type_style, return_type) {
+        (_, DemangledArg::Repeat { .. } | DemangledArg::Ellipsis) => {
+            return Err(DemangleError::MalformedTemplateWithReturnTypeMissingReturnType(ret));
+        }
+        (ReturnTypeStyle::Omit | ReturnTypeStyle::TrailingArrow, return_type) => {
+            let mut out = match specialization_namespace {
+                Some(specialization_namespace)
+                    if matches!(return_type, DemangledArg::Plain(..)) =>
+                {
+                    format!("{specialization_namespace} {name}")
+                }
+                _ => name,
+            };
+            if config.return_type_style == ReturnTypeStyle::TrailingArrow {
+                out.push_str(" -> ");
+                out.push_str(&return_type.type_string(config.type_pointer_spacing));
+            }
+            out
         }
+        (ReturnTypeStyle::CfiltLeading, DemangledArg::Plain(return_type, array_qualifiers)) => {
+            let mut out = return_type;
+            if let Some(specialization_namespace) = specialization_namespace {
+                out.push(' ');
+                out.push_str(&specialization_namespace);
+            }
+            if let Some(array_qualifiers) = array_qualifiers.as_option() {
+                config.saw(Construct::ArrayReturn);
+                if config.fix_array_in_return_position {
+                    return Ok(array_qualifiers.qualify(
+                        &out,
+                        ArrayPosition::Return(&name, config.cuts()),
+                        config.type_pointer_spacing,
+                    ));
+                }
+                out = array_qualifiers.qualify(
+                    &out,
+                    ArrayPosition::Argument,
+                    config.type_pointer_spacing,
+                );
+                out.push(' ');
+            } else if needs_space(config.type_pointer_spacing, &out, &name) {
+                out.push(' ');
+            }
+            out.push_str(&name);
+            out
+        }
+        (ReturnTypeStyle::CfiltLeading, DemangledArg::FunctionPointer(fp)) => {
+            config.saw(Construct::FunctionPointerReturn);
+            if config.fix_function_pointer_in_return_position {
+                // The function goes inside the declarator of the returned
+                // function pointer, like `void (*f<int>(int))(int)`.
+                let post_qualifiers = return_declarator(config, &fp.post_qualifiers, &name);
+                FunctionPointer {
+                    post_qualifiers,
+                    ..fp
+                }
+                .to_string()
+            } else {
+                format!("{fp} {name}")
+            }
+        }
+        (ReturnTypeStyle::CfiltLeading, DemangledArg::MethodPointer(mp)) => {
+            config.saw(Construct::FunctionPointerReturn);
+            if config.fix_function_pointer_in_return_position {
+                let post_qualifiers = return_declarator(config, &mp.post_qualifiers, &name);
+                MethodPointer {
+                    post_qualifiers,
+                    ..mp
+                }
+                .to_string()
+            } else {
+                format!("{mp} {name}")
+            }
+        }
+    };
+
+    Ok(out)
+}
+
+/// Append the `name` of a function to the declarator of the function pointer
+/// it returns, like `*` or `* const`.
+fn return_declarator(config: &Context, post_qualifiers: &str, name: &str) -> String {
+    let post_qualifiers = post_qualifiers.trim();
+    if needs_space_in_declarator(config.type_pointer_spacing, post_qualifiers, name) {
+        format!("{post_qualifiers} {name}")
     } else {
-        out.push(' ');
+        format!("{post_qualifiers}{name}")
     }
-    if let Some(typ) = typ {
-        out.push_str(&typ);
-        out.push_str("::");
+}
+
+/// The scope of a function without its template arguments, if it has to be
+/// shown like that.
+fn bare_scope<'a>(config: &Context, bare: impl Into<Cow<'a, str>>) -> Option<Cow<'a, str>> {
+    config.omit_template_args_in_scope.then(|| bare.into())
+}
+
+/// Same as [`bare_scope`], for the bare name of a template, like `List`.
+fn bare_template_scope<'a>(config: &Context, typ: &'a str) -> Option<Cow<'a, str>> {
+    bare_scope(config, config.tokens().marked(Token::Type, typ))
+}
+
+/// Record the class or namespace parsed from `s` until `remaining` as a
+/// [`SpanKind::Scope`].
+fn scope_span(config: &Context, s: &str, remaining: &str, shown: &str) {
+    config.span(SpanKind::Scope, s, remaining, || String::from(shown));
+}
+
+/// Put together a function or method, like `tName::SetText(char const *)`,
+/// leaving out its parameter list if asked by the config.
+fn function_signature(
+    config: &Context,
+    scope: Option<&str>,
+    name: &str,
+    argument_list: &str,
+    qualifiers: &str,
+) -> String {
+    let mut out = String::new();
+    if let Some(scope) = scope {
+        out.push_str(&config.tokens().scope(scope));
+        out.push_str(config.scope_separator);
     }
-    out.push_str(func_name);
-    out.push_str(&formated_template_args);
-    out.push('(');
-    out.push_str(&argument_list);
-    out.push(')');
-    out.push_str(suffix);
-    if let Some(array_qualifiers) = array_qualifiers.as_option() {
-        if config.fix_array_in_return_position {
-            out.push(')');
-            out.push_str(&array_qualifiers.arrays);
-        }
+    out.push_str(name);
+    if !config.omit_parameter_list {
+        let parameters =
+            ParameterList::new(argument_list, config.argument_list_parens, config.cuts());
+        out += &format!("{parameters}{qualifiers}");
     }
-
-    Ok(out)
+    out
 }
 
 fn demangle_namespaced_function<'s>(
-    config: &DemangleConfig,
+    config: &Context,
     func_name: &'s str,
     s: &'s str,
 ) -> Result<String, DemangleError<'s>> {
     let allow_array_fixup = true;
 
-    let (remaining, namespaces, _trailing_namespace) =
-        demangle_namespaces(config, s, &ArgVec::new(config, None), allow_array_fixup)?;
+    let scope = demangle_scope(config, s, &ArgVec::new(config, None), allow_array_fixup)?;
 
-    let argument_list = if remaining.is_empty() {
-        "void"
+    let argument_list = if scope.remaining.is_empty() {
+        config.tokens().marked(Token::Type, "void")
     } else {
-        &demangle_argument_list(
+        Cow::from(demangle_argument_list(
             config,
-            remaining,
-            Some(&namespaces),
+            scope.remaining,
+            Some(&scope.namespaces),
             &ArgVec::new(config, None),
             allow_array_fixup,
-        )?
+            true,
+        )?)
     };
 
-    let out = format!("{namespaces}::{func_name}({argument_list})");
+    let shown = bare_scope(config, scope.bare).unwrap_or(Cow::from(scope.namespaces.as_str()));
+    let func_name = config.tokens().marked(Token::Name, func_name);
+    let out = function_signature(config, Some(&shown), &func_name, &argument_list, "");
     Ok(out)
 }
 
 fn demangle_type_info_function<'s>(
-    config: &DemangleConfig,
+    config: &Context,
     s: &'s str,
 ) -> Result<String, DemangleError<'s>> {
     let allow_array_fixup = true;
 
-    if let (remaining, DemangledArg::Plain(demangled_type, array_qualifiers)) = demangle_argument(
+    let (remaining, arg) = demangle_argument(
         config,
         s,
         &ArgVec::new(config, None),
         &ArgVec::new(config, None),
         allow_array_fixup,
-    )? {
-        if remaining.is_empty() {
-            Ok(format!(
-                "{demangled_type}{array_qualifiers} type_info function"
-            ))
-        } else {
-            Err(DemangleError::TrailingDataOnTypeInfoFunction(remaining))
-        }
+    )?;
+    let Some(demangled_type) = type_info_type(arg, config.type_pointer_spacing) else {
+        return Err(DemangleError::InvalidTypeOnTypeInfoFunction(s));
+    };
+
+    if remaining.is_empty() {
+        Ok(format!("{demangled_type} type_info function"))
     } else {
-        Err(DemangleError::InvalidTypeOnTypeInfoFunction(s))
+        Err(DemangleError::TrailingDataOnTypeInfoFunction(remaining))
     }
 }
 
-fn demangle_type_info_node<'s>(
-    config: &DemangleConfig,
-    s: &'s str,
-) -> Result<String, DemangleError<'s>> {
+fn demangle_type_info_node<'s>(config: &Context, s: &'s str) -> Result<String, DemangleError<'s>> {
     let allow_array_fixup = true;
 
-    if let (remaining, DemangledArg::Plain(demangled_type, array_qualifiers)) = demangle_argument(
+    let (remaining, arg) = demangle_argument(
         config,
         s,
         &ArgVec::new(config, None),
         &ArgVec::new(config, None),
         allow_array_fixup,
-    )? {
-        if remaining.is_empty() {
-            Ok(format!("{demangled_type}{array_qualifiers} type_info node"))
-        } else {
-            Err(DemangleError::TrailingDataOnTypeInfoNode(remaining))
-        }
+    )?;
+    let Some(demangled_type) = type_info_type(arg, config.type_pointer_spacing) else {
+        return Err(DemangleError::InvalidTypeOnTypeInfoNode(s));
+    };
+
+    if remaining.is_empty() {
+        Ok(format!("{demangled_type} type_info node"))
     } else {
-        Err(DemangleError::InvalidTypeOnTypeInfoNode(s))
+        Err(DemangleError::TrailingDataOnTypeInfoNode(remaining))
     }
 }
 
+/// The type described by a type_info function or node.
+///
+/// Repeats and ellipsis only make sense inside of argument lists.
+fn type_info_type(arg: DemangledArg, spacing: Option<TypePointerSpacing>) -> Option<String> {
+    match arg {
+        DemangledArg::Plain(demangled_type, array_qualifiers) => {
+            Some(match array_qualifiers.as_option() {
+                Some(arr) => arr.qualify(&demangled_type, ArrayPosition::Argument, spacing),
+                None => demangled_type,
+            })
+        }
+        DemangledArg::FunctionPointer(function_pointer) => Some(function_pointer.to_string()),
+        DemangledArg::MethodPointer(method_pointer) => Some(method_pointer.to_string()),
+        DemangledArg::Repeat { .. } | DemangledArg::Ellipsis => None,
+    }
+}
+
+/// Split the `_vt` prefix from a virtual table, along with the base class
+/// offset some targets put before it, like the `_8` of `_8_vt$7Derived$4Base`.
+fn virtual_table_prefix<'s>(config: &Context, sym: &'s str) -> Option<(Option<usize>, &'s str)> {
+    if let Some(s) = sym.strip_prefix("_vt") {
+        return Some((None, s));
+    }
+
+    let Remaining { r, d: offset } = sym.strip_prefix('_')?.p_number()?;
+    let s = r.strip_prefix("_vt")?;
+    config.saw(Construct::VTableOffset);
+    config.demangle_vtable_offsets.then_some((Some(offset), s))
+}
+
+/// `offset` is the base class offset found before the `_vt` prefix, if any.
 fn demangle_virtual_table<'s>(
-    config: &DemangleConfig,
+    config: &Context,
     s: &'s str,
     cplus_marker: char,
+    mut offset: Option<usize>,
 ) -> Result<String, DemangleError<'s>> {
     let allow_array_fixup = true;
     let mut remaining = s;
@@ -690,78 +1309,175 @@ fn demangle_virtual_table<'s>(
             .strip_prefix(cplus_marker)
             .ok_or(DemangleError::VTableMissingDollarSeparator(remaining))?;
 
+        // A trailing offset, like the `$8` of `_vt$7Derived$4Base$8`. A
+        // class name can't be only digits, so this can't be confused with
+        // one. With `.` as the marker it would be a clone suffix instead,
+        // like `_vt.5tName.4`.
+        if cplus_marker != '.'
+            && !stuff.is_empty()
+            && offset.is_none()
+            && remaining.bytes().all(|c| c.is_ascii_digit())
+        {
+            if let Some(Remaining { d, .. }) = remaining.p_number() {
+                config.saw(Construct::VTableOffset);
+                if config.demangle_vtable_offsets {
+                    offset = Some(d);
+                    break;
+                }
+            }
+        }
+
+        // Every class of the chain is its own scope, including each one of
+        // a `Q` component, so `$Q23bar3baz` is the same as `$3bar$3baz`.
         remaining = if let Some(r) = remaining.strip_prefix('t') {
             let (r, template, _typ) =
                 demangle_template(config, r, &ArgVec::new(config, None), allow_array_fixup)?;
+            scope_span(config, remaining, r, &template);
 
             stuff.push(Cow::from(template));
             r
         } else if let Some(r) = remaining.strip_prefix('Q') {
-            let (r, namespaces, _trailing_namespace) =
-                demangle_namespaces(config, r, &ArgVec::new(config, None), allow_array_fixup)?;
+            let scope = demangle_scope(config, r, &ArgVec::new(config, None), allow_array_fixup)?;
 
-            stuff.push(Cow::from(namespaces));
-            r
+            stuff.push(Cow::from(scope.namespaces));
+            scope.remaining
         } else {
-            let Remaining { r, d: class_name } =
-                demangle_custom_name(remaining, DemangleError::InvalidClassNameOnVirtualTable)?
-                    .d_as_cow();
+            let Remaining { r, d: class_name } = demangle_custom_name(
+                config,
+                remaining,
+                DemangleError::InvalidClassNameOnVirtualTable,
+            )?;
+            scope_span(config, remaining, r, class_name);
 
-            stuff.push(class_name);
+            stuff.push(config.tokens().marked(Token::Type, class_name));
             r
         };
     }
 
-    Ok(format!("{} virtual table", stuff.join("::")))
+    let classes = config.tokens().join_scopes(&stuff, config.scope_separator);
+    if let Some(offset) = offset {
+        let offset = offset.to_string();
+        let offset = config.tokens().mark(Token::Literal, &offset);
+        Ok(format!("{classes} virtual table (at offset {offset})"))
+    } else {
+        Ok(format!("{classes} virtual table"))
+    }
 }
 
+/// `s` and `name` are `sym` split at its first `cplus_marker`, only used for
+/// reporting a symbol that is not a namespaced global at all. The scope is
+/// parsed from `sym` itself, since names in it may contain the marker too.
 fn demangle_namespaced_global<'s>(
-    config: &DemangleConfig,
+    config: &Context,
+    sym: &'s str,
+    cplus_marker: char,
     s: &'s str,
     name: &'s str,
 ) -> Result<String, DemangleError<'s>> {
     let allow_array_fixup = true;
 
-    let Some(remaining) = s.strip_prefix('_') else {
+    let Some(remaining) = sym.strip_prefix('_') else {
         return Err(DemangleError::InvalidNamespacedGlobal(s, name));
     };
 
     let (r, space) = if let Some(r) = remaining.strip_prefix('t') {
-        let (r, template, _typ) =
+        let (r, template, typ) =
             demangle_template(config, r, &ArgVec::new(config, None), allow_array_fixup)?;
+        let shown = bare_template_scope(config, typ).unwrap_or(Cow::from(template));
+        scope_span(config, remaining, r, &shown);
 
-        (r, Cow::from(template))
+        (r, shown)
     } else if let Some(r) = remaining.strip_prefix('Q') {
-        let (r, namespaces, _trailing_namespace) =
-            demangle_namespaces(config, r, &ArgVec::new(config, None), allow_array_fixup)?;
+        let scope = demangle_scope(config, r, &ArgVec::new(config, None), allow_array_fixup)?;
 
-        (r, Cow::from(namespaces))
+        let shown = bare_scope(config, scope.bare).unwrap_or(Cow::from(scope.namespaces));
+        (scope.remaining, shown)
     } else {
-        let Remaining { r, d: class_name } =
-            demangle_custom_name(remaining, DemangleError::InvalidNamespaceOnNamespacedGlobal)?
-                .d_as_cow();
+        let Remaining { r, d: class_name } = demangle_custom_name(
+            config,
+            remaining,
+            DemangleError::InvalidNamespaceOnNamespacedGlobal,
+        )?;
+        scope_span(config, remaining, r, class_name);
 
-        (r, class_name)
+        (r, config.tokens().marked(Token::Type, class_name))
     };
 
-    if !r.is_empty() {
+    let Some(name) = r.strip_prefix(cplus_marker).filter(|name| !name.is_empty()) else {
         return Err(DemangleError::TrailingDataOnNamespacedGlobal(r));
-    }
+    };
 
-    Ok(format!("{space}::{name}"))
+    let space = config.tokens().scope(&space);
+    let name = config.tokens().mark(Token::Name, name);
+    Ok(format!("{space}{}{name}", config.scope_separator))
 }
 
+fn demangle_thunk<'s>(
+    config: &Context,
+    s: &'s str,
+    cplus_marker: char,
+) -> Result<String, DemangleError<'s>> {
+    // `__thunk_{delta}_{symbol}` or `__thunk_{delta}_{vcall_offset}_{symbol}`.
+    // Symbols can't start with a digit, so a second number is always the
+    // vcall offset.
+    let Some(Remaining { r, d: delta }) = s.p_number() else {
+        return Err(DemangleError::InvalidDeltaOnThunk(s));
+    };
+    let Some(r) = r.strip_prefix('_') else {
+        return Err(DemangleError::InvalidDeltaOnThunk(s));
+    };
+
+    let (r, vcall_offset) = if r.starts_with(|c: char| c.is_ascii_digit()) {
+        let Some(Remaining { r, d: vcall_offset }) = r.p_number() else {
+            return Err(DemangleError::InvalidVcallOffsetOnThunk(s));
+        };
+        let Some(r) = r.strip_prefix('_') else {
+            return Err(DemangleError::InvalidVcallOffsetOnThunk(s));
+        };
+        (r, Some(vcall_offset))
+    } else {
+        (r, None)
+    };
+
+    let method = demangle_impl(r, config, cplus_marker, false)?;
+
+    // The offsets are mangled as positive numbers, but they are applied as
+    // negative adjustments.
+    let offset = |offset: usize| {
+        let offset = format!("-{offset}");
+        config.tokens().marked(Token::Literal, &offset).into_owned()
+    };
+    let delta = offset(delta);
+    if let Some(vcall_offset) = vcall_offset {
+        let vcall_offset = offset(vcall_offset);
+        Ok(format!(
+            "virtual function thunk (delta:{delta}, vcall:{vcall_offset}) for {method}"
+        ))
+    } else {
+        Ok(format!(
+            "virtual function thunk (delta:{delta}) for {method}"
+        ))
+    }
+}
+
+/// `allow_nested` tells whether the payload may be a keyed symbol too, see
+/// [`DemangleConfig::demangle_nested_global_keyed_symbols`]. It is only set
+/// for the outermost key, so nesting stops after a single level.
+///
+/// [`DemangleConfig::demangle_nested_global_keyed_symbols`]: crate::DemangleConfig::demangle_nested_global_keyed_symbols
 fn demangle_global_sym_keyed<'s>(
-    config: &DemangleConfig,
+    config: &Context,
     s: &'s str,
     cplus_marker: char,
     full_sym: &'s str,
+    allow_nested: bool,
 ) -> Result<String, DemangleError<'s>> {
     let (remaining, which, is_constructor) = if let Some(r) = s.strip_prefix("I") {
         (r, "constructors", true)
     } else if let Some(r) = s.strip_prefix("D") {
         (r, "destructors", false)
     } else if let Some(r) = s.strip_prefix("F") {
+        config.saw(Construct::GlobalKeyedFrame);
         if config.demangle_global_keyed_frames {
             (r, "frames", false)
         } else {
@@ -769,6 +1485,14 @@ fn demangle_global_sym_keyed<'s>(
             // !tries to demangle it as anything else.
             return demangle_impl(full_sym, config, cplus_marker, false);
         }
+    } else if let Some(r) = s.strip_prefix("T") {
+        config.saw(Construct::GlobalKeyedTemplateInstantiation);
+        if config.demangle_global_keyed_template_instantiations {
+            (r, "template instantiations", false)
+        } else {
+            // !HACK(c++filt): Same as `_GLOBAL_$F$`.
+            return demangle_impl(full_sym, config, cplus_marker, false);
+        }
     } else {
         return Err(DemangleError::InvalidGlobalSymKeyed(s));
     };
@@ -777,19 +1501,65 @@ fn demangle_global_sym_keyed<'s>(
         return Err(DemangleError::InvalidGlobalSymKeyed(s));
     };
 
-    let demangled_sym = demangle_impl(remaining, config, cplus_marker, false);
-    if !config.fix_namespaced_global_constructor_bug
-        && is_constructor
-        && remaining.starts_with("__Q")
-    {
-        // !HACK(c++filt): Seems like c++filt has a bug where it won't output
-        // !the "global constructors keyed to " prefix for namespaced functions
-        return demangled_sym;
+    if matches!(which, "constructors" | "destructors") {
+        if let Some(priority) = static_init_priority(remaining) {
+            config.saw(Construct::StaticInitFunction);
+            if config.describe_static_init_functions {
+                let priority =
+                    priority.map(|priority| config.tokens().mark(Token::Literal, priority));
+                return Ok(match priority {
+                    Some(priority) => format!(
+                        "static initialization and destruction (priority {priority}) for global {which}"
+                    ),
+                    None => format!("static initialization and destruction for global {which}"),
+                });
+            }
+        }
+    }
+
+    // Only a known key followed by the separator, so anything else is still
+    // shown as-is.
+    let nested = remaining
+        .c_cond_and_strip_prefix_and_char(allow_nested, "_GLOBAL_", cplus_marker)
+        .filter(|r| r.starts_with(['I', 'D', 'F', 'T']) && r[1..].starts_with(cplus_marker));
+    if nested.is_some() {
+        config.saw(Construct::GlobalKeyedNested);
+    }
+    let demangled_sym = match nested {
+        Some(nested) if config.demangle_nested_global_keyed_symbols => {
+            demangle_global_sym_keyed(config, nested, cplus_marker, remaining, false)
+        }
+        _ => demangle_impl(remaining, config, cplus_marker, false),
+    };
+    if is_constructor && remaining.starts_with("__Q") {
+        config.saw(Construct::GlobalKeyedNamespaced);
+        if !config.fix_namespaced_global_constructor_bug {
+            // !HACK(c++filt): Seems like c++filt has a bug where it won't
+            // !output the "global constructors keyed to " prefix for
+            // !namespaced functions
+            return demangled_sym;
+        }
     }
 
     let actual_sym = demangled_sym
-        .map(Cow::from)
-        .unwrap_or_else(|_| Cow::from(remaining));
+        .unwrap_or_else(|_| config.tokens().marked(Token::Name, remaining).into_owned());
 
     Ok(format!("global {which} keyed to {actual_sym}"))
 }
+
+/// Recognize the function g++ generates to initialize and destroy the static
+/// objects of a file, like `__static_initialization_and_destruction_0__Fii`,
+/// returning its `init_priority` if it has one, like the `65535` of
+/// `__static_initialization_and_destruction_0_65535__Fii`.
+fn static_init_priority(sym: &str) -> Option<Option<&str>> {
+    let name = sym
+        .strip_prefix("__static_initialization_and_destruction_")?
+        .strip_suffix("__Fii")?;
+    let is_number = |x: &str| !x.is_empty() && x.bytes().all(|c| c.is_ascii_digit());
+
+    match name.split_once('_') {
+        None if is_number(name) => Some(None),
+        Some((index, priority)) if is_number(index) && is_number(priority) => Some(Some(priority)),
+        _ => None,
+    }
+}
@@ -0,0 +1,125 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+use core::fmt;
+
+use alloc::string::String;
+
+use crate::{demangle, extract::find_invalid_char, extract::looks_mangled, DemangleConfig};
+
+static CONFIG: DemangleConfig = DemangleConfig::new_g2dem();
+
+/// Demangle `sym` only if it is a GNU V2 symbol, for tools that try multiple
+/// demangling schemes one after the other.
+///
+/// Symbols that belong to other schemes return `None` without attempting to
+/// demangle them, so callers can chain to demanglers like `cpp_demangle` or
+/// `rustc-demangle`. This covers Itanium symbols (`_Z`, also `__Z` on macOS),
+/// which includes Rust's legacy mangling, and Rust's v0 mangling (`_R`).
+///
+/// Uses [`DemangleConfig::new_g2dem`].
+///
+/// # Examples
+///
+/// ```
+/// use gnuv2_demangle::try_demangle_auto;
+///
+/// assert_eq!(
+///     try_demangle_auto("SetText__5tNamePCc").as_deref(),
+///     Some("tName::SetText(char const *)")
+/// );
+/// assert_eq!(try_demangle_auto("_ZN5tName7SetTextEPKc"), None);
+/// assert_eq!(try_demangle_auto("main"), None);
+/// ```
+#[must_use]
+pub fn try_demangle_auto(sym: &str) -> Option<String> {
+    if !is_gnuv2_shaped(sym) {
+        return None;
+    }
+
+    demangle(sym, &CONFIG).ok()
+}
+
+/// Wrap `sym` so it gets demangled when formatted, the same way as
+/// `rustc_demangle::demangle`.
+///
+/// Symbols that can't be demangled by [`try_demangle_auto`] are printed
+/// as-is.
+///
+/// # Examples
+///
+/// ```
+/// use gnuv2_demangle::demangle_auto;
+///
+/// assert_eq!(
+///     demangle_auto("SetText__5tNamePCc").to_string(),
+///     "tName::SetText(char const *)"
+/// );
+/// assert_eq!(
+///     demangle_auto("_ZN5tName7SetTextEPKc").to_string(),
+///     "_ZN5tName7SetTextEPKc"
+/// );
+/// ```
+#[must_use]
+#[inline]
+pub const fn demangle_auto(sym: &str) -> AutoDemangled<'_> {
+    AutoDemangled { sym }
+}
+
+/// A symbol that gets demangled lazily, only when it is formatted.
+///
+/// See [`demangle_auto`].
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AutoDemangled<'s> {
+    sym: &'s str,
+}
+
+impl<'s> AutoDemangled<'s> {
+    /// The symbol as it was passed.
+    #[must_use]
+    #[inline]
+    pub const fn as_str(&self) -> &'s str {
+        self.sym
+    }
+
+    /// Whether the symbol looks like a GNU V2 symbol.
+    ///
+    /// This is a cheap check that doesn't demangle anything, so it is still
+    /// possible for the symbol to fail to demangle.
+    #[must_use]
+    pub fn is_gnuv2(&self) -> bool {
+        is_gnuv2_shaped(self.sym)
+    }
+}
+
+impl fmt::Display for AutoDemangled<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match try_demangle_auto(self.sym) {
+            Some(demangled) => f.write_str(&demangled),
+            None => f.write_str(self.sym),
+        }
+    }
+}
+
+impl fmt::Debug for AutoDemangled<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AutoDemangled")
+            .field("sym", &self.sym)
+            .field("demangled", &try_demangle_auto(self.sym))
+            .finish()
+    }
+}
+
+fn is_gnuv2_shaped(sym: &str) -> bool {
+    // Itanium (`__Z` on Mach-O) and Rust v0.
+    const FOREIGN_PREFIXES: [&str; 3] = ["_Z", "__Z", "_R"];
+
+    if FOREIGN_PREFIXES
+        .iter()
+        .any(|prefix| sym.starts_with(prefix))
+    {
+        return false;
+    }
+
+    sym.is_ascii() && find_invalid_char(sym).is_none() && looks_mangled(sym)
+}
@@ -0,0 +1,94 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+use alloc::{string::String, vec::Vec};
+
+use crate::{
+    dem::CvQualifiers, demangle_components, demangle_with_spans, features::canonical_config,
+    DemangleConfig, DemangleError, LeafKind, ScopeKind, SpanKind,
+};
+
+/// What tells apart the entity a symbol refers to, see [`equivalent`].
+#[derive(Debug, PartialEq, Eq)]
+struct Identity {
+    name: Name,
+    /// The demangled scopes, template arguments, arguments and return type,
+    /// in the order they were parsed. Repeats and lookbacks are already
+    /// resolved to the argument they refer to.
+    parts: Vec<(SpanKind, String)>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Name {
+    /// The scope is already part of [`Identity::parts`], so only the kind of
+    /// each segment is kept.
+    Components {
+        scope: Vec<ScopeKind>,
+        leaf: String,
+        leaf_kind: LeafKind,
+        qualifiers: CvQualifiers,
+    },
+    /// Symbols not supported by [`demangle_components`], like templated
+    /// functions or virtual tables, are identified by their whole demangled
+    /// text.
+    Demangled(String),
+}
+
+/// Check if two symbols refer to the same entity, even if they are mangled
+/// differently.
+///
+/// Some compilers encode repeated arguments with `T` lookbacks while others
+/// use `N` repeats, like `Debug_Assert__FPcT0T0i` and
+/// `Debug_Assert__FPcN20i`. Both symbols are parsed and compared by their
+/// scopes, name, argument types, template arguments and method qualifiers,
+/// with every repeat and lookback expanded.
+///
+/// The settings of `config` that change how a symbol is parsed, like
+/// [`DemangleConfig::fix_array_length_arg`], are honored. The settings that
+/// only change how the result looks, like
+/// [`DemangleConfig::type_pointer_spacing`], or that leave out information,
+/// like [`DemangleConfig::omit_parameter_list`], are ignored. Top level
+/// `const` of arguments passed by value doesn't change the function being
+/// referred to, so it is ignored too.
+///
+/// Fails if either symbol fails to demangle.
+///
+/// # Examples
+///
+/// ```
+/// use gnuv2_demangle::{equivalent, DemangleConfig};
+///
+/// let config = DemangleConfig::new();
+///
+/// let same = equivalent("Debug_Assert__FPcT0T0i", "Debug_Assert__FPcN20i", &config);
+/// assert_eq!(same, Ok(true));
+///
+/// let same = equivalent("Debug_Assert__FPcT0T0i", "Debug_Assert__FPCcT0T0i", &config);
+/// assert_eq!(same, Ok(false));
+/// ```
+pub fn equivalent<'s>(
+    a: &'s str,
+    b: &'s str,
+    config: &DemangleConfig,
+) -> Result<bool, DemangleError<'s>> {
+    let config = canonical_config(config);
+
+    Ok(identity(a, &config)? == identity(b, &config)?)
+}
+
+fn identity<'s>(sym: &'s str, config: &DemangleConfig) -> Result<Identity, DemangleError<'s>> {
+    let (demangled, spans) = demangle_with_spans(sym, config)?;
+
+    let name = match demangle_components(sym, config) {
+        Ok(components) => Name::Components {
+            scope: components.scope().iter().map(|x| x.kind()).collect(),
+            leaf: String::from(components.leaf()),
+            leaf_kind: components.leaf_kind(),
+            qualifiers: components.qualifiers(),
+        },
+        Err(_) => Name::Demangled(demangled),
+    };
+    let parts = spans.into_iter().map(|x| (x.kind, x.text)).collect();
+
+    Ok(Identity { name, parts })
+}
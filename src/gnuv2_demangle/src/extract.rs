@@ -0,0 +1,61 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+/// Characters that may appear in a mangled symbol.
+///
+/// `$` and `.` are used as the cplus marker, i.e. `_$_3Foo` or `_._3Foo`.
+pub(crate) const fn is_mangled_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '$' | '.')
+}
+
+/// Find the first character that can't be part of a mangled symbol, with its
+/// position.
+pub(crate) fn find_invalid_char(sym: &str) -> Option<(char, usize)> {
+    sym.char_indices()
+        .find(|(_, c)| !is_mangled_char(*c))
+        .map(|(i, c)| (c, i))
+}
+
+/// Pull the first token that looks like a mangled symbol out of a longer line,
+/// like the output of `nm`, `objdump` or a linker map file.
+///
+/// The line is split at every character that can't be part of a mangled
+/// symbol, and the first piece that resembles one is returned. This is just a
+/// heuristic, the returned token may still fail to demangle.
+///
+/// # Examples
+///
+/// ```
+/// use gnuv2_demangle::extract_mangled_token;
+///
+/// assert_eq!(
+///     extract_mangled_token("0800efa0 T SetText__5tNamePCc"),
+///     Some("SetText__5tNamePCc")
+/// );
+/// assert_eq!(
+///     extract_mangled_token("0800efa0 <_$_5tName>:"),
+///     Some("_$_5tName")
+/// );
+/// assert_eq!(extract_mangled_token("0800efa0 T main"), None);
+/// ```
+#[must_use]
+pub fn extract_mangled_token(line: &str) -> Option<&str> {
+    line.split(|c| !is_mangled_char(c))
+        .find(|token| looks_mangled(token))
+}
+
+pub(crate) fn looks_mangled(token: &str) -> bool {
+    // Destructors, virtual tables and global constructors/destructors.
+    const PREFIXES: [&str; 5] = ["_$_", "_._", "_vt$", "_vt.", "_GLOBAL_"];
+
+    if PREFIXES
+        .iter()
+        .any(|prefix| token.len() > prefix.len() && token.starts_with(prefix))
+    {
+        return true;
+    }
+
+    // Everything else has a `__` separator followed by something, like
+    // `SetText__5tNamePCc` or `__5tName`.
+    token.find("__").is_some_and(|i| i + 2 < token.len())
+}
@@ -0,0 +1,183 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+use crate::{Construct, DemangleConfig, ReturnTypeStyle, TypePointerSpacing};
+
+/// Describes a single flag of [`DemangleConfig`].
+///
+/// See [`features`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub struct FeatureDesc {
+    /// The name of the field in [`DemangleConfig`].
+    pub name: &'static str,
+    /// A short, single line description of the flag.
+    pub doc: &'static str,
+    /// The value of the flag in [`DemangleConfig::new_g2dem`].
+    pub default_g2dem: bool,
+    /// The value of the flag in [`DemangleConfig::new_cfilt`].
+    pub default_cfilt: bool,
+    /// The flag can only make a difference on symbols with this construct.
+    pub construct: Construct,
+}
+
+/// Lists every flag of [`DemangleConfig`], in declaration order.
+///
+/// Useful for tools that want to show the available settings at runtime,
+/// like the web UI.
+///
+/// Settings that are not plain flags, like
+/// [`DemangleConfig::vendor_type_map`], are not listed. Neither are the ones
+/// that don't change the output of [`demangle`](crate::demangle), like
+/// [`DemangleConfig::annotate_ambiguous_scope`], or that only leave parts of
+/// it out, like [`DemangleConfig::omit_parameter_list`].
+///
+/// # Examples
+///
+/// ```
+/// use gnuv2_demangle::features;
+///
+/// let feature = features()
+///     .iter()
+///     .find(|x| x.name == "fix_array_length_arg")
+///     .unwrap();
+/// assert!(feature.default_g2dem);
+/// assert!(!feature.default_cfilt);
+/// ```
+#[must_use]
+pub fn features() -> &'static [FeatureDesc] {
+    FEATURES
+}
+
+/// The value a setting gets in [`canonical_config`], either the one given
+/// when registering it or the one of the original config.
+macro_rules! canonical_value {
+    ($original:expr) => {
+        $original
+    };
+    ($original:expr, $canonical:expr) => {
+        $canonical
+    };
+}
+
+macro_rules! register_features {
+    (
+        $( $name:ident ( $construct:ident ) $( = $canonical:literal )? : $doc:literal, )*
+        ;
+        ignored: $( $ignored:ident $( = $ignored_canonical:expr )? ),* $(,)?
+    ) => {
+        static FEATURES: &[FeatureDesc] = &[
+            $(
+                FeatureDesc {
+                    name: stringify!($name),
+                    doc: $doc,
+                    default_g2dem: DemangleConfig::new_g2dem().$name,
+                    default_cfilt: DemangleConfig::new_cfilt().$name,
+                    construct: Construct::$construct,
+                },
+            )*
+        ];
+
+        /// Set a flag of `config` by its field name.
+        ///
+        /// Returns `false` if there's no flag with that name.
+        pub(crate) fn set_feature(config: &mut DemangleConfig, name: &str, value: bool) -> bool {
+            match name {
+                $( stringify!($name) => config.$name = value, )*
+                _ => return false,
+            }
+            true
+        }
+
+        /// Keep the settings of `config` that change how a symbol is parsed,
+        /// and pin the ones that only change how it is shown, or that leave
+        /// information out of it, to the value they are registered with.
+        ///
+        /// Used by [`equivalent`](crate::equivalent). Fails to build if a
+        /// field is added to `DemangleConfig` without registering it here.
+        pub(crate) fn canonical_config(config: &DemangleConfig) -> DemangleConfig {
+            DemangleConfig {
+                $( $name: canonical_value!(config.$name $(, $canonical)?), )*
+                $( $ignored: canonical_value!(config.$ignored $(, $ignored_canonical)?), )*
+            }
+        }
+    };
+}
+
+register_features! {
+    fix_namespaced_global_constructor_bug(GlobalKeyedNamespaced):
+        "Emit the \"global constructors keyed to\" prefix for namespaced functions.",
+    fix_array_length_arg(ArrayArgument):
+        "Add 1 to the length of array arguments, matching the original C++ symbol.",
+    fix_array_length_skip_zero(ArrayArgument):
+        "Don't add 1 to the length of zero-length arrays.",
+    fix_array_of_references(ArrayOfReferences):
+        "Demangle arrays of references as references to arrays.",
+    demangle_global_keyed_frames(GlobalKeyedFrame):
+        "Recognize symbols prefixed by `_GLOBAL_$F$`.",
+    demangle_global_keyed_template_instantiations(GlobalKeyedTemplateInstantiation):
+        "Recognize symbols prefixed by `_GLOBAL_$T$`.",
+    demangle_nested_global_keyed_symbols(GlobalKeyedNested):
+        "Demangle `_GLOBAL_` keyed symbols keyed to another keyed symbol.",
+    describe_static_init_functions(StaticInitFunction):
+        "Describe the static initialization functions keyed by `_GLOBAL_` symbols.",
+    demangle_vtable_offsets(VTableOffset):
+        "Demangle the base class offset of secondary virtual tables.",
+    reject_plain_c_lookalikes(PlainCLookalike):
+        "Don't demangle C symbols like `crc32__8_compute` as methods.",
+    strip_top_level_const_on_value_args(TopLevelConst) = true:
+        "Drop the `const` of parameters passed by value, like `f(Foo const)`.",
+    pretty_anonymous_namespaces(AnonymousNamespace) = false:
+        "Emit anonymous namespaces as `(anonymous namespace)`.",
+    ellipsis_emit_space_after_comma(Ellipsis) = true:
+        "Emit an space between a comma and an ellipsis in argument lists.",
+    fix_extension_int(ExtensionInt):
+        "Emit `__int128_t` and `uint64_t` instead of `int128_t` and `unsigned int64_t`.",
+    fix_array_in_return_position(ArrayReturn):
+        "Emit proper syntax for arrays returned by templated functions.",
+    fix_function_pointers_in_template_lists(FunctionPointerInTemplate):
+        "Emit proper syntax for function pointers in template lists.",
+    simplify_stl_containers(StlContainer) = false:
+        "Collapse the SGI-STL templates into their user-facing names.",
+    demangle_constructor_variants(ConstructorVariant):
+        "Recognize the constructor variant markers of hybrid toolchains.",
+    emit_enum_casts_in_templates(EnumInTemplate) = true:
+        "Emit the enum type of enum values in template lists, as a cast.",
+    fix_function_pointer_in_return_position(FunctionPointerReturn):
+        "Emit proper syntax for function pointers returned by templated functions.",
+    emit_plain_restrict(Restrict) = false:
+        "Emit the `restrict` keyword instead of `__restrict`.",
+    demangle_clone_suffix(CloneSuffix):
+        "Demangle symbols with a trailing `.<number>` discriminator.",
+    emit_clone_suffix(CloneSuffix) = true:
+        "Emit the trailing `.<number>` discriminator as `[clone .<number>]`.",
+    tolerate_stray_underscores_between_args(StrayUnderscore):
+        "Skip a lone `_` between the arguments of a function.",
+    lenient(Malformed):
+        "Accept malformed symbols seen in the wild, like `Q0` namespaces.",
+    fix_function_pointer_template_lookbacks(FunctionPointerLookback):
+        "Emit proper syntax for pointers to function pointer template parameters.",
+    emit_integer_suffixes_in_templates(IntegerInTemplate) = true:
+        "Emit the literal suffix of integer values in template lists, like `1u`.",
+    emit_wide_character_literals_in_templates(WideCharacterInTemplate) = true:
+        "Emit `wchar_t` values in template lists as wide character literals, like `L'a'`.",
+    fix_template_parameter_levels(TemplateParameterLevel):
+        "Resolve template parameters of templated methods by their level.",
+    complete_truncated_symbols(Truncated):
+        "End the argument list of truncated symbols with a `<truncated>` marker.",
+    ;
+    ignored:
+    annotate_ambiguous_scope = false,
+    omit_parameter_list = false,
+    return_type_style = ReturnTypeStyle::CfiltLeading,
+    omit_template_args_in_scope = false,
+    type_pointer_spacing = Some(TypePointerSpacing::BeforeStar),
+    scope_separator = "::",
+    template_brackets = ('<', '>'),
+    argument_list_parens = true,
+    vendor_type_map,
+    max_repeat_count,
+    max_array_dimensions,
+    max_template_depth = None,
+    max_output_len = None,
+}
@@ -0,0 +1,122 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+use alloc::{string::String, vec::Vec};
+
+use crate::{
+    demangle_info::Context,
+    demangler::demangle_checked,
+    markup::{tokens, Token},
+    DemangleConfig, DemangleError,
+};
+
+/// What a piece of a demangled symbol is, see [`demangle_highlighted`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum TokenClass {
+    /// A class or namespace followed by the scope separator, like the `sim`
+    /// of `sim::Collision::Add(void)`.
+    Scope,
+    /// The name of the function, method or variable the symbol refers to,
+    /// like the `Add` of `sim::Collision::Add(void)`.
+    Name,
+    /// The name of an operator, like `operator<<` or `operator new []`.
+    Operator,
+    /// Qualifiers, like `const`, and the words describing special symbols,
+    /// like the `virtual table` of `Foo virtual table`.
+    Keyword,
+    /// A type outside of template lists, like the `char` of
+    /// `tName::SetText(char const *)`.
+    Type,
+    /// Brackets, separators, pointers, references and spaces.
+    Punctuation,
+    /// A type inside of a template list, like the `int` of
+    /// `List<int>::Insert(void)`.
+    TemplateArg,
+    /// A number, a character or a boolean, like the `'a'` of `Foo<'a'>`.
+    Literal,
+}
+
+/// Demangle a symbol, same as [`demangle`](crate::demangle), and split the result in pieces
+/// tagged with what they are, meant for syntax highlighting.
+///
+/// Joining the pieces gives back the output of [`demangle`](crate::demangle) with the same
+/// `config`. Consecutive pieces of punctuation are merged together.
+///
+/// # Examples
+///
+/// ```
+/// use gnuv2_demangle::{demangle_highlighted, DemangleConfig, TokenClass};
+///
+/// let config = DemangleConfig::new();
+///
+/// let pieces = demangle_highlighted("__ls__FR7ostreamPCc", &config).unwrap();
+/// assert_eq!(
+///     pieces,
+///     [
+///         ("operator<<".to_string(), TokenClass::Operator),
+///         ("(".to_string(), TokenClass::Punctuation),
+///         ("ostream".to_string(), TokenClass::Type),
+///         (" &, ".to_string(), TokenClass::Punctuation),
+///         ("char".to_string(), TokenClass::Type),
+///         (" ".to_string(), TokenClass::Punctuation),
+///         ("const".to_string(), TokenClass::Keyword),
+///         (" *)".to_string(), TokenClass::Punctuation),
+///     ]
+/// );
+/// ```
+pub fn demangle_highlighted<'s>(
+    sym: &'s str,
+    config: &DemangleConfig,
+) -> Result<Vec<(String, TokenClass)>, DemangleError<'s>> {
+    let config = Context::new(config);
+    config.mark_tokens();
+    let demangled = demangle_checked(sym, &config)?;
+
+    let mut pieces: Vec<(String, TokenClass)> = Vec::new();
+    let mut push = |text: &str, class: TokenClass| match pieces.last_mut() {
+        Some((last, TokenClass::Punctuation)) if class == TokenClass::Punctuation => {
+            last.push_str(text)
+        }
+        _ => pieces.push((String::from(text), class)),
+    };
+
+    for (token, text) in tokens(&demangled) {
+        match token {
+            Some(token) => push(text, TokenClass::from(token)),
+            None => {
+                // What the formatter didn't mark are the keywords and the
+                // punctuation around them.
+                let mut rest = text;
+                while let Some(c) = rest.chars().next() {
+                    let is_word = |c: char| c.is_ascii_alphanumeric() || c == '_';
+                    let class = if is_word(c) {
+                        TokenClass::Keyword
+                    } else {
+                        TokenClass::Punctuation
+                    };
+                    let len = rest
+                        .find(|c| is_word(c) != (class == TokenClass::Keyword))
+                        .unwrap_or(rest.len());
+                    push(&rest[..len], class);
+                    rest = &rest[len..];
+                }
+            }
+        }
+    }
+
+    Ok(pieces)
+}
+
+impl From<Token> for TokenClass {
+    fn from(token: Token) -> Self {
+        match token {
+            Token::Scope => Self::Scope,
+            Token::Name => Self::Name,
+            Token::Operator => Self::Operator,
+            Token::Type => Self::Type,
+            Token::TemplateArg => Self::TemplateArg,
+            Token::Literal => Self::Literal,
+        }
+    }
+}
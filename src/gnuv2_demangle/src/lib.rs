@@ -7,13 +7,70 @@
 #[macro_use]
 extern crate alloc;
 
+#[cfg(feature = "cache")]
+mod cache;
+mod compiler;
+mod components;
+#[cfg(feature = "test-utils")]
+pub mod corpus;
+mod demangle_both;
 mod demangle_config;
+mod demangle_config_builder;
 mod demangle_error;
+mod demangle_info;
+mod demangled;
 pub(crate) mod demangler;
+#[cfg(feature = "detect")]
+mod detect;
+mod equivalent;
+mod extract;
+mod features;
+#[cfg(feature = "classify-output")]
+mod highlight;
+mod map;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod partial;
+mod scope_only;
+mod scratch;
+mod spans;
+mod trace;
+mod type_name;
 
-pub use demangle_config::DemangleConfig;
-pub use demangle_error::DemangleError;
-pub use demangler::demangle;
+#[cfg(feature = "cache")]
+pub use cache::{CachedDemangler, SyncCachedDemangler};
+pub use compiler::CompilerId;
+pub use components::{demangle_components, Components, LeafKind, ScopeKind, ScopeSegment};
+pub use demangle_both::{demangle_both, BothOutputs};
+pub use demangle_config::{DemangleConfig, ReturnTypeStyle, TypePointerSpacing, VendorTypeMap};
+pub use demangle_config_builder::DemangleConfigBuilder;
+pub use demangle_error::{DemangleError, DemangleErrorKind, OwnedDemangleError};
+pub use demangle_info::{Construct, DemangleInfo};
+pub use demangled::Demangled;
+#[cfg(feature = "allocator-api2")]
+pub use demangler::demangle_in;
+#[cfg(feature = "fallible-alloc")]
+pub use demangler::try_demangle;
+pub use demangler::{demangle, demangle_with_info};
+#[cfg(feature = "detect")]
+pub use detect::{demangle_auto, try_demangle_auto, AutoDemangled};
+pub use equivalent::equivalent;
+pub use extract::extract_mangled_token;
+pub use features::{features, FeatureDesc};
+#[cfg(feature = "classify-output")]
+pub use highlight::{demangle_highlighted, TokenClass};
+pub use map::{demangle_map_line, MapDemangler, MapWrapping};
+#[cfg(feature = "parallel")]
+pub use parallel::demangle_all;
+pub use partial::{DemanglePartial, FeedResult};
+pub use scope_only::demangle_scope_only;
+pub use spans::{demangle_with_spans, Span, SpanKind};
+#[cfg(feature = "trace")]
+pub use trace::{demangle_traced, TraceEvent, TraceOutcome};
+pub use type_name::demangle_type_name;
+
+/// The version of this crate.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 // internal utilities
 pub(crate) mod dem;
@@ -21,6 +78,9 @@ pub(crate) mod dem_arg;
 pub(crate) mod dem_arg_list;
 pub(crate) mod dem_namespace;
 pub(crate) mod dem_template;
+pub(crate) mod markup;
 pub(crate) mod option_display;
 pub(crate) mod remainer;
+pub(crate) mod spacing;
+pub(crate) mod stl_simplify;
 pub(crate) mod str_cutter;
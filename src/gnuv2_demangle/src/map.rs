@@ -0,0 +1,326 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+use alloc::{borrow::Cow, string::String};
+use core::iter::Peekable;
+
+use crate::{demangle, DemangleConfig};
+
+/// How [`MapDemangler`] lays out names that don't fit in their column, like
+/// the section name of `.text.__ls__FR7ostreamPCc`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[non_exhaustive]
+pub enum MapWrapping {
+    /// Put the name in a line of its own, followed by a line with the rest of
+    /// the columns, like ld does.
+    #[default]
+    Wrap,
+    /// Put the name and the rest of the columns in the same line, with a
+    /// single space between them if the name doesn't fit.
+    Join,
+}
+
+/// The parts of a map file line that may have a symbol in it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MapLine<'l> {
+    /// A name alone in its line, usually wrapped because it is too long for
+    /// its column.
+    ///
+    /// `gCurrentScene__5Scene`
+    Name { start: usize, name: &'l str },
+    /// An address followed by the symbol at it.
+    ///
+    /// `0x00100000                SetText__5tNamePCc`
+    Symbol { start: usize, name: &'l str },
+    /// An input section with its address, size and file.
+    ///
+    /// `.text.f__Fv    0x00100000       0x10 foo.o`
+    Section {
+        start: usize,
+        name: &'l str,
+        rest: usize,
+    },
+    /// Anything else, like headers or linker script statements.
+    Other,
+}
+
+impl<'l> MapLine<'l> {
+    fn parse(line: &'l str) -> Self {
+        let Some((start, end)) = next_field(line, 0) else {
+            return Self::Other;
+        };
+        let name = &line[start..end];
+
+        match next_field(line, end) {
+            None => Self::Name { start, name },
+            Some((second, second_end)) if name.starts_with("0x") => {
+                if next_field(line, second_end).is_none() {
+                    Self::Symbol {
+                        start: second,
+                        name: &line[second..second_end],
+                    }
+                } else {
+                    Self::Other
+                }
+            }
+            Some((rest, _)) if name.starts_with('.') && line[rest..].starts_with("0x") => {
+                Self::Section { start, name, rest }
+            }
+            Some(_) => Self::Other,
+        }
+    }
+}
+
+/// Find the start and the end of the first field of `line` after `from`.
+fn next_field(line: &str, from: usize) -> Option<(usize, usize)> {
+    let start = from + line[from..].find(|c: char| !c.is_ascii_whitespace())?;
+    let end = line[start..]
+        .find(|c: char| c.is_ascii_whitespace())
+        .map_or(line.len(), |len| start + len);
+
+    Some((start, end))
+}
+
+/// If `line` holds the rest of the columns of a wrapped name, return where
+/// they start.
+fn continuation_column(line: &str) -> Option<usize> {
+    let (start, _) = next_field(line, 0)?;
+
+    (start > 0 && line[start..].starts_with("0x")).then_some(start)
+}
+
+/// Demangle a name from a map file, keeping the section prefix of names like
+/// `.text.__ls__FR7ostreamPCc` or `.gnu.linkonce.t.Insert__t4List1ZiRCi`.
+fn demangle_name(name: &str, config: &DemangleConfig) -> Option<String> {
+    let Some((prefix, sym)) = split_section_prefix(name) else {
+        return demangle(name, config).ok();
+    };
+
+    let demangled = demangle(sym, config).ok()?;
+    let mut out = String::with_capacity(prefix.len() + demangled.len());
+    out.push_str(prefix);
+    out.push_str(&demangled);
+    Some(out)
+}
+
+fn split_section_prefix(name: &str) -> Option<(&str, &str)> {
+    let section = name
+        .strip_prefix(".gnu.linkonce.")
+        .or_else(|| name.strip_prefix('.'))?;
+    let end = section.find('.')? + 1;
+
+    Some(name.split_at(name.len() - section.len() + end))
+}
+
+/// Append `rest` to `out`, starting at `column` if `out` is short enough and
+/// after a single space otherwise.
+fn push_aligned(out: &mut String, column: usize, rest: &str) {
+    let width = out.chars().count();
+    out.extend(core::iter::repeat(' ').take(column.saturating_sub(width).max(1)));
+    out.push_str(rest);
+}
+
+/// Demangle the symbol in a line of a GNU ld map file, keeping the rest of
+/// the line as-is.
+///
+/// The symbol is taken from:
+/// - Lines with an address followed by a symbol, like
+///   `0x00100000        SetText__5tNamePCc`.
+/// - Lines with a single field, like the name of a common symbol or of a
+///   section that was wrapped because it is too long.
+/// - The name of an input section, like `.text.f__Fv  0x00100000  0x10 foo.o`,
+///   as long as it is in the same line as its address. The columns after it
+///   are padded to start where they did, or moved after a single space if the
+///   demangled name doesn't fit.
+///
+/// Section names are demangled without their prefix, like `.text.` or
+/// `.gnu.linkonce.t.`, which is kept in the output. Lines without a symbol or
+/// with one that fails to demangle are returned unchanged.
+///
+/// Use [`MapDemangler`] to process a whole map file, joining wrapped lines if
+/// needed.
+///
+/// # Examples
+///
+/// ```
+/// use gnuv2_demangle::{demangle_map_line, DemangleConfig};
+///
+/// let config = DemangleConfig::new();
+///
+/// assert_eq!(
+///     demangle_map_line("                0x00100000                SetText__5tNamePCc", &config),
+///     "                0x00100000                tName::SetText(char const *)"
+/// );
+/// assert_eq!(
+///     demangle_map_line(" .text.f__Fv    0x00100000       0x10 foo.o", &config),
+///     " .text.f(void)  0x00100000       0x10 foo.o"
+/// );
+/// assert_eq!(
+///     demangle_map_line(" .text          0x00100000       0xd0 name.o", &config),
+///     " .text          0x00100000       0xd0 name.o"
+/// );
+/// ```
+#[must_use]
+pub fn demangle_map_line<'l>(line: &'l str, config: &DemangleConfig) -> Cow<'l, str> {
+    match MapLine::parse(line) {
+        MapLine::Name { start, name } | MapLine::Symbol { start, name } => {
+            let Some(demangled) = demangle_name(name, config) else {
+                return Cow::Borrowed(line);
+            };
+            let end = start + name.len();
+            let mut out = String::with_capacity(line.len() - name.len() + demangled.len());
+            out.push_str(&line[..start]);
+            out.push_str(&demangled);
+            out.push_str(&line[end..]);
+            Cow::Owned(out)
+        }
+        MapLine::Section { start, name, rest } => {
+            let Some(demangled) = demangle_name(name, config) else {
+                return Cow::Borrowed(line);
+            };
+            let mut out = String::with_capacity(line.len() + demangled.len());
+            out.push_str(&line[..start]);
+            out.push_str(&demangled);
+            push_aligned(&mut out, rest, &line[rest..]);
+            Cow::Owned(out)
+        }
+        MapLine::Other => Cow::Borrowed(line),
+    }
+}
+
+/// Demangle every line of a GNU ld map file, keeping its layout so two map
+/// files can still be diffed.
+///
+/// Every line is demangled as [`demangle_map_line`] does. On top of that, ld
+/// puts names that are too long for their column in a line of their own,
+/// followed by a line with the rest of the columns. Depending on
+/// [`MapWrapping`], those lines are either kept apart or joined, and names
+/// that only become too long once demangled are wrapped or not in the same
+/// way.
+///
+/// Lines are expected without their line terminator, like the ones produced
+/// by [`str::lines`].
+///
+/// # Examples
+///
+/// ```
+/// use gnuv2_demangle::{DemangleConfig, MapDemangler, MapWrapping};
+///
+/// let map = " .text.__ls__FR7ostreamPCc
+///                 0x00100170       0x30 stream.o
+///  .text.Id__3Foo 0x001001a0       0x10 foo.o";
+/// let config = DemangleConfig::new();
+///
+/// let wrapped: Vec<_> = MapDemangler::new(map.lines(), &config, MapWrapping::Wrap).collect();
+/// assert_eq!(
+///     wrapped,
+///     [
+///         " .text.operator<<(ostream &, char const *)",
+///         "                0x00100170       0x30 stream.o",
+///         " .text.Foo::Id(void)",
+///         "                0x001001a0       0x10 foo.o",
+///     ]
+/// );
+///
+/// let joined: Vec<_> = MapDemangler::new(map.lines(), &config, MapWrapping::Join).collect();
+/// assert_eq!(
+///     joined,
+///     [
+///         " .text.operator<<(ostream &, char const *) 0x00100170       0x30 stream.o",
+///         " .text.Foo::Id(void) 0x001001a0       0x10 foo.o",
+///     ]
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct MapDemangler<'a, I>
+where
+    I: Iterator<Item = &'a str>,
+{
+    lines: Peekable<I>,
+    config: DemangleConfig,
+    wrapping: MapWrapping,
+    /// The second half of a line that was wrapped.
+    queued: Option<String>,
+}
+
+impl<'a, I> MapDemangler<'a, I>
+where
+    I: Iterator<Item = &'a str>,
+{
+    /// Demangle the map file given as `lines`.
+    #[must_use]
+    pub fn new(lines: I, config: &DemangleConfig, wrapping: MapWrapping) -> Self {
+        Self {
+            lines: lines.peekable(),
+            config: *config,
+            wrapping,
+            queued: None,
+        }
+    }
+
+    fn join(&self, line: &str, start: usize, name: &str, continuation: &str) -> String {
+        let column = continuation_column(continuation).unwrap_or(0);
+        let demangled = demangle_name(name, &self.config);
+
+        let mut out = String::with_capacity(line.len() + continuation.len());
+        out.push_str(&line[..start]);
+        out.push_str(demangled.as_deref().unwrap_or(name));
+        push_aligned(&mut out, column, &continuation[column..]);
+        out
+    }
+
+    fn wrap_section(
+        &mut self,
+        line: &'a str,
+        start: usize,
+        name: &str,
+        rest: usize,
+    ) -> Cow<'a, str> {
+        let Some(demangled) = demangle_name(name, &self.config) else {
+            return Cow::Borrowed(line);
+        };
+
+        let mut out = String::with_capacity(line.len() + demangled.len());
+        out.push_str(&line[..start]);
+        out.push_str(&demangled);
+        if out.chars().count() < rest {
+            push_aligned(&mut out, rest, &line[rest..]);
+        } else {
+            let mut queued = String::with_capacity(line.len());
+            push_aligned(&mut queued, rest, &line[rest..]);
+            self.queued = Some(queued);
+        }
+        Cow::Owned(out)
+    }
+}
+
+impl<'a, I> Iterator for MapDemangler<'a, I>
+where
+    I: Iterator<Item = &'a str>,
+{
+    type Item = Cow<'a, str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(queued) = self.queued.take() {
+            return Some(Cow::Owned(queued));
+        }
+
+        let line = self.lines.next()?;
+        let demangled = match (MapLine::parse(line), self.wrapping) {
+            (MapLine::Name { start, name }, MapWrapping::Join) => {
+                match self
+                    .lines
+                    .next_if(|next| continuation_column(next).is_some())
+                {
+                    Some(continuation) => Cow::Owned(self.join(line, start, name, continuation)),
+                    None => demangle_map_line(line, &self.config),
+                }
+            }
+            (MapLine::Section { start, name, rest }, MapWrapping::Wrap) => {
+                self.wrap_section(line, start, name, rest)
+            }
+            _ => demangle_map_line(line, &self.config),
+        };
+        Some(demangled)
+    }
+}
@@ -0,0 +1,346 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+//! Marks put in the demangled symbol while it is being put together, so it
+//! can be cut or highlighted later without parsing it again.
+//!
+//! The marks are private use characters, which can't come from the symbol
+//! since it must be ASCII. They are removed before the output is returned.
+
+use core::fmt;
+
+use alloc::{
+    borrow::Cow,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// Put right before a bracket opening a template list, a parameter list or a
+/// declarator containing one of them.
+const OPEN: char = '\u{F0000}';
+/// Put right before the bracket closing one opened by [`OPEN`].
+const CLOSE: char = '\u{F0001}';
+/// Put right before the comma between two template arguments or parameters.
+const SEPARATOR: char = '\u{F0002}';
+/// Put right after a token, which starts with the mark of its [`Token`].
+const TOKEN_END: char = '\u{F0003}';
+/// The mark of the first [`Token`], the others follow it.
+const FIRST_TOKEN: u32 = 0xF0010;
+
+/// The marks of the places the output may be cut at, see [`truncate`].
+///
+/// Writes nothing unless they were asked for, see
+/// [`Context::mark_cuts`](crate::demangle_info::Context::mark_cuts).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct CutMarks {
+    enabled: bool,
+}
+
+impl CutMarks {
+    pub(crate) const fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+
+    pub(crate) const fn is_enabled(self) -> bool {
+        self.enabled
+    }
+
+    pub(crate) const fn open(self) -> &'static str {
+        if self.enabled {
+            "\u{F0000}"
+        } else {
+            ""
+        }
+    }
+
+    pub(crate) const fn close(self) -> &'static str {
+        if self.enabled {
+            "\u{F0001}"
+        } else {
+            ""
+        }
+    }
+
+    pub(crate) const fn separator(self) -> &'static str {
+        if self.enabled {
+            "\u{F0002}"
+        } else {
+            ""
+        }
+    }
+}
+
+/// What a word of the output is, see
+/// [`TokenClass`](crate::highlight::TokenClass).
+///
+/// The words that are not marked are either keywords, like `const`, or
+/// punctuation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum Token {
+    Scope,
+    Name,
+    Operator,
+    Type,
+    TemplateArg,
+    Literal,
+}
+
+impl Token {
+    const ALL: [Self; 6] = [
+        Self::Scope,
+        Self::Name,
+        Self::Operator,
+        Self::Type,
+        Self::TemplateArg,
+        Self::Literal,
+    ];
+
+    const fn mark(self) -> char {
+        match char::from_u32(FIRST_TOKEN + self as u32) {
+            Some(c) => c,
+            None => TOKEN_END,
+        }
+    }
+
+    fn from_mark(c: char) -> Option<Self> {
+        let index = u32::from(c).checked_sub(FIRST_TOKEN)?;
+        Self::ALL.get(usize::try_from(index).ok()?).copied()
+    }
+}
+
+/// The marks of the tokens of the output, see [`tokens`].
+///
+/// Writes the tokens on their own unless they were asked for, see
+/// [`Context::mark_tokens`](crate::demangle_info::Context::mark_tokens).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct TokenMarks {
+    enabled: bool,
+}
+
+impl TokenMarks {
+    pub(crate) const fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+
+    /// `text` marked as `token`.
+    pub(crate) fn mark(self, token: Token, text: &str) -> Marked<'_> {
+        Marked {
+            token: self.enabled.then_some(token),
+            text,
+        }
+    }
+
+    /// Same as [`TokenMarks::mark`], but as a string.
+    pub(crate) fn marked(self, token: Token, text: &str) -> Cow<'_, str> {
+        if self.enabled {
+            Cow::from(self.mark(token, text).to_string())
+        } else {
+            Cow::from(text)
+        }
+    }
+
+    /// Mark the types of `out` written after `start` as template arguments,
+    /// since they went in a template list.
+    pub(crate) fn template_args(self, out: &mut String, start: usize) {
+        if !self.enabled {
+            return;
+        }
+
+        let (typ, template_arg) = (Token::Type.mark(), Token::TemplateArg.mark());
+        let args = out.split_off(start);
+        out.extend(
+            args.chars()
+                .map(|c| if c == typ { template_arg } else { c }),
+        );
+    }
+
+    /// `scope` as it goes before the scope separator, with the type it is
+    /// marked as a scope instead, like the `Bar` of `Foo::Bar` or the `List`
+    /// of `List<int>`.
+    ///
+    /// The cut marks must still be there, to tell which tokens are in its
+    /// template lists.
+    pub(crate) fn scope(self, scope: &str) -> Cow<'_, str> {
+        if !self.enabled {
+            return Cow::from(scope);
+        }
+
+        let mut depth = 0usize;
+        let mut last = None;
+        for (i, c) in scope.char_indices() {
+            match c {
+                OPEN => depth += 1,
+                CLOSE => depth = depth.saturating_sub(1),
+                _ if depth == 0 && Token::from_mark(c).is_some() => last = Some((i, c)),
+                _ => {}
+            }
+        }
+
+        match last {
+            Some((i, c)) if c == Token::Type.mark() => {
+                let mut retagged = String::with_capacity(scope.len());
+                retagged.push_str(&scope[..i]);
+                retagged.push(Token::Scope.mark());
+                retagged.push_str(&scope[i + c.len_utf8()..]);
+                Cow::from(retagged)
+            }
+            _ => Cow::from(scope),
+        }
+    }
+
+    /// Join `scopes` with `separator`, each one of them marked as a scope but
+    /// the last, like `Foo::Bar`.
+    pub(crate) fn join_scopes<S: AsRef<str>>(self, scopes: &[S], separator: &str) -> String {
+        let mut out = String::new();
+        for (i, scope) in scopes.iter().enumerate() {
+            if i + 1 == scopes.len() {
+                out.push_str(scope.as_ref());
+            } else {
+                out.push_str(&self.scope(scope.as_ref()));
+                out.push_str(separator);
+            }
+        }
+        out
+    }
+}
+
+/// A piece of text marked as a token, see [`TokenMarks::mark`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct Marked<'a> {
+    token: Option<Token>,
+    text: &'a str,
+}
+
+impl fmt::Display for Marked<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.token {
+            Some(token) => write!(f, "{}{}{TOKEN_END}", token.mark(), self.text),
+            None => f.write_str(self.text),
+        }
+    }
+}
+
+const fn is_cut_mark(c: char) -> bool {
+    matches!(c, OPEN | CLOSE | SEPARATOR)
+}
+
+fn is_mark(c: char) -> bool {
+    is_cut_mark(c) || c == TOKEN_END || Token::from_mark(c).is_some()
+}
+
+/// Remove the marks of `marked`.
+pub(crate) fn unmark(marked: &str) -> String {
+    marked.chars().filter(|c| !is_mark(*c)).collect()
+}
+
+/// Whether `marked` is `plain` once its marks are removed.
+pub(crate) fn is_unmarked(marked: &str, plain: &str) -> bool {
+    marked.chars().filter(|c| !is_mark(*c)).eq(plain.chars())
+}
+
+/// The last character of `marked` that is not a mark.
+pub(crate) fn last_unmarked(marked: &str) -> Option<char> {
+    marked.chars().rev().find(|c| !is_mark(*c))
+}
+
+fn unmarked_len(marked: &str) -> usize {
+    marked.chars().filter(|c| !is_mark(*c)).count()
+}
+
+/// Split `marked` in its tokens, with `None` for the text in between them.
+///
+/// Its cut marks must be removed first.
+#[cfg(feature = "classify-output")]
+pub(crate) fn tokens(marked: &str) -> Vec<(Option<Token>, &str)> {
+    let mut tokens = Vec::new();
+    let mut rest = marked;
+
+    while let Some((start, token)) = rest
+        .char_indices()
+        .find_map(|(i, c)| Some((i, Token::from_mark(c)?)))
+    {
+        if start != 0 {
+            tokens.push((None, &rest[..start]));
+        }
+        let text = &rest[start + token.mark().len_utf8()..];
+        let (text, after) = text.split_once(TOKEN_END).unwrap_or((text, ""));
+        tokens.push((Some(token), text));
+        rest = after;
+    }
+    if !rest.is_empty() {
+        tokens.push((None, rest));
+    }
+
+    tokens
+}
+
+/// Cut `marked` to at most `max_len` characters, counting the `…` that marks
+/// the cut, and remove its cut marks.
+///
+/// It is only cut right after one of the marked brackets or separators, and
+/// the brackets left open are closed, with `close_template` for the ones of
+/// template lists. If not even the shortest of those cuts
+/// fits then it is used anyway, and symbols without any of them are not cut
+/// at all.
+///
+/// The marks of the tokens are kept, and not counted.
+pub(crate) fn truncate(marked: &str, max_len: usize, close_template: char) -> String {
+    let uncut = || marked.chars().filter(|c| !is_cut_mark(*c)).collect();
+    if unmarked_len(marked) <= max_len {
+        return uncut();
+    }
+
+    let mut prefix = String::with_capacity(marked.len());
+    let mut open = Vec::new();
+    let mut shortest: Option<String> = None;
+    let mut best: Option<String> = None;
+    let mut chars = marked.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if !is_cut_mark(c) {
+            prefix.push(c);
+            continue;
+        }
+        let Some(bracket) = chars.next() else {
+            break;
+        };
+        prefix.push(bracket);
+        match c {
+            OPEN if bracket == '(' => open.push(')'),
+            OPEN => open.push(close_template),
+            CLOSE => {
+                open.pop();
+            }
+            _ => {
+                if let Some(space) = chars.next_if_eq(&' ') {
+                    prefix.push(space);
+                }
+            }
+        }
+
+        let candidate = cut_at(&prefix, &open);
+        if unmarked_len(&candidate) <= max_len {
+            best = Some(candidate);
+        } else if shortest.is_none() {
+            shortest = Some(candidate);
+        }
+    }
+
+    best.or(shortest).unwrap_or_else(uncut)
+}
+
+/// Close the brackets left open in `prefix`, given by their closing bracket,
+/// after the `…`.
+fn cut_at(prefix: &str, open: &[char]) -> String {
+    let mut cut = String::with_capacity(prefix.len() + "…".len() + open.len() * 2);
+    cut.push_str(prefix);
+    cut.push('…');
+    for c in open.iter().rev() {
+        // Avoid writing `>>`
+        if *c == '>' && cut.ends_with('>') {
+            cut.push(' ');
+        }
+        cut.push(*c);
+    }
+    cut
+}
@@ -0,0 +1,39 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+use alloc::{string::String, vec::Vec};
+
+use rayon::prelude::*;
+
+use crate::{demangle, DemangleConfig, DemangleError};
+
+/// Demangle every symbol of `syms` in parallel, using the global [`rayon`]
+/// thread pool.
+///
+/// The results are in the same order as `syms`, and are the same as calling
+/// [`demangle`] on each symbol. Useful for huge symbol dumps, since each
+/// symbol is demangled independently of the others.
+///
+/// # Examples
+///
+/// ```
+/// use gnuv2_demangle::{demangle_all, DemangleConfig, DemangleError};
+///
+/// let config = DemangleConfig::new();
+///
+/// let demangled = demangle_all(&["SetText__5tNamePCc", "main", "_$_5tName"], &config);
+/// assert_eq!(
+///     demangled,
+///     [
+///         Ok(String::from("tName::SetText(char const *)")),
+///         Err(DemangleError::NotMangled),
+///         Ok(String::from("tName::~tName(void)")),
+///     ]
+/// );
+/// ```
+pub fn demangle_all<'s>(
+    syms: &[&'s str],
+    config: &DemangleConfig,
+) -> Vec<Result<String, DemangleError<'s>>> {
+    syms.par_iter().map(|sym| demangle(sym, config)).collect()
+}
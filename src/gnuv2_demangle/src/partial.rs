@@ -0,0 +1,174 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+use alloc::string::String;
+
+use crate::{demangle, DemangleConfig, DemangleError, OwnedDemangleError};
+
+/// Starts of text appended to the input to check if an error depends on the
+/// input ending where it does, like a lookback missing its digits or a
+/// qualifier missing the type it applies to.
+const PROBES: [&str; 6] = ["", "_", "0", "1_", "11", "C"];
+
+/// How many filler characters are added after each probe, so a
+/// length-prefixed name that was cut short doesn't fail the same way once
+/// padded.
+const PADDING: usize = 128;
+
+/// The state of a [`DemanglePartial`] after being fed a chunk of input.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FeedResult {
+    /// The input so far can't be demangled, but it may be the start of a
+    /// valid symbol.
+    NeedMoreInput,
+    /// The input so far is a whole symbol, demangled.
+    ///
+    /// Some symbols are the start of longer ones, like `foo__Fi` and
+    /// `foo__Fii`, so more input can still be fed if there is any.
+    Complete(String),
+    /// The input so far fails to demangle in a way that more input is not
+    /// going to fix.
+    Error(OwnedDemangleError),
+}
+
+/// Demangle a symbol that arrives in chunks, like a name read from a remote
+/// target in fixed-size packets.
+///
+/// Every call to [`feed`] demangles the input received so far and reports
+/// if it is a whole symbol, if it is still missing data, or if it is not
+/// going to demangle at all, which allows to stop fetching the rest of it.
+/// [`finish`] demangles the whole input, the same as [`demangle`] would.
+///
+/// Telling both kinds of errors apart is a best-effort guess. Only errors
+/// about data that is already complete, like an unknown type code or
+/// trailing data, are reported as [`FeedResult::Error`], and only if they
+/// don't change when more text is appended to the input.
+///
+/// [`feed`]: DemanglePartial::feed
+/// [`finish`]: DemanglePartial::finish
+///
+/// # Examples
+///
+/// ```
+/// use gnuv2_demangle::{DemangleConfig, DemanglePartial, FeedResult};
+///
+/// let mut partial = DemanglePartial::new(&DemangleConfig::new());
+///
+/// assert_eq!(partial.feed("SetText__5tN"), FeedResult::NeedMoreInput);
+/// assert_eq!(
+///     partial.feed("amePCc"),
+///     FeedResult::Complete("tName::SetText(char const *)".to_string())
+/// );
+/// assert_eq!(
+///     partial.finish().as_deref(),
+///     Ok("tName::SetText(char const *)")
+/// );
+///
+/// let mut partial = DemanglePartial::new(&DemangleConfig::new());
+/// assert!(matches!(partial.feed("_$_5tNamei"), FeedResult::Error(_)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DemanglePartial {
+    config: DemangleConfig,
+    buffer: String,
+}
+
+impl DemanglePartial {
+    /// Start with no input.
+    #[must_use]
+    pub fn new(config: &DemangleConfig) -> Self {
+        Self {
+            config: *config,
+            buffer: String::new(),
+        }
+    }
+
+    /// The input received so far.
+    #[must_use]
+    #[inline]
+    pub fn input(&self) -> &str {
+        &self.buffer
+    }
+
+    /// Append `chunk` to the input and demangle it.
+    pub fn feed(&mut self, chunk: &str) -> FeedResult {
+        self.buffer.push_str(chunk);
+
+        match demangle(&self.buffer, &self.config) {
+            Ok(demangled) => FeedResult::Complete(demangled),
+            Err(err) if self.needs_more_input(&err) => FeedResult::NeedMoreInput,
+            Err(err) => FeedResult::Error(err.into_owned()),
+        }
+    }
+
+    /// Demangle the input received so far, as a whole symbol.
+    pub fn finish(self) -> Result<String, OwnedDemangleError> {
+        demangle(&self.buffer, &self.config).map_err(DemangleError::into_owned)
+    }
+
+    /// Forget the input received so far, to start with a new symbol.
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+    }
+
+    fn needs_more_input(&self, err: &DemangleError) -> bool {
+        match err {
+            DemangleError::NonAscii
+            | DemangleError::InvalidCharacter(..)
+            | DemangleError::AllocationFailure => false,
+            DemangleError::TrailingDataOnDestructor(_)
+            | DemangleError::UnknownType(..)
+            | DemangleError::PrimitiveInsteadOfClass(_)
+            | DemangleError::InvalidTypeOnTypeInfoFunction(_)
+            | DemangleError::TrailingDataOnTypeInfoFunction(_)
+            | DemangleError::InvalidTypeOnTypeInfoNode(_)
+            | DemangleError::TrailingDataOnTypeInfoNode(_)
+            | DemangleError::TrailingDataAfterEllipsis(_)
+            | DemangleError::TrailingDataOnNamespacedGlobal(_)
+            | DemangleError::TrailingDataAfterArgumentList(_)
+            | DemangleError::ArrayLengthOverflow(_)
+            | DemangleError::RepeatCountTooLarge(..)
+            | DemangleError::TooManyArrayDimensions(_) => !self.fails_the_same_when_padded(err),
+            // Anything else may be an element that was cut short.
+            _ => true,
+        }
+    }
+
+    /// Check if `err` is raised on data that is already in the input, by
+    /// demangling it again with some text appended.
+    fn fails_the_same_when_padded(&self, err: &DemangleError) -> bool {
+        let Some(position) = error_position(&self.buffer, err) else {
+            return false;
+        };
+        let context = &self.buffer[position..];
+        // An error at the very end, or at the start of a clone suffix or a
+        // keyed name, can go away with more input.
+        if context.is_empty() || context.contains(['.', '$']) {
+            return false;
+        }
+
+        let mut padded = String::with_capacity(self.buffer.len() + 2 + PADDING);
+        PROBES.iter().all(|probe| {
+            padded.clear();
+            padded.push_str(&self.buffer);
+            padded.push_str(probe);
+            padded.extend(core::iter::repeat('z').take(PADDING));
+
+            match demangle(&padded, &self.config) {
+                Ok(_) => false,
+                Err(padded_err) => {
+                    padded_err.kind_name() == err.kind_name()
+                        && error_position(&padded, &padded_err) == Some(position)
+                }
+            }
+        })
+    }
+}
+
+/// Where in `sym` the error happened, if the error points into it.
+fn error_position(sym: &str, err: &DemangleError) -> Option<usize> {
+    let context = err.context()?;
+    let position = (context.as_ptr() as usize).checked_sub(sym.as_ptr() as usize)?;
+
+    (position <= sym.len()).then_some(position)
+}
@@ -1,8 +1,6 @@
 /* SPDX-FileCopyrightText: © 2025 Decompollaborate */
 /* SPDX-License-Identifier: MIT OR Apache-2.0 */
 
-use alloc::borrow::Cow;
-
 /// The result of partially or totally consuming an str from left to right,
 /// storing the part that haven't been consumed yet (`remaining`) and the
 /// consumed part (`data`), possibly converted to a different type.
@@ -26,14 +24,6 @@ impl<'s, T> Remaining<'s, T> {
     }
 }
 
-impl<'s, 'd> Remaining<'s, &'d str> {
-    pub(crate) fn d_as_cow(self) -> Remaining<'s, Cow<'d, str>> {
-        let Self { r, d } = self;
-
-        Remaining::new(r, Cow::from(d))
-    }
-}
-
 impl<'s> Remaining<'s, &'s str> {
     pub(crate) fn split_at(s: &'s str, mid: usize) -> Self {
         let (data, remaining) = s.split_at(mid);
@@ -83,23 +73,14 @@ impl<'s> StrParsing<'s> for str {
     }
 
     fn p_digit(&'s self) -> Option<Remaining<'s, usize>> {
-        let c = self.chars().next()?;
-        if c.is_ascii_digit() {
-            let digit = (c as usize).wrapping_sub('0' as usize);
+        let Remaining { r, d: c } = self.p_first()?;
+        let digit = c.to_digit(10)?;
 
-            Some(Remaining::new(&self[1..], digit))
-        } else {
-            None
-        }
+        Some(Remaining::new(r, digit as usize))
     }
 
     fn p_number_maybe_multi_digit(&'s self) -> Option<Remaining<'s, usize>> {
-        if self.is_empty() {
-            None
-        } else if self.len() == 1 {
-            // Single digit should be fine to just parse
-            Some(Remaining::new("", self.parse().ok()?))
-        } else if let Some(index) = self.find(|c: char| !c.is_ascii_digit()) {
+        if let Some(index) = self.find(|c: char| !c.is_ascii_digit()) {
             if index == 0 {
                 None
             } else if self[index..].starts_with('_') {
@@ -112,18 +93,18 @@ impl<'s> StrParsing<'s> for str {
                 ))
             } else {
                 // Only consume a single digit
-                Some(Remaining::new(&self[1..], self[..1].parse().ok()?))
+                self.p_digit()
             }
         } else {
             // Only consume a single digit
-            Some(Remaining::new(&self[1..], self[..1].parse().ok()?))
+            self.p_digit()
         }
     }
 
     fn p_first(&'s self) -> Option<Remaining<'s, char>> {
         let c = self.chars().next()?;
 
-        Some(Remaining::new(&self[1..], c))
+        Some(Remaining::new(&self[c.len_utf8()..], c))
     }
 }
 
@@ -158,4 +139,22 @@ mod tests {
             Some(Remaining::new("_junk", 1)),
         );
     }
+
+    #[test]
+    fn test_parse_multi_byte() {
+        assert_eq!("ñ1".p_first(), Some(Remaining::new("1", 'ñ')));
+        assert_eq!("ñ1".p_digit(), None);
+        assert_eq!("1ñ".p_digit(), Some(Remaining::new("ñ", 1)));
+        assert_eq!("ñ1".p_number_maybe_multi_digit(), None);
+        assert_eq!(
+            "1ñ".p_number_maybe_multi_digit(),
+            Some(Remaining::new("ñ", 1)),
+        );
+        assert_eq!(
+            "12ñ".p_number_maybe_multi_digit(),
+            Some(Remaining::new("2ñ", 1)),
+        );
+        assert_eq!("١".p_digit(), None);
+        assert_eq!("١".p_number_maybe_multi_digit(), None);
+    }
 }
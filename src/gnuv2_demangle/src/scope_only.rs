@@ -0,0 +1,390 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+use alloc::{borrow::Cow, string::String};
+
+use crate::{
+    demangle_info::{Construct, Context},
+    DemangleConfig, DemangleError,
+};
+
+use crate::{
+    dem::{
+        demangle_custom_name, demangle_method_qualifier, demangle_operator_name,
+        demangle_trailing_custom_name,
+    },
+    dem_arg_list::ArgVec,
+    dem_namespace::demangle_scope,
+    dem_template::{
+        demangle_template, demangle_template_and_types, demangle_template_with_return_type,
+    },
+    demangler::{looks_like_plain_c, split_clone_suffix},
+    extract::find_invalid_char,
+    remainer::Remaining,
+    str_cutter::StrCutter,
+};
+
+/// Demangle only the scope of a symbol, like `sim::CollisionManager::Area`
+/// for `AddPair__Q33sim16CollisionManager4AreaPQ23sim6Object`.
+///
+/// This is a lot faster than [`demangle`] when the rest of the symbol is not
+/// needed, since the argument list and the return type are skipped without
+/// being demangled or validated. Because of that, a symbol with a broken
+/// argument list may still have its scope demangled.
+///
+/// Free functions, including free operators, return `None`. Functions,
+/// methods, operators, constructors, destructors and static members are
+/// supported, anything else, like virtual tables or thunks, returns
+/// [`DemangleError::UnsupportedSymbolKindOnComponents`], same as
+/// [`demangle_components`].
+///
+/// The scope is the same as the one [`demangle`] emits with the same
+/// configuration, for example without its template arguments if
+/// [`DemangleConfig::omit_template_args_in_scope`] is turned on.
+///
+/// [`demangle`]: crate::demangle
+/// [`demangle_components`]: crate::demangle_components
+///
+/// # Examples
+///
+/// ```
+/// use gnuv2_demangle::{demangle_scope_only, DemangleConfig};
+///
+/// let config = DemangleConfig::new();
+///
+/// let scope = demangle_scope_only(
+///     "AddPair__Q33sim16CollisionManager4AreaPQ23sim6Object",
+///     &config,
+/// );
+/// assert_eq!(scope, Ok(Some(String::from("sim::CollisionManager::Area"))));
+///
+/// let scope = demangle_scope_only("Insert__t4List1Z5tNamei", &config);
+/// assert_eq!(scope, Ok(Some(String::from("List<tName>"))));
+///
+/// let scope = demangle_scope_only("_$_5tName", &config);
+/// assert_eq!(scope, Ok(Some(String::from("tName"))));
+///
+/// let scope = demangle_scope_only("__ls__FR7ostreamPCc", &config);
+/// assert_eq!(scope, Ok(None));
+/// ```
+pub fn demangle_scope_only<'s>(
+    sym: &'s str,
+    config: &DemangleConfig,
+) -> Result<Option<String>, DemangleError<'s>> {
+    let config = &Context::new(config);
+    if !sym.is_ascii() {
+        return Err(DemangleError::NonAscii);
+    }
+    if let Some((c, position)) = find_invalid_char(sym) {
+        return Err(DemangleError::InvalidCharacter(c, position));
+    }
+
+    let scope = scope_with_marker(config, sym);
+    if scope.is_ok() || !config.demangle_clone_suffix {
+        return scope;
+    }
+
+    // Same as `demangle`, the `.<number>` discriminator is only stripped if
+    // the whole symbol failed.
+    match split_clone_suffix(sym) {
+        Some((core, _number)) => scope_with_marker(config, core),
+        None => scope,
+    }
+}
+
+/// Same as `demangle_with_marker`, including the retry with the alternative
+/// template readings.
+fn scope_with_marker<'s>(
+    config: &Context,
+    sym: &'s str,
+) -> Result<Option<String>, DemangleError<'s>> {
+    let cplus_marker = sym.chars().find(|x| *x == '.').unwrap_or('$');
+
+    let scope = scope_impl(config, sym, cplus_marker);
+    let Err(err) = scope else {
+        return scope;
+    };
+    if !config.take_ambiguous_template() {
+        return Err(err);
+    }
+
+    config
+        .with_alternative_template_readings(|| scope_impl(config, sym, cplus_marker))
+        .map_err(|_| match err.context() {
+            Some(context) => DemangleError::AllTemplateReadingsFailed(context),
+            None => err,
+        })
+}
+
+fn scope_impl<'s>(
+    config: &Context,
+    sym: &'s str,
+    cplus_marker: char,
+) -> Result<Option<String>, DemangleError<'s>> {
+    if let Some(s) = sym.c_strip_prefix_3chars('_', cplus_marker, '_') {
+        let (r, scope) = class_scope(config, s, DemangleError::InvalidClassNameOnDestructor)?;
+        if !r.is_empty() {
+            return Err(DemangleError::TrailingDataOnDestructor(r));
+        }
+        Ok(Some(scope))
+    } else if sym.starts_with("__thunk_") || sym.starts_with("_GLOBAL_") {
+        Err(DemangleError::UnsupportedSymbolKindOnComponents(sym))
+    } else if let Some(s) = sym.strip_prefix("__") {
+        scope_special(config, s, sym)
+    } else {
+        scope_failables(config, sym, cplus_marker)
+    }
+}
+
+/// Same as `demangle_special`, stopping once the scope is known.
+fn scope_special<'s>(
+    config: &Context,
+    s: &'s str,
+    full_sym: &'s str,
+) -> Result<Option<String>, DemangleError<'s>> {
+    if s.starts_with("tf") || s.starts_with("ti") {
+        return Err(DemangleError::UnsupportedSymbolKindOnComponents(full_sym));
+    }
+
+    if let Some(templated) = s
+        .strip_prefix('H')
+        .filter(|r| r.starts_with(|c: char| c.is_ascii_digit()))
+    {
+        return templated_function_scope(config, templated);
+    }
+
+    // Same as `demangle_special`, plain C symbols like `__throw` are not
+    // templated or namespaced constructors.
+    let is_constructor = s.starts_with(|c| matches!(c, '1'..='9'))
+        || s.strip_prefix('t')
+            .is_some_and(|r| r.starts_with(|c: char| c.is_ascii_digit()))
+        || s.strip_prefix('Q')
+            .is_some_and(|r| r.starts_with(|c: char| c == '_' || c.is_ascii_digit()));
+    if is_constructor {
+        let (_r, scope) = class_scope(config, s, DemangleError::InvalidClassNameOnConstructor)?;
+        return Ok(Some(scope));
+    }
+
+    let end_index = s.find("__").ok_or(DemangleError::NotMangled)?;
+    let op = &s[..end_index];
+    let remaining = &s[end_index + 2..];
+
+    if demangle_operator_name(op).is_none() && !op.starts_with("op") {
+        // Maybe a plain function starting with `__`, tried in the same order
+        // as `demangle_special`.
+        if full_sym.c_split2("__F").is_some() {
+            return Ok(None);
+        }
+        if let Some((incomplete_method_name, class_and_args, _c)) =
+            s.c_split2_r_starts_with("__", |c| matches!(c, '1'..='9' | 'C' | 'V' | 'u' | 't'))
+        {
+            let method_name = &full_sym[..incomplete_method_name.len() + 2];
+            if let Ok(scope) = method_scope(config, method_name, class_and_args) {
+                return Ok(Some(scope));
+            }
+        }
+        if let Some((_, s)) = full_sym.c_split2("__H") {
+            if let Ok(scope) = templated_function_scope(config, s) {
+                return Ok(scope);
+            }
+        }
+        return Err(DemangleError::NotMangled);
+    }
+
+    if remaining.starts_with('F') {
+        Ok(None)
+    } else if let Some(remaining) = remaining.strip_prefix('H') {
+        templated_function_scope(config, remaining)
+    } else {
+        let Remaining { r, d: _suffix } = demangle_method_qualifier(config, remaining);
+        let (_r, scope) = class_scope(config, r, DemangleError::InvalidClassNameOnOperator)?;
+        Ok(Some(scope))
+    }
+}
+
+/// Same as `demangle_impl_failables`, stopping once the scope is known.
+fn scope_failables<'s>(
+    config: &Context,
+    sym: &'s str,
+    cplus_marker: char,
+) -> Result<Option<String>, DemangleError<'s>> {
+    let leading_error = if let Some((method_name, the_rest, c)) = sym
+        .c_split2_r_starts_with("__", |c| {
+            matches!(c, 'F' | '1'..='9' | 'C' | 'V' | 'u' | 't' | 'H' | 'Q')
+        }) {
+        let scope = match c {
+            'F' => Ok(None),
+            '1'..='9' | 'C' | 'V' | 'u' | 't' => {
+                method_scope(config, method_name, the_rest).map(Some)
+            }
+            'H' => templated_function_scope(config, &the_rest[1..]),
+            'Q' => demangle_scope(config, &the_rest[1..], &ArgVec::new(config, None), true)
+                .map(|scope| Some(shown_scope(config, scope.namespaces, scope.bare))),
+            _ => unreachable!(),
+        };
+        match scope {
+            Ok(scope) => return Ok(scope),
+            Err(e) => Some(e),
+        }
+    } else {
+        None
+    };
+
+    if sym.starts_with("_vt") {
+        return Err(DemangleError::UnsupportedSymbolKindOnComponents(sym));
+    }
+
+    let leading_error = if let Some((s, name)) = sym.c_split2_char(cplus_marker) {
+        match namespaced_global_scope(config, sym, cplus_marker, s, name) {
+            Ok(scope) => return Ok(Some(scope)),
+            Err(e) => leading_error.or(Some(e)),
+        }
+    } else {
+        leading_error
+    };
+
+    Err(leading_error.unwrap_or(DemangleError::NotMangled))
+}
+
+/// Same as `demangle_method`, including its check for plain C symbols.
+fn method_scope<'s>(
+    config: &Context,
+    method_name: &'s str,
+    class_and_args: &'s str,
+) -> Result<String, DemangleError<'s>> {
+    let Remaining {
+        r: remaining,
+        d: suffix,
+    } = demangle_method_qualifier(config, class_and_args);
+
+    if remaining.starts_with(|c| matches!(c, '1'..='9')) {
+        let class_name = demangle_trailing_custom_name(
+            config,
+            remaining,
+            DemangleError::InvalidClassNameOnMethod,
+        );
+        let no_arguments = match &class_name {
+            Ok(Remaining { r, .. }) => r.is_empty(),
+            Err(DemangleError::LikelyTruncated(..)) => true,
+            Err(_) => false,
+        };
+        if no_arguments && suffix.is_empty() && looks_like_plain_c(method_name, remaining) {
+            config.saw(Construct::PlainCLookalike);
+            if config.reject_plain_c_lookalikes {
+                return Err(DemangleError::NotMangled);
+            }
+        }
+    }
+
+    let (_r, scope) = class_scope(config, remaining, DemangleError::InvalidClassNameOnMethod)?;
+    Ok(scope)
+}
+
+/// Same as `demangle_templated_function`, without the argument list nor the
+/// return type.
+fn templated_function_scope<'s>(
+    config: &Context,
+    s: &'s str,
+) -> Result<Option<String>, DemangleError<'s>> {
+    let (remaining, _template_args, typ) = demangle_template_with_return_type(config, s, true)?;
+    let Remaining {
+        r: remaining,
+        d: _suffix,
+    } = demangle_method_qualifier(config, remaining);
+
+    let scope = if let Some((typ, bare, _)) = typ {
+        Some(shown_scope(config, typ, bare))
+    } else if remaining.starts_with(|c| matches!(c, '1'..='9')) {
+        let Remaining { d: namespace, .. } = demangle_custom_name(
+            config,
+            remaining,
+            DemangleError::InvalidNamespaceOnTemplatedFunction,
+        )?;
+        Some(String::from(namespace))
+    } else if let Some(r) = remaining.strip_prefix('t') {
+        let (_r, template, typ, _types) =
+            demangle_template_and_types(config, r, &ArgVec::new(config, None), false)?;
+        Some(shown_scope(config, template, typ))
+    } else if let Some(r) = remaining.strip_prefix('Q') {
+        let scope = demangle_scope(config, r, &ArgVec::new(config, None), false)?;
+        Some(shown_scope(config, scope.namespaces, scope.bare))
+    } else {
+        None
+    };
+
+    Ok(scope)
+}
+
+/// Same as `demangle_namespaced_global`, without the name of the global.
+fn namespaced_global_scope<'s>(
+    config: &Context,
+    sym: &'s str,
+    cplus_marker: char,
+    s: &'s str,
+    name: &'s str,
+) -> Result<String, DemangleError<'s>> {
+    let Some(remaining) = sym.strip_prefix('_') else {
+        return Err(DemangleError::InvalidNamespacedGlobal(s, name));
+    };
+
+    let (r, scope) = if remaining.starts_with(['t', 'Q']) {
+        class_scope(
+            config,
+            remaining,
+            DemangleError::InvalidNamespaceOnNamespacedGlobal,
+        )?
+    } else {
+        let Remaining { r, d: class_name } = demangle_custom_name(
+            config,
+            remaining,
+            DemangleError::InvalidNamespaceOnNamespacedGlobal,
+        )?;
+        (r, String::from(class_name))
+    };
+
+    if r.strip_prefix(cplus_marker)
+        .is_some_and(|name| !name.is_empty())
+    {
+        Ok(scope)
+    } else {
+        Err(DemangleError::TrailingDataOnNamespacedGlobal(r))
+    }
+}
+
+/// A class name, either plain, templated or namespaced, as shown in front of
+/// the name of its members.
+fn class_scope<'s, F>(
+    config: &Context,
+    s: &'s str,
+    err: F,
+) -> Result<(&'s str, String), DemangleError<'s>>
+where
+    F: Fn(&'s str) -> DemangleError<'s>,
+{
+    if let Some(r) = s.strip_prefix('t') {
+        let (r, template, typ) = demangle_template(config, r, &ArgVec::new(config, None), true)?;
+        Ok((r, shown_scope(config, template, typ)))
+    } else if let Some(r) = s.strip_prefix('Q') {
+        let scope = demangle_scope(config, r, &ArgVec::new(config, None), true)?;
+        Ok((
+            scope.remaining,
+            shown_scope(config, scope.namespaces, scope.bare),
+        ))
+    } else {
+        let Remaining { r, d: class_name } = demangle_trailing_custom_name(config, s, err)?;
+        Ok((r, String::from(class_name)))
+    }
+}
+
+/// Same as `bare_scope`, picking the scope that gets shown.
+fn shown_scope<'a>(
+    config: &Context,
+    scope: impl Into<Cow<'a, str>>,
+    bare: impl Into<Cow<'a, str>>,
+) -> String {
+    if config.omit_template_args_in_scope {
+        bare.into().into_owned()
+    } else {
+        scope.into().into_owned()
+    }
+}
@@ -0,0 +1,127 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+//! The buffers used while demangling a symbol that come from the allocator
+//! given to [`demangle_in`](crate::demangle_in), or from the global one.
+//!
+//! Without the `allocator-api2` feature these are the plain `alloc` types.
+
+use core::fmt;
+#[cfg(feature = "allocator-api2")]
+use core::ops::Deref;
+
+#[cfg(feature = "allocator-api2")]
+use allocator_api2::alloc::Allocator;
+
+/// The allocator of the buffers of a single demangling.
+#[derive(Clone, Copy)]
+pub(crate) struct ScratchAlloc<'a> {
+    #[cfg(feature = "allocator-api2")]
+    alloc: &'a dyn Allocator,
+    #[cfg(not(feature = "allocator-api2"))]
+    alloc: core::marker::PhantomData<&'a ()>,
+}
+
+impl<'a> ScratchAlloc<'a> {
+    /// The global allocator, used when demangling without
+    /// [`demangle_in`](crate::demangle_in).
+    pub(crate) const GLOBAL: ScratchAlloc<'static> = ScratchAlloc {
+        #[cfg(feature = "allocator-api2")]
+        alloc: &allocator_api2::alloc::Global,
+        #[cfg(not(feature = "allocator-api2"))]
+        alloc: core::marker::PhantomData,
+    };
+
+    #[cfg(feature = "allocator-api2")]
+    pub(crate) fn new(alloc: &'a dyn Allocator) -> Self {
+        Self { alloc }
+    }
+
+    pub(crate) fn vec<T>(self) -> ScratchVec<'a, T> {
+        #[cfg(feature = "allocator-api2")]
+        return ScratchVec::new_in(self.alloc);
+        #[cfg(not(feature = "allocator-api2"))]
+        return ScratchVec::new();
+    }
+
+    pub(crate) fn string(self) -> ScratchString<'a> {
+        #[cfg(feature = "allocator-api2")]
+        return ScratchString { bytes: self.vec() };
+        #[cfg(not(feature = "allocator-api2"))]
+        return ScratchString::new();
+    }
+}
+
+impl fmt::Debug for ScratchAlloc<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ScratchAlloc")
+    }
+}
+
+#[cfg(feature = "allocator-api2")]
+pub(crate) type ScratchVec<'a, T> = allocator_api2::vec::Vec<T, &'a dyn Allocator>;
+#[cfg(not(feature = "allocator-api2"))]
+pub(crate) type ScratchVec<'a, T> = alloc::vec::Vec<T>;
+
+#[cfg(not(feature = "allocator-api2"))]
+pub(crate) type ScratchString<'a> = alloc::string::String;
+
+/// An UTF-8 string whose bytes live in a [`ScratchVec`].
+///
+/// Only has the operations the demangler needs.
+#[cfg(feature = "allocator-api2")]
+pub(crate) struct ScratchString<'a> {
+    bytes: ScratchVec<'a, u8>,
+}
+
+#[cfg(feature = "allocator-api2")]
+impl ScratchString<'_> {
+    pub(crate) fn push(&mut self, c: char) {
+        self.push_str(c.encode_utf8(&mut [0; 4]));
+    }
+
+    pub(crate) fn push_str(&mut self, s: &str) {
+        self.bytes.extend_from_slice(s.as_bytes());
+    }
+
+    pub(crate) fn insert(&mut self, index: usize, c: char) {
+        self.insert_str(index, c.encode_utf8(&mut [0; 4]));
+    }
+
+    pub(crate) fn insert_str(&mut self, index: usize, s: &str) {
+        assert!(self.is_char_boundary(index));
+        self.bytes.extend_from_slice(s.as_bytes());
+        self.bytes[index..].rotate_right(s.len());
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<char> {
+        let c = self.chars().next_back()?;
+        self.bytes.truncate(self.bytes.len() - c.len_utf8());
+        Some(c)
+    }
+}
+
+#[cfg(feature = "allocator-api2")]
+impl Deref for ScratchString<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        // SAFETY: Only whole `str`s and `char`s get written, at char
+        // boundaries.
+        unsafe { core::str::from_utf8_unchecked(&self.bytes) }
+    }
+}
+
+#[cfg(feature = "allocator-api2")]
+impl fmt::Debug for ScratchString<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+#[cfg(feature = "allocator-api2")]
+impl fmt::Display for ScratchString<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
@@ -0,0 +1,85 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+use alloc::string::String;
+
+use crate::TypePointerSpacing;
+
+const fn is_declarator(c: char) -> bool {
+    matches!(c, '*' | '&')
+}
+
+/// Whether a space should be placed between `left` and `right`.
+///
+/// `None` uses the spacing of c++filt, which always puts a space between a
+/// type and its declarator. Use [`needs_space_in_declarator`] for the spots
+/// where c++filt doesn't do that.
+pub(crate) fn needs_space(spacing: Option<TypePointerSpacing>, left: &str, right: &str) -> bool {
+    let (Some(l), Some(r)) = (left.chars().next_back(), right.chars().next()) else {
+        return false;
+    };
+
+    match spacing {
+        None => true,
+        Some(TypePointerSpacing::BeforeStar) => !is_declarator(l),
+        Some(TypePointerSpacing::AfterType) => !is_declarator(r),
+        Some(TypePointerSpacing::Compact) => !is_declarator(l) && !is_declarator(r),
+    }
+}
+
+/// Same as [`needs_space`], but c++filt follows [`TypePointerSpacing::BeforeStar`]
+/// here, like between the return type of a function pointer and its
+/// declarator, i.e. `char *(*)(void)`.
+pub(crate) fn needs_space_in_declarator(
+    spacing: Option<TypePointerSpacing>,
+    left: &str,
+    right: &str,
+) -> bool {
+    needs_space(
+        Some(spacing.unwrap_or(TypePointerSpacing::BeforeStar)),
+        left,
+        right,
+    )
+}
+
+/// Append `right` to `out`, with a space in between if the spacing asks for
+/// it.
+pub(crate) fn push_spaced(out: &mut String, spacing: Option<TypePointerSpacing>, right: &str) {
+    if needs_space(spacing, out, right) {
+        out.push(' ');
+    }
+    out.push_str(right);
+}
+
+/// Rearrange the spaces of the qualifiers made by `demangle_arg_qualifiers`,
+/// like `const *` or `*const volatile`.
+///
+/// The input is expected to use the [`TypePointerSpacing::BeforeStar`]
+/// spacing.
+pub(crate) fn respace_qualifiers(spacing: Option<TypePointerSpacing>, qualifiers: &str) -> String {
+    let qualifiers = qualifiers.trim_matches(' ');
+    let spacing = spacing.unwrap_or(TypePointerSpacing::BeforeStar);
+    if spacing == TypePointerSpacing::BeforeStar {
+        return String::from(qualifiers);
+    }
+
+    let mut out = String::new();
+    for word in qualifiers.split(' ') {
+        // Split runs like `**const` into `*`, `*` and `const`.
+        let mut rest = word;
+        while !rest.is_empty() {
+            let len = if rest.starts_with(is_declarator) {
+                1
+            } else {
+                rest.find(is_declarator).unwrap_or(rest.len())
+            };
+            let (token, r) = rest.split_at(len);
+            if needs_space(Some(spacing), &out, token) {
+                out.push(' ');
+            }
+            out.push_str(token);
+            rest = r;
+        }
+    }
+    out
+}
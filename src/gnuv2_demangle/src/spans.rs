@@ -0,0 +1,148 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+use core::{cell::RefCell, ops::Range};
+
+use alloc::{string::String, vec::Vec};
+
+use crate::{demangle_info::Context, demangler::demangle_checked, DemangleConfig, DemangleError};
+
+/// What a [`Span`] was parsed as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum SpanKind {
+    /// A class or namespace the function belongs to, like the `3sim` of
+    /// `Update__Q23sim9Collision`.
+    Scope,
+    /// An argument of a function, method or function pointer.
+    Argument,
+    /// The return type of a templated function or of a function pointer.
+    ReturnType,
+    /// An argument of a template, like the `Zi` of `t4List1Zi`.
+    TemplateArgument,
+}
+
+/// A part of a demangled symbol, see [`demangle_with_spans`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Span {
+    /// What the part is.
+    pub kind: SpanKind,
+    /// The byte range of the mangled symbol the part was parsed from.
+    pub input: Range<usize>,
+    /// The demangled part, like `char const *`.
+    pub text: String,
+}
+
+/// Demangle a symbol, same as [`demangle`](crate::demangle), and also list
+/// the part of the symbol each scope, argument, return type and template
+/// argument was parsed from.
+///
+/// The spans are sorted by where they start, with the ones containing others
+/// first, so the arguments of a function pointer come right after the
+/// function pointer itself.
+///
+/// Repeated arguments, like `N`, and lookbacks, like `T`, span their own
+/// token instead of the argument they refer to. A `N` repeat produces a span
+/// for every argument it stands for.
+///
+/// # Examples
+///
+/// ```
+/// use gnuv2_demangle::{demangle_with_spans, DemangleConfig, SpanKind};
+///
+/// let config = DemangleConfig::new();
+///
+/// let sym = "Add__Q23sim9CollisionPQ23sim15CollisionObjectT1";
+/// let (demangled, spans) = demangle_with_spans(sym, &config).unwrap();
+/// assert_eq!(
+///     demangled,
+///     "sim::Collision::Add(sim::CollisionObject *, sim::CollisionObject *)"
+/// );
+///
+/// let parts: Vec<_> = spans
+///     .iter()
+///     .map(|x| (x.kind, &sym[x.input.clone()], x.text.as_str()))
+///     .collect();
+/// assert_eq!(
+///     parts,
+///     [
+///         (SpanKind::Scope, "3sim", "sim"),
+///         (SpanKind::Scope, "9Collision", "Collision"),
+///         (
+///             SpanKind::Argument,
+///             "PQ23sim15CollisionObject",
+///             "sim::CollisionObject *"
+///         ),
+///         (SpanKind::Argument, "T1", "sim::CollisionObject *"),
+///     ]
+/// );
+/// ```
+pub fn demangle_with_spans<'s>(
+    sym: &'s str,
+    config: &DemangleConfig,
+) -> Result<(String, Vec<Span>), DemangleError<'s>> {
+    let mut config = Context::new(config);
+    config.spans = Some(SpanRecorder::new(sym));
+    let demangled = demangle_checked(sym, &config)?;
+
+    let mut spans = config
+        .spans
+        .map(|recorder| recorder.spans.into_inner())
+        .unwrap_or_default();
+    spans.sort_by_key(|x| (x.input.start, core::cmp::Reverse(x.input.end)));
+    Ok((demangled, spans))
+}
+
+/// Records the [`Span`]s of a [`Context`].
+#[derive(Debug)]
+pub(crate) struct SpanRecorder {
+    origin: usize,
+    len: usize,
+    spans: RefCell<Vec<Span>>,
+}
+
+impl SpanRecorder {
+    fn new(sym: &str) -> Self {
+        Self {
+            origin: sym.as_ptr() as usize,
+            len: sym.len(),
+            spans: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// The offset of `s` into the symbol. Every string being parsed is a
+    /// slice of it.
+    fn offset(&self, s: &str) -> usize {
+        (s.as_ptr() as usize)
+            .saturating_sub(self.origin)
+            .min(self.len)
+    }
+
+    /// Record the part parsed from `s` until `remaining`.
+    pub(crate) fn record(&self, kind: SpanKind, s: &str, remaining: &str, text: String) {
+        let start = self.offset(s);
+        let end = (start + s.len().saturating_sub(remaining.len())).min(self.len);
+
+        self.spans.borrow_mut().push(Span {
+            kind,
+            input: start..end,
+            text,
+        });
+    }
+
+    /// How many spans were recorded so far.
+    pub(crate) fn mark(&self) -> usize {
+        self.spans.borrow().len()
+    }
+
+    /// Forget the spans recorded after `mark`.
+    pub(crate) fn truncate(&self, mark: usize) {
+        self.spans.borrow_mut().truncate(mark);
+    }
+
+    /// Forget the spans recorded between two marks.
+    pub(crate) fn discard(&self, marks: Range<usize>) {
+        self.spans.borrow_mut().drain(marks);
+    }
+}
@@ -0,0 +1,132 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+use alloc::{boxed::Box, string::String, vec, vec::Vec};
+
+use crate::markup::is_unmarked;
+
+/// Whether `class_name` is one of the templates [`simplify_stl_template`]
+/// knows about.
+pub(crate) fn is_stl_container(class_name: &str) -> bool {
+    matches!(
+        class_name,
+        "vector" | "list" | "deque" | "basic_string" | "map" | "_Rb_tree"
+    )
+}
+
+/// A template argument, as [`simplify_stl_template`] sees it.
+///
+/// Two arguments are the same type if they compare equal, no matter how
+/// they were written by the rest of the configuration.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct StlArg {
+    /// The demangled argument, as it goes in the template list.
+    pub(crate) text: String,
+    /// The same argument without its top-level `const`, if it has one, like
+    /// `Foo *` for `Foo *const`.
+    pub(crate) unqualified: Option<Box<StlArg>>,
+    /// The class name and the arguments, if this is a template.
+    pub(crate) template: Option<(String, Vec<StlArg>)>,
+}
+
+impl StlArg {
+    /// Any argument other than a template, like `int` or `Foo *`.
+    pub(crate) fn other(text: String) -> Self {
+        Self {
+            text,
+            unqualified: None,
+            template: None,
+        }
+    }
+
+    /// Whether this is the plain type `typ`, like `char`.
+    fn is_plain(&self, typ: &str) -> bool {
+        self.unqualified.is_none() && self.template.is_none() && is_unmarked(&self.text, typ)
+    }
+
+    /// Whether this is the template `name<args>`.
+    fn is_template(&self, name: &str, args: &[&StlArg]) -> bool {
+        matches!(
+            &self.template,
+            Some((class_name, template_args))
+                if class_name == name && template_args.iter().eq(args.iter().copied())
+        )
+    }
+
+    /// The arguments of this template, if it is the template `name`.
+    fn template_args(&self, name: &str) -> Option<&[StlArg]> {
+        match &self.template {
+            Some((class_name, args)) if class_name == name => Some(args),
+            _ => None,
+        }
+    }
+
+    /// Whether this is `typ const`.
+    fn is_const_of(&self, typ: &StlArg) -> bool {
+        self.unqualified.as_deref() == Some(typ)
+    }
+}
+
+/// What a template gets simplified to by [`simplify_stl_template`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct Simplified<'a> {
+    pub(crate) name: &'a str,
+    /// The arguments of its template list, or none if it doesn't have one,
+    /// like `string`.
+    pub(crate) args: Vec<&'a str>,
+}
+
+/// Try to collapse well-known SGI-STL templates, as shipped with GCC 2.9x,
+/// into the name an user would have written.
+///
+/// Nested templates in `args` have been simplified by the time the outer one
+/// gets here, but they are still compared by what they were parsed as.
+///
+/// Returns `None` if the template is not one of the recognized patterns.
+pub(crate) fn simplify_stl_template<'a>(
+    class_name: &'a str,
+    args: &'a [StlArg],
+) -> Option<Simplified<'a>> {
+    let (name, args) = match (class_name, args) {
+        ("vector" | "list" | "deque", [typ, alloc]) if is_default_allocator(alloc, typ) => {
+            (class_name, vec![typ.text.as_str()])
+        }
+        ("basic_string", [chr, traits, alloc])
+            if chr.is_plain("char")
+                && traits.is_template("string_char_traits", &[chr])
+                && is_default_allocator(alloc, chr) =>
+        {
+            ("string", Vec::new())
+        }
+        ("map", [key, value, compare, alloc])
+            if is_less(compare, key) && is_default_allocator(alloc, value) =>
+        {
+            ("map", vec![key.text.as_str(), value.text.as_str()])
+        }
+        ("_Rb_tree", [key, pair, select, compare, alloc])
+            if is_less(compare, key) && select.is_template("_Select1st", &[pair]) =>
+        {
+            match pair.template_args("pair")? {
+                [const_key, value]
+                    if const_key.is_const_of(key) && is_default_allocator(alloc, value) =>
+                {
+                    ("map", vec![key.text.as_str(), value.text.as_str()])
+                }
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+
+    Some(Simplified { name, args })
+}
+
+/// Either `allocator<T>` or the SGI `alloc` typedef.
+fn is_default_allocator(alloc: &StlArg, typ: &StlArg) -> bool {
+    alloc.template_args("__default_alloc_template").is_some()
+        || alloc.is_template("allocator", &[typ])
+}
+
+fn is_less(compare: &StlArg, key: &StlArg) -> bool {
+    compare.is_template("less", &[key])
+}
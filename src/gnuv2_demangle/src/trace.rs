@@ -0,0 +1,196 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+#[cfg(feature = "trace")]
+use core::{
+    cell::{Cell, RefCell},
+    fmt,
+};
+
+#[cfg(feature = "trace")]
+use alloc::{string::String, vec::Vec};
+
+use crate::{dem_namespace::Scope, remainer::Remaining};
+#[cfg(feature = "trace")]
+use crate::{demangle_info::Context, demangler::demangle_checked, DemangleConfig, DemangleError};
+
+#[cfg(feature = "trace")]
+/// A step of the parse of a symbol, see [`demangle_traced`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub struct TraceEvent {
+    /// How many steps contain this one.
+    pub depth: usize,
+    /// What was being parsed, like `"argument"` or `"template"`.
+    pub construct: &'static str,
+    /// The byte offset into the symbol where the step started.
+    pub start: usize,
+    /// How the step ended.
+    pub outcome: TraceOutcome,
+}
+
+#[cfg(feature = "trace")]
+/// How a [`TraceEvent`] ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum TraceOutcome {
+    /// Parsed up to the `end` byte offset of the symbol, exclusive.
+    Parsed {
+        /// The byte offset where the step stopped parsing.
+        end: usize,
+    },
+    /// Failed with the error of the given
+    /// [`kind_name`](DemangleError::kind_name).
+    Failed(&'static str),
+}
+
+#[cfg(feature = "trace")]
+impl fmt::Display for TraceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for _ in 0..self.depth {
+            f.write_str("  ")?;
+        }
+        write!(f, "{} at {}: ", self.construct, self.start)?;
+        match self.outcome {
+            TraceOutcome::Parsed { end } => write!(f, "parsed until {end}"),
+            TraceOutcome::Failed(kind) => write!(f, "failed with {kind}"),
+        }
+    }
+}
+
+#[cfg(feature = "trace")]
+/// Demangle a symbol, same as [`demangle`](crate::demangle), and also list
+/// each step of the parse.
+///
+/// The steps are listed in the order they started, so a step is followed by
+/// the steps it contains, which have a bigger [`TraceEvent::depth`].
+///
+/// Useful for finding out where a symbol goes wrong.
+///
+/// # Examples
+///
+/// ```
+/// use gnuv2_demangle::{demangle_traced, DemangleConfig, TraceOutcome};
+///
+/// let config = DemangleConfig::new();
+///
+/// let (demangled, events) = demangle_traced("SetText__5tNamePCc", &config);
+/// assert_eq!(demangled.as_deref(), Ok("tName::SetText(char const *)"));
+///
+/// let steps: Vec<_> = events.iter().map(|x| x.to_string()).collect();
+/// assert_eq!(
+///     steps,
+///     [
+///         "argument list at 15: parsed until 18",
+///         "  argument at 15: parsed until 18",
+///     ]
+/// );
+/// ```
+pub fn demangle_traced<'s>(
+    sym: &'s str,
+    config: &DemangleConfig,
+) -> (Result<String, DemangleError<'s>>, Vec<TraceEvent>) {
+    let mut config = Context::new(config);
+    config.tracer = Some(Tracer::new(sym));
+    let demangled = demangle_checked(sym, &config);
+
+    let events = config
+        .tracer
+        .map(|tracer| tracer.events.into_inner())
+        .unwrap_or_default();
+    (demangled, events)
+}
+
+#[cfg(feature = "trace")]
+/// Records the [`TraceEvent`]s of a [`Context`].
+#[derive(Debug)]
+pub(crate) struct Tracer {
+    origin: usize,
+    len: usize,
+    depth: Cell<usize>,
+    events: RefCell<Vec<TraceEvent>>,
+}
+
+#[cfg(feature = "trace")]
+impl Tracer {
+    fn new(sym: &str) -> Self {
+        Self {
+            origin: sym.as_ptr() as usize,
+            len: sym.len(),
+            depth: Cell::new(0),
+            events: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// The offset of `s` into the symbol. Every string being parsed is a
+    /// slice of it.
+    fn offset(&self, s: &str) -> usize {
+        (s.as_ptr() as usize)
+            .saturating_sub(self.origin)
+            .min(self.len)
+    }
+
+    /// Start a step, returning its index.
+    pub(crate) fn enter(&self, construct: &'static str, s: &str) -> usize {
+        let depth = self.depth.get();
+        self.depth.set(depth + 1);
+
+        let mut events = self.events.borrow_mut();
+        events.push(TraceEvent {
+            depth,
+            construct,
+            start: self.offset(s),
+            // Filled by `exit`.
+            outcome: TraceOutcome::Parsed { end: usize::MAX },
+        });
+        events.len() - 1
+    }
+
+    /// Finish the step started by `enter`.
+    pub(crate) fn exit<T: Traceable>(&self, index: usize, result: &Result<T, DemangleError>) {
+        let outcome = match result {
+            Ok(parsed) => TraceOutcome::Parsed {
+                end: self.offset(parsed.remaining()),
+            },
+            Err(err) => TraceOutcome::Failed(err.kind_name()),
+        };
+        self.events.borrow_mut()[index].outcome = outcome;
+        self.depth.set(self.depth.get() - 1);
+    }
+}
+
+/// The result of a traced step, which knows where the step stopped parsing.
+pub(crate) trait Traceable {
+    #[cfg_attr(not(feature = "trace"), expect(dead_code))]
+    fn remaining(&self) -> &str;
+}
+
+impl<T> Traceable for Remaining<'_, T> {
+    fn remaining(&self) -> &str {
+        self.r
+    }
+}
+
+impl<A> Traceable for (&str, A) {
+    fn remaining(&self) -> &str {
+        self.0
+    }
+}
+
+impl<A, B> Traceable for (&str, A, B) {
+    fn remaining(&self) -> &str {
+        self.0
+    }
+}
+
+impl<A, B, C> Traceable for (&str, A, B, C) {
+    fn remaining(&self) -> &str {
+        self.0
+    }
+}
+
+impl Traceable for Scope<'_, '_> {
+    fn remaining(&self) -> &str {
+        self.remaining
+    }
+}
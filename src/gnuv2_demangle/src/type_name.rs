@@ -0,0 +1,78 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+use alloc::string::String;
+
+use crate::{demangle_info::Context, DemangleConfig, DemangleError};
+
+use crate::{
+    dem::demangle_custom_name, dem_arg_list::ArgVec, dem_namespace::demangle_namespaces,
+    dem_template::demangle_template, extract::find_invalid_char, markup::truncate,
+    remainer::Remaining,
+};
+
+/// Demangle the name of a class, like the ones found in stabs debugging
+/// information.
+///
+/// The name must be a single counted name (`6Wibble`), a namespaced name
+/// (`Q23foo3Bar`) or a templated class (`t4List1Zi`), and nothing else.
+/// Other types, like `PCc`, are not accepted.
+///
+/// # Examples
+///
+/// ```
+/// use gnuv2_demangle::{demangle_type_name, DemangleConfig, DemangleError};
+///
+/// let config = DemangleConfig::new();
+///
+/// assert_eq!(demangle_type_name("6Wibble", &config).as_deref(), Ok("Wibble"));
+/// assert_eq!(
+///     demangle_type_name("Q23sim9Collision", &config).as_deref(),
+///     Ok("sim::Collision")
+/// );
+/// assert_eq!(
+///     demangle_type_name("t4List1Zi", &config).as_deref(),
+///     Ok("List<int>")
+/// );
+/// assert_eq!(
+///     demangle_type_name("PCc", &config),
+///     Err(DemangleError::InvalidTypeName("PCc"))
+/// );
+/// ```
+pub fn demangle_type_name<'s>(
+    s: &'s str,
+    config: &DemangleConfig,
+) -> Result<String, DemangleError<'s>> {
+    let config = &Context::new(config);
+    if !s.is_ascii() {
+        return Err(DemangleError::NonAscii);
+    }
+    if let Some((c, position)) = find_invalid_char(s) {
+        return Err(DemangleError::InvalidCharacter(c, position));
+    }
+    if config.max_output_len.is_some() {
+        config.mark_cuts();
+    }
+
+    let (r, mut demangled) = if s.starts_with(|c| matches!(c, '1'..='9')) {
+        let Remaining { r, d: name } =
+            demangle_custom_name(config, s, DemangleError::InvalidCustomNameOnArgument)?;
+        (r, String::from(name))
+    } else if let Some(q) = s.strip_prefix('Q') {
+        let (r, namespaces, _) = demangle_namespaces(config, q, &ArgVec::new(config, None), true)?;
+        (r, namespaces)
+    } else if let Some(t) = s.strip_prefix('t') {
+        let (r, template, _) = demangle_template(config, t, &ArgVec::new(config, None), true)?;
+        (r, template)
+    } else {
+        return Err(DemangleError::InvalidTypeName(s));
+    };
+    if !r.is_empty() {
+        return Err(DemangleError::TrailingDataOnTypeName(r));
+    }
+
+    if let Some(max_len) = config.max_output_len {
+        demangled = truncate(&demangled, max_len, config.template_brackets.1);
+    }
+    Ok(demangled)
+}
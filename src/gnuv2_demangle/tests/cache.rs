@@ -0,0 +1,128 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+#![cfg(feature = "cache")]
+
+use std::{num::NonZeroUsize, sync::Arc, thread};
+
+use gnuv2_demangle::{
+    demangle, CachedDemangler, DemangleConfig, ReturnTypeStyle, SyncCachedDemangler,
+};
+
+use pretty_assertions::assert_eq;
+
+fn capacity(x: usize) -> NonZeroUsize {
+    NonZeroUsize::new(x).unwrap()
+}
+
+#[test]
+fn test_cache_hits_and_misses() {
+    let mut demangler = CachedDemangler::new(DemangleConfig::new(), capacity(8));
+
+    let first = demangler.demangle_cached("SetText__5tNamePCc").unwrap();
+    assert_eq!(&*first, "tName::SetText(char const *)");
+    assert_eq!((demangler.hits(), demangler.misses()), (0, 1));
+
+    // The same allocation is handed out again.
+    let second = demangler.demangle_cached("SetText__5tNamePCc").unwrap();
+    assert!(Arc::ptr_eq(&first, &second));
+    assert_eq!((demangler.hits(), demangler.misses()), (1, 1));
+
+    // Failures are cached too.
+    assert_eq!(demangler.demangle_cached("main"), None);
+    assert_eq!(demangler.demangle_cached("main"), None);
+    assert_eq!((demangler.hits(), demangler.misses()), (2, 2));
+    assert_eq!(demangler.len(), 2);
+
+    demangler.clear();
+    assert!(demangler.is_empty());
+    demangler.demangle_cached("SetText__5tNamePCc");
+    assert_eq!((demangler.hits(), demangler.misses()), (2, 3));
+}
+
+#[test]
+fn test_cache_evicts_least_recently_used() {
+    let mut demangler = CachedDemangler::new(DemangleConfig::new(), capacity(2));
+
+    demangler.demangle_cached("a__Fi");
+    demangler.demangle_cached("b__Fi");
+    // Makes `b__Fi` the least recently used one.
+    demangler.demangle_cached("a__Fi");
+    demangler.demangle_cached("c__Fi");
+    assert_eq!(demangler.len(), 2);
+    assert_eq!((demangler.hits(), demangler.misses()), (1, 3));
+
+    demangler.demangle_cached("a__Fi");
+    demangler.demangle_cached("c__Fi");
+    assert_eq!((demangler.hits(), demangler.misses()), (3, 3));
+
+    demangler.demangle_cached("b__Fi");
+    assert_eq!((demangler.hits(), demangler.misses()), (3, 4));
+    assert_eq!(demangler.len(), 2);
+}
+
+#[test]
+fn test_cache_config_fingerprint() {
+    let mut demangler = CachedDemangler::new(DemangleConfig::new_g2dem(), capacity(8));
+    let sym = "Swap__H1Zi_RX01T0_v";
+
+    assert_eq!(
+        demangler.demangle_cached(sym).as_deref(),
+        Some("void Swap<int>(int &, int &)")
+    );
+
+    let mut config = DemangleConfig::new_g2dem();
+    config.return_type_style = ReturnTypeStyle::Omit;
+    demangler.set_config(config);
+    assert_eq!(
+        demangler.demangle_cached(sym).as_deref(),
+        Some("Swap<int>(int &, int &)")
+    );
+    assert_eq!((demangler.hits(), demangler.misses()), (0, 2));
+
+    // Going back to the previous config reuses what was cached with it.
+    demangler.set_config(DemangleConfig::new_g2dem());
+    assert_eq!(
+        demangler.demangle_cached(sym).as_deref(),
+        Some("void Swap<int>(int &, int &)")
+    );
+    assert_eq!((demangler.hits(), demangler.misses()), (1, 2));
+}
+
+#[test]
+fn test_cache_matches_demangle() {
+    let config = DemangleConfig::new();
+    let mut demangler = CachedDemangler::new(config, capacity(16));
+    let syms: Vec<_> = include_str!("mangled_lists/parappa2.txt").lines().collect();
+
+    // Small enough to evict constantly.
+    for sym in syms.iter().chain(syms.iter().rev()) {
+        assert_eq!(
+            demangler.demangle_cached(sym).as_deref(),
+            demangle(sym, &config).ok().as_deref(),
+            "{sym}"
+        );
+        assert!(demangler.len() <= 16);
+    }
+}
+
+#[test]
+fn test_sync_cache() {
+    let demangler = SyncCachedDemangler::new(DemangleConfig::new(), capacity(8));
+
+    thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| {
+                for _ in 0..100 {
+                    assert_eq!(
+                        demangler.demangle_cached("SetText__5tNamePCc").as_deref(),
+                        Some("tName::SetText(char const *)")
+                    );
+                }
+            });
+        }
+    });
+
+    let demangler = demangler.lock();
+    assert_eq!((demangler.hits(), demangler.misses()), (399, 1));
+}
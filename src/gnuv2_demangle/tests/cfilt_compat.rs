@@ -0,0 +1,203 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+//! Compare the `new_cfilt` mode against a real `c++filt` binary.
+//!
+//! This test does nothing unless `G2DEM_CFILT_PATH` points to a `c++filt`
+//! that still supports `--format=gnu`, which was removed in binutils 2.32.
+//!
+//! ```bash
+//! G2DEM_CFILT_PATH=/opt/binutils-2.31/bin/c++filt cargo test --test cfilt_compat -- --nocapture
+//! ```
+//!
+//! Differences are classified into three categories:
+//! - `we-error`: We fail to demangle a symbol `c++filt` can demangle.
+//! - `they-error`: `c++filt` fails to demangle a symbol we can demangle.
+//! - `differ`: Both demangle the symbol, but the outputs don't match.
+//!
+//! Only `differ` fails the test by default. Set `G2DEM_CFILT_FAIL_ON` to a
+//! comma-separated list of categories to change it, i.e.
+//! `G2DEM_CFILT_FAIL_ON=we-error,differ`.
+
+mod testutil;
+
+use std::{
+    env,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use gnuv2_demangle::{demangle, DemangleConfig};
+
+use testutil::MANGLED_LISTS;
+
+/// How many symbols are passed to each `c++filt` invocation, to stay under
+/// the command line length limit.
+const CHUNK_SIZE: usize = 512;
+
+/// How many examples of each category get printed.
+const MAX_EXAMPLES: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Category {
+    WeError,
+    TheyError,
+    Differ,
+}
+
+impl Category {
+    const ALL: [Self; 3] = [Self::WeError, Self::TheyError, Self::Differ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::WeError => "we-error",
+            Self::TheyError => "they-error",
+            Self::Differ => "differ",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|x| x.name() == name)
+    }
+}
+
+#[derive(Debug)]
+struct Mismatch<'s> {
+    list: &'static str,
+    line: usize,
+    sym: &'s str,
+    ours: String,
+    theirs: String,
+}
+
+fn run_cfilt(cfilt: &Path, syms: &[&str]) -> Vec<String> {
+    let output = Command::new(cfilt)
+        .arg("--format=gnu")
+        .arg("--no-strip-underscore")
+        .args(syms)
+        .output()
+        .unwrap_or_else(|e| panic!("Failed to run `{}`: {e}", cfilt.display()));
+
+    assert!(
+        output.status.success(),
+        "`{}` failed. Does it support `--format=gnu`?\n{}",
+        cfilt.display(),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8(output.stdout).expect("c++filt output is not UTF-8");
+    let demangled: Vec<String> = stdout.lines().map(String::from).collect();
+    assert_eq!(
+        demangled.len(),
+        syms.len(),
+        "c++filt didn't print one line per symbol"
+    );
+
+    demangled
+}
+
+fn compare_list<'s>(
+    cfilt: &Path,
+    config: &DemangleConfig,
+    list: &'static str,
+    contents: &'s str,
+    mismatches: &mut [Vec<Mismatch<'s>>; 3],
+) -> usize {
+    // Blank lines would not survive the roundtrip through the command line.
+    let syms: Vec<(usize, &str)> = contents
+        .lines()
+        .enumerate()
+        .filter(|(_, sym)| !sym.trim().is_empty())
+        .map(|(i, sym)| (i + 1, sym))
+        .collect();
+
+    for chunk in syms.chunks(CHUNK_SIZE) {
+        let chunk_syms: Vec<&str> = chunk.iter().map(|(_, sym)| *sym).collect();
+        let theirs = run_cfilt(cfilt, &chunk_syms);
+
+        for (&(line, sym), theirs) in chunk.iter().zip(theirs) {
+            // `c++filt` echoes back the symbols it can't demangle.
+            let they_demangled = theirs != sym;
+
+            let (category, ours) = match demangle(sym, config) {
+                Ok(ours) if they_demangled && ours == theirs => continue,
+                Ok(ours) if they_demangled => (Category::Differ, ours),
+                Ok(ours) => (Category::TheyError, ours),
+                Err(e) if they_demangled => (Category::WeError, format!("{e:?}")),
+                Err(_) => continue,
+            };
+
+            mismatches[category as usize].push(Mismatch {
+                list,
+                line,
+                sym,
+                ours,
+                theirs,
+            });
+        }
+    }
+
+    syms.len()
+}
+
+fn fail_on() -> Vec<Category> {
+    let Ok(value) = env::var("G2DEM_CFILT_FAIL_ON") else {
+        return vec![Category::Differ];
+    };
+
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|x| !x.is_empty())
+        .map(|x| {
+            Category::from_name(x).unwrap_or_else(|| {
+                panic!(
+                    "Unknown category `{x}` in `G2DEM_CFILT_FAIL_ON`. Valid values: `we-error`, `they-error` and `differ`"
+                )
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn cfilt_compat_mangled_lists() {
+    let Some(cfilt) = env::var_os("G2DEM_CFILT_PATH").map(PathBuf::from) else {
+        eprintln!("`G2DEM_CFILT_PATH` is not set, skipping c++filt comparison");
+        return;
+    };
+    let fail_on = fail_on();
+    let config = DemangleConfig::new_cfilt();
+
+    let mut mismatches: [Vec<Mismatch>; 3] = Default::default();
+    let mut total = 0;
+    for (list, contents) in MANGLED_LISTS {
+        total += compare_list(&cfilt, &config, list, contents, &mut mismatches);
+    }
+
+    println!("Compared {total} symbols against `{}`", cfilt.display());
+    for category in Category::ALL {
+        let entries = &mismatches[category as usize];
+
+        println!();
+        println!("{}: {} symbols", category.name(), entries.len());
+        for m in entries.iter().take(MAX_EXAMPLES) {
+            println!("  {}:{}: {}", m.list, m.line, m.sym);
+            println!("    ours:   {}", m.ours);
+            println!("    theirs: {}", m.theirs);
+        }
+        if entries.len() > MAX_EXAMPLES {
+            println!("  ... and {} more", entries.len() - MAX_EXAMPLES);
+        }
+    }
+
+    let failed: Vec<&str> = fail_on
+        .iter()
+        .filter(|x| !mismatches[**x as usize].is_empty())
+        .map(|x| x.name())
+        .collect();
+    assert!(
+        failed.is_empty(),
+        "Found mismatches against c++filt in: {}",
+        failed.join(", ")
+    );
+}
@@ -0,0 +1,127 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+use gnuv2_demangle::{demangle, CompilerId, DemangleConfig, DemangleError};
+
+use pretty_assertions::assert_eq;
+
+type Fixture = (CompilerId, &'static [(&'static str, &'static str)]);
+
+/// Symbols as emitted by each compiler, and what they demangle to.
+static FIXTURES: [Fixture; 5] = [
+    (
+        CompilerId::EeGcc29_990721,
+        &[
+            (
+                "Debug_Assert__FPcT0T0i",
+                "Debug_Assert(char *, char *, char *, int)",
+            ),
+            ("_$_5tName", "tName::~tName(void)"),
+            (
+                "_GLOBAL_$I$SetText__5tNamePCc",
+                "global constructors keyed to tName::SetText(char const *)",
+            ),
+        ],
+    ),
+    (
+        CompilerId::EeGcc296,
+        &[
+            (
+                "Debug_Assert__FPcN20i",
+                "Debug_Assert(char *, char *, char *, int)",
+            ),
+            (
+                "setg__9streambufPcT1T1",
+                "streambuf::setg(char *, char *, char *)",
+            ),
+            ("_$_5tName", "tName::~tName(void)"),
+        ],
+    ),
+    (
+        CompilerId::Gcc295SnSystems,
+        &[
+            (
+                "Debug_Assert__FPcN20_i",
+                "Debug_Assert(char *, char *, char *, int)",
+            ),
+            ("SetTime__5TimerUJ", "Timer::SetTime(unsigned __int64)"),
+            ("_._5tName", "tName::~tName(void)"),
+            (
+                "_GLOBAL_.I.SetText__5tNamePCc",
+                "global constructors keyed to tName::SetText(char const *)",
+            ),
+        ],
+    ),
+    (
+        CompilerId::Gcc296RedHat,
+        &[
+            ("__5tName2i", "tName::tName(int) [not-in-charge]"),
+            ("SetText__5tNamePCc", "tName::SetText(char const *)"),
+        ],
+    ),
+    (
+        CompilerId::GenericGnu2,
+        &[
+            ("SetText__5tNamePCc", "tName::SetText(char const *)"),
+            ("_$_5tName", "tName::~tName(void)"),
+        ],
+    ),
+];
+
+#[test]
+fn test_compiler_presets() {
+    for (compiler, syms) in FIXTURES {
+        let config = DemangleConfig::for_compiler(compiler);
+
+        for (mangled, demangled) in syms {
+            assert_eq!(
+                demangle(mangled, &config).as_deref(),
+                Ok(*demangled),
+                "{compiler:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_compiler_presets_reject_other_quirks() {
+    let config = DemangleConfig::for_compiler(CompilerId::EeGcc296);
+
+    assert_eq!(
+        demangle("__5tName2i", &config),
+        Err(DemangleError::ConstructorVariantMarker("2i"))
+    );
+    assert_eq!(
+        demangle("SetTime__5TimerUJ", &config),
+        Err(DemangleError::UnknownType('J', "J"))
+    );
+    assert_eq!(
+        demangle("Debug_Assert__FPcN20_i", &config),
+        Err(DemangleError::TrailingDataAfterArgumentList("_i"))
+    );
+}
+
+#[test]
+fn test_compiler_generic_is_g2dem() {
+    assert_eq!(
+        DemangleConfig::for_compiler(CompilerId::GenericGnu2),
+        DemangleConfig::new_g2dem()
+    );
+}
+
+#[test]
+fn test_compiler_detect() {
+    for (compiler, syms) in FIXTURES {
+        let detected = CompilerId::detect(syms.iter().map(|(mangled, _)| *mangled));
+        assert_eq!(detected, Some(compiler));
+    }
+}
+
+#[test]
+fn test_compiler_detect_nothing_demangled() {
+    assert_eq!(CompilerId::detect(core::iter::empty()), None);
+    assert_eq!(
+        CompilerId::detect(["main", "_ZN5tName7SetTextEPKc"].into_iter()),
+        None
+    );
+}
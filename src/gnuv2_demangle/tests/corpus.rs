@@ -0,0 +1,106 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+#![cfg(feature = "test-utils")]
+
+use gnuv2_demangle::corpus::{CorpusOutcome, CorpusPreset, CorpusReport};
+
+use pretty_assertions::assert_eq;
+
+static CORPUS: &str = "\
+SetText__5tNamePCc
+_GLOBAL_$F$foo__Fi
+memcpy
+
+foo__FiN31
+func__FI80\r
+";
+
+#[test]
+fn test_corpus_stats() {
+    let report = CorpusReport::demangle_lines(CORPUS);
+    assert_eq!(report.entries.len(), 5);
+    assert_eq!(report.entries[4].symbol, "func__FI80");
+
+    let cfilt = report.stats(CorpusPreset::Cfilt);
+    assert_eq!(cfilt.total, 5);
+    assert_eq!(cfilt.not_mangled, 1);
+    assert_eq!(cfilt.failed, 1);
+    assert_eq!(
+        cfilt.failures_by_kind.into_iter().collect::<Vec<_>>(),
+        [("InvalidRepeatingArgument".to_string(), 1)]
+    );
+    assert_eq!(report.stats(CorpusPreset::G2dem).failure_rate(), 25.0);
+
+    assert_eq!(
+        report.entries[1].cfilt,
+        CorpusOutcome::Demangled("_GLOBAL_$F$foo(int)".to_string())
+    );
+    assert_eq!(
+        report.entries[1].g2dem,
+        CorpusOutcome::Demangled("global frames keyed to foo(int)".to_string())
+    );
+
+    let empty = CorpusReport::demangle_lines("");
+    assert_eq!(empty.stats(CorpusPreset::Cfilt).failure_rate(), 0.0);
+}
+
+#[test]
+fn test_corpus_report_round_trip() {
+    let report = CorpusReport::demangle_lines(CORPUS);
+    let written = report.to_string();
+
+    assert!(written.starts_with("# gnuv2_demangle "));
+    assert!(written.contains("# cfilt: 5 symbols, 1 not mangled, 1 failed (25.00%)\n"));
+    assert!(written.contains("#   InvalidRepeatingArgument: 1\n"));
+    assert!(written.contains("\nfunc__FI80\tok:func(int128_t)\tok:func(__int128_t)\n"));
+    assert_eq!(CorpusReport::parse(&written), Ok(report));
+
+    assert_eq!(CorpusReport::parse("# comment\nfoo\tok:foo\n"), Err(2));
+    assert_eq!(CorpusReport::parse("foo\tok:foo\tbad:foo\n"), Err(1));
+    assert_eq!(
+        CorpusReport::parse("foo\terr:NotMangled\terr:NotMangled\textra\n"),
+        Err(1)
+    );
+}
+
+#[test]
+fn test_corpus_diff() {
+    let previous = CorpusReport::parse(
+        "\
+SetText__5tNamePCc\terr:UnknownType\tok:tName::SetText(char const *)
+memcpy\terr:NotMangled\terr:NotMangled
+func__FI80\tok:func(int128_t)\tok:func(__int128)
+removed__Fv\tok:removed(void)\tok:removed(void)
+",
+    )
+    .unwrap();
+    let report = CorpusReport::parse(
+        "\
+SetText__5tNamePCc\tok:tName::SetText(char const *)\tok:tName::SetText(char const *)
+memcpy\terr:NotMangled\terr:NotMangled
+func__FI80\tok:func(int128_t)\terr:InvalidBitwidthForExtensionInteger
+added__Fv\tok:added(void)\tok:added(void)
+",
+    )
+    .unwrap();
+
+    let changes = report.diff(&previous);
+    let summary: Vec<_> = changes
+        .iter()
+        .map(|x| (x.symbol, x.preset, x.is_regression()))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("SetText__5tNamePCc", CorpusPreset::Cfilt, false),
+            ("func__FI80", CorpusPreset::G2dem, true),
+        ]
+    );
+
+    assert_eq!(
+        changes[1].to_string(),
+        "g2dem func__FI80\n  - ok:func(__int128)\n  + err:InvalidBitwidthForExtensionInteger"
+    );
+    assert!(report.diff(&report).is_empty());
+}
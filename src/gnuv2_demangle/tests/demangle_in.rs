@@ -0,0 +1,143 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+#![cfg(feature = "allocator-api2")]
+
+mod testutil;
+
+use std::{
+    alloc::{GlobalAlloc, System},
+    cell::Cell,
+    ptr::NonNull,
+};
+
+use allocator_api2::alloc::{AllocError, Allocator, Global, Layout};
+use bumpalo::Bump;
+use gnuv2_demangle::{demangle, demangle_in};
+
+use pretty_assertions::assert_eq;
+
+/// How many symbols are demangled before resetting the arena.
+const BATCH: usize = 1000;
+
+thread_local! {
+    /// The allocations made by [`CountingGlobal`] on this thread, since tests
+    /// run in parallel.
+    static GLOBAL_ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Counts the allocations made through the global allocator.
+struct CountingGlobal;
+
+unsafe impl GlobalAlloc for CountingGlobal {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        GLOBAL_ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        // SAFETY: Forwarded as is.
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: Forwarded as is.
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        GLOBAL_ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        // SAFETY: Forwarded as is.
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingGlobal = CountingGlobal;
+
+/// How many allocations `f` made through the global allocator.
+fn global_allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = GLOBAL_ALLOCATIONS.with(Cell::get);
+    let result = f();
+    (result, GLOBAL_ALLOCATIONS.with(Cell::get) - before)
+}
+
+/// Counts the allocations made through it, which bypass [`CountingGlobal`].
+#[derive(Default)]
+struct CountingAllocator {
+    allocations: Cell<usize>,
+}
+
+unsafe impl Allocator for CountingAllocator {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.allocations.set(self.allocations.get() + 1);
+        if layout.size() == 0 {
+            return Global.allocate(layout);
+        }
+        // SAFETY: The layout is not zero-sized.
+        let ptr = NonNull::new(unsafe { System.alloc(layout) }).ok_or(AllocError)?;
+        Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        if layout.size() != 0 {
+            // SAFETY: Allocated by `System` with the same layout.
+            unsafe { System.dealloc(ptr.as_ptr(), layout) }
+        }
+    }
+}
+
+#[test]
+fn test_demangle_in_matches_demangle() {
+    let mut symbols: Vec<&str> = testutil::MANGLED_LISTS
+        .iter()
+        .flat_map(|(_, contents)| contents.lines())
+        .collect();
+    symbols.extend(testutil::PATHOLOGICAL.iter().map(|(_, sym)| *sym));
+    symbols.extend(testutil::NOT_MANGLED);
+
+    for (preset, config) in testutil::presets() {
+        let mut bump = Bump::new();
+        for batch in symbols.chunks(BATCH) {
+            for sym in batch {
+                assert_eq!(
+                    demangle_in(sym, &config, &bump),
+                    demangle(sym, &config),
+                    "{preset} {sym}"
+                );
+            }
+            bump.reset();
+        }
+    }
+}
+
+#[test]
+fn test_demangle_in_uses_the_allocator() {
+    let symbols = testutil::MANGLED_LISTS
+        .iter()
+        .flat_map(|(_, contents)| contents.lines().take(200));
+
+    for (preset, config) in testutil::presets() {
+        let mut total_plain = 0;
+        let mut total_global = 0;
+        for sym in symbols.clone() {
+            let (demangled, plain) = global_allocations(|| demangle(sym, &config));
+            let alloc = CountingAllocator::default();
+            let (demangled_in, global) = global_allocations(|| demangle_in(sym, &config, &alloc));
+            assert_eq!(demangled_in, demangled, "{preset} {sym}");
+
+            // Some of the allocations go through the given allocator instead
+            // of the global one, but the rest of them are still global.
+            assert!(global <= plain, "{preset} {sym}: {global} > {plain}");
+            total_plain += plain;
+            total_global += global;
+        }
+        assert!(total_global < total_plain, "{preset}");
+
+        // The argument lists and the qualifiers of the arguments.
+        let alloc = CountingAllocator::default();
+        let demangled = demangle_in(
+            "Insert__t4List1Zt4Pair2ZiZPCcRCt4Pair2ZiZPCcPCVA3_i",
+            &config,
+            &alloc,
+        );
+        assert!(demangled.is_ok(), "{preset}");
+        assert!(alloc.allocations.get() >= 4, "{preset}");
+    }
+}
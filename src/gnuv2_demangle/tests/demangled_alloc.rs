@@ -0,0 +1,78 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+//! Lives in its own test binary because it replaces the global allocator.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    fmt::Write,
+};
+
+use gnuv2_demangle::{DemangleConfig, Demangled};
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Counts the allocations made by the current thread, so other tests running
+/// in parallel don't get in the way.
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|x| x.set(x.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+#[test]
+fn test_demangled_is_lazy() {
+    let config = DemangleConfig::new();
+    // Allocate the output buffer upfront so it doesn't count.
+    let mut out = String::with_capacity(256);
+
+    let before = allocations();
+    let demangled = Demangled::new("Printf__7ConsolePce", &config);
+    let copied = demangled;
+    assert_eq!(copied.sym(), "Printf__7ConsolePce");
+    assert_eq!(allocations(), before);
+
+    write!(out, "{demangled}").unwrap();
+    assert!(allocations() > before);
+    assert_eq!(out, "Console::Printf(char *, ...)");
+}
+
+#[test]
+fn test_demangled_fallback() {
+    let config = DemangleConfig::new();
+
+    for sym in ["main", "f__FiN31", "__thunk_x_foo__3Bar", ""] {
+        assert_eq!(format!("{}", Demangled::new(sym, &config)), sym);
+    }
+}
+
+#[test]
+fn test_demangled_debug() {
+    let config = DemangleConfig::new();
+
+    assert_eq!(
+        format!("{:?}", Demangled::new("foo__3Bar", &config)),
+        r#"Demangled { sym: "foo__3Bar", demangled: Ok("Bar::foo(void)") }"#
+    );
+    assert_eq!(
+        format!("{:?}", Demangled::new("main", &config)),
+        r#"Demangled { sym: "main", demangled: Err(NotMangled) }"#
+    );
+}
@@ -0,0 +1,75 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+#![cfg(feature = "detect")]
+
+use gnuv2_demangle::{demangle_auto, try_demangle_auto};
+
+use pretty_assertions::assert_eq;
+
+static GNUV2: [(&str, &str); 6] = [
+    ("SetText__5tNamePCc", "tName::SetText(char const *)"),
+    ("_$_5tName", "tName::~tName(void)"),
+    ("_vt$5tName", "tName virtual table"),
+    ("__5tNamePCc", "tName::tName(char const *)"),
+    (
+        "__eq__C5tNameRC5tName",
+        "tName::operator==(tName const &) const",
+    ),
+    (
+        "_GLOBAL_$I$SetText__5tNamePCc",
+        "global constructors keyed to tName::SetText(char const *)",
+    ),
+];
+
+static FOREIGN: [&str; 17] = [
+    // Itanium
+    "_Z3fooi",
+    "_ZN5tName7SetTextEPKc",
+    "_ZN5tNameD2Ev",
+    "_ZTV5tName",
+    "_ZNSt6vectorIiSaIiEE9push_backERKi",
+    "__ZN5tName7SetTextEPKc",
+    // Rust, legacy and v0
+    "_ZN4core3fmt5write17h0123456789abcdefE",
+    "_ZN3std2rt10lang_start28_$u7b$$u7b$closure$u7d$$u7d$17hb8a7d5c0aef2e4b6E",
+    "_RNvCs1234_7mycrate3foo",
+    "_RNvMs_NtCs4fqI2P2rA04_4core3fmtNtB4_9Formatter3pad",
+    // Plain C
+    "main",
+    "memcpy",
+    "_start",
+    "__divdi3",
+    "__FRAME_BEGIN__",
+    "__do_global_ctors_aux",
+    "__libc_start_main",
+];
+
+#[test]
+fn test_try_demangle_auto_gnuv2() {
+    for (mangled, demangled) in GNUV2 {
+        assert_eq!(Some(demangled), try_demangle_auto(mangled).as_deref());
+    }
+}
+
+#[test]
+fn test_try_demangle_auto_foreign() {
+    for mangled in FOREIGN {
+        assert_eq!(None, try_demangle_auto(mangled), "{mangled}");
+    }
+}
+
+#[test]
+fn test_demangle_auto_display() {
+    for (mangled, demangled) in GNUV2 {
+        let wrapped = demangle_auto(mangled);
+        assert_eq!(wrapped.to_string(), demangled);
+        assert_eq!(wrapped.as_str(), mangled);
+        assert!(wrapped.is_gnuv2());
+    }
+
+    for mangled in FOREIGN {
+        assert_eq!(demangle_auto(mangled).to_string(), mangled);
+    }
+    assert!(!demangle_auto("_ZN5tName7SetTextEPKc").is_gnuv2());
+}
@@ -0,0 +1,625 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+//! Malformed symbols and the exact error each one produces.
+//!
+//! Changing which error a symbol fails with is a breaking change for anyone
+//! matching on them, so any change to this table should be deliberate.
+
+use gnuv2_demangle::{
+    demangle, demangle_type_name, DemangleConfig, DemangleError, DemangleErrorKind,
+};
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_demangle_errors() {
+    static CASES: [(&str, DemangleError); 94] = [
+        // Not a symbol at all
+        ("x", DemangleError::NotMangled),
+        ("__op", DemangleError::NotMangled),
+        ("f__Fi\u{e9}", DemangleError::NonAscii),
+        // Counted names running past the end of the symbol
+        ("f__F10Foo", DemangleError::LikelyTruncated("10Foo", 7)),
+        ("f__F3Fooi5Ba", DemangleError::LikelyTruncated("5Ba", 3)),
+        (
+            "Set__15Collisi",
+            DemangleError::LikelyTruncated("15Collisi", 8),
+        ),
+        ("Set__3Foo10Ba", DemangleError::LikelyTruncated("10Ba", 8)),
+        ("_._10Foo", DemangleError::LikelyTruncated("10Foo", 7)),
+        ("__10Foo", DemangleError::LikelyTruncated("10Foo", 7)),
+        ("__as__10Foo", DemangleError::LikelyTruncated("10Foo", 7)),
+        (
+            "Update__Q23sim9Collisi",
+            DemangleError::LikelyTruncated("9Collisi", 2),
+        ),
+        // Namespaces
+        (
+            "f__FQ23foo",
+            DemangleError::InvalidCustomNameOnNamespace(""),
+        ),
+        (
+            "f__FQ03foo3Bar",
+            DemangleError::InvalidNamespaceCount("03foo3Bar"),
+        ),
+        (
+            "f__FQ_3foo3Bar",
+            DemangleError::InvalidNamespaceCount("_3foo3Bar"),
+        ),
+        (
+            "Update__Q03sim9Collision",
+            DemangleError::InvalidNamespaceCount("03sim9Collision"),
+        ),
+        ("__tiPQ", DemangleError::InvalidNamespaceCount("")),
+        // Templated classes
+        ("f__Ft4List0", DemangleError::TemplateReturnCountIsZero("0")),
+        ("f__Ft4ListZi", DemangleError::InvalidTemplateCount("Zi")),
+        ("__t3Foo", DemangleError::InvalidTemplateCount("")),
+        ("f__Ft4List1", DemangleError::RanOutOfArguments),
+        ("f__Ft3Foo1bx", DemangleError::InvalidTemplatedBoolean("x")),
+        (
+            "f__Ft3Foo1ci",
+            DemangleError::InvalidTemplatedNumberForCharacterValue("i"),
+        ),
+        (
+            "f__Ft3Foo1c1114112",
+            DemangleError::InvalidTemplatedCharacterValue("", 1114112),
+        ),
+        (
+            "f__Ft3Foo1ix",
+            DemangleError::InvalidValueForIntegralTemplated("x"),
+        ),
+        (
+            "f__Ft3Foo1PiL",
+            DemangleError::InvalidSymbolNameOnTemplateType("L"),
+        ),
+        // Templated functions
+        ("foo__H0_v", DemangleError::TemplateReturnCountIsZero("0_v")),
+        (
+            "foo__Hx_v",
+            DemangleError::InvalidTemplateReturnCount("x_v"),
+        ),
+        (
+            "foo__H1Zi_v",
+            DemangleError::MalformedTemplateWithReturnTypeMissingReturnType(""),
+        ),
+        (
+            "foo__H1Zi_Pi",
+            DemangleError::MalformedTemplateWithReturnTypeMissingReturnType(""),
+        ),
+        (
+            "foo__H1Zi_X91_v",
+            DemangleError::IndexTooBigForXArgument("_v", 9),
+        ),
+        (
+            "foo__H1Zi_X0_v",
+            DemangleError::InvalidValueForNumber1OnXArgument("_v"),
+        ),
+        ("foo__H1Zi_Y21_v", DemangleError::UnknownType('Y', "Y21_v")),
+        // Repeats
+        ("f__FN31", DemangleError::InvalidRepeatingArgument("N31")),
+        ("f__FiN31", DemangleError::InvalidRepeatingArgument("N31")),
+        ("f__FiN0", DemangleError::InvalidRepeatingArgument("N0")),
+        ("f__FiN", DemangleError::InvalidRepeatingArgument("N")),
+        (
+            "f__FiN9999999999999999999999999991",
+            DemangleError::InvalidRepeatingArgument("N9999999999999999999999999991"),
+        ),
+        // Lookbacks
+        ("f__FT1", DemangleError::LookbackCountTooBig("T1", 1)),
+        ("f__FiT5", DemangleError::LookbackCountTooBig("T5", 5)),
+        ("f__FiTx", DemangleError::InvalidLookbackCount("Tx")),
+        // A `void` argument can't be repeated, and an ellipsis can't be
+        // used as a type.
+        ("f__FvT0", DemangleError::LookbackToVoid("T0")),
+        ("f__FvN10", DemangleError::LookbackToVoid("N10")),
+        ("f__FvPFT0_v", DemangleError::LookbackToVoid("T0_v")),
+        ("f__Ft3Foo2ZeZT0", DemangleError::LookbackToEllipsis("T0")),
+        (
+            "f__Ft3Foo2ZeZN10",
+            DemangleError::LookbackToEllipsis("ZN10"),
+        ),
+        ("__tfX", DemangleError::InvalidValueForIndexOnXArgument("")),
+        // Names
+        ("Get__Q23foo0", DemangleError::EmptyCustomName("0")),
+        // Arrays
+        ("f__FA", DemangleError::InvalidArraySize("")),
+        ("f__FAx_i", DemangleError::InvalidArraySize("x_i")),
+        ("f__FA3i", DemangleError::MalformedArrayArgumment("i")),
+        // The length doesn't fit, which is not the same as a missing length.
+        (
+            "f__FA99999999999999999999999999_i",
+            DemangleError::ArrayLengthOverflow("A99999999999999999999999999_i"),
+        ),
+        (
+            "f__FPA99999999999999999999999_i",
+            DemangleError::ArrayLengthOverflow("A99999999999999999999999_i"),
+        ),
+        // Function pointers
+        (
+            "f__FPFi",
+            DemangleError::MissingReturnTypeForFunctionPointer(""),
+        ),
+        // Used to be `RanOutOfArguments`, but the symbol only lacks the
+        // return type, same as the previous one.
+        (
+            "f__FPFi_",
+            DemangleError::MissingReturnTypeForFunctionPointer(""),
+        ),
+        ("f__FPFi_z", DemangleError::UnknownType('z', "z")),
+        // Method pointers
+        ("f__FM3Foo", DemangleError::UnknownMethodMemberArgKind("")),
+        ("f__FM3Fooi", DemangleError::UnknownMethodMemberArgKind("i")),
+        ("f__FM3FooQ", DemangleError::UnknownMethodMemberArgKind("Q")),
+        (
+            "f__FM3FooFP3Fooi",
+            DemangleError::MissingReturnTypeForFunctionPointer(""),
+        ),
+        (
+            "f__FM3FooFi_v",
+            DemangleError::MethodPointerNotHavingAPointerFirst("i_v"),
+        ),
+        (
+            "f__FM3FooFCP3Fooi_v",
+            DemangleError::MethodPointerNotHavingAPointerFirst("CP3Fooi_v"),
+        ),
+        (
+            "f__FM3FooFP3Bari_v",
+            DemangleError::MethodPointerWrongClassName("P3Bari_v"),
+        ),
+        (
+            "f__FM3FooFPC3Fooi_v",
+            DemangleError::MethodPointerWrongClassName("PC3Fooi_v"),
+        ),
+        (
+            "f__FM3FooCFP3Fooi_v",
+            DemangleError::MethodPointerMissingConstness("P3Fooi_v"),
+        ),
+        (
+            "f__FUM3FooFP3Fooi_v",
+            DemangleError::InvalidQualifierForMethodMemberArg("UM3FooFP3Fooi_v"),
+        ),
+        // Object member pointers
+        (
+            "f__FO3Foo",
+            DemangleError::MissingTypeForObjectMemberPointer(""),
+        ),
+        // Used to be `RanOutOfArguments`, but the symbol only lacks the type
+        // of the member, same as the previous one.
+        (
+            "f__FO3Foo_",
+            DemangleError::MissingTypeForObjectMemberPointer("_"),
+        ),
+        (
+            "f__FUO3Foo_i",
+            DemangleError::InvalidQualifierForObjectMemberArg("UO3Foo_i"),
+        ),
+        // Extension integers
+        (
+            "f__FI",
+            DemangleError::MissingBitwidthForExtensionInteger(""),
+        ),
+        (
+            "f__FIg",
+            DemangleError::MissingBitwidthForExtensionInteger("g"),
+        ),
+        (
+            "f__FI7",
+            DemangleError::InvalidBitwidthForExtensionInteger("I7", 7),
+        ),
+        (
+            "f__FI100",
+            DemangleError::InvalidBitwidthForExtensionInteger("I100", 256),
+        ),
+        // Unknown types
+        ("f__Fiq", DemangleError::UnknownType('q', "q")),
+        ("f__Fi.", DemangleError::UnknownType('.', ".")),
+        ("f__FUPi", DemangleError::UnknownType('P', "Pi")),
+        ("__opz__3Foo", DemangleError::UnknownType('z', "z")),
+        // Trailing data
+        ("_._3Foo_", DemangleError::TrailingDataOnDestructor("_")),
+        ("_._3Fooi", DemangleError::TrailingDataOnDestructor("i")),
+        (
+            "__tf3Foo_x",
+            DemangleError::TrailingDataOnTypeInfoFunction("_x"),
+        ),
+        (
+            "__ti3Foo_x",
+            DemangleError::TrailingDataOnTypeInfoNode("_x"),
+        ),
+        ("f__FeX", DemangleError::TrailingDataAfterEllipsis("X")),
+        ("f__Fi_", DemangleError::TrailingDataAfterArgumentList("_")),
+        (
+            "_vt$3Foo_x",
+            DemangleError::VTableMissingDollarSeparator("_x"),
+        ),
+        (
+            "__opi_x__3Foo",
+            DemangleError::MalformedCastOperatorOverload("_x"),
+        ),
+        (
+            "foo__H1Zi_i_v_x",
+            DemangleError::TrailingDataAfterReturnTypeOfMalformedTemplateWithReturnType("_x"),
+        ),
+        // Virtual tables
+        ("_vt$", DemangleError::InvalidClassNameOnVirtualTable("")),
+        (
+            "_vt3Foo",
+            DemangleError::VTableMissingDollarSeparator("3Foo"),
+        ),
+        // Keyed globals
+        (
+            "_GLOBAL_$Z$foo",
+            DemangleError::InvalidGlobalSymKeyed("Z$foo"),
+        ),
+        ("_GLOBAL_$I", DemangleError::InvalidGlobalSymKeyed("I")),
+        (
+            "_GLOBAL_$Ifoo",
+            DemangleError::InvalidGlobalSymKeyed("Ifoo"),
+        ),
+        (
+            "_GLOBAL_$I_foo",
+            DemangleError::InvalidGlobalSymKeyed("I_foo"),
+        ),
+        // Thunks
+        (
+            "__thunk_x_foo__3Bar",
+            DemangleError::InvalidDeltaOnThunk("x_foo__3Bar"),
+        ),
+        ("__thunk_8", DemangleError::InvalidDeltaOnThunk("8")),
+        (
+            "__thunk_8_9x_foo__3Bar",
+            DemangleError::InvalidVcallOffsetOnThunk("8_9x_foo__3Bar"),
+        ),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, error) in CASES {
+        assert_eq!(demangle(mangled, &config), Err(error), "{mangled}");
+    }
+}
+
+#[test]
+fn test_demangle_type_name_errors() {
+    static CASES: [(&str, DemangleError); 4] = [
+        ("PCc", DemangleError::InvalidTypeName("PCc")),
+        ("6Wibblei", DemangleError::TrailingDataOnTypeName("i")),
+        ("Q23foo3Bar_", DemangleError::TrailingDataOnTypeName("_")),
+        ("t4List1Zix", DemangleError::TrailingDataOnTypeName("x")),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, error) in CASES {
+        assert_eq!(
+            demangle_type_name(mangled, &config),
+            Err(error),
+            "{mangled}"
+        );
+    }
+}
+
+#[test]
+fn test_demangle_error_kinds() {
+    use DemangleErrorKind::*;
+
+    static CASES: [(DemangleError, DemangleErrorKind); 96] = [
+        (DemangleError::NotMangled, NotMangled),
+        (DemangleError::NonAscii, NonAscii),
+        (
+            DemangleError::TrailingDataOnDestructor("x"),
+            MalformedSpecial,
+        ),
+        (
+            DemangleError::InvalidClassNameOnDestructor("x"),
+            MalformedName,
+        ),
+        (
+            DemangleError::InvalidClassNameOnConstructor("x"),
+            MalformedName,
+        ),
+        (
+            DemangleError::InvalidClassNameOnOperator("x"),
+            MalformedName,
+        ),
+        (DemangleError::InvalidClassNameOnMethod("x"), MalformedName),
+        (
+            DemangleError::InvalidClassNameOnVirtualTable("x"),
+            MalformedName,
+        ),
+        (
+            DemangleError::InvalidNamespaceOnNamespacedGlobal("x"),
+            MalformedName,
+        ),
+        (
+            DemangleError::InvalidCustomNameOnArgument("x"),
+            MalformedName,
+        ),
+        (
+            DemangleError::InvalidCustomNameOnNamespace("x"),
+            MalformedName,
+        ),
+        (
+            DemangleError::InvalidCustomNameOnTemplate("x"),
+            MalformedName,
+        ),
+        (
+            DemangleError::InvalidNamespaceOnTemplatedFunction("x"),
+            MalformedName,
+        ),
+        (
+            DemangleError::InvalidSymbolNameOnTemplateType("x"),
+            MalformedTemplate,
+        ),
+        (
+            DemangleError::InvalidClassNameOnMethodArgument("x"),
+            MalformedName,
+        ),
+        (DemangleError::UnknownType('x', "x"), MalformedArguments),
+        (
+            DemangleError::InvalidRepeatingArgument("x"),
+            MalformedArguments,
+        ),
+        (DemangleError::RanOutWhileDemanglingSpecial, Truncated),
+        (DemangleError::RanOutOfArguments, Truncated),
+        (
+            DemangleError::FoundDuplicatedPrevQualifierOnArgument("x", 'x'),
+            MalformedArguments,
+        ),
+        (DemangleError::InvalidSpecialMethod("x"), MalformedSpecial),
+        (
+            DemangleError::UnrecognizedSpecialMethod("x"),
+            MalformedSpecial,
+        ),
+        (
+            DemangleError::PrimitiveInsteadOfClass("x"),
+            MalformedArguments,
+        ),
+        (DemangleError::InvalidNamespaceCount("x"), MalformedName),
+        (DemangleError::InvalidLookbackCount("x"), MalformedArguments),
+        (
+            DemangleError::LookbackCountTooBig("x", 0),
+            MalformedArguments,
+        ),
+        (
+            DemangleError::InvalidTypeOnTypeInfoFunction("x"),
+            MalformedSpecial,
+        ),
+        (
+            DemangleError::TrailingDataOnTypeInfoFunction("x"),
+            MalformedSpecial,
+        ),
+        (
+            DemangleError::InvalidTypeOnTypeInfoNode("x"),
+            MalformedSpecial,
+        ),
+        (
+            DemangleError::TrailingDataOnTypeInfoNode("x"),
+            MalformedSpecial,
+        ),
+        (
+            DemangleError::TrailingDataAfterEllipsis("x"),
+            MalformedArguments,
+        ),
+        (
+            DemangleError::InvalidTypeValueForTemplated('x', "x"),
+            MalformedTemplate,
+        ),
+        (
+            DemangleError::InvalidValueForIntegralTemplated("x"),
+            MalformedTemplate,
+        ),
+        (
+            DemangleError::InvalidTemplatedPointerReferenceValue("x"),
+            MalformedTemplate,
+        ),
+        (
+            DemangleError::InvalidFunctionPointerTypeInTemplatedList("x", "x"),
+            MalformedTemplate,
+        ),
+        (
+            DemangleError::InvalidTemplatedNumberForCharacterValue("x"),
+            MalformedTemplate,
+        ),
+        (
+            DemangleError::InvalidTemplatedCharacterValue("x", 0),
+            MalformedTemplate,
+        ),
+        (
+            DemangleError::InvalidTemplatedBoolean("x"),
+            MalformedTemplate,
+        ),
+        (
+            DemangleError::VTableMissingDollarSeparator("x"),
+            MalformedSpecial,
+        ),
+        (
+            DemangleError::InvalidNamespacedGlobal("x", "x"),
+            MalformedSpecial,
+        ),
+        (
+            DemangleError::TrailingDataOnNamespacedGlobal("x"),
+            MalformedSpecial,
+        ),
+        (
+            DemangleError::MissingReturnTypeForFunctionPointer("x"),
+            MalformedArguments,
+        ),
+        (
+            DemangleError::InvalidReturnTypeForFunctionPointer("x"),
+            MalformedArguments,
+        ),
+        (DemangleError::InvalidGlobalSymKeyed("x"), MalformedSpecial),
+        (DemangleError::InvalidArraySize("x"), MalformedArguments),
+        (
+            DemangleError::MalformedArrayArgumment("x"),
+            MalformedArguments,
+        ),
+        (
+            DemangleError::PrevQualifiersInInvalidPostioniAtArrayArgument("x"),
+            MalformedArguments,
+        ),
+        (
+            DemangleError::MalformedCastOperatorOverload("x"),
+            MalformedSpecial,
+        ),
+        (DemangleError::InvalidTemplateCount("x"), MalformedTemplate),
+        (
+            DemangleError::InvalidTemplateReturnCount("x"),
+            MalformedTemplate,
+        ),
+        (
+            DemangleError::TemplateReturnCountIsZero("x"),
+            MalformedTemplate,
+        ),
+        (
+            DemangleError::MalformedTemplateWithReturnType("x"),
+            MalformedTemplate,
+        ),
+        (
+            DemangleError::InvalidValueForIndexOnXArgument("x"),
+            MalformedTemplate,
+        ),
+        (
+            DemangleError::InvalidValueForNumber1OnXArgument("x"),
+            MalformedTemplate,
+        ),
+        (
+            DemangleError::InvalidNumber1OnXArgument("x", 0),
+            MalformedTemplate,
+        ),
+        (
+            DemangleError::IndexTooBigForXArgument("x", 0),
+            MalformedTemplate,
+        ),
+        (
+            DemangleError::TrailingDataAfterArgumentList("x"),
+            MalformedArguments,
+        ),
+        (
+            DemangleError::MalformedTemplateWithReturnTypeMissingReturnType("x"),
+            MalformedTemplate,
+        ),
+        (
+            DemangleError::MalformedTemplateWithReturnTypeMissingMalformedReturnType("x"),
+            MalformedTemplate,
+        ),
+        (
+            DemangleError::TrailingDataAfterReturnTypeOfMalformedTemplateWithReturnType("x"),
+            MalformedTemplate,
+        ),
+        (
+            DemangleError::InvalidQualifierForMethodMemberArg("x"),
+            MalformedArguments,
+        ),
+        (
+            DemangleError::MissingFirstClassArgumentForMethodMemberArg("x"),
+            MalformedArguments,
+        ),
+        (
+            DemangleError::MethodPointerNotHavingAPointerFirst("x"),
+            MalformedArguments,
+        ),
+        (
+            DemangleError::MethodPointerMissingConstness("x"),
+            MalformedArguments,
+        ),
+        (
+            DemangleError::MethodPointerWrongClassName("x"),
+            MalformedArguments,
+        ),
+        (
+            DemangleError::MethodPointerClassNameAsArray("x"),
+            MalformedArguments,
+        ),
+        (
+            DemangleError::UnknownMethodMemberArgKind("x"),
+            MalformedArguments,
+        ),
+        (
+            DemangleError::MissingBitwidthForExtensionInteger("x"),
+            MalformedArguments,
+        ),
+        (
+            DemangleError::InvalidBitwidthForExtensionInteger("x", 0),
+            MalformedArguments,
+        ),
+        (
+            DemangleError::InvalidEnumNameForTemplatedValue("x"),
+            MalformedTemplate,
+        ),
+        (
+            DemangleError::MissingLookbackIndexForTemplatedValue("x"),
+            MalformedTemplate,
+        ),
+        (
+            DemangleError::MissingLookbackSecondDigitForTemplatedValue("x"),
+            MalformedTemplate,
+        ),
+        (
+            DemangleError::InvalidLookbackSecondDigitForTemplatedValue("x", 0),
+            MalformedTemplate,
+        ),
+        (
+            DemangleError::IndexTooBigForYArgument("x", 0),
+            MalformedTemplate,
+        ),
+        (
+            DemangleError::InvalidQualifierForObjectMemberArg("x"),
+            MalformedArguments,
+        ),
+        (
+            DemangleError::InvalidClassNameOnObjectMemberArgument("x"),
+            MalformedArguments,
+        ),
+        (
+            DemangleError::MissingTypeForObjectMemberPointer("x"),
+            MalformedArguments,
+        ),
+        (
+            DemangleError::InvalidTypeForObjectMemberPointer("x"),
+            MalformedArguments,
+        ),
+        (
+            DemangleError::MalformedTemplatedSpecializationInvalidNamespace("x"),
+            MalformedTemplate,
+        ),
+        (
+            DemangleError::TrailingDataAfterReturnTypeOfTemplatedSpecialization("x"),
+            MalformedTemplate,
+        ),
+        (
+            DemangleError::UnsupportedSymbolKindOnComponents("x"),
+            Unsupported,
+        ),
+        (DemangleError::InvalidDeltaOnThunk("x"), MalformedSpecial),
+        (
+            DemangleError::InvalidVcallOffsetOnThunk("x"),
+            MalformedSpecial,
+        ),
+        (
+            DemangleError::ConstructorVariantMarker("x"),
+            MalformedSpecial,
+        ),
+        (DemangleError::InvalidCharacter('x', 0), InvalidCharacter),
+        (DemangleError::ArrayLengthOverflow("x"), InternalLimit),
+        (DemangleError::RepeatCountTooLarge("x", 0), InternalLimit),
+        (DemangleError::TooManyArrayDimensions("x"), InternalLimit),
+        (DemangleError::LikelyTruncated("x", 0), Truncated),
+        (DemangleError::AllocationFailure, InternalLimit),
+        (
+            DemangleError::AllTemplateReadingsFailed("x"),
+            MalformedTemplate,
+        ),
+        (DemangleError::InvalidTypeName("x"), MalformedName),
+        (DemangleError::TrailingDataOnTypeName("x"), MalformedName),
+        (DemangleError::LookbackToEllipsis("x"), MalformedArguments),
+        (DemangleError::LookbackToVoid("x"), MalformedArguments),
+        (DemangleError::EmptyCustomName("x"), MalformedName),
+    ];
+
+    for (error, kind) in CASES {
+        assert_eq!(error.kind(), kind, "{error:?}");
+        assert_eq!(error.into_owned().kind(), kind, "{error:?}");
+    }
+}
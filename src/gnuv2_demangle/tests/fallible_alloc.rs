@@ -0,0 +1,82 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+#![cfg(feature = "fallible-alloc")]
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use gnuv2_demangle::{try_demangle, DemangleConfig, DemangleError};
+
+use pretty_assertions::assert_eq;
+
+/// Fails every allocation bigger than `LIMIT` bytes.
+struct FailingAllocator;
+
+static LIMIT: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+unsafe impl GlobalAlloc for FailingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if layout.size() > LIMIT.load(Ordering::SeqCst) {
+            return std::ptr::null_mut();
+        }
+        // SAFETY: Forwarded as is.
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: Forwarded as is.
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if new_size > LIMIT.load(Ordering::SeqCst) {
+            return std::ptr::null_mut();
+        }
+        // SAFETY: Forwarded as is.
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: FailingAllocator = FailingAllocator;
+
+// A single test, since the limit is shared with every thread.
+#[test]
+fn test_try_demangle_allocation_failure() {
+    let config = DemangleConfig::new();
+
+    // Long namespaces, so reserving the demangled namespaces goes past the
+    // limit.
+    let long_name = "a".repeat(4000);
+    let namespaced = format!("foo__Q23Bar4000{long_name}i");
+    // Many repeated arguments, so reserving the argument list goes past the
+    // limit.
+    let repeated = "foo__FiN200_0";
+
+    LIMIT.store(2048, Ordering::SeqCst);
+    let failures = [
+        try_demangle(&namespaced, &config),
+        try_demangle(repeated, &config),
+    ];
+    let small = try_demangle("SetText__5tNamePCc", &config);
+    LIMIT.store(usize::MAX, Ordering::SeqCst);
+
+    assert_eq!(
+        failures,
+        [
+            Err(DemangleError::AllocationFailure),
+            Err(DemangleError::AllocationFailure)
+        ]
+    );
+    assert_eq!(small.as_deref(), Ok("tName::SetText(char const *)"));
+
+    let demangled = try_demangle(&namespaced, &config).unwrap();
+    assert_eq!(demangled, format!("Bar::{long_name}::foo(int)"));
+    assert_eq!(
+        try_demangle(repeated, &config).map(|x| x.matches("int").count()),
+        Ok(201)
+    );
+}
@@ -0,0 +1,36 @@
+# SPDX-FileCopyrightText: © 2025 Decompollaborate
+# SPDX-License-Identifier: MIT OR Apache-2.0
+
+# Source of gnuv2_symbols.o, used by the demangle_elf example.
+# Rebuild with `as --32 -o gnuv2_symbols.o gnuv2_symbols.s`.
+
+    .text
+    .globl main
+main:
+    ret
+    .globl "SetText__5tNamePCc"
+"SetText__5tNamePCc":
+    ret
+    .globl "__5tNamePCc"
+"__5tNamePCc":
+    ret
+    .globl "Printf__7ConsolePce"
+"Printf__7ConsolePce":
+    ret
+    .globl "Insert__t4List1ZiRCi"
+"Insert__t4List1ZiRCi":
+    ret
+    .globl "__ls__FR7ostreamPCc"
+"__ls__FR7ostreamPCc":
+    ret
+    .globl "_GLOBAL_$I$SetText__5tNamePCc"
+"_GLOBAL_$I$SetText__5tNamePCc":
+    ret
+    .globl "foo__FiN31"
+"foo__FiN31":
+    ret
+
+    .data
+    .globl "_vt$5tName"
+"_vt$5tName":
+    .long 0
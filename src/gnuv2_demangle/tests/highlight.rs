@@ -0,0 +1,322 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+#![cfg(feature = "classify-output")]
+
+mod testutil;
+
+use gnuv2_demangle::{demangle, demangle_highlighted, DemangleConfig, TokenClass};
+
+use pretty_assertions::assert_eq;
+
+use testutil::{presets, MANGLED_LISTS};
+
+fn highlighted(sym: &str, config: &DemangleConfig) -> Vec<(String, TokenClass)> {
+    demangle_highlighted(sym, config).unwrap()
+}
+
+fn pieces(list: &[(&str, TokenClass)]) -> Vec<(String, TokenClass)> {
+    list.iter()
+        .map(|(text, class)| (text.to_string(), *class))
+        .collect()
+}
+
+#[test]
+fn test_highlighted_joins_to_demangled() {
+    let mut restyled = DemangleConfig::new_cfilt();
+    restyled.scope_separator = ".";
+    restyled.template_brackets = ('[', ']');
+    let mut truncated = DemangleConfig::new_g2dem();
+    truncated.max_output_len = Some(40);
+    let configs = presets()
+        .into_iter()
+        .chain([("restyled", restyled), ("truncated", truncated)]);
+
+    for (preset, config) in configs {
+        for (name, contents) in MANGLED_LISTS {
+            for sym in contents.lines() {
+                let highlighted = demangle_highlighted(sym, &config);
+                let joined = highlighted.map(|x| x.into_iter().map(|(text, _)| text).collect());
+                assert_eq!(joined, demangle(sym, &config), "{preset}/{name}: {sym}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_highlighted_operator() {
+    use TokenClass::*;
+
+    let config = DemangleConfig::new();
+
+    assert_eq!(
+        highlighted("__ls__FR7ostreamPCc", &config),
+        pieces(&[
+            ("operator<<", Operator),
+            ("(", Punctuation),
+            ("ostream", Type),
+            (" &, ", Punctuation),
+            ("char", Type),
+            (" ", Punctuation),
+            ("const", Keyword),
+            (" *)", Punctuation),
+        ])
+    );
+    assert_eq!(
+        highlighted("__lt__H1Zi_RCt4List1ZX01T0_b", &config),
+        pieces(&[
+            ("bool", Type),
+            (" ", Punctuation),
+            ("operator<", Operator),
+            (" <", Punctuation),
+            ("int", TemplateArg),
+            (">(", Punctuation),
+            ("List", Type),
+            ("<", Punctuation),
+            ("int", TemplateArg),
+            ("> ", Punctuation),
+            ("const", Keyword),
+            (" &, ", Punctuation),
+            ("List", Type),
+            ("<", Punctuation),
+            ("int", TemplateArg),
+            ("> ", Punctuation),
+            ("const", Keyword),
+            (" &)", Punctuation),
+        ])
+    );
+    assert_eq!(
+        highlighted("__opi__3Foo", &config),
+        pieces(&[
+            ("Foo", Scope),
+            ("::", Punctuation),
+            ("operator", Operator),
+            (" ", Punctuation),
+            ("int", Type),
+            ("(", Punctuation),
+            ("void", Type),
+            (")", Punctuation),
+        ])
+    );
+}
+
+#[test]
+fn test_highlighted_template() {
+    use TokenClass::*;
+
+    let config = DemangleConfig::new();
+
+    assert_eq!(
+        highlighted("Set__t4List1ZPFPc_iRCt4pair2ZiZi", &config),
+        pieces(&[
+            ("List", Scope),
+            ("<", Punctuation),
+            ("int", TemplateArg),
+            (" (*)(", Punctuation),
+            ("char", TemplateArg),
+            (" *)>::", Punctuation),
+            ("Set", Name),
+            ("(", Punctuation),
+            ("pair", Type),
+            ("<", Punctuation),
+            ("int", TemplateArg),
+            (", ", Punctuation),
+            ("int", TemplateArg),
+            ("> ", Punctuation),
+            ("const", Keyword),
+            (" &)", Punctuation),
+        ])
+    );
+    assert_eq!(
+        highlighted("Get__H1Zi_i_PFi_v", &config),
+        pieces(&[
+            ("void", Type),
+            (" (*", Punctuation),
+            ("Get", Name),
+            ("<", Punctuation),
+            ("int", TemplateArg),
+            (">(", Punctuation),
+            ("int", Type),
+            ("))(", Punctuation),
+            ("int", Type),
+            (")", Punctuation),
+        ])
+    );
+    assert_eq!(
+        highlighted(
+            "_Q43UTL3COMt7Factory3ZiZ11InputDeviceZ6UCrc32_9Prototype.mHead",
+            &config
+        ),
+        pieces(&[
+            ("UTL", Scope),
+            ("::", Punctuation),
+            ("COM", Scope),
+            ("::", Punctuation),
+            ("Factory", Scope),
+            ("<", Punctuation),
+            ("int", TemplateArg),
+            (", ", Punctuation),
+            ("InputDevice", TemplateArg),
+            (", ", Punctuation),
+            ("UCrc32", TemplateArg),
+            (">::", Punctuation),
+            ("Prototype", Scope),
+            ("::", Punctuation),
+            ("mHead", Name),
+        ])
+    );
+    assert_eq!(
+        highlighted("f__Ft4List1Zt4List1Zi", &config),
+        pieces(&[
+            ("f", Name),
+            ("(", Punctuation),
+            ("List", Type),
+            ("<", Punctuation),
+            ("List", TemplateArg),
+            ("<", Punctuation),
+            ("int", TemplateArg),
+            ("> >)", Punctuation),
+        ])
+    );
+
+    let mut restyled = DemangleConfig::new();
+    restyled.scope_separator = ".";
+    restyled.template_brackets = ('[', ']');
+    assert_eq!(
+        highlighted("Get__t3Foo1Zt4List1Zt4List1Zi", &restyled),
+        pieces(&[
+            ("Foo", Scope),
+            ("[", Punctuation),
+            ("List", TemplateArg),
+            ("[", Punctuation),
+            ("List", TemplateArg),
+            ("[", Punctuation),
+            ("int", TemplateArg),
+            ("]]].", Punctuation),
+            ("Get", Name),
+            ("(", Punctuation),
+            ("void", Type),
+            (")", Punctuation),
+        ])
+    );
+}
+
+#[test]
+fn test_highlighted_char_literal() {
+    use TokenClass::*;
+
+    assert_eq!(
+        highlighted("Get__t3Foo2c97w97", &DemangleConfig::new_g2dem()),
+        pieces(&[
+            ("Foo", Scope),
+            ("<", Punctuation),
+            ("'a'", Literal),
+            (", ", Punctuation),
+            ("L'a'", Literal),
+            (">::", Punctuation),
+            ("Get", Name),
+            ("(", Punctuation),
+            ("void", Type),
+            (")", Punctuation),
+        ])
+    );
+    assert_eq!(
+        highlighted("Get__t3Foo2c97w97", &DemangleConfig::new_cfilt()),
+        pieces(&[
+            ("Foo", Scope),
+            ("<", Punctuation),
+            ("'a'", Literal),
+            (", ", Punctuation),
+            ("'a'", Literal),
+            (">::", Punctuation),
+            ("Get", Name),
+            ("(", Punctuation),
+            ("void", Type),
+            (")", Punctuation),
+        ])
+    );
+}
+
+#[test]
+fn test_highlighted_special_symbols() {
+    use TokenClass::*;
+
+    let config = DemangleConfig::new();
+
+    assert_eq!(
+        highlighted("_vt$7Derived$4Base$8", &config),
+        pieces(&[
+            ("Derived", Scope),
+            ("::", Punctuation),
+            ("Base", Type),
+            (" ", Punctuation),
+            ("virtual", Keyword),
+            (" ", Punctuation),
+            ("table", Keyword),
+            (" (", Punctuation),
+            ("at", Keyword),
+            (" ", Punctuation),
+            ("offset", Keyword),
+            (" ", Punctuation),
+            ("8", Literal),
+            (")", Punctuation),
+        ])
+    );
+    let mut pretty = DemangleConfig::new();
+    pretty.pretty_anonymous_namespaces = true;
+    assert_eq!(
+        highlighted("Id__Q2_17_GLOBAL_$N$foo_cc3Bar", &pretty),
+        pieces(&[
+            ("(anonymous namespace)", Scope),
+            ("::", Punctuation),
+            ("Bar", Scope),
+            ("::", Punctuation),
+            ("Id", Name),
+            ("(", Punctuation),
+            ("void", Type),
+            (")", Punctuation),
+        ])
+    );
+    assert_eq!(
+        highlighted("foo__Fi.23", &config),
+        pieces(&[
+            ("foo", Name),
+            ("(", Punctuation),
+            ("int", Type),
+            (") [", Punctuation),
+            ("clone", Keyword),
+            (" .", Punctuation),
+            ("23", Literal),
+            ("]", Punctuation),
+        ])
+    );
+}
+
+#[test]
+fn test_highlighted_type_named_like_a_keyword() {
+    use TokenClass::*;
+
+    let config = DemangleConfig::new();
+
+    assert_eq!(
+        highlighted("f__FP5tableR4node", &config),
+        pieces(&[
+            ("f", Name),
+            ("(", Punctuation),
+            ("table", Type),
+            (" *, ", Punctuation),
+            ("node", Type),
+            (" &)", Punctuation),
+        ])
+    );
+    assert_eq!(
+        highlighted("_vt$2to", &config),
+        pieces(&[
+            ("to", Type),
+            (" ", Punctuation),
+            ("virtual", Keyword),
+            (" ", Punctuation),
+            ("table", Keyword),
+        ])
+    );
+}
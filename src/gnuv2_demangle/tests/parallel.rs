@@ -0,0 +1,29 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+#![cfg(feature = "parallel")]
+
+mod testutil;
+
+use gnuv2_demangle::{demangle, demangle_all, DemangleConfig};
+
+use pretty_assertions::assert_eq;
+
+use testutil::MANGLED_LISTS;
+
+#[test]
+fn test_demangle_all_matches_demangle() {
+    for config in [DemangleConfig::new_g2dem(), DemangleConfig::new_cfilt()] {
+        for (name, contents) in MANGLED_LISTS {
+            let syms: Vec<&str> = contents.lines().collect();
+            let sequential: Vec<_> = syms.iter().map(|sym| demangle(sym, &config)).collect();
+
+            assert_eq!(demangle_all(&syms, &config), sequential, "{name}");
+        }
+    }
+}
+
+#[test]
+fn test_demangle_all_empty() {
+    assert_eq!(demangle_all(&[], &DemangleConfig::new()), []);
+}
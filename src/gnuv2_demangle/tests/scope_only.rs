@@ -0,0 +1,130 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+//! Check `demangle_scope_only` against the full demangling of the mangled
+//! lists.
+
+mod testutil;
+
+use gnuv2_demangle::{
+    demangle, demangle_scope_only, DemangleConfig, DemangleError, ReturnTypeStyle,
+};
+
+use pretty_assertions::assert_eq;
+
+use testutil::MANGLED_LISTS;
+
+/// The return type of templated functions is emitted in front of the scope,
+/// so leave it out to compare them too.
+fn configs() -> [DemangleConfig; 3] {
+    let mut g2dem = DemangleConfig::new_g2dem();
+    g2dem.return_type_style = ReturnTypeStyle::Omit;
+    let mut cfilt = DemangleConfig::new_cfilt();
+    cfilt.return_type_style = ReturnTypeStyle::Omit;
+    let mut bare = g2dem;
+    bare.omit_template_args_in_scope = true;
+
+    [g2dem, cfilt, bare]
+}
+
+#[test]
+fn test_scope_only_matches_demangle() {
+    for config in configs() {
+        for (name, contents) in MANGLED_LISTS {
+            for sym in contents.lines() {
+                let Ok(demangled) = demangle(sym, &config) else {
+                    continue;
+                };
+
+                match demangle_scope_only(sym, &config) {
+                    Ok(Some(scope)) => assert!(
+                        demangled.starts_with(&format!("{scope}::")),
+                        "{name}: {sym}: {scope:?} is not the scope of {demangled:?}"
+                    ),
+                    // Free functions
+                    Ok(None) => assert!(
+                        !demangled.split(['(', '<']).next().unwrap().contains("::"),
+                        "{name}: {sym}: {demangled:?} has a scope"
+                    ),
+                    Err(DemangleError::UnsupportedSymbolKindOnComponents(s)) => {
+                        assert_eq!(s, sym);
+                    }
+                    Err(e) => panic!("{name}: {sym}: {e:?}, but demangled as {demangled:?}"),
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_scope_only() {
+    static CASES: [(&str, Option<&str>); 19] = [
+        // Methods
+        ("SetText__5tNamePCc", Some("tName")),
+        ("GetText__C5tName", Some("tName")),
+        (
+            "AddPair__Q33sim16CollisionManager4AreaPQ23sim6Object",
+            Some("sim::CollisionManager::Area"),
+        ),
+        ("Insert__t4List1Z5tNamei", Some("List<tName>")),
+        ("Insert__Q2t4List1Zi4NodeRCi", Some("List<int>::Node")),
+        // Constructors and destructors
+        ("__5tNamePCc", Some("tName")),
+        ("__t4List1Zi", Some("List<int>")),
+        ("_$_Q23foo3Bar", Some("foo::Bar")),
+        ("_._t4List1Zi", Some("List<int>")),
+        // Operators
+        ("__eq__C5tNameRC5tName", Some("tName")),
+        ("__opPCc__C5tName", Some("tName")),
+        ("__ls__FR7ostreamPCc", None),
+        // Templated functions
+        (
+            "Get__H1ZPCc_CQ26Attrib9AttributeUi_RCX01",
+            Some("Attrib::Attribute"),
+        ),
+        ("Swap__H1Zi_RX01T0_v", None),
+        (
+            "__H1ZPCc_t6vector2ZcZt9allocator1ZcX00X00",
+            Some("vector<char, allocator<char> >"),
+        ),
+        // Static members
+        ("_5Scene$gCurrentScene", Some("Scene")),
+        ("_Q23sim5World$sInstance", Some("sim::World")),
+        // Free functions
+        ("memcpy__FPvPCvUi", None),
+        ("Debug_Assert__FPcN20i", None),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, scope) in CASES {
+        assert_eq!(
+            demangle_scope_only(mangled, &config),
+            Ok(scope.map(String::from)),
+            "{mangled}"
+        );
+    }
+
+    // Only the head of the symbol is validated.
+    assert_eq!(
+        demangle_scope_only("SetText__5tNameP!", &config),
+        Err(DemangleError::InvalidCharacter('!', 16))
+    );
+    assert_eq!(
+        demangle_scope_only("SetText__5tNamePQ", &config),
+        Ok(Some(String::from("tName")))
+    );
+    assert_eq!(
+        demangle_scope_only("_vt$5tName", &config),
+        Err(DemangleError::UnsupportedSymbolKindOnComponents(
+            "_vt$5tName"
+        ))
+    );
+    assert_eq!(
+        demangle_scope_only("SetText__9tName", &config),
+        Err(DemangleError::LikelyTruncated("9tName", 4))
+    );
+    assert_eq!(
+        demangle_scope_only("memcpy", &config),
+        Err(DemangleError::NotMangled)
+    );
+}
@@ -4,7 +4,13 @@
 // Run with this command, then see the diff in a git diff client.
 // cargo insta test --accept
 
-use gnuv2_demangle::{demangle, DemangleConfig, DemangleError};
+mod testutil;
+
+use gnuv2_demangle::{
+    demangle, DemangleConfig, DemangleError, ReturnTypeStyle, TypePointerSpacing,
+};
+
+use testutil::MANGLED_LISTS;
 
 fn demangle_lines<'s>(
     contents: &'s str,
@@ -95,3 +101,79 @@ fn snapshot_mangled_list_most_wanted_improved() {
 
     insta::assert_debug_snapshot!(demangle_lines(contents, &config));
 }
+
+/// The templated functions of the lists, the only ones with a return type.
+fn templated_functions() -> String {
+    [
+        include_str!("mangled_lists/hit_and_run.txt"),
+        include_str!("mangled_lists/ty_july_first.txt"),
+    ]
+    .into_iter()
+    .flat_map(str::lines)
+    .filter(|line| line.contains("__H"))
+    .map(|line| format!("{line}\n"))
+    .collect()
+}
+
+#[test]
+fn snapshot_templated_functions_return_type_cfilt_leading() {
+    let contents = templated_functions();
+    let config = DemangleConfig::builder()
+        .return_type_style(ReturnTypeStyle::CfiltLeading)
+        .build();
+
+    insta::assert_debug_snapshot!(demangle_lines(&contents, &config));
+}
+
+#[test]
+fn snapshot_templated_functions_return_type_omit() {
+    let contents = templated_functions();
+    let config = DemangleConfig::builder()
+        .return_type_style(ReturnTypeStyle::Omit)
+        .build();
+
+    insta::assert_debug_snapshot!(demangle_lines(&contents, &config));
+}
+
+#[test]
+fn snapshot_templated_functions_return_type_trailing_arrow() {
+    let contents = templated_functions();
+    let config = DemangleConfig::builder()
+        .return_type_style(ReturnTypeStyle::TrailingArrow)
+        .build();
+
+    insta::assert_debug_snapshot!(demangle_lines(&contents, &config));
+}
+
+#[test]
+fn test_mangled_lists_spacing() {
+    let configs = [
+        DemangleConfig::new_cfilt(),
+        DemangleConfig::new_g2dem(),
+        DemangleConfig::builder()
+            .type_pointer_spacing(Some(TypePointerSpacing::AfterType))
+            .build(),
+        DemangleConfig::builder()
+            .type_pointer_spacing(Some(TypePointerSpacing::Compact))
+            .build(),
+    ];
+
+    for config in &configs {
+        for (_, contents) in MANGLED_LISTS {
+            for (line, demangled) in demangle_lines(contents, config) {
+                let Ok(demangled) = demangled else {
+                    continue;
+                };
+                assert!(
+                    !demangled.contains("  "),
+                    "Double space on `{line}`: `{demangled}`"
+                );
+                assert_eq!(
+                    demangled.trim(),
+                    demangled,
+                    "Stray space on `{line}`: `{demangled}`"
+                );
+            }
+        }
+    }
+}
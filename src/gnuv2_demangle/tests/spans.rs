@@ -0,0 +1,213 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+mod testutil;
+
+use gnuv2_demangle::{demangle, demangle_with_spans, DemangleConfig, Span, SpanKind};
+
+use pretty_assertions::assert_eq;
+
+use testutil::MANGLED_LISTS;
+
+fn spans(sym: &str) -> Vec<(SpanKind, &str, String)> {
+    let config = DemangleConfig::new();
+    let (_, spans) = demangle_with_spans(sym, &config).unwrap();
+
+    spans
+        .into_iter()
+        .map(|x| (x.kind, &sym[x.input], x.text))
+        .collect()
+}
+
+fn contains(outer: &Span, inner: &Span) -> bool {
+    outer.input != inner.input
+        && outer.input.start <= inner.input.start
+        && inner.input.end <= outer.input.end
+}
+
+/// The arguments of the symbol itself should follow each other, so a reading
+/// that was thrown away would show up as a gap or an overlap.
+#[test]
+fn test_spans_match_demangle() {
+    for config in [DemangleConfig::new_g2dem(), DemangleConfig::new_cfilt()] {
+        for (name, contents) in MANGLED_LISTS {
+            for sym in contents.lines() {
+                let Ok(demangled) = demangle(sym, &config) else {
+                    continue;
+                };
+                let (demangled_with_spans, spans) = demangle_with_spans(sym, &config).unwrap();
+                assert_eq!(demangled_with_spans, demangled, "{name}: {sym}");
+
+                let mut outermost: Vec<_> = spans
+                    .iter()
+                    .filter(|x| x.kind == SpanKind::Argument)
+                    .filter(|x| !spans.iter().any(|outer| contains(outer, x)))
+                    .map(|x| x.input.clone())
+                    .collect();
+                outermost.dedup();
+                for pair in outermost.windows(2) {
+                    let gap = &sym[pair[0].end..pair[1].start.max(pair[0].end)];
+                    assert!(
+                        pair[0].end <= pair[1].start && matches!(gap, "" | "_"),
+                        "{name}: {sym}: {pair:?}"
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_spans_method_with_repeats() {
+    assert_eq!(
+        spans("Set__3FooiN21PCcT3"),
+        [
+            (SpanKind::Scope, "3Foo", "Foo".to_string()),
+            (SpanKind::Argument, "i", "int".to_string()),
+            (SpanKind::Argument, "N21", "int".to_string()),
+            (SpanKind::Argument, "N21", "int".to_string()),
+            (SpanKind::Argument, "PCc", "char const *".to_string()),
+            (SpanKind::Argument, "T3", "int".to_string()),
+        ]
+    );
+    assert_eq!(
+        spans("Add__Q23sim9CollisionPQ23sim15CollisionObjectT1"),
+        [
+            (SpanKind::Scope, "3sim", "sim".to_string()),
+            (SpanKind::Scope, "9Collision", "Collision".to_string()),
+            (
+                SpanKind::Argument,
+                "PQ23sim15CollisionObject",
+                "sim::CollisionObject *".to_string()
+            ),
+            (
+                SpanKind::Argument,
+                "T1",
+                "sim::CollisionObject *".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_spans_nested_function_pointers() {
+    assert_eq!(
+        spans("f__FPFPFi_v_iT0"),
+        [
+            (
+                SpanKind::Argument,
+                "PFPFi_v_i",
+                "int (*)(void (*)(int))".to_string()
+            ),
+            (SpanKind::Argument, "PFi_v", "void (*)(int)".to_string()),
+            (SpanKind::Argument, "i", "int".to_string()),
+            (SpanKind::ReturnType, "v", "void".to_string()),
+            (SpanKind::ReturnType, "i", "int".to_string()),
+            (
+                SpanKind::Argument,
+                "T0",
+                "int (*)(void (*)(int))".to_string()
+            ),
+        ]
+    );
+    // Repeats inside the function pointer refer to its own arguments.
+    assert_eq!(
+        spans("f__FPFiiN21_v"),
+        [
+            (
+                SpanKind::Argument,
+                "PFiiN21_v",
+                "void (*)(int, int, int, int)".to_string()
+            ),
+            (SpanKind::Argument, "i", "int".to_string()),
+            (SpanKind::Argument, "i", "int".to_string()),
+            (SpanKind::Argument, "N21", "int".to_string()),
+            (SpanKind::Argument, "N21", "int".to_string()),
+            (SpanKind::ReturnType, "v", "void".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_spans_templates() {
+    assert_eq!(
+        spans("Insert__t4List1Z5tNameRCt4List1Z5tName"),
+        [
+            (SpanKind::Scope, "t4List1Z5tName", "List<tName>".to_string()),
+            (SpanKind::TemplateArgument, "Z5tName", "tName".to_string()),
+            (
+                SpanKind::Argument,
+                "RCt4List1Z5tName",
+                "List<tName> const &".to_string()
+            ),
+            (SpanKind::TemplateArgument, "Z5tName", "tName".to_string()),
+        ]
+    );
+    assert_eq!(
+        spans("foo__H1Zi_3BarPFX01_v_Pc"),
+        [
+            (SpanKind::TemplateArgument, "Zi", "int".to_string()),
+            (SpanKind::Scope, "3Bar", "Bar".to_string()),
+            (SpanKind::Argument, "PFX01_v", "void (*)(int)".to_string()),
+            (SpanKind::Argument, "X01", "int".to_string()),
+            (SpanKind::ReturnType, "v", "void".to_string()),
+            (SpanKind::ReturnType, "Pc", "char *".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_spans_discarded_readings() {
+    // The lookback is read as `T10` after `T1` followed by `0` fails, and
+    // only the spans of the second reading are kept.
+    let args = spans("a__FcT0cccccccccT10");
+    assert_eq!(args.len(), 12);
+    assert_eq!(args[11], (SpanKind::Argument, "T10", "char".to_string()));
+
+    // Tried as a function first, but it is a method.
+    assert_eq!(
+        spans("__Foo__3Bari"),
+        [
+            (SpanKind::Scope, "3Bar", "Bar".to_string()),
+            (SpanKind::Argument, "i", "int".to_string()),
+        ]
+    );
+
+    // The clone suffix is only stripped after the whole symbol fails.
+    assert_eq!(
+        spans("f__Fi.1"),
+        [(SpanKind::Argument, "i", "int".to_string())]
+    );
+}
+
+#[test]
+fn test_spans_vtable_chains() {
+    assert_eq!(
+        spans("_vt$3foo$Q23bar3baz$t7Wrapper1Zi"),
+        [
+            (SpanKind::Scope, "3foo", "foo".to_string()),
+            (SpanKind::Scope, "3bar", "bar".to_string()),
+            (SpanKind::Scope, "3baz", "baz".to_string()),
+            (SpanKind::Scope, "t7Wrapper1Zi", "Wrapper<int>".to_string()),
+            (SpanKind::TemplateArgument, "Zi", "int".to_string()),
+        ]
+    );
+    // A `Q` chain is split into its classes, like separate components.
+    assert_eq!(
+        spans("_vt$t11ChangeState1ZQ211CharacterAi4Loco$Q23bar3baz"),
+        [
+            (
+                SpanKind::Scope,
+                "t11ChangeState1ZQ211CharacterAi4Loco",
+                "ChangeState<CharacterAi::Loco>".to_string()
+            ),
+            (
+                SpanKind::TemplateArgument,
+                "ZQ211CharacterAi4Loco",
+                "CharacterAi::Loco".to_string()
+            ),
+            (SpanKind::Scope, "3bar", "bar".to_string()),
+            (SpanKind::Scope, "3baz", "baz".to_string()),
+        ]
+    );
+}
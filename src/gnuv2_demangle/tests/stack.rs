@@ -0,0 +1,88 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+//! Check that long symbols don't need a deeper stack than short ones, since
+//! the stack should only grow with how nested the symbol is.
+
+use std::thread;
+
+use gnuv2_demangle::{demangle, DemangleConfig};
+
+use pretty_assertions::assert_eq;
+
+const STACK_SIZE: usize = 128 * 1024;
+
+/// The arguments used by [`long_symbol`], in order.
+static ARGS: [(&str, &str); 5] = [
+    ("i", "int"),
+    // The first argument, since the class is the argument 0.
+    ("T1", "int"),
+    ("5Hello", "Hello"),
+    ("PCc", "char const *"),
+    ("RCQ23Foo3Bar", "Foo::Bar const &"),
+];
+
+/// Generate a method nested into `namespaces` namespaces and taking `args`
+/// arguments, returning the mangled symbol and what it demangles to.
+fn long_symbol(namespaces: usize, args: usize) -> (String, String) {
+    let mut mangled = format!("Method__Q_{namespaces}_");
+    let mut demangled = String::new();
+
+    for i in 0..namespaces {
+        let name = format!("ns{i}");
+        mangled += &format!("{}{name}", name.len());
+        demangled += &format!("{name}::");
+    }
+    demangled += "Method(";
+
+    for i in 0..args {
+        let (arg, demangled_arg) = ARGS[i % ARGS.len()];
+        if i != 0 {
+            demangled += ", ";
+        }
+        mangled += arg;
+        demangled += demangled_arg;
+    }
+    demangled += ")";
+
+    (mangled, demangled)
+}
+
+/// Run `f` in a thread with a small stack, which aborts the whole test run
+/// if it overflows.
+fn with_small_stack<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> T {
+    thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(f)
+        .unwrap()
+        .join()
+        .unwrap()
+}
+
+#[test]
+fn test_stack_long_symbols() {
+    for (namespaces, args) in [(1, 5), (500, 1), (1, 500), (500, 500), (2000, 2000)] {
+        let (mangled, demangled) = long_symbol(namespaces, args);
+
+        for config in [DemangleConfig::new_g2dem(), DemangleConfig::new_cfilt()] {
+            let mangled = mangled.clone();
+            let result = with_small_stack(move || demangle(&mangled, &config).map_err(|_| ()));
+            assert_eq!(result.as_deref(), Ok(demangled.as_str()));
+        }
+    }
+}
+
+#[test]
+fn test_stack_ambiguous_lookbacks() {
+    // Every `T11i` may either be `T1` followed by the class `i`, or `T11`
+    // followed by an `int`, which is tried first as long as the rest of the
+    // list can be parsed.
+    let mangled = format!("f__F{}{}", "i".repeat(12), "T11i".repeat(500));
+
+    let args = with_small_stack(move || {
+        demangle(&mangled, &DemangleConfig::new())
+            .map(|x| x.matches(", ").count() + 1)
+            .map_err(|_| ())
+    });
+    assert_eq!(args, Ok(12 + 500 * 2));
+}
@@ -1,7 +1,12 @@
 /* SPDX-FileCopyrightText: © 2025 Decompollaborate */
 /* SPDX-License-Identifier: MIT OR Apache-2.0 */
 
-use gnuv2_demangle::{demangle, DemangleConfig, DemangleError};
+use gnuv2_demangle::{
+    demangle, demangle_both, demangle_components, demangle_map_line, demangle_type_name,
+    demangle_with_info, equivalent, extract_mangled_token, features, Construct, DemangleConfig,
+    DemangleError, DemanglePartial, FeedResult, LeafKind, MapDemangler, MapWrapping,
+    OwnedDemangleError, ReturnTypeStyle, ScopeKind, TypePointerSpacing, VERSION,
+};
 
 use pretty_assertions::assert_eq;
 
@@ -174,6 +179,57 @@ fn test_demangle_operators() {
     }
 }
 
+#[test]
+fn test_demangle_conversion_operators_to_compound_types() {
+    static CASES: [(&str, &str, &str); 6] = [
+        (
+            "__opPFi_v__7Functor",
+            "Functor::operator void (*)(int)(void)",
+            "Functor::operator void (*)(int)(void)",
+        ),
+        (
+            "__opPFi_v__CQ23foo7Functor",
+            "foo::Functor::operator void (*)(int)(void) const",
+            "foo::Functor::operator void (*)(int)(void) const",
+        ),
+        (
+            "__opPM7FunctorFP7Functori_v__7Functor",
+            "Functor::operator void (Functor::*)(int)(void)",
+            "Functor::operator void (Functor::*)(int)(void)",
+        ),
+        (
+            "__opA3_i__6Holder",
+            "Holder::operator int [4](void)",
+            "Holder::operator int [3](void)",
+        ),
+        (
+            "__opRA3_i__6Holder",
+            "Holder::operator int (&)[4](void)",
+            "Holder::operator int (&)[3](void)",
+        ),
+        (
+            "__opRCA3_i__Q23foo6Holder",
+            "foo::Holder::operator int const (&)[4](void)",
+            "foo::Holder::operator int const (&)[3](void)",
+        ),
+    ];
+    let g2dem = DemangleConfig::new_g2dem();
+    let cfilt = DemangleConfig::new_cfilt();
+
+    for (mangled, demangled_g2dem, demangled_cfilt) in CASES {
+        assert_eq!(
+            Ok(demangled_g2dem),
+            demangle(mangled, &g2dem).as_deref(),
+            "{mangled}"
+        );
+        assert_eq!(
+            Ok(demangled_cfilt),
+            demangle(mangled, &cfilt).as_deref(),
+            "{mangled}"
+        );
+    }
+}
+
 #[test]
 fn test_demangle_new_delete() {
     static CASES: [(&str, &str); 6] = [
@@ -209,6 +265,66 @@ fn test_demangle_new_delete() {
     }
 }
 
+#[test]
+fn test_demangle_new_delete_scoped_owners() {
+    static CASES: [(&str, &str); 12] = [
+        (
+            "__nw__Q23foo3BarUiRC9Allocator",
+            "foo::Bar::operator new(unsigned int, Allocator const &)",
+        ),
+        (
+            "__dl__Q23foo3BarPvT1",
+            "foo::Bar::operator delete(void *, void *)",
+        ),
+        (
+            "__vn__Q23foo3BarUi",
+            "foo::Bar::operator new [](unsigned int)",
+        ),
+        (
+            "__vd__Q23foo3BarPvUi",
+            "foo::Bar::operator delete [](void *, unsigned int)",
+        ),
+        (
+            "__nw__t4List1ZiUiPv",
+            "List<int>::operator new(unsigned int, void *)",
+        ),
+        ("__dl__t4List1ZiPv", "List<int>::operator delete(void *)"),
+        (
+            "__vn__t4List1ZiUiPv",
+            "List<int>::operator new [](unsigned int, void *)",
+        ),
+        (
+            "__vd__t4List1ZiPvUi",
+            "List<int>::operator delete [](void *, unsigned int)",
+        ),
+        (
+            "__nw__Q23foot3Bar1ZiUi",
+            "foo::Bar<int>::operator new(unsigned int)",
+        ),
+        (
+            "__dl__Q23foot3Bar1ZiPvT1",
+            "foo::Bar<int>::operator delete(void *, void *)",
+        ),
+        // The repeat refers to the first argument, not to the implicit size.
+        (
+            "__dl__12AnimatedIconPvT1",
+            "AnimatedIcon::operator delete(void *, void *)",
+        ),
+        ("__nw__FUiPv", "operator new(unsigned int, void *)"),
+    ];
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(
+            Ok(demangled),
+            demangle(mangled, &DemangleConfig::new()).as_deref()
+        );
+        assert_eq!(
+            Ok(demangled),
+            demangle(mangled, &DemangleConfig::new_cfilt()).as_deref()
+        );
+    }
+}
+
 #[test]
 fn test_demangle_namespaced_function() {
     static CASES: [(&str, &str); 4] = [
@@ -514,7 +630,7 @@ fn test_demangle_templated_classes_with_numbers() {
         ),
         (
             "wrapper__H1Z4Node_Rt10SomeVector2ZX01R13TestAllocator17AllocatorInstanceX01_RCX01",
-            "Node const & wrapper<Node>(SomeVector<Node, AllocatorInstance> &, Node)",
+            "Node const &wrapper<Node>(SomeVector<Node, AllocatorInstance> &, Node)",
         ),
     ];
     let config = DemangleConfig::new();
@@ -544,9 +660,111 @@ fn test_demangle_vtable() {
     }
 }
 
+#[test]
+fn test_demangle_vtable_chains() {
+    // Every kind of component, in every order, for chains of two and three
+    // components.
+    static COMPONENTS: [(&str, &str); 3] = [
+        ("3foo", "foo"),
+        ("Q23bar3baz", "bar::baz"),
+        ("t7Wrapper1Zi", "Wrapper<int>"),
+    ];
+    let pairs = COMPONENTS
+        .iter()
+        .flat_map(|a| COMPONENTS.iter().map(move |b| vec![a, b]));
+    let triples = COMPONENTS.iter().flat_map(|a| {
+        COMPONENTS
+            .iter()
+            .flat_map(move |b| COMPONENTS.iter().map(move |c| vec![a, b, c]))
+    });
+
+    for config in [DemangleConfig::new(), DemangleConfig::new_cfilt()] {
+        for chain in pairs.clone().chain(triples.clone()) {
+            let demangled: Vec<_> = chain.iter().map(|(_, x)| *x).collect();
+            let demangled = format!("{} virtual table", demangled.join("::"));
+
+            for marker in ["$", "."] {
+                let mangled: String = chain.iter().map(|(x, _)| format!("{marker}{x}")).collect();
+                let mangled = format!("_vt{mangled}");
+
+                assert_eq!(
+                    demangle(&mangled, &config).as_deref(),
+                    Ok(demangled.as_str()),
+                    "{mangled}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_demangle_vtable_offsets() {
+    static CASES: [(&str, &str, Option<&str>); 8] = [
+        (
+            "_vt$7Derived$4Base$8",
+            "Derived::Base virtual table (at offset 8)",
+            None,
+        ),
+        (
+            "_vt$7Derived$4Base$16",
+            "Derived::Base virtual table (at offset 16)",
+            None,
+        ),
+        (
+            "_vt$7Derived$8",
+            "Derived virtual table (at offset 8)",
+            None,
+        ),
+        (
+            "_vt$Q23foo7Derived$t4Base1Zi$4",
+            "foo::Derived::Base<int> virtual table (at offset 4)",
+            None,
+        ),
+        (
+            "_8_vt$7Derived$4Base",
+            "Derived::Base virtual table (at offset 8)",
+            None,
+        ),
+        (
+            "_8_vt.7Derived",
+            "Derived virtual table (at offset 8)",
+            None,
+        ),
+        // Still a clone suffix.
+        (
+            "_vt.5tName.4",
+            "tName virtual table [clone .4]",
+            Some("tName virtual table"),
+        ),
+        // Not an offset, since it isn't the last component.
+        ("_vt$7Derived$8$4Base", "_vt$7Derived$8$4Base", None),
+    ];
+
+    for (mangled, demangled, demangled_cfilt) in CASES {
+        let config = DemangleConfig::new_g2dem();
+        assert_eq!(
+            demangle(mangled, &config).as_deref().unwrap_or(mangled),
+            demangled,
+            "{mangled}"
+        );
+
+        let config = DemangleConfig::new_cfilt();
+        assert_eq!(
+            demangle(mangled, &config).ok().as_deref(),
+            demangled_cfilt,
+            "{mangled}"
+        );
+    }
+
+    assert_eq!(
+        demangle("_8_vt$7Derived$4Base$8", &DemangleConfig::new_g2dem()),
+        Err(DemangleError::InvalidClassNameOnVirtualTable("8"))
+    );
+}
+
 #[test]
 fn test_demangle_namespaced_globals() {
-    static CASES: [(&str, &str); 3] = [
+    static CASES: [(&str, &str); 12] = [
         ("_9TrafficAI$LOOKAHEAD_MIN", "TrafficAI::LOOKAHEAD_MIN"),
         (
             "_Q45First6Second5Third6Fourth$global",
@@ -556,12 +774,32 @@ fn test_demangle_namespaced_globals() {
             "_Q75First6Second5Third6Fourth1A1B1C$funny",
             "First::Second::Third::Fourth::A::B::C::funny",
         ),
+        // Templated components.
+        ("_t3Foo1Zi$x", "Foo<int>::x"),
+        (
+            "_Q2t9Allocator1Zi4Pool$instance",
+            "Allocator<int>::Pool::instance",
+        ),
+        ("_Q23foot3Bar1Zi$x", "foo::Bar<int>::x"),
+        ("_Q3t3Foo1Zi3Midt3Bar1Zc$x", "Foo<int>::Mid::Bar<char>::x"),
+        ("_Q2t3Foo1Zt3Bar1Zi4Pool$x", "Foo<Bar<int> >::Pool::x"),
+        // The marker inside of names.
+        ("_Q23foo3Bar$a$b", "foo::Bar::a$b"),
+        ("_Q24a$bc3Bar$x", "a$bc::Bar::x"),
+        ("_7Foo$Bar$baz", "Foo$Bar::baz"),
+        ("_Q24a.bc3Bar.x.y", "a.bc::Bar::x.y"),
     ];
-    let config = DemangleConfig::new();
 
-    for (mangled, demangled) in CASES {
-        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+    for config in [DemangleConfig::new(), DemangleConfig::new_cfilt()] {
+        for (mangled, demangled) in CASES {
+            assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+        }
     }
+
+    assert_eq!(
+        Err(DemangleError::TrailingDataOnNamespacedGlobal("x$y")),
+        demangle("_Q23foo3Barx$y", &DemangleConfig::new())
+    );
 }
 
 #[test]
@@ -664,7 +902,9 @@ fn test_demangle_global_sym_keyed_frame_cfilt() {
         ),
         (
             "_GLOBAL_$F$cout",
-            Err(DemangleError::InvalidNamespaceOnNamespacedGlobal("GLOBAL_")),
+            Err(DemangleError::InvalidNamespaceOnNamespacedGlobal(
+                "GLOBAL_$F$cout",
+            )),
         ),
         (
             "_GLOBAL_$F$_un_link__9streambuf",
@@ -680,7 +920,9 @@ fn test_demangle_global_sym_keyed_frame_cfilt() {
         ),
         (
             "_GLOBAL_$F$_IO_stdin_",
-            Err(DemangleError::InvalidNamespaceOnNamespacedGlobal("GLOBAL_")),
+            Err(DemangleError::InvalidNamespaceOnNamespacedGlobal(
+                "GLOBAL_$F$_IO_stdin_",
+            )),
         ),
         (
             "_GLOBAL_$F$__8stdiobufP7__sFILE",
@@ -688,12 +930,16 @@ fn test_demangle_global_sym_keyed_frame_cfilt() {
         ),
         (
             "_GLOBAL_$F$__default_terminate",
-            Err(DemangleError::InvalidNamespaceOnNamespacedGlobal("GLOBAL_")),
+            Err(DemangleError::InvalidNamespaceOnNamespacedGlobal(
+                "GLOBAL_$F$__default_terminate",
+            )),
         ),
         ("_GLOBAL_$F$terminate__Fv", Ok("_GLOBAL_$F$terminate(void)")),
         (
             "_GLOBAL_$F$_$_9type_info",
-            Err(DemangleError::InvalidNamespaceOnNamespacedGlobal("GLOBAL_")),
+            Err(DemangleError::InvalidNamespaceOnNamespacedGlobal(
+                "GLOBAL_$F$_$_9type_info",
+            )),
         ),
         (
             "_GLOBAL_$F$before__C9type_infoRC9type_info",
@@ -770,6 +1016,223 @@ fn test_demangle_global_sym_keyed_frame_nocfilt() {
     }
 }
 
+#[test]
+fn test_demangle_global_sym_keyed_template_instantiations() {
+    static CASES: [(&str, &str, Result<&str, DemangleError<'_>>); 6] = [
+        (
+            "_GLOBAL_$T$gErrFileName",
+            "global template instantiations keyed to gErrFileName",
+            Err(DemangleError::InvalidNamespaceOnNamespacedGlobal(
+                "GLOBAL_$T$gErrFileName",
+            )),
+        ),
+        (
+            "_GLOBAL_$T$Insert__t4List1ZiRCi",
+            "global template instantiations keyed to List<int>::Insert(int const &)",
+            Ok("List<int>::_GLOBAL_$T$Insert(int const &)"),
+        ),
+        (
+            "_GLOBAL_$T$Swap__H1Zi_RX01T0_v",
+            "global template instantiations keyed to void Swap<int>(int &, int &)",
+            Ok("void _GLOBAL_$T$Swap<int>(int &, int &)"),
+        ),
+        (
+            "_GLOBAL_$T$terminate__Fv",
+            "global template instantiations keyed to terminate(void)",
+            Ok("_GLOBAL_$T$terminate(void)"),
+        ),
+        (
+            "_GLOBAL_$T$_vt$t4List1Zi",
+            "global template instantiations keyed to List<int> virtual table",
+            Err(DemangleError::InvalidNamespaceOnNamespacedGlobal(
+                "GLOBAL_$T$_vt$t4List1Zi",
+            )),
+        ),
+        (
+            "_GLOBAL_.T._vt.5tName",
+            "global template instantiations keyed to tName virtual table",
+            Err(DemangleError::InvalidNamespaceOnNamespacedGlobal(
+                "GLOBAL_.T._vt.5tName",
+            )),
+        ),
+    ];
+    let config = DemangleConfig::new_g2dem();
+    let cfilt = DemangleConfig::new_cfilt();
+
+    for (mangled, demangled, cfilt_demangled) in CASES {
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+        assert_eq!(
+            cfilt_demangled.as_deref(),
+            demangle(mangled, &cfilt).as_deref()
+        );
+    }
+}
+
+#[test]
+fn test_demangle_nested_global_sym_keyed() {
+    static CASES: [(&str, &str, &str); 6] = [
+        (
+            "_GLOBAL_$I$_GLOBAL_$D$gSomething",
+            "global constructors keyed to global destructors keyed to gSomething",
+            "global constructors keyed to _GLOBAL_$D$gSomething",
+        ),
+        (
+            "_GLOBAL_.I._GLOBAL_.D.gSomething",
+            "global constructors keyed to global destructors keyed to gSomething",
+            "global constructors keyed to _GLOBAL_.D.gSomething",
+        ),
+        (
+            "_GLOBAL_$D$_GLOBAL_$I$foo__Fi",
+            "global destructors keyed to global constructors keyed to foo(int)",
+            "global destructors keyed to _GLOBAL_$I$foo(int)",
+        ),
+        (
+            "_GLOBAL_.D._GLOBAL_.I.Id__3Foo",
+            "global destructors keyed to global constructors keyed to Foo::Id(void)",
+            "global destructors keyed to Foo::_GLOBAL_.I.Id(void)",
+        ),
+        // Only a single level of nesting.
+        (
+            "_GLOBAL_$I$_GLOBAL_$I$_GLOBAL_$I$gSomething",
+            "global constructors keyed to global constructors keyed to _GLOBAL_$I$gSomething",
+            "global constructors keyed to _GLOBAL_$I$_GLOBAL_$I$gSomething",
+        ),
+        // Not a known key.
+        (
+            "_GLOBAL_$I$_GLOBAL_$X$gSomething",
+            "global constructors keyed to _GLOBAL_$X$gSomething",
+            "global constructors keyed to _GLOBAL_$X$gSomething",
+        ),
+    ];
+
+    for (mangled, nested, passed_through) in CASES {
+        for preset in [DemangleConfig::new_g2dem(), DemangleConfig::new_cfilt()] {
+            assert_eq!(demangle(mangled, &preset).as_deref(), Ok(passed_through));
+
+            let mut config = preset;
+            config.demangle_nested_global_keyed_symbols = true;
+            assert_eq!(demangle(mangled, &config).as_deref(), Ok(nested));
+        }
+    }
+}
+
+#[test]
+fn test_demangle_global_sym_keyed_static_init_functions() {
+    static CASES: [(&str, &str, &str); 8] = [
+        (
+            "_GLOBAL_$I$__static_initialization_and_destruction_0_65535__Fii",
+            "static initialization and destruction (priority 65535) for global constructors",
+            "global constructors keyed to __static_initialization_and_destruction_0_65535(int, int)",
+        ),
+        (
+            "_GLOBAL_$D$__static_initialization_and_destruction_0_65535__Fii",
+            "static initialization and destruction (priority 65535) for global destructors",
+            "global destructors keyed to __static_initialization_and_destruction_0_65535(int, int)",
+        ),
+        (
+            "_GLOBAL_.I.__static_initialization_and_destruction_1_101__Fii",
+            "static initialization and destruction (priority 101) for global constructors",
+            "global constructors keyed to __static_initialization_and_destruction_1_101(int, int)",
+        ),
+        (
+            "_GLOBAL_$I$__static_initialization_and_destruction_0__Fii",
+            "static initialization and destruction for global constructors",
+            "global constructors keyed to __static_initialization_and_destruction_0(int, int)",
+        ),
+        (
+            "_GLOBAL_$D$__static_initialization_and_destruction_0__Fii",
+            "static initialization and destruction for global destructors",
+            "global destructors keyed to __static_initialization_and_destruction_0(int, int)",
+        ),
+        // Not the function g++ generates.
+        (
+            "_GLOBAL_$I$__static_initialization_and_destruction_0__Fi",
+            "global constructors keyed to __static_initialization_and_destruction_0(int)",
+            "global constructors keyed to __static_initialization_and_destruction_0(int)",
+        ),
+        (
+            "_GLOBAL_$I$__static_initialization_and_destruction_0_high__Fii",
+            "global constructors keyed to __static_initialization_and_destruction_0_high(int, int)",
+            "global constructors keyed to __static_initialization_and_destruction_0_high(int, int)",
+        ),
+        // Only described when keyed.
+        (
+            "__static_initialization_and_destruction_0_65535__Fii",
+            "__static_initialization_and_destruction_0_65535(int, int)",
+            "__static_initialization_and_destruction_0_65535(int, int)",
+        ),
+    ];
+
+    for (mangled, described, passed_through) in CASES {
+        for preset in [DemangleConfig::new_g2dem(), DemangleConfig::new_cfilt()] {
+            assert_eq!(demangle(mangled, &preset).as_deref(), Ok(passed_through));
+
+            let mut config = preset;
+            config.describe_static_init_functions = true;
+            assert_eq!(demangle(mangled, &config).as_deref(), Ok(described));
+        }
+    }
+}
+
+#[test]
+fn test_demangle_plain_c_lookalikes() {
+    type Demangled = Result<&'static str, DemangleError<'static>>;
+
+    static CASES: [(&str, Demangled, Demangled); 9] = [
+        (
+            "crc32__8_compute",
+            Err(DemangleError::NotMangled),
+            Ok("_compute::crc32(void)"),
+        ),
+        (
+            "obj__23_local",
+            Err(DemangleError::NotMangled),
+            Err(DemangleError::LikelyTruncated("23_local", 17)),
+        ),
+        (
+            "pthread_mutex_lock__compat",
+            Err(DemangleError::NotMangled),
+            Err(DemangleError::NotMangled),
+        ),
+        (
+            "crc32__8_computei",
+            Ok("_compute::crc32(int)"),
+            Ok("_compute::crc32(int)"),
+        ),
+        ("md5__4impl", Ok("impl::md5(void)"), Ok("impl::md5(void)")),
+        (
+            "init__7filebuf",
+            Ok("filebuf::init(void)"),
+            Ok("filebuf::init(void)"),
+        ),
+        (
+            "GetText__5tName",
+            Ok("tName::GetText(void)"),
+            Ok("tName::GetText(void)"),
+        ),
+        (
+            "SetText__5tNamePCc",
+            Ok("tName::SetText(char const *)"),
+            Ok("tName::SetText(char const *)"),
+        ),
+        (
+            "Reset__7_Buffer",
+            Ok("_Buffer::Reset(void)"),
+            Ok("_Buffer::Reset(void)"),
+        ),
+    ];
+    let config = DemangleConfig::new_g2dem();
+    let cfilt = DemangleConfig::new_cfilt();
+
+    for (mangled, demangled, cfilt_demangled) in CASES {
+        assert_eq!(demangled.as_deref(), demangle(mangled, &config).as_deref());
+        assert_eq!(
+            cfilt_demangled.as_deref(),
+            demangle(mangled, &cfilt).as_deref()
+        );
+    }
+}
+
 #[test]
 fn test_demangle_argument_array() {
     static CASES: [(&str, &str); 7] = [
@@ -808,6 +1271,66 @@ fn test_demangle_argument_array_fixed() {
     }
 }
 
+#[test]
+fn test_demangle_argument_array_zero_length_unfixed() {
+    static CASES: [(&str, &str); 4] = [
+        ("zero_array__FPA0_i", "zero_array(int (*)[0])"),
+        ("one_array__FPA1_i", "one_array(int (*)[1])"),
+        ("nested_array__FPA0_A5_i", "nested_array(int (*)[0][5])"),
+        (
+            "templated_array__Ft3Foo1ZPA0_A5_i",
+            "templated_array(Foo<int (*)[0][5]>)",
+        ),
+    ];
+    let mut config = DemangleConfig::new();
+    config.fix_array_length_arg = false;
+    config.fix_array_length_skip_zero = true;
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+    }
+}
+
+#[test]
+fn test_demangle_argument_array_zero_length_fixed() {
+    static CASES: [(&str, &str); 4] = [
+        ("zero_array__FPA0_i", "zero_array(int (*)[1])"),
+        ("one_array__FPA1_i", "one_array(int (*)[2])"),
+        ("nested_array__FPA0_A5_i", "nested_array(int (*)[1][6])"),
+        (
+            "templated_array__Ft3Foo1ZPA0_A5_i",
+            "templated_array(Foo<int (*)[1][6]>)",
+        ),
+    ];
+    let mut config = DemangleConfig::new();
+    config.fix_array_length_arg = true;
+    config.fix_array_length_skip_zero = false;
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+    }
+}
+
+#[test]
+fn test_demangle_argument_array_zero_length_skip_zero() {
+    static CASES: [(&str, &str); 4] = [
+        ("zero_array__FPA0_i", "zero_array(int (*)[0])"),
+        ("one_array__FPA1_i", "one_array(int (*)[2])"),
+        ("nested_array__FPA0_A5_i", "nested_array(int (*)[0][6])"),
+        (
+            "templated_array__Ft3Foo1ZPA0_A5_i",
+            "templated_array(Foo<int (*)[0][6]>)",
+        ),
+    ];
+    let mut config = DemangleConfig::new();
+    config.fix_array_length_arg = true;
+    config.fix_array_length_skip_zero = true;
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+    }
+}
+
 // TODO: rename "template_with_return_type" to "templated_function" or smth
 #[test]
 fn test_demangle_template_with_return_type() {
@@ -820,14 +1343,14 @@ fn test_demangle_template_with_return_type() {
         ("DoThing__H2ZQ35Other11CharacterAi12StateManagerZQ35Other11CharacterAi4Loco_Q25Other11CharacterAii_28some_return_with_underscores", "some_return_with_underscores Other::CharacterAi::DoThing<Other::CharacterAi::StateManager, Other::CharacterAi::Loco>(int)"),
         ("DoThing__H2ZQ35Other11CharacterAi12StateManagerZQ35Other11CharacterAi4Loco_Q25Other11CharacterAi_28some_return_with_underscores", "some_return_with_underscores Other::CharacterAi::DoThing<Other::CharacterAi::StateManager, Other::CharacterAi::Loco>()"),
 
-        ("find__H2ZP5tNameZ5tName_X01X01RCX11G26random_access_iterator_tag_X01", "tName * find<tName *, tName>(tName *, tName *, tName const &, random_access_iterator_tag)"),
+        ("find__H2ZP5tNameZ5tName_X01X01RCX11G26random_access_iterator_tag_X01", "tName *find<tName *, tName>(tName *, tName *, tName const &, random_access_iterator_tag)"),
         ("BlendPriorities__H1ZQ218RadicalMathLibrary6Vector_6choreoPCQ26choreot13BlendPriority1ZX01iRX01_b", "bool choreo::BlendPriorities<RadicalMathLibrary::Vector>(choreo::BlendPriority<RadicalMathLibrary::Vector> const *, int, RadicalMathLibrary::Vector &)"),
         ("SetState__H9ZQ35Other11CharacterAi4LocoZQ35Other11CharacterAi12StateManagerZiZiZiZiZiZQ213radPs2CdDrive14DirectoryEntryZQ35Other11CharacterAi4Loco_Q25Other11CharacterAiRX11X01X21X31X41X51X61X71X81_v", "void Other::CharacterAi::SetState<Other::CharacterAi::Loco, Other::CharacterAi::StateManager, int, int, int, int, int, radPs2CdDrive::DirectoryEntry, Other::CharacterAi::Loco>(Other::CharacterAi::StateManager &, Other::CharacterAi::Loco, int, int, int, int, int, radPs2CdDrive::DirectoryEntry, Other::CharacterAi::Loco)"),
         ("_M_range_insert__H1ZPC5tName_Gt6vector2Z5tNameZt7s2alloc1Z5tNameP5tNameX01X01G20forward_iterator_tag_v", "void _M_range_insert<tName const *>(vector<tName, s2alloc<tName> >, tName *, tName const *, tName const *, forward_iterator_tag)"),
         ("_M_range_insert__H1ZPC5tName_t6vector2Z5tNameZt7s2alloc1Z5tNameP5tNameX00X00G20forward_iterator_tag_v", "void vector<tName, s2alloc<tName> >::_M_range_insert<tName const *>(tName *, tName const *, tName const *, forward_iterator_tag)"),
         ("_M_range_insert__H1ZPC5tName_GQ223some_allocation_libraryt6vector2Z5tNameZt7s2alloc1Z5tNameP5tNameX01X01G20forward_iterator_tag_v", "void _M_range_insert<tName const *>(some_allocation_library::vector<tName, s2alloc<tName> >, tName *, tName const *, tName const *, forward_iterator_tag)"),
         ("_M_range_insert__H1ZPC5tName_Q223some_allocation_libraryt6vector2Z5tNameZt7s2alloc1Z5tNameP5tNameX00X00G20forward_iterator_tag_v", "void some_allocation_library::vector<tName, s2alloc<tName> >::_M_range_insert<tName const *>(tName *, tName const *, tName const *, forward_iterator_tag)"),
-        ("_M_range_insert__H1ZPC5tName_GQ223some_allocation_libraryt6vector2Z5tNameZt7s2alloc1Z5tNameP5tNameX01X01G20forward_iterator_tag_X01", "tName const * _M_range_insert<tName const *>(some_allocation_library::vector<tName, s2alloc<tName> >, tName *, tName const *, tName const *, forward_iterator_tag)"),
+        ("_M_range_insert__H1ZPC5tName_GQ223some_allocation_libraryt6vector2Z5tNameZt7s2alloc1Z5tNameP5tNameX01X01G20forward_iterator_tag_X01", "tName const *_M_range_insert<tName const *>(some_allocation_library::vector<tName, s2alloc<tName> >, tName *, tName const *, tName const *, forward_iterator_tag)"),
 
         ("indexof__H1Zf_PCX01T0_i", "int indexof<float>(float const *, float const *)"),
         ("indexof__H1Z11SGDMATERIAL_PCX01T0_i", "int indexof<SGDMATERIAL>(SGDMATERIAL const *, SGDMATERIAL const *)"),
@@ -883,6 +1406,72 @@ fn test_demangle_operator_on_templated() {
     }
 }
 
+#[test]
+fn test_demangle_operator_owner_lookbacks() {
+    // The owner is the argument 0, so `T0` must spell it the same as if it
+    // was written in full, template arguments included.
+    // The third column is the mangled owner, to spell it again instead of
+    // looking back to it.
+    static CASES: [(&str, &str, &str); 7] = [
+        ("__as__4PairRCT0", "Pair::operator=(Pair const &)", "4Pair"),
+        ("__as__Q23foo4PairRCT0", "foo::Pair::operator=(foo::Pair const &)", "Q23foo4Pair"),
+        ("__as__t4Pair2ZiZfRCT0", "Pair<int, float>::operator=(Pair<int, float> const &)", "t4Pair2ZiZf"),
+        ("__as__Q23foot4Pair2ZiZfRCT0", "foo::Pair<int, float>::operator=(foo::Pair<int, float> const &)", "Q23foot4Pair2ZiZf"),
+        ("__as__Q2t4Pair2ZiZf3FooRCT0", "Pair<int, float>::Foo::operator=(Pair<int, float>::Foo const &)", "Q2t4Pair2ZiZf3Foo"),
+        ("__eq__Ct4Pair2ZiZfRCT0", "Pair<int, float>::operator==(Pair<int, float> const &) const", "t4Pair2ZiZf"),
+        ("__cl__t4Pair2ZiZfRCT0N21", "Pair<int, float>::operator()(Pair<int, float> const &, Pair<int, float> const &, Pair<int, float> const &)", "t4Pair2ZiZf"),
+    ];
+
+    for config in [DemangleConfig::new_g2dem(), DemangleConfig::new_cfilt()] {
+        for (mangled, demangled, owner) in CASES {
+            assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+
+            // Same as spelling the owner again.
+            let respelled = mangled.replacen("RCT0", &format!("RC{owner}"), 1);
+            assert_eq!(Ok(demangled), demangle(&respelled, &config).as_deref());
+        }
+    }
+
+    // Leaving out the template arguments of the scope doesn't affect the
+    // arguments.
+    let config = DemangleConfig::builder()
+        .omit_template_args_in_scope(true)
+        .build();
+    assert_eq!(
+        demangle("__as__t4Pair2ZiZfRCT0", &config).as_deref(),
+        Ok("Pair::operator=(Pair<int, float> const &)")
+    );
+}
+
+#[test]
+fn test_demangle_operator_new_owner_lookbacks() {
+    // Placement arguments of `new` and `delete` referencing the owner.
+    static CASES: [(&str, &str); 8] = [
+        ("__nw__9AllocPoolUiT0", "AllocPool::operator new(unsigned int, AllocPool)"),
+        ("__dl__9AllocPoolPvT0", "AllocPool::operator delete(void *, AllocPool)"),
+        ("__nw__Q23foo9AllocPoolUiT0", "foo::AllocPool::operator new(unsigned int, foo::AllocPool)"),
+        ("__vd__Q23foo9AllocPoolPvRT0", "foo::AllocPool::operator delete [](void *, foo::AllocPool &)"),
+        ("__nw__t9AllocPool1Z8FMVEventUiT0", "AllocPool<FMVEvent>::operator new(unsigned int, AllocPool<FMVEvent>)"),
+        ("__vn__t9AllocPool1Z8FMVEventUiRT0", "AllocPool<FMVEvent>::operator new [](unsigned int, AllocPool<FMVEvent> &)"),
+        ("__dl__t9AllocPool1Z8FMVEventPvT0", "AllocPool<FMVEvent>::operator delete(void *, AllocPool<FMVEvent>)"),
+        ("__nw__Q23foot9AllocPool1Z8FMVEventUiPCT0", "foo::AllocPool<FMVEvent>::operator new(unsigned int, foo::AllocPool<FMVEvent> const *)"),
+    ];
+
+    for config in [DemangleConfig::new_g2dem(), DemangleConfig::new_cfilt()] {
+        for (mangled, demangled) in CASES {
+            assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+        }
+    }
+
+    let config = DemangleConfig::builder()
+        .omit_template_args_in_scope(true)
+        .build();
+    assert_eq!(
+        demangle("__nw__t9AllocPool1Z8FMVEventUiT0", &config).as_deref(),
+        Ok("AllocPool::operator new(unsigned int, AllocPool<FMVEvent>)")
+    );
+}
+
 #[test]
 fn test_demangle_method_as_argument_() {
     // Code to generate first entry:
@@ -1035,6 +1624,22 @@ fn test_demangle_same_sym_but_different_mangling() {
     }
 }
 
+#[test]
+fn test_demangle_void_lookbacks_in_templates() {
+    // Unlike arguments, template arguments may be `void` more than once.
+    static CASES: [(&str, &str); 3] = [
+        ("f__Ft3Foo2ZvZT0", "f(Foo<void, void>)"),
+        ("f__Ft3Foo2ZvZN10", "f(Foo<void, void>)"),
+        ("f__Ft3Foo2ZvZPFT0_v", "f(Foo<void, void (*)(void)>)"),
+    ];
+
+    for config in [DemangleConfig::new_g2dem(), DemangleConfig::new_cfilt()] {
+        for (mangled, demangled) in CASES {
+            assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+        }
+    }
+}
+
 #[test]
 fn test_demangle_128bits_integers_cfilt() {
     static CASES: [(&str, &str); 2] = [
@@ -1070,32 +1675,381 @@ fn test_demangle_128bits_integers_fix() {
 }
 
 #[test]
-fn test_demangle_template_with_enum_value() {
-    // First entry can be generated with
-    /*
-    enum G3DTRANSFORMSTATETYPE {
-        Number0
-    };
-    template <G3DTRANSFORMSTATETYPE N>
-    class CAutoTransform {
-    public:
-        void Pop() {}
-    };
-    void test(CAutoTransform<Number0> & a) {
-        a.Pop();
-    }
-    */
-    static CASES: [(&str, &str); 5] = [
+fn test_demangle_128bits_integers_nested() {
+    // (mangled, fix_extension_int = false, fix_extension_int = true)
+    static CASES: [(&str, &str, &str); 14] = [
         (
-            "Pop__t14CAutoTransform121G3DTRANSFORMSTATETYPE0",
-            "CAutoTransform<0>::Pop(void)",
+            "register_cb__FPFUI80_v",
+            "register_cb(void (*)(unsigned int128_t))",
+            "register_cb(void (*)(__uint128_t))",
         ),
         (
-            "__tft14CAutoTransform121G3DTRANSFORMSTATETYPE0",
-            "CAutoTransform<0> type_info function",
+            "register_cb__FPFI80_v",
+            "register_cb(void (*)(int128_t))",
+            "register_cb(void (*)(__int128_t))",
         ),
         (
-            "__tit14CAutoTransform121G3DTRANSFORMSTATETYPE0",
+            "get_cb__FPFv_UI80",
+            "get_cb(unsigned int128_t (*)(void))",
+            "get_cb(__uint128_t (*)(void))",
+        ),
+        (
+            "chain__FPFPFUI80_v_v",
+            "chain(void (*)(void (*)(unsigned int128_t)))",
+            "chain(void (*)(void (*)(__uint128_t)))",
+        ),
+        (
+            "map__FPFUI80_UI80",
+            "map(unsigned int128_t (*)(unsigned int128_t))",
+            "map(__uint128_t (*)(__uint128_t))",
+        ),
+        (
+            "visit__FM3FooFP3FooUI80_v",
+            "visit(void (Foo::)(unsigned int128_t))",
+            "visit(void (Foo::)(__uint128_t))",
+        ),
+        (
+            "twice__FUI80T0",
+            "twice(unsigned int128_t, unsigned int128_t)",
+            "twice(__uint128_t, __uint128_t)",
+        ),
+        (
+            "ref__FPCUI80",
+            "ref(unsigned int128_t const *)",
+            "ref(__uint128_t const *)",
+        ),
+        (
+            "signed_128__FSI80",
+            "signed_128(signed int128_t)",
+            "signed_128(__int128_t)",
+        ),
+        (
+            "f__t3Foo1ZUI80",
+            "Foo<unsigned int128_t>::f(void)",
+            "Foo<__uint128_t>::f(void)",
+        ),
+        (
+            "f__t3Foo1ZPFUI80_v",
+            "Foo<void (*)(unsigned int128_t)>::f(void)",
+            "Foo<void (*)(__uint128_t)>::f(void)",
+        ),
+        (
+            "Swap__H1ZUI80_RX01_X01",
+            "unsigned int128_t Swap<unsigned int128_t>(unsigned int128_t &)",
+            "__uint128_t Swap<__uint128_t>(__uint128_t &)",
+        ),
+        (
+            "__tfUI80",
+            "unsigned int128_t type_info function",
+            "__uint128_t type_info function",
+        ),
+        (
+            "__tiPFUI80_v",
+            "void (*)(unsigned int128_t) type_info node",
+            "void (*)(__uint128_t) type_info node",
+        ),
+    ];
+    let mut cfilt = DemangleConfig::new();
+    cfilt.fix_extension_int = false;
+    let mut fix = DemangleConfig::new();
+    fix.fix_extension_int = true;
+
+    for (mangled, cfilt_demangled, fix_demangled) in CASES {
+        assert_eq!(Ok(cfilt_demangled), demangle(mangled, &cfilt).as_deref());
+        assert_eq!(Ok(fix_demangled), demangle(mangled, &fix).as_deref());
+    }
+}
+
+#[test]
+fn test_demangle_extension_integers_widths() {
+    // (mangled, fix_extension_int = false, fix_extension_int = true)
+    static CASES: [(&str, &str, &str); 12] = [
+        ("f__FI08", "f(int8_t)", "f(int8_t)"),
+        ("f__FUI08", "f(unsigned int8_t)", "f(uint8_t)"),
+        ("f__FPI10", "f(int16_t *)", "f(int16_t *)"),
+        ("f__FPUI10", "f(unsigned int16_t *)", "f(uint16_t *)"),
+        ("f__FRCI20", "f(int32_t const &)", "f(int32_t const &)"),
+        (
+            "f__FRCUI20",
+            "f(unsigned int32_t const &)",
+            "f(uint32_t const &)",
+        ),
+        ("f__FSI40", "f(signed int64_t)", "f(int64_t)"),
+        (
+            "f__FUI40T0",
+            "f(unsigned int64_t, unsigned int64_t)",
+            "f(uint64_t, uint64_t)",
+        ),
+        (
+            "f__t3Foo1ZI40",
+            "Foo<int64_t>::f(void)",
+            "Foo<int64_t>::f(void)",
+        ),
+        (
+            "f__t3Foo1ZUI40",
+            "Foo<unsigned int64_t>::f(void)",
+            "Foo<uint64_t>::f(void)",
+        ),
+        (
+            "f__t3Foo1ZPCUI20",
+            "Foo<unsigned int32_t const *>::f(void)",
+            "Foo<uint32_t const *>::f(void)",
+        ),
+        (
+            "f__t3Foo1ZRUI80",
+            "Foo<unsigned int128_t &>::f(void)",
+            "Foo<__uint128_t &>::f(void)",
+        ),
+    ];
+    let mut cfilt = DemangleConfig::new();
+    cfilt.fix_extension_int = false;
+    let mut fix = DemangleConfig::new();
+    fix.fix_extension_int = true;
+
+    for (mangled, cfilt_demangled, fix_demangled) in CASES {
+        assert_eq!(Ok(cfilt_demangled), demangle(mangled, &cfilt).as_deref());
+        assert_eq!(Ok(fix_demangled), demangle(mangled, &fix).as_deref());
+    }
+
+    for config in [cfilt, fix] {
+        assert_eq!(
+            demangle("f__FI18", &config),
+            Err(DemangleError::InvalidBitwidthForExtensionInteger("I18", 24))
+        );
+        assert_eq!(
+            demangle("f__FUI04", &config),
+            Err(DemangleError::InvalidBitwidthForExtensionInteger("I04", 4))
+        );
+    }
+}
+
+#[test]
+fn test_demangle_strip_top_level_const_on_value_args() {
+    // (mangled, kept, stripped)
+    static CASES: [(&str, &str, &str); 12] = [
+        ("f__FC3Foo", "f(Foo const)", "f(Foo)"),
+        ("f__FPC3Foo", "f(Foo const *)", "f(Foo const *)"),
+        ("f__FC3FooT0", "f(Foo const, Foo const)", "f(Foo, Foo)"),
+        (
+            "f__FC3FooN20",
+            "f(Foo const, Foo const, Foo const)",
+            "f(Foo, Foo, Foo)",
+        ),
+        ("f__FRC3Foo", "f(Foo const &)", "f(Foo const &)"),
+        ("f__FCP3Foo", "f(Foo *const)", "f(Foo *const)"),
+        ("f__FCi", "f(int const)", "f(int)"),
+        ("f__FCUi", "f(unsigned int const)", "f(unsigned int)"),
+        ("f__FCVi", "f(int const volatile)", "f(int volatile)"),
+        ("f__FCQ23Foo3Bar", "f(Foo::Bar const)", "f(Foo::Bar)"),
+        (
+            "f__FPFC3Foo_v",
+            "f(void (*)(Foo const))",
+            "f(void (*)(Foo))",
+        ),
+        (
+            "f__t3Box1ZC3FooC3Foo",
+            "Box<Foo const>::f(Foo const)",
+            "Box<Foo const>::f(Foo)",
+        ),
+    ];
+    let mut kept = DemangleConfig::new();
+    kept.strip_top_level_const_on_value_args = false;
+    let mut stripped = DemangleConfig::new();
+    stripped.strip_top_level_const_on_value_args = true;
+
+    for (mangled, kept_demangled, stripped_demangled) in CASES {
+        assert_eq!(Ok(kept_demangled), demangle(mangled, &kept).as_deref());
+        assert_eq!(
+            Ok(stripped_demangled),
+            demangle(mangled, &stripped).as_deref()
+        );
+    }
+}
+
+#[test]
+fn test_demangle_namespace_digit_separators() {
+    // Some compilers emit an `_` between a namespace component ending in a
+    // digit and the length of the next one.
+    static CASES: [(&str, Result<&str, DemangleError<'_>>); 16] = [
+        (
+            "CreateRoadBlock__12AICopManagerP8IPursuitiP8IVehiclePQ43UTL11Collectionst11ListableSet4Z8IVehiclei10Z12eVehicleListUi10_4List",
+            Ok("AICopManager::CreateRoadBlock(IPursuit *, int, IVehicle *, UTL::Collections::ListableSet<IVehicle, 10, eVehicleList, 10>::List *)"),
+        ),
+        (
+            "Get__Q33UTLt11ListableSet2Z8IVehicleUi10_4List5Entry",
+            Ok("UTL::ListableSet<IVehicle, 10>::List::Get(Entry)"),
+        ),
+        ("Get__Q2t3Foo1i7_3Bar", Ok("Foo<7>::Bar::Get(void)")),
+        (
+            "_Q43UTL3COMt7Factory3ZiZ11InputDeviceZ6UCrc32_9Prototype.mHead",
+            Ok("UTL::COM::Factory<int, InputDevice, UCrc32>::Prototype::mHead"),
+        ),
+        (
+            "_._Q313FEPlayerCarDB42GetTotalNumInfractions__13FEPlayerCarDBb.0_19TotalNumInfractions",
+            Ok("FEPlayerCarDB::GetTotalNumInfractions__13FEPlayerCarDBb.0::TotalNumInfractions::~TotalNumInfractions(void)"),
+        ),
+        ("Get__Q24Vec3_4List", Ok("Vec3::List::Get(void)")),
+        ("Get__Q24Vec34List", Ok("Vec3::List::Get(void)")),
+        (
+            "Get__Q_10_1a1b1c1d1e2f12g21h2i31j",
+            Ok("a::b::c::d::e::f1::g2::h::i3::j::Get(void)"),
+        ),
+        (
+            "Get__Q_10_1a1b1c1d1e2f1_2g21h2i3_1j",
+            Ok("a::b::c::d::e::f1::g2::h::i3::j::Get(void)"),
+        ),
+        // Only after a digit, and only a single one.
+        (
+            "Get__Q23Foo_3Bar",
+            Err(DemangleError::InvalidCustomNameOnNamespace("_3Bar")),
+        ),
+        (
+            "Get__Q2t3Foo1Zi_3Bar",
+            Err(DemangleError::InvalidCustomNameOnNamespace("_3Bar")),
+        ),
+        (
+            "Get__Q2t3Foo1i10__4List",
+            Err(DemangleError::InvalidCustomNameOnNamespace("__4List")),
+        ),
+        (
+            "Get__Q_10_1a1b1c1d1e2f1__2g21h2i31j",
+            Err(DemangleError::InvalidCustomNameOnNamespace("__2g21h2i31j")),
+        ),
+        // Only before the digits of a length.
+        (
+            "Get__Q2t3Foo1i10_t3Bar1i2",
+            Err(DemangleError::InvalidCustomNameOnNamespace("_t3Bar1i2")),
+        ),
+        // Not after the last component, the `_` belongs to what follows it.
+        (
+            "Get__H11_ZiZiZiZiZiZiZiZiZiZiZ4Vec3_Q24List4Vec3X_10_0_v",
+            Ok("void List::Vec3::Get<int, int, int, int, int, int, int, int, int, int, Vec3>(Vec3)"),
+        ),
+        (
+            "Get__H11_ZiZiZiZiZiZiZiZiZiZiZ4Vec3_Q24List4Vec3_X_10_0_v",
+            Ok("void Vec3 List::Vec3::Get<int, int, int, int, int, int, int, int, int, int, Vec3>()"),
+        ),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(
+            demangle(mangled, &config),
+            demangled.map(String::from),
+            "{mangled}"
+        );
+    }
+
+    let components = demangle_components("Get__Q2t3Foo1i7_3Bar", &config).unwrap();
+    let scope: Vec<_> = components.scope().iter().map(|x| x.name()).collect();
+    assert_eq!(scope, ["Foo", "Bar"]);
+    assert_eq!(
+        demangle_components("Get__Q23Foo_3Bar", &config),
+        Err(DemangleError::InvalidCustomNameOnNamespace("_3Bar"))
+    );
+}
+
+#[test]
+fn test_demangle_pretty_anonymous_namespaces() {
+    // (mangled, generated, pretty)
+    static CASES: [(&str, &str, &str); 10] = [
+        // First component
+        (
+            "Id__Q2_17_GLOBAL_.N.foo_cc3Bar",
+            "_GLOBAL_.N.foo_cc::Bar::Id(void)",
+            "(anonymous namespace)::Bar::Id(void)",
+        ),
+        (
+            "Id__Q2_17_GLOBAL_$N$foo_cc3Bar",
+            "_GLOBAL_$N$foo_cc::Bar::Id(void)",
+            "(anonymous namespace)::Bar::Id(void)",
+        ),
+        (
+            "Id__Q210__N_foo_cc3Bar",
+            "__N_foo_cc::Bar::Id(void)",
+            "(anonymous namespace)::Bar::Id(void)",
+        ),
+        // Middle component
+        (
+            "Id__Q33gui17_GLOBAL_.N.foo_cc3Bar",
+            "gui::_GLOBAL_.N.foo_cc::Bar::Id(void)",
+            "gui::(anonymous namespace)::Bar::Id(void)",
+        ),
+        // Last component
+        (
+            "Id__Q23gui17_GLOBAL_.N.foo_cc",
+            "gui::_GLOBAL_.N.foo_cc::Id(void)",
+            "gui::(anonymous namespace)::Id(void)",
+        ),
+        // Argument types
+        (
+            "f__FQ2_17_GLOBAL_.N.foo_cc3Bar",
+            "f(_GLOBAL_.N.foo_cc::Bar)",
+            "f((anonymous namespace)::Bar)",
+        ),
+        (
+            "f__FRCQ33gui17_GLOBAL_.N.foo_cc3BarT0",
+            "f(gui::_GLOBAL_.N.foo_cc::Bar const &, gui::_GLOBAL_.N.foo_cc::Bar const &)",
+            "f(gui::(anonymous namespace)::Bar const &, gui::(anonymous namespace)::Bar const &)",
+        ),
+        (
+            "f__Ft4List1ZQ2_17_GLOBAL_.N.foo_cc3Bar",
+            "f(List<_GLOBAL_.N.foo_cc::Bar>)",
+            "f(List<(anonymous namespace)::Bar>)",
+        ),
+        // Not generated names
+        (
+            "Id__Q28_GLOBAL_3Bar",
+            "_GLOBAL_::Bar::Id(void)",
+            "_GLOBAL_::Bar::Id(void)",
+        ),
+        (
+            "Id__Q2_17_GLOBAL_.N$foo_cc3Bar",
+            "_GLOBAL_.N$foo_cc::Bar::Id(void)",
+            "_GLOBAL_.N$foo_cc::Bar::Id(void)",
+        ),
+    ];
+    let mut generated = DemangleConfig::new();
+    generated.pretty_anonymous_namespaces = false;
+    let mut pretty = DemangleConfig::new();
+    pretty.pretty_anonymous_namespaces = true;
+
+    for (mangled, generated_demangled, pretty_demangled) in CASES {
+        assert_eq!(
+            Ok(generated_demangled),
+            demangle(mangled, &generated).as_deref()
+        );
+        assert_eq!(Ok(pretty_demangled), demangle(mangled, &pretty).as_deref());
+    }
+}
+
+#[test]
+fn test_demangle_template_with_enum_value() {
+    // First entry can be generated with
+    /*
+    enum G3DTRANSFORMSTATETYPE {
+        Number0
+    };
+    template <G3DTRANSFORMSTATETYPE N>
+    class CAutoTransform {
+    public:
+        void Pop() {}
+    };
+    void test(CAutoTransform<Number0> & a) {
+        a.Pop();
+    }
+    */
+    static CASES: [(&str, &str); 5] = [
+        (
+            "Pop__t14CAutoTransform121G3DTRANSFORMSTATETYPE0",
+            "CAutoTransform<0>::Pop(void)",
+        ),
+        (
+            "__tft14CAutoTransform121G3DTRANSFORMSTATETYPE0",
+            "CAutoTransform<0> type_info function",
+        ),
+        (
+            "__tit14CAutoTransform121G3DTRANSFORMSTATETYPE0",
             "CAutoTransform<0> type_info node",
         ),
         (
@@ -1109,6 +2063,48 @@ fn test_demangle_template_with_enum_value() {
     ];
     let mut config = DemangleConfig::new();
     config.fix_extension_int = true;
+    config.emit_enum_casts_in_templates = false;
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+    }
+}
+
+#[test]
+fn test_demangle_template_with_enum_value_casts() {
+    static CASES: [(&str, &str); 7] = [
+        (
+            "Pop__t14CAutoTransform121G3DTRANSFORMSTATETYPE0",
+            "CAutoTransform<(G3DTRANSFORMSTATETYPE)0>::Pop(void)",
+        ),
+        (
+            "__tft14CAutoTransform121G3DTRANSFORMSTATETYPE0",
+            "CAutoTransform<(G3DTRANSFORMSTATETYPE)0> type_info function",
+        ),
+        (
+            "__tit14CAutoTransform121G3DTRANSFORMSTATETYPE0",
+            "CAutoTransform<(G3DTRANSFORMSTATETYPE)0> type_info node",
+        ),
+        (
+            "_$_t14CAutoTransform121G3DTRANSFORMSTATETYPE0",
+            "CAutoTransform<(G3DTRANSFORMSTATETYPE)0>::~CAutoTransform(void)",
+        ),
+        (
+            "_vt$t14CAutoTransform121G3DTRANSFORMSTATETYPE0",
+            "CAutoTransform<(G3DTRANSFORMSTATETYPE)0> virtual table",
+        ),
+        (
+            "Pop__t14CAutoTransform121G3DTRANSFORMSTATETYPEm3",
+            "CAutoTransform<(G3DTRANSFORMSTATETYPE)-3>::Pop(void)",
+        ),
+        (
+            "Push__5StackRCt14CAutoTransform121G3DTRANSFORMSTATETYPE2",
+            "Stack::Push(CAutoTransform<(G3DTRANSFORMSTATETYPE)2> const &)",
+        ),
+    ];
+    let mut config = DemangleConfig::new();
+    config.fix_extension_int = true;
+    config.emit_enum_casts_in_templates = true;
 
     for (mangled, demangled) in CASES {
         assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
@@ -1164,7 +2160,7 @@ fn test_demangle_array_without_pointer_cfilt() {
         */
         (
             "_fixed_array_verifyrange__H1ZA3_i_UiUi_PX01",
-            "int [3] * _fixed_array_verifyrange<int [3]>(unsigned int, unsigned int)",
+            "int [3] *_fixed_array_verifyrange<int [3]>(unsigned int, unsigned int)",
         ),
         ("__tiA3_i", "int [3] type_info node"),
         ("__tiA3_f", "float [3] type_info node"),
@@ -1174,11 +2170,11 @@ fn test_demangle_array_without_pointer_cfilt() {
         ("__tfA3_i", "int [3] type_info function"),
         (
             "_fixed_array_verifyrange__H1ZA3_A3_f_UiUi_PX01",
-            "float [3][3] * _fixed_array_verifyrange<float [3][3]>(unsigned int, unsigned int)",
+            "float [3][3] *_fixed_array_verifyrange<float [3][3]>(unsigned int, unsigned int)",
         ),
         (
             "_fixed_array_verifyrange__H1ZA3_f_UiUi_PX01",
-            "float [3] * _fixed_array_verifyrange<float [3]>(unsigned int, unsigned int)",
+            "float [3] *_fixed_array_verifyrange<float [3]>(unsigned int, unsigned int)",
         ),
     ];
     let mut config = DemangleConfig::new();
@@ -1205,7 +2201,7 @@ fn test_demangle_array_without_pointer_fixed() {
         */
         (
             "_fixed_array_verifyrange__H1ZA3_i_UiUi_PX01",
-            "int [4] * _fixed_array_verifyrange<int [4]>(unsigned int, unsigned int)",
+            "int [4] *_fixed_array_verifyrange<int [4]>(unsigned int, unsigned int)",
         ),
         ("__tiA3_i", "int [4] type_info node"),
         ("__tiA3_f", "float [4] type_info node"),
@@ -1215,11 +2211,11 @@ fn test_demangle_array_without_pointer_fixed() {
         ("__tfA3_i", "int [4] type_info function"),
         (
             "_fixed_array_verifyrange__H1ZA3_A3_f_UiUi_PX01",
-            "float [4][4] * _fixed_array_verifyrange<float [4][4]>(unsigned int, unsigned int)",
+            "float [4][4] *_fixed_array_verifyrange<float [4][4]>(unsigned int, unsigned int)",
         ),
         (
             "_fixed_array_verifyrange__H1ZA3_f_UiUi_PX01",
-            "float [4] * _fixed_array_verifyrange<float [4]>(unsigned int, unsigned int)",
+            "float [4] *_fixed_array_verifyrange<float [4]>(unsigned int, unsigned int)",
         ),
     ];
     let mut config = DemangleConfig::new();
@@ -2080,6 +3076,3645 @@ fn test_demangle_mangled_within_mangled() {
     }
 }
 
+#[test]
+fn test_demangle_function_pointer_in_templated_class() {
+    static CASES: [(&str, &str); 4] = [
+        (
+            "__cl__t7Functor1ZPFi_i_iRCi",
+            "Functor<int (*)(int)>::operator()(int, int const &)",
+        ),
+        ("get__t7Functor1ZPFi_i_", "Functor<int (*)(int)>::get(void)"),
+        (
+            "__t7Functor1ZPFi_i_PFi_i",
+            "Functor<int (*)(int)>::Functor(int (*)(int))",
+        ),
+        (
+            "foo__FRt4List1Zt7Functor1ZPFi_i_",
+            "foo(List<Functor<int (*)(int)> > &)",
+        ),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+    }
+}
+
+#[test]
+fn test_demangle_stl_containers() {
+    static CASES: [(&str, &str); 4] = [
+        ("find__t8_Rb_tree5ZUiZt4pair2ZCUiZiZt10_Select1st1Zt4pair2ZCUiZiZt4less1ZUiZt9allocator1ZiRCUi", "_Rb_tree<unsigned int, pair<unsigned int const, int>, _Select1st<pair<unsigned int const, int> >, less<unsigned int>, allocator<int> >::find(unsigned int const &)"),
+        ("size__Ct6vector2ZiZt9allocator1Zi", "vector<int, allocator<int> >::size(void) const"),
+        ("foo__FRCt12basic_string3ZcZt18string_char_traits1ZcZt24__default_alloc_template2b0i0", "foo(basic_string<char, string_char_traits<char>, __default_alloc_template<false, 0> > const &)"),
+        ("foo__FRt6vector2Zt6vector2ZiZt9allocator1ZiZt9allocator1Zt6vector2ZiZt9allocator1Zi", "foo(vector<vector<int, allocator<int> >, allocator<vector<int, allocator<int> > > > &)"),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+    }
+}
+
+#[test]
+fn test_demangle_stl_containers_simplified() {
+    static CASES: [(&str, &str); 6] = [
+        ("find__t8_Rb_tree5ZUiZt4pair2ZCUiZiZt10_Select1st1Zt4pair2ZCUiZiZt4less1ZUiZt9allocator1ZiRCUi", "map<unsigned int, int>::find(unsigned int const &)"),
+        ("size__Ct6vector2ZiZt9allocator1Zi", "vector<int>::size(void) const"),
+        ("foo__FRCt12basic_string3ZcZt18string_char_traits1ZcZt24__default_alloc_template2b0i0", "foo(string const &)"),
+        ("foo__FRt6vector2Zt6vector2ZiZt9allocator1ZiZt9allocator1Zt6vector2ZiZt9allocator1Zi", "foo(vector<vector<int> > &)"),
+        ("foo__FRt3map4ZP3FooZt4list2ZiZt9allocator1ZiZt4less1ZP3FooZt9allocator1Zt4list2ZiZt9allocator1Zi", "foo(map<Foo *, list<int> > &)"),
+        // Custom allocators are left alone
+        ("_M_range_insert__H1ZPC5tName_Gt6vector2Z5tNameZt7s2alloc1Z5tNameP5tNameX01X01G20forward_iterator_tag_v", "void _M_range_insert<tName const *>(vector<tName, s2alloc<tName> >, tName *, tName const *, tName const *, forward_iterator_tag)"),
+    ];
+    let mut config = DemangleConfig::new();
+    config.simplify_stl_containers = true;
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+    }
+}
+
+#[test]
+fn test_demangle_stl_containers_simplified_respaced() {
+    // The arguments are compared as parsed, not as written.
+    static CASES: [(&str, &str, &str); 4] = [
+        (
+            "size__Ct8_Rb_tree5ZP3FooZt4pair2ZCP3FooZiZt10_Select1st1Zt4pair2ZCP3FooZiZt4less1ZP3FooZt9allocator1Zi",
+            "map<Foo *, int>::size(void) const",
+            "map<Foo*, int>::size(void) const",
+        ),
+        (
+            "find__t8_Rb_tree5ZUiZt4pair2ZCUiZiZt10_Select1st1Zt4pair2ZCUiZiZt4less1ZUiZt9allocator1ZiRCUi",
+            "map<unsigned int, int>::find(unsigned int const &)",
+            "map<unsigned int, int>::find(unsigned int const&)",
+        ),
+        (
+            "size__Ct8_Rb_tree5Zt12basic_string3ZcZt18string_char_traits1ZcZt24__default_alloc_template2b0i0Zt4pair2ZCt12basic_string3ZcZt18string_char_traits1ZcZt24__default_alloc_template2b0i0ZiZt10_Select1st1Zt4pair2ZCt12basic_string3ZcZt18string_char_traits1ZcZt24__default_alloc_template2b0i0ZiZt4less1Zt12basic_string3ZcZt18string_char_traits1ZcZt24__default_alloc_template2b0i0Zt9allocator1Zi",
+            "map<string, int>::size(void) const",
+            "map<string, int>::size(void) const",
+        ),
+        // Not the default allocator of `int const`
+        (
+            "f__FRt6vector2ZCiZt9allocator1Zi",
+            "f(vector<int const, allocator<int> > &)",
+            "f(vector<int const, allocator<int> >&)",
+        ),
+    ];
+
+    for strip_const in [false, true] {
+        for (spacing, compact) in [
+            (None, false),
+            (Some(TypePointerSpacing::BeforeStar), false),
+            (Some(TypePointerSpacing::AfterType), true),
+            (Some(TypePointerSpacing::Compact), true),
+        ] {
+            let mut config = DemangleConfig::new();
+            config.simplify_stl_containers = true;
+            config.type_pointer_spacing = spacing;
+            config.strip_top_level_const_on_value_args = strip_const;
+
+            for (mangled, spaced, unspaced) in CASES {
+                let demangled = if compact { unspaced } else { spaced };
+                assert_eq!(
+                    Ok(demangled),
+                    demangle(mangled, &config).as_deref(),
+                    "{spacing:?}, {strip_const}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_demangle_array_cv_qualifiers() {
+    // cv-qualifiers on the array itself apply to its elements, while the ones
+    // before the pointer apply to the pointer.
+    static CASES: [(&str, &str); 12] = [
+        ("f__FPCA41_i", "f(int const (*)[42])"),
+        ("f__FPA41_Ci", "f(int const (*)[42])"),
+        ("f__FCPA41_i", "f(int (*const)[42])"),
+        ("f__FCPCA41_i", "f(int const (*const)[42])"),
+        ("f__FRCA41_i", "f(int const (&)[42])"),
+        ("f__FPCA41_Pi", "f(int *const (*)[42])"),
+        ("f__FPVA41_i", "f(int volatile (*)[42])"),
+        ("f__FPA41_Vi", "f(int volatile (*)[42])"),
+        ("f__FVPA41_i", "f(int (*volatile)[42])"),
+        ("f__FPCVA41_i", "f(int const volatile (*)[42])"),
+        ("f__FPVCA41_i", "f(int const volatile (*)[42])"),
+        // The signedness goes with the element type, after the array.
+        ("f__FPCA41_Ui", "f(unsigned int const (*)[42])"),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+    }
+
+    // A signedness qualifier can't be applied to the array itself.
+    assert_eq!(
+        demangle("f__FPCUA41_i", &config),
+        Err(DemangleError::PrevQualifiersInInvalidPostioniAtArrayArgument("A41_i"))
+    );
+}
+
+#[test]
+fn test_demangle_reference_to_array_arguments() {
+    // Some compiler revisions emit the reference before the array and others
+    // after it, but both are a reference to an array.
+    static CASES: [(&str, &str, &str); 10] = [
+        // void f(int (&)[10]);
+        ("f__FRA9_i", "f(int (&)[10])", "f(int (&)[9])"),
+        ("f__FA9_Ri", "f(int (&)[10])", "f(int & [9])"),
+        // void f(int (&)[10][20]);
+        ("f__FRA9_A19_i", "f(int (&)[10][20])", "f(int (&)[9][19])"),
+        ("f__FA9_A19_Ri", "f(int (&)[10][20])", "f(int & [9][19])"),
+        // void f(const int (&)[10]);
+        ("f__FRCA9_i", "f(int const (&)[10])", "f(int const (&)[9])"),
+        ("f__FA9_RCi", "f(int const (&)[10])", "f(int const & [9])"),
+        // void f(char *(&)[10]);
+        ("f__FRA9_Pc", "f(char *(&)[10])", "f(char * (&)[9])"),
+        ("f__FA9_RPc", "f(char *(&)[10])", "f(char *& [9])"),
+        // void f(unsigned char (&)[10]);
+        (
+            "f__FA9_RUc",
+            "f(unsigned char (&)[10])",
+            "f(unsigned char & [9])",
+        ),
+        // void f(int *[10]);
+        // Arrays of pointers are fine as they are.
+        ("f__FA9_Pi", "f(int *[10])", "f(int * [9])"),
+    ];
+
+    for (mangled, demangled, demangled_cfilt) in CASES {
+        let config = DemangleConfig::new_g2dem();
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+
+        let config = DemangleConfig::new_cfilt();
+        assert_eq!(Ok(demangled_cfilt), demangle(mangled, &config).as_deref());
+    }
+}
+
+#[test]
+fn test_config_builder_presets() {
+    assert_eq!(DemangleConfig::builder().build(), DemangleConfig::new());
+    assert_eq!(
+        DemangleConfig::builder().preset_g2dem().build(),
+        DemangleConfig::new_g2dem()
+    );
+    assert_eq!(
+        DemangleConfig::builder().preset_cfilt().build(),
+        DemangleConfig::new_cfilt()
+    );
+
+    let cfilt = DemangleConfig::new_cfilt();
+    let built = DemangleConfig::builder()
+        .fix_namespaced_global_constructor_bug(cfilt.fix_namespaced_global_constructor_bug)
+        .fix_array_length_arg(cfilt.fix_array_length_arg)
+        .fix_array_length_skip_zero(cfilt.fix_array_length_skip_zero)
+        .fix_array_of_references(cfilt.fix_array_of_references)
+        .demangle_global_keyed_frames(cfilt.demangle_global_keyed_frames)
+        .demangle_global_keyed_template_instantiations(
+            cfilt.demangle_global_keyed_template_instantiations,
+        )
+        .demangle_nested_global_keyed_symbols(cfilt.demangle_nested_global_keyed_symbols)
+        .describe_static_init_functions(cfilt.describe_static_init_functions)
+        .demangle_vtable_offsets(cfilt.demangle_vtable_offsets)
+        .reject_plain_c_lookalikes(cfilt.reject_plain_c_lookalikes)
+        .strip_top_level_const_on_value_args(cfilt.strip_top_level_const_on_value_args)
+        .pretty_anonymous_namespaces(cfilt.pretty_anonymous_namespaces)
+        .ellipsis_emit_space_after_comma(cfilt.ellipsis_emit_space_after_comma)
+        .fix_extension_int(cfilt.fix_extension_int)
+        .fix_array_in_return_position(cfilt.fix_array_in_return_position)
+        .fix_function_pointers_in_template_lists(cfilt.fix_function_pointers_in_template_lists)
+        .simplify_stl_containers(cfilt.simplify_stl_containers)
+        .demangle_constructor_variants(cfilt.demangle_constructor_variants)
+        .emit_enum_casts_in_templates(cfilt.emit_enum_casts_in_templates)
+        .fix_function_pointer_in_return_position(cfilt.fix_function_pointer_in_return_position)
+        .emit_plain_restrict(cfilt.emit_plain_restrict)
+        .demangle_clone_suffix(cfilt.demangle_clone_suffix)
+        .emit_clone_suffix(cfilt.emit_clone_suffix)
+        .tolerate_stray_underscores_between_args(cfilt.tolerate_stray_underscores_between_args)
+        .lenient(cfilt.lenient)
+        .fix_function_pointer_template_lookbacks(cfilt.fix_function_pointer_template_lookbacks)
+        .emit_integer_suffixes_in_templates(cfilt.emit_integer_suffixes_in_templates)
+        .emit_wide_character_literals_in_templates(cfilt.emit_wide_character_literals_in_templates)
+        .fix_template_parameter_levels(cfilt.fix_template_parameter_levels)
+        .complete_truncated_symbols(cfilt.complete_truncated_symbols)
+        .annotate_ambiguous_scope(cfilt.annotate_ambiguous_scope)
+        .omit_parameter_list(cfilt.omit_parameter_list)
+        .return_type_style(cfilt.return_type_style)
+        .omit_template_args_in_scope(cfilt.omit_template_args_in_scope)
+        .type_pointer_spacing(cfilt.type_pointer_spacing)
+        .scope_separator(cfilt.scope_separator)
+        .template_brackets(cfilt.template_brackets)
+        .argument_list_parens(cfilt.argument_list_parens)
+        .vendor_type_map(cfilt.vendor_type_map)
+        .max_repeat_count(cfilt.max_repeat_count)
+        .max_array_dimensions(cfilt.max_array_dimensions)
+        .max_template_depth(cfilt.max_template_depth)
+        .max_output_len(cfilt.max_output_len)
+        .build();
+    assert_eq!(built, cfilt);
+}
+
+#[test]
+fn test_config_builder_single_flag() {
+    let mut expected = DemangleConfig::new_cfilt();
+    expected.fix_array_length_arg = true;
+
+    let built = DemangleConfig::builder()
+        .preset_cfilt()
+        .fix_array_length_arg(true)
+        .build();
+    assert_eq!(built, expected);
+}
+
+#[test]
+fn test_demangle_array_of_classes() {
+    static CASES: [(&str, &str); 4] = [
+        ("f__FPA5_9SomeClass", "f(SomeClass (*)[6])"),
+        ("f__FPA5_Q22ns3Cls", "f(ns::Cls (*)[6])"),
+        ("f__FPA5_A2_Q22ns3Cls", "f(ns::Cls (*)[6][3])"),
+        ("f__FPA5_t3Foo1Zi", "f(Foo<int> (*)[6])"),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+    }
+}
+
+#[test]
+fn test_demangle_array_of_function_pointers() {
+    static CASES: [(&str, &str); 8] = [
+        ("f__FPA3_PFi_v", "f(void (*(*)[4])(int))"),
+        ("f__FA3_PFi_v", "f(void (*[4])(int))"),
+        ("f__FPA3_A2_PFi_v", "f(void (*(*)[4][3])(int))"),
+        ("f__FRA3_PFPc_i", "f(int (*(&)[4])(char *))"),
+        ("f__FPA3_CPFi_v", "f(void (*const (*)[4])(int))"),
+        ("f__FA3_PFi_PFc_v", "f(void (*(*[4])(int))(char))"),
+        ("f__FCPFi_PFc_v", "f(void (*(*const)(int))(char))"),
+        ("f__FPA3_PM3FooFP3Fooi_v", "f(void (Foo::*(*)[4])(int))"),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+    }
+}
+
+#[test]
+fn test_demangle_array_of_function_pointers_cfilt() {
+    static CASES: [(&str, &str); 2] = [
+        ("f__FPA3_PFi_v", "f(void (*(*)[3])(int))"),
+        ("f__FPA5_9SomeClass", "f(SomeClass (*)[5])"),
+    ];
+    let config = DemangleConfig::new_cfilt();
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+    }
+}
+
+#[test]
+fn test_demangle_components_namespaced_operator() {
+    let config = DemangleConfig::new();
+
+    let components = demangle_components(
+        "__as__Q33sim15CollisionObject20CollisionVolumeOwnerRCQ33sim15CollisionObject20CollisionVolumeOwner",
+        &config,
+    )
+    .unwrap();
+    let scope: Vec<_> = components
+        .scope()
+        .iter()
+        .map(|x| (x.name(), x.kind(), x.template_args()))
+        .collect();
+
+    assert_eq!(
+        scope,
+        [
+            ("sim", ScopeKind::Namespace, None),
+            ("CollisionObject", ScopeKind::Namespace, None),
+            ("CollisionVolumeOwner", ScopeKind::Class, None),
+        ]
+    );
+    assert_eq!(components.leaf(), "operator=");
+    assert_eq!(components.leaf_kind(), LeafKind::Operator);
+}
+
+#[test]
+fn test_demangle_components_templated_class() {
+    let config = DemangleConfig::new();
+
+    let components = demangle_components(
+        "find__t8_Rb_tree5ZUiZt4pair2ZCUiZiZt10_Select1st1Zt4pair2ZCUiZiZt4less1ZUiZt9allocator1ZiRCUi",
+        &config,
+    )
+    .unwrap();
+    let scope = components.scope();
+
+    assert_eq!(scope.len(), 1);
+    assert_eq!(scope[0].name(), "_Rb_tree");
+    assert_eq!(scope[0].kind(), ScopeKind::TemplateInstance);
+    assert_eq!(
+        scope[0].template_args(),
+        Some("ZUiZt4pair2ZCUiZiZt10_Select1st1Zt4pair2ZCUiZiZt4less1ZUiZt9allocator1Zi")
+    );
+    assert_eq!(components.leaf(), "find");
+    assert_eq!(components.leaf_kind(), LeafKind::Function);
+}
+
+#[test]
+fn test_demangle_components_kinds() {
+    static CASES: [(&str, &[&str], &str, LeafKind); 6] = [
+        ("_$_5tName", &["tName"], "tName", LeafKind::Destructor),
+        ("__5tNamePCc", &["tName"], "tName", LeafKind::Constructor),
+        (
+            "GetText__C5tName",
+            &["tName"],
+            "GetText",
+            LeafKind::Function,
+        ),
+        (
+            "whatever_default__Fcsilx",
+            &[],
+            "whatever_default",
+            LeafKind::Function,
+        ),
+        (
+            "__opi__5tName",
+            &["tName"],
+            "operator int",
+            LeafKind::Operator,
+        ),
+        (
+            "a_function__Q35silly8my_thing17another_namespacefffi",
+            &["silly", "my_thing", "another_namespace"],
+            "a_function",
+            LeafKind::Function,
+        ),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, scope, leaf, leaf_kind) in CASES {
+        let components = demangle_components(mangled, &config).unwrap();
+        let names: Vec<_> = components.scope().iter().map(|x| x.name()).collect();
+
+        assert_eq!(names, scope);
+        assert_eq!(components.leaf(), leaf);
+        assert_eq!(components.leaf_kind(), leaf_kind);
+    }
+}
+
+#[test]
+fn test_demangle_type_name() {
+    static CASES: [(&str, &str); 10] = [
+        // Counted names
+        ("6Wibble", "Wibble"),
+        ("12_GLOBAL_.N.a", "_GLOBAL_.N.a"),
+        // Namespaces
+        ("Q23foo3Bar", "foo::Bar"),
+        ("Q23sim9Collision", "sim::Collision"),
+        (
+            "Q_12_1a1b1c1d1e1f1g1h1i1j1k1l",
+            "a::b::c::d::e::f::g::h::i::j::k::l",
+        ),
+        // Templates
+        ("t4List1Zi", "List<int>"),
+        (
+            "t3Map2ZQ23foo3BarZt4List1Zt4Pair2ZiZPCc",
+            "Map<foo::Bar, List<Pair<int, char const *> > >",
+        ),
+        ("t5Array2Zii10", "Array<int, 10>"),
+        // Templates inside namespaces
+        ("Q2t4List1Zi4Node", "List<int>::Node"),
+        ("Q3t5Outer1i4t5Inner1iY013Bar", "Outer<4>::Inner<4>::Bar"),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(
+            demangle_type_name(mangled, &config).as_deref(),
+            Ok(demangled),
+            "{mangled}"
+        );
+    }
+
+    type Demangled = Result<String, DemangleError<'static>>;
+    static ERROR_CASES: [(&str, DemangleError); 8] = [
+        ("", DemangleError::InvalidTypeName("")),
+        ("PCc", DemangleError::InvalidTypeName("PCc")),
+        ("i", DemangleError::InvalidTypeName("i")),
+        ("0", DemangleError::InvalidTypeName("0")),
+        ("6Wibblei", DemangleError::TrailingDataOnTypeName("i")),
+        ("t4List1Zi_", DemangleError::TrailingDataOnTypeName("_")),
+        (
+            "9Wibble",
+            DemangleError::InvalidCustomNameOnArgument("9Wibble"),
+        ),
+        ("Q23foo", DemangleError::InvalidCustomNameOnNamespace("")),
+    ];
+    for (mangled, err) in ERROR_CASES {
+        assert_eq!(
+            demangle_type_name(mangled, &config),
+            Demangled::Err(err),
+            "{mangled}"
+        );
+    }
+}
+
+#[test]
+fn test_demangle_class_lookback_with_repeats() {
+    static CASES: [(&str, &str); 7] = [
+        (
+            "AddPair__Q33sim16CollisionManager4AreaPQ23sim15CollisionObjectT0N21",
+            "sim::CollisionManager::Area::AddPair(sim::CollisionObject *, sim::CollisionManager::Area, sim::CollisionObject *, sim::CollisionObject *)",
+        ),
+        ("f__3FooiT0N21", "Foo::f(int, Foo, int, int)"),
+        ("f__3FooiN20T2", "Foo::f(int, Foo, Foo, Foo)"),
+        ("f__3FooN20", "Foo::f(Foo, Foo)"),
+        ("f__FiT0N21", "f(int, int, int, int)"),
+        ("f__3FooPFiT0N21_v", "Foo::f(void (*)(int, int, int, int))"),
+        (
+            "f__FPFiT0N21_vT0",
+            "f(void (*)(int, int, int, int), void (*)(int, int, int, int))",
+        ),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+    }
+}
+
+#[test]
+fn test_demangle_class_lookback_out_of_bounds() {
+    static CASES: [(&str, DemangleError); 3] = [
+        ("f__3FooN21", DemangleError::InvalidRepeatingArgument("N21")),
+        ("f__FiN31", DemangleError::InvalidRepeatingArgument("N31")),
+        (
+            "f__3FooPFiN21_v",
+            DemangleError::InvalidRepeatingArgument("N21_v"),
+        ),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, err) in CASES {
+        assert_eq!(Err(err), demangle(mangled, &config));
+    }
+}
+
+#[test]
+fn test_demangle_templated_member_pointer_values() {
+    static CASES: [(&str, &str, &str); 9] = [
+        (
+            "Get__t9Extractor1PO3Foo_i5field",
+            "Extractor<&Foo::field>::Get(void)",
+            "Extractor<&Foo::field>::Get(void)",
+        ),
+        (
+            "_vt.t9Extractor1PO3Foo_i5field",
+            "Extractor<&Foo::field> virtual table",
+            "Extractor<&Foo::field> virtual table",
+        ),
+        (
+            "Get__t9Extractor1POQ22ns3Foo_i5fieldP3Foo",
+            "Extractor<&ns::Foo::field>::Get(Foo *)",
+            "Extractor<&ns::Foo::field>::Get(Foo *)",
+        ),
+        (
+            "Call__t7Invoker1PM3FooFP3Foo_v6method",
+            "Invoker<&Foo::method>::Call(void)",
+            "Invoker<(void (Foo::*)()) &Foo::method>::Call(void)",
+        ),
+        (
+            "_vt$t7Invoker1PM3FooFP3Fooi_v6method",
+            "Invoker<&Foo::method> virtual table",
+            "Invoker<(void (Foo::*)(int)) &Foo::method> virtual table",
+        ),
+        (
+            "Call__t7Invoker1PM3FooCFPC3Foo_i3get",
+            "Invoker<&Foo::get>::Call(void)",
+            "Invoker<(int (Foo::*)() const) &Foo::get>::Call(void)",
+        ),
+        (
+            "Call__t7Invoker1PM3FooFP3Foo_v12method__3Foo",
+            "Invoker<&Foo::method>::Call(void)",
+            "Invoker<(void (Foo::*)()) &Foo::method>::Call(void)",
+        ),
+        (
+            "Run__t7Invoker1PM3FooFP3Foo_v6methodP3Foo",
+            "Invoker<&Foo::method>::Run(Foo *)",
+            "Invoker<(void (Foo::*)()) &Foo::method>::Run(Foo *)",
+        ),
+        (
+            "Call__t7Invoker1PM3FooFP3FooPc_PFi_v6method",
+            "Invoker<&Foo::method>::Call(void)",
+            "Invoker<(void (Foo::*(*)(char *))(int)) &Foo::method>::Call(void)",
+        ),
+    ];
+    let cfilt = DemangleConfig::new_cfilt();
+    let g2dem = DemangleConfig::new_g2dem();
+
+    for (mangled, demangled_cfilt, demangled_g2dem) in CASES {
+        assert_eq!(Ok(demangled_cfilt), demangle(mangled, &cfilt).as_deref());
+        assert_eq!(Ok(demangled_g2dem), demangle(mangled, &g2dem).as_deref());
+    }
+}
+
+#[test]
+fn test_demangle_nested_ellipsis() {
+    static CASES: [(&str, &str); 7] = [
+        ("f__FPFPCce_v", "f(void (*)(char const *, ...))"),
+        ("f__FPFe_v", "f(void (*)(...))"),
+        (
+            "f__FPM3FooFP3FooPCce_v",
+            "f(void (Foo::*)(char const *, ...))",
+        ),
+        (
+            "f__FPFPFPCce_v_v",
+            "f(void (*)(void (*)(char const *, ...)))",
+        ),
+        (
+            "f__FPFPCce_PFie_v",
+            "f(void (*(*)(char const *, ...))(int, ...))",
+        ),
+        (
+            "Get__t3Foo1ZPFPCce_v",
+            "Foo<void (*)(char const *, ...)>::Get(void)",
+        ),
+        (
+            "f__H1ZPFPCce_v_X01_v",
+            "void f<void (*)(char const *, ...)>(void (*)(char const *, ...))",
+        ),
+    ];
+    let config = DemangleConfig::new_g2dem();
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+    }
+}
+
+#[test]
+fn test_demangle_nested_ellipsis_cfilt() {
+    static CASES: [(&str, &str); 7] = [
+        ("f__FPFPCce_v", "f(void (*)(char const *,...))"),
+        ("f__FPFe_v", "f(void (*)(...))"),
+        (
+            "f__FPM3FooFP3FooPCce_v",
+            "f(void (Foo::*)(char const *,...))",
+        ),
+        (
+            "f__FPFPFPCce_v_v",
+            "f(void (*)(void (*)(char const *,...)))",
+        ),
+        (
+            "f__FPFPCce_PFie_v",
+            "f(void (*(*)(char const *,...))(int,...))",
+        ),
+        (
+            "Get__t3Foo1ZPFPCce_v",
+            "Foo<void (*)(char const *,...)>::Get(void)",
+        ),
+        (
+            "f__H1ZPFPCce_v_X01_v",
+            "void f<void (*)(char const *,...)>(void (*)(char const *,...))",
+        ),
+    ];
+    let config = DemangleConfig::new_cfilt();
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+    }
+}
+
+#[test]
+fn test_demangle_thunks() {
+    static CASES: [(&str, &str); 6] = [
+        (
+            "__thunk_4_foo__3Bar",
+            "virtual function thunk (delta:-4) for Bar::foo(void)",
+        ),
+        (
+            "__thunk_16__$_7Derived",
+            "virtual function thunk (delta:-16) for Derived::~Derived(void)",
+        ),
+        (
+            "__thunk_12_Draw__CQ24Game6Sprite",
+            "virtual function thunk (delta:-12) for Game::Sprite::Draw(void) const",
+        ),
+        (
+            "__thunk_8___opPCc__C6String",
+            "virtual function thunk (delta:-8) for String::operator char const *(void) const",
+        ),
+        (
+            "__thunk_16_8_foo__3Bari",
+            "virtual function thunk (delta:-16, vcall:-8) for Bar::foo(int)",
+        ),
+        (
+            "__thunk_16_8__$_7MyClass",
+            "virtual function thunk (delta:-16, vcall:-8) for MyClass::~MyClass(void)",
+        ),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+    }
+}
+
+#[test]
+fn test_demangle_thunks_malformed() {
+    static CASES: [(&str, DemangleError); 5] = [
+        ("__thunk_", DemangleError::InvalidDeltaOnThunk("")),
+        (
+            "__thunk_x_foo__3Bar",
+            DemangleError::InvalidDeltaOnThunk("x_foo__3Bar"),
+        ),
+        (
+            "__thunk_16foo__3Bar",
+            DemangleError::InvalidDeltaOnThunk("16foo__3Bar"),
+        ),
+        (
+            "__thunk_16_8foo__3Bar",
+            DemangleError::InvalidVcallOffsetOnThunk("16_8foo__3Bar"),
+        ),
+        ("__thunk_4_notmangled", DemangleError::NotMangled),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, err) in CASES {
+        assert_eq!(Err(err), demangle(mangled, &config));
+    }
+}
+
+#[test]
+fn test_demangle_ambiguous_template_readings() {
+    static CASES: [(&str, &str); 8] = [
+        // Class arguments without their `Z`
+        ("push__t4List2Z5tName5tName", "List<tName, tName>::push(void)"),
+        ("get__t4Pair2Zi5tNamei", "Pair<int, tName>::get(int)"),
+        (
+            "Swap__H2Z5tName5tName_RX01RX11_v",
+            "void Swap<tName, tName>(tName &, tName &)",
+        ),
+        // Counts of more than one digit
+        (
+            "Get__t5Tuple12_ZiZcZsZlZfZdZiZiZiZiZiZiPC5tName",
+            "Tuple<int, char, short, long, float, double, int, int, int, int, int, int>::Get(tName const *)",
+        ),
+        // The first reading still wins if it works
+        (
+            "__vc__t4List1Z15tSpriteParticles",
+            "List<tSpriteParticle>::operator[](short)",
+        ),
+        ("push__t4List1Z5tName5tName", "List<tName>::push(tName)"),
+        (
+            "Pop__t14CAutoTransform121G3DTRANSFORMSTATETYPE0",
+            "CAutoTransform<0>::Pop(void)",
+        ),
+        ("f__t3Foo1i10", "Foo<10>::f(void)"),
+    ];
+    let config = DemangleConfig::new_g2dem();
+    let cfilt = DemangleConfig::new_cfilt();
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+        assert_eq!(Ok(demangled), demangle(mangled, &cfilt).as_deref());
+    }
+
+    assert_eq!(
+        Err(DemangleError::AllTemplateReadingsFailed("z")),
+        demangle("push__t4List2Z5tName5tNamez", &config)
+    );
+}
+
+#[test]
+fn test_demangle_error_kind_name() {
+    static CASES: [(DemangleError, &str); 6] = [
+        (DemangleError::NotMangled, "NotMangled"),
+        (DemangleError::NonAscii, "NonAscii"),
+        (
+            DemangleError::InvalidRepeatingArgument("N31"),
+            "InvalidRepeatingArgument",
+        ),
+        (DemangleError::UnknownType('z', "z"), "UnknownType"),
+        (
+            DemangleError::InvalidDeltaOnThunk(""),
+            "InvalidDeltaOnThunk",
+        ),
+        (
+            DemangleError::AllTemplateReadingsFailed("z"),
+            "AllTemplateReadingsFailed",
+        ),
+    ];
+
+    for (err, name) in CASES {
+        assert_eq!(name, err.kind_name());
+    }
+}
+
+#[test]
+fn test_demangle_error_into_owned() {
+    fn assert_send_sync_static<T: Send + Sync + 'static>() {}
+    assert_send_sync_static::<OwnedDemangleError>();
+
+    static CASES: [(DemangleError, Option<&str>, &str); 5] = [
+        (DemangleError::NotMangled, None, "NotMangled"),
+        (
+            DemangleError::InvalidRepeatingArgument("N31"),
+            Some("N31"),
+            "InvalidRepeatingArgument(\"N31\")",
+        ),
+        (
+            DemangleError::UnknownType('z', "z"),
+            Some("z"),
+            "UnknownType('z', \"z\")",
+        ),
+        (
+            DemangleError::InvalidNamespacedGlobal("Q23std6vector", "_size"),
+            Some("Q23std6vector"),
+            "InvalidNamespacedGlobal(\"Q23std6vector\", \"_size\")",
+        ),
+        (
+            DemangleError::InvalidCharacter('\n', 4),
+            None,
+            "InvalidCharacter('\\n', 4)",
+        ),
+    ];
+
+    for (err, context, description) in CASES {
+        let owned = err.into_owned();
+        assert_eq!(owned.kind_name(), err.kind_name());
+        assert_eq!(owned.context(), context);
+        assert_eq!(err.context(), context);
+        assert_eq!(owned.to_string(), description);
+        assert_eq!(OwnedDemangleError::from(err), owned);
+    }
+
+    // Outlives the symbol it came from.
+    let config = DemangleConfig::new();
+    let mut errors = Vec::new();
+    let mut line = String::new();
+    for sym in ["f__FiN31", "foo__FiPQ"] {
+        line.clear();
+        line.push_str(sym);
+        errors.push(demangle(&line, &config).unwrap_err().into_owned());
+    }
+    drop(line);
+    assert_eq!(errors[0].context(), Some("N31"));
+    assert_eq!(errors[1].kind_name(), "InvalidNamespaceCount");
+
+    let boxed: Box<dyn std::error::Error + Send + Sync> = Box::new(errors.remove(0));
+    assert!(boxed.source().is_none());
+}
+
+#[test]
+fn test_demangle_nested_template_lookbacks() {
+    static CASES: [(&str, &str); 5] = [
+        (
+            "Get__Q2t5Outer1Zit5Inner1iY01i",
+            "Outer<int>::Inner<int>::Get(int)",
+        ),
+        (
+            "Get__Q2t5Outer1i4t5Inner1iY01",
+            "Outer<4>::Inner<4>::Get(void)",
+        ),
+        (
+            "_vt$Q2t5Outer1i4t5Inner1iY01",
+            "Outer<4>::Inner<4> virtual table",
+        ),
+        (
+            "Get__Q3t5Outer1i4t6Middle2ZfiY01t5Inner2ZciY21",
+            "Outer<4>::Middle<float, 4>::Inner<char, 4>::Get(void)",
+        ),
+        (
+            "Get__Q3t5Outer1Zi5Plaint5Inner1iY01",
+            "Outer<int>::Plain::Inner<int>::Get(void)",
+        ),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+    }
+
+    let components =
+        demangle_components("Get__Q3t5Outer1i4t6Middle2ZfiY01t5Inner2ZciY21", &config).unwrap();
+    let scope = components.scope();
+    assert_eq!(scope.len(), 3);
+    assert_eq!(scope[2].name(), "Inner");
+    assert_eq!(scope[2].template_args(), Some("ZciY21"));
+}
+
+#[test]
+fn test_demangle_qualified_member_pointers() {
+    static CASES: [(&str, &str); 8] = [
+        ("f__FPM3FooFP3Fooi_v", "f(void (Foo::*)(int))"),
+        ("f__FCPM3FooFP3Fooi_v", "f(void (Foo::*const)(int))"),
+        ("f__FRPM3FooFP3Fooi_v", "f(void (Foo::*&)(int))"),
+        ("f__FRCPM3FooFP3Fooi_v", "f(void (Foo::*const &)(int))"),
+        ("f__FPCPM3FooFP3Fooi_v", "f(void (Foo::*const *)(int))"),
+        (
+            "f__FRCPM3FooCFPC3Fooi_v",
+            "f(void (Foo::*const &)(int) const)",
+        ),
+        ("f__FCPO3Foo_i", "f(int (Foo::*const))"),
+        ("f__FRCPO3Foo_i", "f(int (Foo::*const &))"),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+    }
+}
+
+#[test]
+fn test_demangle_invalid_qualified_member_pointers() {
+    static CASES: [(&str, DemangleError); 4] = [
+        (
+            "f__FRM3FooFP3Fooi_v",
+            DemangleError::InvalidQualifierForMethodMemberArg("RM3FooFP3Fooi_v"),
+        ),
+        (
+            "f__FCM3FooFP3Fooi_v",
+            DemangleError::InvalidQualifierForMethodMemberArg("CM3FooFP3Fooi_v"),
+        ),
+        (
+            "f__FPRPM3FooFP3Fooi_v",
+            DemangleError::InvalidQualifierForMethodMemberArg("PRPM3FooFP3Fooi_v"),
+        ),
+        (
+            "f__FRO3Foo_i",
+            DemangleError::InvalidQualifierForObjectMemberArg("RO3Foo_i"),
+        ),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, err) in CASES {
+        assert_eq!(Err(err), demangle(mangled, &config));
+    }
+}
+
+#[test]
+fn test_demangle_templated_free_operators() {
+    static CASES: [(&str, &str); 7] = [
+        (
+            "__eq__H1Z5tName_RCX01T0_b",
+            "bool operator==<tName>(tName const &, tName const &)",
+        ),
+        (
+            "__ne__H1Z5tName_RCX01T0_b",
+            "bool operator!=<tName>(tName const &, tName const &)",
+        ),
+        (
+            "__ls__H1Z5tName_R7ostreamRCX01_R7ostream",
+            "ostream &operator<< <tName>(ostream &, tName const &)",
+        ),
+        (
+            "__pl__H1Z5tName_RCX01T0_X01",
+            "tName operator+<tName>(tName const &, tName const &)",
+        ),
+        ("__eq__FRC3FooT0", "operator==(Foo const &, Foo const &)"),
+        (
+            "__ls__FR7ostreamRC3Foo",
+            "operator<<(ostream &, Foo const &)",
+        ),
+        ("__pl__FRC3FooT0", "operator+(Foo const &, Foo const &)"),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+    }
+}
+
+#[test]
+fn test_demangle_templated_free_operators_components() {
+    let config = DemangleConfig::new();
+
+    assert_eq!(
+        Err(DemangleError::UnsupportedSymbolKindOnComponents(
+            "__eq__H1Z5tName_RCX01T0_b"
+        )),
+        demangle_components("__eq__H1Z5tName_RCX01T0_b", &config)
+    );
+    assert_eq!(
+        Err(DemangleError::UnsupportedSymbolKindOnComponents(
+            "__opX01__H1Zi_t5MyVec1Zi_X01"
+        )),
+        demangle_components("__opX01__H1Zi_t5MyVec1Zi_X01", &config)
+    );
+}
+
+fn test_vendor_types(code: char, rest: &str) -> Option<(usize, &'static str)> {
+    match code {
+        'J' => Some((0, "__int64")),
+        'K' if rest.starts_with("16") => Some((2, "__vector16")),
+        // Consumes more than what's available.
+        'Y' => Some((10, "broken")),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_demangle_vendor_type_map() {
+    static CASES: [(&str, &str); 8] = [
+        ("SetTime__5TimerJ", "Timer::SetTime(__int64)"),
+        ("SetTime__5TimerUJ", "Timer::SetTime(unsigned __int64)"),
+        (
+            "SetTimes__5TimerPCJRCJ",
+            "Timer::SetTimes(__int64 const *, __int64 const &)",
+        ),
+        ("SetTable__5TimerPA3_J", "Timer::SetTable(__int64 (*)[4])"),
+        (
+            "SetTimes__5TimerJT1N31",
+            "Timer::SetTimes(__int64, __int64, __int64, __int64, __int64)",
+        ),
+        (
+            "LoadMatrix__8RendererPK16",
+            "Renderer::LoadMatrix(__vector16 *)",
+        ),
+        (
+            "LoadMatrices__8RendererPK16iT1",
+            "Renderer::LoadMatrices(__vector16 *, int, __vector16 *)",
+        ),
+        (
+            "Sum__H1ZJ_X01X01_X01",
+            "__int64 Sum<__int64>(__int64, __int64)",
+        ),
+    ];
+    let config = DemangleConfig::builder()
+        .vendor_type_map(Some(test_vendor_types))
+        .build();
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+    }
+}
+
+#[test]
+fn test_demangle_vendor_type_map_unknown() {
+    static CASES: [(&str, DemangleError); 3] = [
+        (
+            "LoadMatrix__8RendererPK8",
+            DemangleError::UnknownType('K', "K8"),
+        ),
+        ("Load__8RendererPY", DemangleError::UnknownType('Y', "Y")),
+        ("Load__8RendererPW", DemangleError::UnknownType('W', "W")),
+    ];
+    let config = DemangleConfig::builder()
+        .vendor_type_map(Some(test_vendor_types))
+        .build();
+
+    for (mangled, err) in CASES {
+        assert_eq!(Err(err), demangle(mangled, &config));
+    }
+
+    // No mapping by default
+    assert_eq!(
+        Err(DemangleError::UnknownType('J', "J")),
+        demangle("SetTime__5TimerJ", &DemangleConfig::new())
+    );
+}
+
+#[test]
+fn test_demangle_constructor_variants() {
+    static CASES: [(&str, &str); 10] = [
+        // The digit is the length of a class name argument
+        ("__5tName4Node", "tName::tName(Node)"),
+        ("__5tName2PCc", "tName::tName(PC, char)"),
+        ("__5tName1i", "tName::tName(i)"),
+        ("__5tName1RC5tName", "tName::tName(R, tName const)"),
+        ("__t4List1Zi4Node", "List<int>::List(Node)"),
+        // The digit is a variant marker
+        ("__5tName1", "tName::tName(void) [in-charge]"),
+        ("__5tName2i", "tName::tName(int) [not-in-charge]"),
+        ("__5tName2iT1", "tName::tName(int, int) [not-in-charge]"),
+        ("__t4List1Zi2i", "List<int>::List(int) [not-in-charge]"),
+        ("__Q23Foo3Bar2i", "Foo::Bar::Bar(int) [not-in-charge]"),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+    }
+}
+
+#[test]
+fn test_demangle_constructor_variants_cfilt() {
+    static CASES: [(&str, DemangleError); 4] = [
+        ("__5tName1", DemangleError::ConstructorVariantMarker("1")),
+        ("__5tName2i", DemangleError::ConstructorVariantMarker("2i")),
+        (
+            "__t4List1Zi2i",
+            DemangleError::ConstructorVariantMarker("2i"),
+        ),
+        // Not a known variant
+        ("__5tName3i", DemangleError::LikelyTruncated("3i", 2)),
+    ];
+    let config = DemangleConfig::new_cfilt();
+
+    for (mangled, err) in CASES {
+        assert_eq!(Err(err), demangle(mangled, &config));
+    }
+}
+
+#[test]
+fn test_demangle_plain_c_symbols_with_leading_underscores() {
+    static CASES: [&str; 14] = [
+        "__main",
+        "__divdi3",
+        "__udivdi3",
+        "__ashldi3",
+        "__builtin_delete",
+        "__gnu_compiled_cplusplus",
+        "__throw",
+        "__terminate",
+        "__tcf_0",
+        "__Qsort",
+        "__ashldi3__compat",
+        "__FRAME_BEGIN__",
+        "__FUNCTION__.976",
+        "__static_initialization_and_destruction_0",
+    ];
+    let config = DemangleConfig::new();
+
+    for mangled in CASES {
+        assert_eq!(Err(DemangleError::NotMangled), demangle(mangled, &config));
+        assert_eq!(
+            Err(DemangleError::NotMangled),
+            demangle_components(mangled, &config)
+        );
+    }
+}
+
+#[test]
+fn test_demangle_special_methods_with_leading_underscores() {
+    static CASES: [(&str, &str); 4] = [
+        ("__ashldi3__3Foo", "Foo::__ashldi3(void)"),
+        ("__InitGX__Fv", "__InitGX(void)"),
+        (
+            "__16__user_type_infoPCc",
+            "__user_type_info::__user_type_info(char const *)",
+        ),
+        ("__t4List1Zi", "List<int>::List(void)"),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+    }
+}
+
+#[test]
+fn test_demangle_invalid_characters() {
+    static CASES: [(&str, DemangleError); 6] = [
+        (
+            "0800efa0 T SetText__5tNamePCc",
+            DemangleError::InvalidCharacter(' ', 8),
+        ),
+        (
+            "SetText__5tNamePCc ",
+            DemangleError::InvalidCharacter(' ', 18),
+        ),
+        (
+            "SetText__5tName\tPCc",
+            DemangleError::InvalidCharacter('\t', 15),
+        ),
+        (
+            "<SetText__5tNamePCc>:",
+            DemangleError::InvalidCharacter('<', 0),
+        ),
+        (
+            "SetText__5tNamePCc+0x10",
+            DemangleError::InvalidCharacter('+', 18),
+        ),
+        (
+            "// SetText__5tNamePCc",
+            DemangleError::InvalidCharacter('/', 0),
+        ),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, err) in CASES {
+        assert_eq!(Err(err), demangle(mangled, &config));
+        assert_eq!(Err(err), demangle_components(mangled, &config));
+    }
+}
+
+#[test]
+fn test_extract_mangled_token() {
+    static CASES: [(&str, Option<&str>); 12] = [
+        // nm
+        ("0800efa0 T SetText__5tNamePCc", Some("SetText__5tNamePCc")),
+        ("         U __builtin_delete", Some("__builtin_delete")),
+        ("0800efa0 T main", None),
+        // objdump -d
+        ("0800efa0 <SetText__5tNamePCc>:", Some("SetText__5tNamePCc")),
+        (
+            "  8000f0:\t0c203b28 \tjal\t800eca0 <_$_5tName+0x10>",
+            Some("_$_5tName"),
+        ),
+        // objdump -t
+        (
+            "0800efa0 g     F .text\t00000020 _vt$5tName",
+            Some("_vt$5tName"),
+        ),
+        ("0800efa0 l    df *ABS*\t00000000 tName.cpp", None),
+        // Linker map files
+        (
+            "                0x0800efa0                _GLOBAL_$I$__5tName",
+            Some("_GLOBAL_$I$__5tName"),
+        ),
+        (" .text          0x0800efa0       0x20 obj/tName.o", None),
+        // Already a symbol
+        ("__5tName", Some("__5tName")),
+        ("_._5tName", Some("_._5tName")),
+        ("", None),
+    ];
+
+    for (line, token) in CASES {
+        assert_eq!(token, extract_mangled_token(line), "{line:?}");
+    }
+}
+
+#[test]
+fn test_demangle_map_line() {
+    static CASES: [(&str, &str); 8] = [
+        (
+            "                0x0800efa0                SetText__5tNamePCc",
+            "                0x0800efa0                tName::SetText(char const *)",
+        ),
+        (
+            "                0x0800efa0                main",
+            "                0x0800efa0                main",
+        ),
+        (
+            " .text          0x0800efa0       0x20 obj/tName.o",
+            " .text          0x0800efa0       0x20 obj/tName.o",
+        ),
+        (
+            " .text.f__Fv    0x0800efa0        0x8 obj/f.o",
+            " .text.f(void)  0x0800efa0        0x8 obj/f.o",
+        ),
+        (
+            " .text.Id__3Foo 0x0800efa0       0x10 obj/foo.o",
+            " .text.Foo::Id(void) 0x0800efa0       0x10 obj/foo.o",
+        ),
+        (
+            " .gnu.linkonce.t.Insert__t4List1ZiRCi",
+            " .gnu.linkonce.t.List<int>::Insert(int const &)",
+        ),
+        ("_5Scene$gCurrentScene", "Scene::gCurrentScene"),
+        (
+            "                0x00100000                . = 0x100000",
+            "                0x00100000                . = 0x100000",
+        ),
+    ];
+    let config = DemangleConfig::new();
+
+    for (line, demangled) in CASES {
+        assert_eq!(demangled, demangle_map_line(line, &config), "{line:?}");
+    }
+}
+
+#[test]
+fn test_demangle_map_file() {
+    static MAP: &str = include_str!("fixtures/gnuv2_symbols.map");
+    static CASES: [(MapWrapping, &str); 2] = [
+        (
+            MapWrapping::Wrap,
+            include_str!("fixtures/gnuv2_symbols.demangled.map"),
+        ),
+        (
+            MapWrapping::Join,
+            include_str!("fixtures/gnuv2_symbols.joined.map"),
+        ),
+    ];
+    let config = DemangleConfig::new();
+
+    for (wrapping, expected) in CASES {
+        let demangled: Vec<_> = MapDemangler::new(MAP.lines(), &config, wrapping).collect();
+        assert_eq!(
+            expected.lines().collect::<Vec<_>>(),
+            demangled,
+            "{wrapping:?}"
+        );
+    }
+}
+
+#[test]
+fn test_demangle_function_pointer_lookback_into_outer_list() {
+    static CASES: [(&str, &str); 6] = [
+        ("f__FiPFT0_v", "f(int, void (*)(int))"),
+        ("f__FiPFN20_v", "f(int, void (*)(int, int))"),
+        ("f__3FooPFT0_v", "Foo::f(void (*)(Foo))"),
+        ("f__FiPFPFT0_v_v", "f(int, void (*)(void (*)(int)))"),
+        ("f__FicPFiT1_v", "f(int, char, void (*)(int, char))"),
+        // Lookbacks into the inner list itself keep working
+        ("f__FcPFiT0_v", "f(char, void (*)(int, int))"),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+    }
+}
+
+fn feature_flag(config: &DemangleConfig, name: &str) -> bool {
+    match name {
+        "fix_namespaced_global_constructor_bug" => config.fix_namespaced_global_constructor_bug,
+        "fix_array_length_arg" => config.fix_array_length_arg,
+        "fix_array_length_skip_zero" => config.fix_array_length_skip_zero,
+        "fix_array_of_references" => config.fix_array_of_references,
+        "demangle_global_keyed_frames" => config.demangle_global_keyed_frames,
+        "demangle_global_keyed_template_instantiations" => {
+            config.demangle_global_keyed_template_instantiations
+        }
+        "demangle_nested_global_keyed_symbols" => config.demangle_nested_global_keyed_symbols,
+        "describe_static_init_functions" => config.describe_static_init_functions,
+        "demangle_vtable_offsets" => config.demangle_vtable_offsets,
+        "reject_plain_c_lookalikes" => config.reject_plain_c_lookalikes,
+        "strip_top_level_const_on_value_args" => config.strip_top_level_const_on_value_args,
+        "pretty_anonymous_namespaces" => config.pretty_anonymous_namespaces,
+        "ellipsis_emit_space_after_comma" => config.ellipsis_emit_space_after_comma,
+        "fix_extension_int" => config.fix_extension_int,
+        "fix_array_in_return_position" => config.fix_array_in_return_position,
+        "fix_function_pointers_in_template_lists" => config.fix_function_pointers_in_template_lists,
+        "simplify_stl_containers" => config.simplify_stl_containers,
+        "demangle_constructor_variants" => config.demangle_constructor_variants,
+        "emit_enum_casts_in_templates" => config.emit_enum_casts_in_templates,
+        "fix_function_pointer_in_return_position" => config.fix_function_pointer_in_return_position,
+        "emit_plain_restrict" => config.emit_plain_restrict,
+        "demangle_clone_suffix" => config.demangle_clone_suffix,
+        "emit_clone_suffix" => config.emit_clone_suffix,
+        "tolerate_stray_underscores_between_args" => config.tolerate_stray_underscores_between_args,
+        "lenient" => config.lenient,
+        "fix_function_pointer_template_lookbacks" => config.fix_function_pointer_template_lookbacks,
+        "emit_integer_suffixes_in_templates" => config.emit_integer_suffixes_in_templates,
+        "emit_wide_character_literals_in_templates" => {
+            config.emit_wide_character_literals_in_templates
+        }
+        "fix_template_parameter_levels" => config.fix_template_parameter_levels,
+        "complete_truncated_symbols" => config.complete_truncated_symbols,
+        _ => panic!("Unknown feature `{name}`"),
+    }
+}
+
+#[test]
+fn test_features() {
+    let g2dem = DemangleConfig::new_g2dem();
+    let cfilt = DemangleConfig::new_cfilt();
+
+    let features = features();
+    assert_eq!(features.len(), 30);
+
+    for (i, feature) in features.iter().enumerate() {
+        assert!(
+            features[..i].iter().all(|x| x.name != feature.name),
+            "`{}` is registered twice",
+            feature.name
+        );
+        assert!(!feature.doc.is_empty());
+        assert_eq!(feature.default_g2dem, feature_flag(&g2dem, feature.name));
+        assert_eq!(feature.default_cfilt, feature_flag(&cfilt, feature.name));
+    }
+}
+
+#[test]
+fn test_version() {
+    assert_eq!(VERSION, env!("CARGO_PKG_VERSION"));
+}
+
+#[test]
+fn test_demangle_method_pointer_to_templated_class_with_lookbacks() {
+    static CASES: [(&str, &str); 4] = [
+        (
+            "Foo__H1Zi_PMt7Wrapper1ZX01FPt7Wrapper1ZX01_v_v",
+            "void Foo<int>(void (Wrapper<int>::*)())",
+        ),
+        (
+            "Foo__H1Zi_PMt7Wrapper1ZX01CFPCt7Wrapper1ZX01i_v_v",
+            "void Foo<int>(void (Wrapper<int>::*)(int) const)",
+        ),
+        (
+            "Foo__H2ZiZ3Bar_PMt7Wrapper2ZX01ZX11FPt7Wrapper2ZX01ZX11_v_v",
+            "void Foo<int, Bar>(void (Wrapper<int, Bar>::*)())",
+        ),
+        (
+            "Foo__H1Zi_PMQ23Fart7Wrapper1ZX01FPQ23Fart7Wrapper1ZX01_v_v",
+            "void Foo<int>(void (Far::Wrapper<int>::*)())",
+        ),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+    }
+}
+
+#[test]
+fn test_demangle_class_names_with_leading_underscores() {
+    static CASES: [(&str, &str); 14] = [
+        // Constructors
+        (
+            "__13_0x1A_HandlerPv",
+            "_0x1A_Handler::_0x1A_Handler(void *)",
+        ),
+        ("__9__HandlerPv", "__Handler::__Handler(void *)"),
+        (
+            "__Q26_Outer9__Handleri",
+            "_Outer::__Handler::__Handler(int)",
+        ),
+        // Destructors
+        ("_$_13_0x1A_Handler", "_0x1A_Handler::~_0x1A_Handler(void)"),
+        ("_$_6_12Foo", "_12Foo::~_12Foo(void)"),
+        ("_._9__Handler", "__Handler::~__Handler(void)"),
+        (
+            "_$_Q26_Outer9__Handler",
+            "_Outer::__Handler::~__Handler(void)",
+        ),
+        // Methods
+        ("Run__13_0x1A_HandlerPv", "_0x1A_Handler::Run(void *)"),
+        ("Run__9__HandlerPv", "__Handler::Run(void *)"),
+        ("Run__C9__Handler", "__Handler::Run(void) const"),
+        ("Run__t9__Handler1Zi", "__Handler<int>::Run(void)"),
+        // Arguments
+        ("f__FP13_0x1A_Handler", "f(_0x1A_Handler *)"),
+        ("f__FR9__Handler", "f(__Handler &)"),
+        (
+            "f__FQ26_Outer9__HandlerT0",
+            "f(_Outer::__Handler, _Outer::__Handler)",
+        ),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+    }
+}
+
+#[test]
+fn test_demangle_templated_member_operators() {
+    static CASES: [(&str, &str); 8] = [
+        (
+            "__eq__H1ZUi_t5MyVec1ZiRCX00_b",
+            "bool MyVec<int>::operator==<unsigned int>(unsigned int const &)",
+        ),
+        (
+            "__eq__H1ZUi_Ct5MyVec1ZiRCX00_b",
+            "bool MyVec<int>::operator==<unsigned int>(unsigned int const &) const",
+        ),
+        (
+            "__pl__H1Zi_3BarRCX01_3Bar",
+            "Bar Bar::operator+<int>(int const &)",
+        ),
+        (
+            "__ls__H1Z5tName_Q23Foo3BarRCX01_RQ23Foo3Bar",
+            "Foo::Bar &Foo::Bar::operator<< <tName>(tName const &)",
+        ),
+        // Conversion operators
+        (
+            "__opX01__H1Zi_t5MyVec1Zi_X01",
+            "int MyVec<int>::operator int<int>()",
+        ),
+        (
+            "__opX01__H1Zi_Ct5MyVec1Zi_X01",
+            "int MyVec<int>::operator int<int>() const",
+        ),
+        (
+            "__opPX01__H1Zi_3Bar_PX01",
+            "int *Bar::operator int *<int>()",
+        ),
+        // Free operator
+        (
+            "__eq__H1ZUi_RCX01RCt5MyVec1ZX01_b",
+            "bool operator==<unsigned int>(unsigned int const &, MyVec<unsigned int> const &)",
+        ),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+    }
+}
+
+#[test]
+fn test_demangle_pathological_numbers() {
+    static CASES: [(&str, DemangleError); 7] = [
+        (
+            "f__FA18446744073709551615_i",
+            DemangleError::ArrayLengthOverflow("A18446744073709551615_i"),
+        ),
+        (
+            "f__FRA18446744073709551615_i",
+            DemangleError::ArrayLengthOverflow("A18446744073709551615_i"),
+        ),
+        (
+            "f__FiN999999999999_0",
+            DemangleError::RepeatCountTooLarge("N999999999999_0", 999999999999),
+        ),
+        (
+            "f__FiN18446744073709551615_0",
+            DemangleError::RepeatCountTooLarge(
+                "N18446744073709551615_0",
+                18446744073709551615,
+            ),
+        ),
+        (
+            "f__FiN257_0",
+            DemangleError::RepeatCountTooLarge("N257_0", 257),
+        ),
+        // Doesn't fit in an `usize` at all
+        (
+            "f__FA18446744073709551616_i",
+            DemangleError::ArrayLengthOverflow("A18446744073709551616_i"),
+        ),
+        (
+            "f__FPA1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_i",
+            DemangleError::TooManyArrayDimensions("A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_A1_i"),
+        ),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, err) in CASES {
+        assert_eq!(Err(err), demangle(mangled, &config));
+    }
+}
+
+#[test]
+fn test_demangle_pathological_numbers_without_fixup() {
+    let config = DemangleConfig::new_cfilt();
+
+    assert_eq!(
+        Ok("f(int [18446744073709551615])"),
+        demangle("f__FA18446744073709551615_i", &config).as_deref()
+    );
+
+    let config = DemangleConfig::builder()
+        .preset_cfilt()
+        .max_repeat_count(3)
+        .build();
+    assert_eq!(
+        Ok("f(int, int, int, int)"),
+        demangle("f__FiN30", &config).as_deref()
+    );
+    assert_eq!(
+        Err(DemangleError::RepeatCountTooLarge("N40", 4)),
+        demangle("f__FiN40", &config)
+    );
+}
+
+#[test]
+fn test_demangle_templated_function_returning_function_pointer() {
+    static CASES: [(&str, &str, &str); 5] = [
+        (
+            "make_handler__H1Zi_X01_PFiX01_v",
+            "void (*make_handler<int>(int))(int, int)",
+            "void (*)(int, int) make_handler<int>(int)",
+        ),
+        (
+            "make_handler__H1Zi__PFiX01_v",
+            "void (*make_handler<int>())(int, int)",
+            "void (*)(int, int) make_handler<int>()",
+        ),
+        (
+            "get__H1Zi_X01_RFX01_i",
+            "int (&get<int>(int))(int)",
+            "int (&)(int) get<int>(int)",
+        ),
+        // Method pointers
+        (
+            "get__H1Zi_3Foo_PM3FooFP3FooX01_v",
+            "void (Foo::*Foo::get<int>())(int)",
+            "void (Foo::*)(int) Foo::get<int>()",
+        ),
+        (
+            "get__H1Zi_3Foo_PM3FooCFPC3FooX01_v",
+            "void (Foo::*Foo::get<int>())(int) const",
+            "void (Foo::*)(int) const Foo::get<int>()",
+        ),
+    ];
+    let fixed = DemangleConfig::builder()
+        .fix_function_pointer_in_return_position(true)
+        .build();
+    let unfixed = DemangleConfig::builder()
+        .fix_function_pointer_in_return_position(false)
+        .build();
+
+    for (mangled, demangled_fixed, demangled_unfixed) in CASES {
+        assert_eq!(Ok(demangled_fixed), demangle(mangled, &fixed).as_deref());
+        assert_eq!(
+            Ok(demangled_unfixed),
+            demangle(mangled, &unfixed).as_deref()
+        );
+    }
+}
+
+#[test]
+fn test_demangle_return_type_styles() {
+    static CASES: [(&str, &str, &str); 6] = [
+        (
+            "Swap__H1Zi_RX01T0_v",
+            "Swap<int>(int &, int &)",
+            "Swap<int>(int &, int &) -> void",
+        ),
+        (
+            "radBinarySearch__H1ZQ213radPs2CdDrive14DirectoryEntry_RCX01PCX01iPUi_b",
+            "radBinarySearch<radPs2CdDrive::DirectoryEntry>(radPs2CdDrive::DirectoryEntry const &, radPs2CdDrive::DirectoryEntry const *, int, unsigned int *)",
+            "radBinarySearch<radPs2CdDrive::DirectoryEntry>(radPs2CdDrive::DirectoryEntry const &, radPs2CdDrive::DirectoryEntry const *, int, unsigned int *) -> bool",
+        ),
+        (
+            "SetState__H1ZQ211CharacterAi4Loco_11CharacterAiPQ211CharacterAi12StateManager_v",
+            "CharacterAi::SetState<CharacterAi::Loco>(CharacterAi::StateManager *)",
+            "CharacterAi::SetState<CharacterAi::Loco>(CharacterAi::StateManager *) -> void",
+        ),
+        (
+            "an_array__H1Zi_C14SomethingSillyX01_PA3_i",
+            "SomethingSilly::an_array<int>(int) const",
+            "SomethingSilly::an_array<int>(int) const -> int (*)[3]",
+        ),
+        (
+            "make_handler__H1Zi_X01_PFiX01_v",
+            "make_handler<int>(int)",
+            "make_handler<int>(int) -> void (*)(int, int)",
+        ),
+        // Function pointers in the arguments keep their return type.
+        (
+            "apply__H1Zi_PFX01_X01X01_X01",
+            "apply<int>(int (*)(int), int)",
+            "apply<int>(int (*)(int), int) -> int",
+        ),
+    ];
+    let omit = DemangleConfig::builder()
+        .return_type_style(ReturnTypeStyle::Omit)
+        .build();
+    let trailing = DemangleConfig::builder()
+        .return_type_style(ReturnTypeStyle::TrailingArrow)
+        .build();
+
+    for (mangled, demangled_omit, demangled_trailing) in CASES {
+        assert_eq!(Ok(demangled_omit), demangle(mangled, &omit).as_deref());
+        assert_eq!(
+            Ok(demangled_trailing),
+            demangle(mangled, &trailing).as_deref()
+        );
+    }
+
+    // Non-templated functions don't have a return type to place.
+    for config in [omit, trailing] {
+        assert_eq!(
+            demangle("SetText__5tNamePCc", &config).as_deref(),
+            Ok("tName::SetText(char const *)")
+        );
+    }
+}
+
+#[test]
+fn test_demangle_templated_constructors() {
+    static CASES: [(&str, &str, &str); 5] = [
+        /*
+        template <class T>
+        class allocator {};
+
+        template <class T, class Alloc = allocator<T> >
+        class vector {
+        public:
+            template <class InputIterator>
+            vector(InputIterator first, InputIterator last);
+        };
+
+        template <class T, class Alloc>
+        template <class InputIterator>
+        vector<T, Alloc>::vector(InputIterator first, InputIterator last) {}
+
+        template vector<char>::vector(const char *, const char *);
+        */
+        (
+            "__H1ZPCc_t6vector2ZcZt9allocator1ZcX00X00",
+            "vector<char, allocator<char> >::vector<char const *>(char const *, char const *)",
+            "vector<char, allocator<char> >::vector<char const *>(char const *, char const *)",
+        ),
+        // The template parameters of the constructor are on the level of
+        // the class when they aren't fixed.
+        (
+            "__H1ZPCc_t6vector2ZcZt9allocator1Zc_X01X01_v",
+            "vector<char, allocator<char> >::vector<char const *>(char, char)",
+            "vector<char, allocator<char> >::vector<char const *>(char const *, char const *)",
+        ),
+        (
+            "__H1ZPCc_Q22ns6BufferX00Ui",
+            "ns::Buffer::Buffer<char const *>(char const *, unsigned int)",
+            "ns::Buffer::Buffer<char const *>(char const *, unsigned int)",
+        ),
+        (
+            "__H2ZiZf_3FooX00X10_v",
+            "Foo::Foo<int, float>(int, float)",
+            "Foo::Foo<int, float>(int, float)",
+        ),
+        ("__H1Zi_3Foo", "Foo::Foo<int>()", "Foo::Foo<int>()"),
+    ];
+
+    for (mangled, g2dem, cfilt) in CASES {
+        assert_eq!(
+            demangle(mangled, &DemangleConfig::new_g2dem()).as_deref(),
+            Ok(g2dem)
+        );
+        assert_eq!(
+            demangle(mangled, &DemangleConfig::new_cfilt()).as_deref(),
+            Ok(cfilt)
+        );
+    }
+
+    let mut config = DemangleConfig::new();
+    config.omit_template_args_in_scope = true;
+    assert_eq!(
+        demangle("__H1ZPCc_t6vector2ZcZt9allocator1ZcX00X00", &config).as_deref(),
+        Ok("vector::vector<char const *>(char const *, char const *)")
+    );
+
+    // There are no templated destructors.
+    assert_eq!(
+        demangle("_$_H1Zi_3Foo_v", &config),
+        Err(DemangleError::InvalidClassNameOnDestructor("H1Zi_3Foo_v"))
+    );
+}
+
+#[test]
+fn test_demangle_qualifier_ordering() {
+    static CASES: [(&str, &str); 40] = [
+        // Arguments
+        ("f__FPVc", "f(char volatile *)"),
+        ("f__FVPc", "f(char *volatile)"),
+        ("f__FPCVc", "f(char const volatile *)"),
+        ("f__FPVCc", "f(char const volatile *)"),
+        ("f__FCVPc", "f(char *const volatile)"),
+        ("f__FCPVc", "f(char volatile *const)"),
+        ("f__FRCVc", "f(char const volatile &)"),
+        ("f__FRCPVc", "f(char volatile *const &)"),
+        ("f__FuPc", "f(char *__restrict)"),
+        ("f__FPuc", "f(char __restrict *)"),
+        ("f__FuPCc", "f(char const *__restrict)"),
+        ("f__FPCVuc", "f(char const volatile __restrict *)"),
+        ("f__FPuVCc", "f(char const volatile __restrict *)"),
+        ("f__FCuPVc", "f(char volatile *const __restrict)"),
+        ("f__FPVSc", "f(signed char volatile *)"),
+        ("f__FRCVUc", "f(unsigned char const volatile &)"),
+        ("f__FuPVUi", "f(unsigned int volatile *__restrict)"),
+        ("f__FPCVA41_i", "f(int const volatile (*)[42])"),
+        // Return types
+        ("get__H1Zc_v_PCVX01", "char const volatile *get<char>(void)"),
+        ("get__H1Zc_v_RVX01", "char volatile &get<char>(void)"),
+        ("get__H1Zc_v_uPX01", "char *__restrict get<char>(void)"),
+        (
+            "get__H1Zc_v_CVPUX01",
+            "unsigned char *const volatile get<char>(void)",
+        ),
+        // Methods
+        ("foo__C3Foo", "Foo::foo(void) const"),
+        ("foo__V3Foo", "Foo::foo(void) volatile"),
+        ("foo__CV3Foo", "Foo::foo(void) const volatile"),
+        ("foo__u3Foo", "Foo::foo(void) __restrict"),
+        (
+            "__as__V3FooRCV3Foo",
+            "Foo::operator=(Foo const volatile &) volatile",
+        ),
+        (
+            "__opPCVc__3Foo",
+            "Foo::operator char const volatile *(void)",
+        ),
+        // Method pointers
+        ("f__FPM3FooVFPV3Foo_v", "f(void (Foo::*)() volatile)"),
+        ("f__FPM3FooCVFPCV3Foo_i", "f(int (Foo::*)() const volatile)"),
+        (
+            "f__FPM3FooVCFPVC3Foo_v",
+            "f(void (Foo::*)() const volatile)",
+        ),
+        ("f__FPM3FoouFPu3Foo_v", "f(void (Foo::*)() __restrict)"),
+        (
+            "f__FPM3FooCuFPCu3Foo_v",
+            "f(void (Foo::*)() const __restrict)",
+        ),
+        ("f__FVPM3FooFP3Foo_v", "f(void (Foo::*volatile)())"),
+        // Data member pointers
+        ("f__FPO3Foo_Vi", "f(int volatile (Foo::*))"),
+        ("f__FPO3Foo_CVi", "f(int const volatile (Foo::*))"),
+        ("f__FPO3Foo_PVc", "f(char volatile *(Foo::*))"),
+        ("f__FPO3Foo_uPc", "f(char *__restrict (Foo::*))"),
+        ("f__FVPO3Foo_i", "f(int (Foo::*volatile))"),
+        ("f__FuPO3Foo_i", "f(int (Foo::*__restrict))"),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+    }
+
+    let config = DemangleConfig::builder().emit_plain_restrict(true).build();
+    assert_eq!(
+        demangle("f__FCuPVc", &config).as_deref(),
+        Ok("f(char volatile *const restrict)")
+    );
+    assert_eq!(
+        demangle("foo__Cu3Foo", &config).as_deref(),
+        Ok("Foo::foo(void) const restrict")
+    );
+
+    // The `this` pointer of a method pointer must be as qualified as the
+    // method itself.
+    assert_eq!(
+        demangle("f__FPM3FooVFP3Foo_v", &config),
+        Err(DemangleError::MethodPointerMissingConstness("P3Foo_v"))
+    );
+}
+
+#[test]
+fn test_demangle_method_pointer_owners() {
+    static CASES: [(&str, &str); 9] = [
+        // Namespaced owners
+        (
+            "register__9PublisherPMQ23gui6WidgetFPQ23gui6Widget_viT1",
+            "Publisher::register(void (gui::Widget::*)(), int, void (gui::Widget::*)())",
+        ),
+        (
+            "f__FPMQ23gui6WidgetCFPCQ23gui6Widgeti_i",
+            "f(int (gui::Widget::*)(int) const)",
+        ),
+        // Templated owners
+        (
+            "f__FPMt7Wrapper1ZQ23gui6WidgetFPt7Wrapper1ZQ23gui6Widget_vT0",
+            "f(void (Wrapper<gui::Widget>::*)(), void (Wrapper<gui::Widget>::*)())",
+        ),
+        (
+            "f__FPMQ23guit4List1ZiCFPCQ23guit4List1Zi_i",
+            "f(int (gui::List<int>::*)() const)",
+        ),
+        (
+            "f__H1ZQ23gui6Widget_PMt7Wrapper1ZX01FPt7Wrapper1ZX01_v_v",
+            "void f<gui::Widget>(void (Wrapper<gui::Widget>::*)())",
+        ),
+        // The `this` pointer as a lookback to an outer argument
+        (
+            "f__FQ23gui6WidgetPMQ23gui6WidgetFPT0_v",
+            "f(gui::Widget, void (gui::Widget::*)())",
+        ),
+        (
+            "f__FQ23gui6WidgetPMQ23gui6WidgetCFPCT0_vT1",
+            "f(gui::Widget, void (gui::Widget::*)() const, void (gui::Widget::*)() const)",
+        ),
+        (
+            "f__FQ23gui6WidgetiPMQ23gui6WidgetFPT0i_vT2",
+            "f(gui::Widget, int, void (gui::Widget::*)(int), void (gui::Widget::*)(int))",
+        ),
+        (
+            "f__Ft4List1ZiPMt4List1ZiFPT0_v",
+            "f(List<int>, void (List<int>::*)())",
+        ),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(
+            demangle(mangled, &config).as_deref(),
+            Ok(demangled),
+            "{mangled}"
+        );
+    }
+
+    assert_eq!(
+        demangle("f__FPMQ23gui6WidgetFPQ23gui6Button_v", &config),
+        Err(DemangleError::MethodPointerWrongClassName(
+            "PQ23gui6Button_v"
+        ))
+    );
+    assert_eq!(
+        demangle("f__FiPMQ23gui6WidgetFPT0_v", &config),
+        Err(DemangleError::MethodPointerWrongClassName("PT0_v"))
+    );
+}
+
+#[test]
+fn test_demangle_type_pointer_spacing() {
+    static CASES: [(&str, [&str; 4]); 6] = [
+        (
+            "get__H1ZPc_PCc_PX01",
+            [
+                "char * * get<char *>(char const *)",
+                "char **get<char *>(char const *)",
+                "char** get<char*>(char const*)",
+                "char**get<char*>(char const*)",
+            ],
+        ),
+        (
+            "Get__H1ZPCc_CQ26Attrib9AttributeUi_RCX01",
+            [
+                "char const * const & Attrib::Attribute::Get<char const *>(unsigned int) const",
+                "char const *const &Attrib::Attribute::Get<char const *>(unsigned int) const",
+                "char const* const& Attrib::Attribute::Get<char const*>(unsigned int) const",
+                "char const*const&Attrib::Attribute::Get<char const*>(unsigned int) const",
+            ],
+        ),
+        (
+            "f__FRCPCc",
+            [
+                "f(char const *const &)",
+                "f(char const *const &)",
+                "f(char const* const&)",
+                "f(char const*const&)",
+            ],
+        ),
+        (
+            "f__FPFPc_PCc",
+            [
+                "f(char const *(*)(char *))",
+                "f(char const *(*)(char *))",
+                "f(char const* (*)(char*))",
+                "f(char const*(*)(char*))",
+            ],
+        ),
+        (
+            "f__FPA3_Pc",
+            [
+                "f(char * (*)[3])",
+                "f(char *(*)[4])",
+                "f(char* (*)[4])",
+                "f(char*(*)[4])",
+            ],
+        ),
+        (
+            "f__FPM3FooCFPC3FooPc_PCc",
+            [
+                "f(char const *(Foo::*)(char *) const)",
+                "f(char const *(Foo::*)(char *) const)",
+                "f(char const* (Foo::*)(char*) const)",
+                "f(char const*(Foo::*)(char*) const)",
+            ],
+        ),
+    ];
+    let configs = [
+        DemangleConfig::new_cfilt(),
+        DemangleConfig::new_g2dem(),
+        DemangleConfig::builder()
+            .type_pointer_spacing(Some(TypePointerSpacing::AfterType))
+            .build(),
+        DemangleConfig::builder()
+            .type_pointer_spacing(Some(TypePointerSpacing::Compact))
+            .build(),
+    ];
+
+    for (mangled, demangled) in CASES {
+        for (config, demangled) in configs.iter().zip(demangled) {
+            assert_eq!(Ok(demangled), demangle(mangled, config).as_deref());
+        }
+    }
+}
+
+#[test]
+fn test_demangle_array_qualifier_spacing() {
+    static CASES: [(&str, [&str; 4]); 10] = [
+        // Arguments
+        (
+            "f__FPA3_Pi",
+            [
+                "f(int * (*)[3])",
+                "f(int *(*)[4])",
+                "f(int* (*)[4])",
+                "f(int*(*)[4])",
+            ],
+        ),
+        (
+            "f__FRA3_A4_i",
+            [
+                "f(int (&)[3][4])",
+                "f(int (&)[4][5])",
+                "f(int (&)[4][5])",
+                "f(int (&)[4][5])",
+            ],
+        ),
+        // Cast targets
+        (
+            "__opPA3_Pi__5Thing",
+            [
+                "Thing::operator int * (*)[3](void)",
+                "Thing::operator int *(*)[4](void)",
+                "Thing::operator int* (*)[4](void)",
+                "Thing::operator int*(*)[4](void)",
+            ],
+        ),
+        (
+            "__opRA3_A4_i__5Thing",
+            [
+                "Thing::operator int (&)[3][4](void)",
+                "Thing::operator int (&)[4][5](void)",
+                "Thing::operator int (&)[4][5](void)",
+                "Thing::operator int (&)[4][5](void)",
+            ],
+        ),
+        // Types on their own
+        (
+            "__tfPA3_Pi",
+            [
+                "int * (*)[3] type_info function",
+                "int *(*)[4] type_info function",
+                "int* (*)[4] type_info function",
+                "int*(*)[4] type_info function",
+            ],
+        ),
+        (
+            "__tiA3_A2_f",
+            [
+                "float [3][2] type_info node",
+                "float [4][3] type_info node",
+                "float [4][3] type_info node",
+                "float [4][3] type_info node",
+            ],
+        ),
+        // Return types
+        (
+            "f__H1Zi_X01_PA3_Pi",
+            [
+                "int * (*)[3] f<int>(int)",
+                "int *(*f<int>(int))[3]",
+                "int* (*f<int>(int))[3]",
+                "int*(*f<int>(int))[3]",
+            ],
+        ),
+        (
+            "f__H1Zi_X01_PA3_A2_i",
+            [
+                "int (*)[3][2] f<int>(int)",
+                "int (*f<int>(int))[3][2]",
+                "int (*f<int>(int))[3][2]",
+                "int (*f<int>(int))[3][2]",
+            ],
+        ),
+        (
+            "f__H1Zi_X01_A3_A2_i",
+            [
+                "int [3][2] f<int>(int)",
+                "int (f<int>(int))[3][2]",
+                "int (f<int>(int))[3][2]",
+                "int (f<int>(int))[3][2]",
+            ],
+        ),
+        (
+            "f__H1Zi_X01_RA3_i",
+            [
+                "int (&)[3] f<int>(int)",
+                "int (&f<int>(int))[3]",
+                "int (&f<int>(int))[3]",
+                "int (&f<int>(int))[3]",
+            ],
+        ),
+    ];
+    let configs = [
+        DemangleConfig::new_cfilt(),
+        DemangleConfig::new_g2dem(),
+        DemangleConfig::builder()
+            .type_pointer_spacing(Some(TypePointerSpacing::AfterType))
+            .build(),
+        DemangleConfig::builder()
+            .type_pointer_spacing(Some(TypePointerSpacing::Compact))
+            .build(),
+    ];
+
+    for (mangled, demangled) in CASES {
+        for (config, demangled) in configs.iter().zip(demangled) {
+            assert_eq!(Ok(demangled), demangle(mangled, config).as_deref());
+        }
+    }
+}
+
+#[test]
+fn test_demangle_class_by_value_marker() {
+    static CASES: [(&str, &str); 14] = [
+        // Template arguments
+        ("f__Ft3Box1ZG4Node", "f(Box<Node>)"),
+        ("f__Ft4Pair2ZG4NodeZG4Node", "f(Pair<Node, Node>)"),
+        ("f__Ft3Box1Zt3Box1ZG4Node", "f(Box<Box<Node> >)"),
+        ("get__t3Box1ZG4NodeG4Node", "Box<Node>::get(Node)"),
+        ("__t3Box1ZG4NodeG4Node", "Box<Node>::Box(Node)"),
+        // `T` lookbacks
+        ("f__FG4NodeT0", "f(Node, Node)"),
+        ("f__FG4NodeRT0", "f(Node, Node &)"),
+        ("f__FG4NodePG4NodeT1", "f(Node, Node *, Node *)"),
+        ("f__Fit3Box1ZG4NodeT1", "f(int, Box<Node>, Box<Node>)"),
+        ("f__FG4NodePFT0_v", "f(Node, void (*)(Node))"),
+        // `N` repeats
+        ("f__FG4NodeN20", "f(Node, Node, Node)"),
+        ("f__FiG4NodeN21", "f(int, Node, Node, Node)"),
+        ("f__Ft3Box1ZG4NodeN20", "f(Box<Node>, Box<Node>, Box<Node>)"),
+        (
+            "get__t3Box1ZG4NodeG4NodeN21",
+            "Box<Node>::get(Node, Node, Node)",
+        ),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, demangled) in CASES {
+        assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+    }
+
+    // `G` must always be followed by a class-like type.
+    assert_eq!(
+        demangle("f__Ft3Box1ZGi", &config),
+        Err(DemangleError::PrimitiveInsteadOfClass("Gi"))
+    );
+}
+
+#[test]
+fn test_demangle_info() {
+    use Construct::*;
+
+    static ALL: [Construct; 31] = [
+        GlobalKeyedNamespaced,
+        ArrayArgument,
+        GlobalKeyedFrame,
+        Ellipsis,
+        ExtensionInt,
+        ArrayReturn,
+        FunctionPointerInTemplate,
+        StlContainer,
+        ConstructorVariant,
+        EnumInTemplate,
+        FunctionPointerReturn,
+        Restrict,
+        Declarator,
+        VendorType,
+        Repeat,
+        CloneSuffix,
+        StrayUnderscore,
+        FunctionPointerLookback,
+        IntegerInTemplate,
+        WideCharacterInTemplate,
+        TemplateParameterLevel,
+        Truncated,
+        GlobalKeyedTemplateInstantiation,
+        PlainCLookalike,
+        TopLevelConst,
+        AnonymousNamespace,
+        Malformed,
+        GlobalKeyedNested,
+        VTableOffset,
+        ArrayOfReferences,
+        StaticInitFunction,
+    ];
+    static CASES: [(&str, &[Construct]); 24] = [
+        ("SetText__5tNamePCc", &[Declarator]),
+        ("__5tName1", &[ConstructorVariant]),
+        ("foo__FPA3_iPCce", &[ArrayArgument, Ellipsis, Declarator]),
+        ("f__FPCVA41_i", &[ArrayArgument]),
+        ("f__FuPc", &[Restrict, Declarator]),
+        ("func__FI80", &[ExtensionInt]),
+        (
+            "_GLOBAL_$I$__Q210Scenegraph10Scenegraph",
+            &[GlobalKeyedNamespaced],
+        ),
+        ("_GLOBAL_$F$foo__Fi", &[GlobalKeyedFrame]),
+        ("insert__t6vector2ZiZt9allocator1Zi", &[StlContainer]),
+        ("foo__FRCt4List1Z5tNameN20", &[Declarator, Repeat]),
+        ("foo__Fi.23", &[CloneSuffix]),
+        (
+            "Pop__t14CAutoTransform121G3DTRANSFORMSTATETYPE0",
+            &[EnumInTemplate],
+        ),
+        (
+            "alloc__t5Table1PFUi_Pv16DefaultFunc__FUiUi",
+            &[FunctionPointerInTemplate, Declarator],
+        ),
+        (
+            "an_array__H1Zi_C14SomethingSillyX01_PA3_i",
+            &[ArrayArgument, ArrayReturn],
+        ),
+        ("SetText__5tNamePCcP5tNam", &[Truncated, Declarator]),
+        ("_GLOBAL_$T$foo__Fi", &[GlobalKeyedTemplateInstantiation]),
+        ("crc32__8_compute", &[PlainCLookalike]),
+        ("f__FC3Foo", &[TopLevelConst]),
+        ("Id__Q2_17_GLOBAL_.N.foo_cc3Bar", &[AnonymousNamespace]),
+        ("Id__Q03Foo", &[Malformed]),
+        ("_GLOBAL_$I$_GLOBAL_$D$gSomething", &[GlobalKeyedNested]),
+        ("_vt$7Derived$4Base$8", &[VTableOffset]),
+        ("f__FA9_Ri", &[ArrayArgument, ArrayOfReferences]),
+        (
+            "_GLOBAL_$I$__static_initialization_and_destruction_0__Fii",
+            &[StaticInitFunction],
+        ),
+    ];
+    let config = DemangleConfig::new();
+
+    for (mangled, constructs) in CASES {
+        let (demangled, info) = demangle_with_info(mangled, &config);
+        assert_eq!(demangle(mangled, &config), demangled);
+
+        for construct in ALL {
+            assert_eq!(
+                info.saw(construct),
+                constructs.contains(&construct),
+                "{mangled}: {construct:?}"
+            );
+        }
+    }
+
+    // The constructs found before failing are still reported.
+    let (demangled, info) = demangle_with_info("foo__FPCce_", &config);
+    assert!(demangled.is_err());
+    assert!(info.saw(Ellipsis));
+    assert!(info.saw(Declarator));
+
+    assert!(demangle_with_info("main", &config).1.is_empty());
+}
+
+#[test]
+fn test_demangle_both() {
+    static CASES: [(&str, &str, &str, &[&str]); 9] = [
+        (
+            "foo__Fi.23",
+            "foo(int)",
+            "foo(int) [clone .23]",
+            &["emit_clone_suffix"],
+        ),
+        (
+            "SetText__5tNamePCc",
+            "tName::SetText(char const *)",
+            "tName::SetText(char const *)",
+            &[],
+        ),
+        (
+            "foo__FPA3_iPCce",
+            "foo(int (*)[3], char const *,...)",
+            "foo(int (*)[4], char const *, ...)",
+            &["fix_array_length_arg", "ellipsis_emit_space_after_comma"],
+        ),
+        (
+            "func__FI80",
+            "func(int128_t)",
+            "func(__int128_t)",
+            &["fix_extension_int"],
+        ),
+        (
+            "_GLOBAL_$I$__Q210Scenegraph10Scenegraph",
+            "Scenegraph::Scenegraph::Scenegraph(void)",
+            "global constructors keyed to Scenegraph::Scenegraph::Scenegraph(void)",
+            &["fix_namespaced_global_constructor_bug"],
+        ),
+        (
+            "_GLOBAL_$F$foo__Fi",
+            "_GLOBAL_$F$foo(int)",
+            "global frames keyed to foo(int)",
+            &["demangle_global_keyed_frames"],
+        ),
+        (
+            "find__H2ZP5tNameZ5tName_X01X01RCX11G26random_access_iterator_tag_X01",
+            "tName * find<tName *, tName>(tName *, tName *, tName const &, random_access_iterator_tag)",
+            "tName *find<tName *, tName>(tName *, tName *, tName const &, random_access_iterator_tag)",
+            &["type_pointer_spacing"],
+        ),
+        (
+            "make_handler__H1Zi_X01_PFiX01_v",
+            "void (*)(int, int) make_handler<int>(int)",
+            "void (*make_handler<int>(int))(int, int)",
+            &["fix_function_pointer_in_return_position"],
+        ),
+        (
+            // The array length is not fixed up in the return type.
+            "an_array__H1Zi_C14SomethingSillyX01_PA3_i",
+            "int (*)[3] SomethingSilly::an_array<int>(int) const",
+            "int (*SomethingSilly::an_array<int>(int) const)[3]",
+            &["fix_array_in_return_position"],
+        ),
+    ];
+
+    for (mangled, cfilt, g2dem, changed_by) in CASES {
+        let both = demangle_both(mangled).unwrap();
+
+        assert_eq!(both.cfilt, cfilt);
+        assert_eq!(both.g2dem, g2dem);
+        assert_eq!(both.changed_by, changed_by, "{mangled}");
+        assert_eq!(
+            both.info,
+            demangle_with_info(mangled, &DemangleConfig::new_g2dem()).1
+        );
+    }
+
+    // c++filt can't demangle constructor variants.
+    assert_eq!(
+        demangle_both("__5tName1"),
+        Err(DemangleError::ConstructorVariantMarker("1"))
+    );
+}
+
+#[test]
+fn test_equivalent() {
+    static CASES: [(&str, &str, bool); 9] = [
+        // Same function from different compilers.
+        ("Debug_Assert__FPcT0T0i", "Debug_Assert__FPcN20i", true),
+        (
+            "Add__Q23sim9CollisionPQ23sim15CollisionObjectT1",
+            "Add__Q23sim9CollisionPQ23sim15CollisionObjectN11",
+            true,
+        ),
+        ("Get__H1Zi_X01_v", "Get__H1Zi_i_v", true),
+        ("_vt$3Foo", "_vt.3Foo", true),
+        // Top level `const` doesn't change the function.
+        ("f__FC3Fooi", "f__F3Fooi", true),
+        ("Debug_Assert__FPcT0T0i", "Debug_Assert__FPCcT0T0i", false),
+        ("Get__C3Foo", "Get__3Foo", false),
+        ("Get__t3Foo1Ui1", "Get__t3Foo1i1", false),
+        ("Get__t3Foo2c97w97", "Get__t3Foo2c97c97", false),
+    ];
+
+    for config in [DemangleConfig::new_g2dem(), DemangleConfig::new_cfilt()] {
+        for (a, b, expected) in CASES {
+            assert_eq!(equivalent(a, b, &config), Ok(expected), "{a} {b}");
+            assert_eq!(equivalent(b, a, &config), Ok(expected), "{b} {a}");
+        }
+    }
+
+    // Settings that leave out information don't make different symbols
+    // equivalent.
+    let config = DemangleConfig::builder().omit_parameter_list(true).build();
+    assert_eq!(
+        demangle("Update__3Simi", &config),
+        demangle("Update__3Simf", &config)
+    );
+    assert_eq!(
+        equivalent("Update__3Simi", "Update__3Simf", &config),
+        Ok(false)
+    );
+
+    let config = DemangleConfig::builder()
+        .max_template_depth(Some(1))
+        .build();
+    assert_eq!(
+        demangle("f__Ft3Foo1Zt3Bar1Zi", &config),
+        demangle("f__Ft3Foo1Zt3Bar1Zf", &config)
+    );
+    assert_eq!(
+        equivalent("f__Ft3Foo1Zt3Bar1Zi", "f__Ft3Foo1Zt3Bar1Zf", &config),
+        Ok(false)
+    );
+
+    assert_eq!(
+        equivalent("Update__3Simi", "Update", &DemangleConfig::new()),
+        Err(DemangleError::NotMangled)
+    );
+}
+
+#[test]
+fn test_demangle_clone_suffix() {
+    static CASES: [(&str, &str, &str); 5] = [
+        (
+            "SetText__5tNamePCc.1",
+            "tName::SetText(char const *) [clone .1]",
+            "tName::SetText(char const *)",
+        ),
+        ("foo__Fi.23", "foo(int) [clone .23]", "foo(int)"),
+        (
+            "_vt.5tName.4",
+            "tName virtual table [clone .4]",
+            "tName virtual table",
+        ),
+        (
+            "SetState__H1ZQ211CharacterAi4Loco_11CharacterAiPQ211CharacterAi12StateManager_v.1",
+            "void CharacterAi::SetState<CharacterAi::Loco>(CharacterAi::StateManager *) [clone .1]",
+            "void CharacterAi::SetState<CharacterAi::Loco>(CharacterAi::StateManager *)",
+        ),
+        // The class name legitimately ends in a `.<number>`.
+        (
+            "__7Foo.123",
+            "Foo.123::Foo.123(void)",
+            "Foo.123::Foo.123(void)",
+        ),
+    ];
+    let g2dem = DemangleConfig::new_g2dem();
+    let cfilt = DemangleConfig::new_cfilt();
+
+    for (mangled, demangled_g2dem, demangled_cfilt) in CASES {
+        assert_eq!(Ok(demangled_g2dem), demangle(mangled, &g2dem).as_deref());
+        assert_eq!(Ok(demangled_cfilt), demangle(mangled, &cfilt).as_deref());
+    }
+
+    let config = DemangleConfig::builder()
+        .demangle_clone_suffix(false)
+        .build();
+    assert_eq!(
+        demangle("foo__Fi.23", &config),
+        Err(DemangleError::UnknownType('.', ".23"))
+    );
+
+    // If the symbol without the suffix fails too, the error is the one of
+    // the whole symbol.
+    assert_eq!(
+        demangle("foo__FQ.1", &g2dem),
+        Err(DemangleError::InvalidNamespaceCount(".1"))
+    );
+    // Only a single suffix gets stripped.
+    assert_eq!(
+        demangle("foo__Fi.1.2", &g2dem),
+        Err(DemangleError::UnknownType('.', ".1.2"))
+    );
+    // Not a number.
+    assert_eq!(
+        demangle("foo__Fi.a", &g2dem),
+        Err(DemangleError::UnknownType('.', ".a"))
+    );
+    // The suffix is part of the counted class name, so stripping it would
+    // leave it too short.
+    assert_eq!(
+        demangle("_vt.7Foo.123", &g2dem).as_deref(),
+        Ok("Foo.123 virtual table")
+    );
+    assert_eq!(
+        demangle("SetText__5Foo.1PCc.2", &g2dem).as_deref(),
+        Ok("Foo.1::SetText(char const *) [clone .2]")
+    );
+    assert!(demangle("_vt.7Foo.12", &g2dem).is_err());
+}
+
+#[test]
+fn test_demangle_type_info_compound_types() {
+    static CASES: [(&str, &str, &str); 14] = [
+        (
+            "__tiPFv_v",
+            "void (*)(void) type_info node",
+            "void (*)(void) type_info node",
+        ),
+        (
+            "__tfPFv_v",
+            "void (*)(void) type_info function",
+            "void (*)(void) type_info function",
+        ),
+        (
+            "__tiPFiPCc_P9SomeClass",
+            "SomeClass *(*)(int, char const *) type_info node",
+            "SomeClass *(*)(int, char const *) type_info node",
+        ),
+        (
+            "__tiPM9SomeClassFP9SomeClass_v",
+            "void (SomeClass::*)() type_info node",
+            "void (SomeClass::*)() type_info node",
+        ),
+        (
+            "__tfPM9SomeClassFP9SomeClass_v",
+            "void (SomeClass::*)() type_info function",
+            "void (SomeClass::*)() type_info function",
+        ),
+        (
+            "__tiA5_9SomeClass",
+            "SomeClass [6] type_info node",
+            "SomeClass [5] type_info node",
+        ),
+        (
+            "__tfA5_9SomeClass",
+            "SomeClass [6] type_info function",
+            "SomeClass [5] type_info function",
+        ),
+        (
+            "__tiPA5_9SomeClass",
+            "SomeClass (*)[6] type_info node",
+            "SomeClass (*)[5] type_info node",
+        ),
+        (
+            "__tfPA5_9SomeClass",
+            "SomeClass (*)[6] type_info function",
+            "SomeClass (*)[5] type_info function",
+        ),
+        (
+            "__tiPA2_PFv_v",
+            "void (*(*)[3])(void) type_info node",
+            "void (*(*)[2])(void) type_info node",
+        ),
+        (
+            "__tiR9SomeClass",
+            "SomeClass & type_info node",
+            "SomeClass & type_info node",
+        ),
+        (
+            "__tfRC9SomeClass",
+            "SomeClass const & type_info function",
+            "SomeClass const & type_info function",
+        ),
+        (
+            "__tiRA5_9SomeClass",
+            "SomeClass (&)[6] type_info node",
+            "SomeClass (&)[5] type_info node",
+        ),
+        (
+            "__tfRFi_v",
+            "void (&)(int) type_info function",
+            "void (&)(int) type_info function",
+        ),
+    ];
+    let fixed = DemangleConfig::builder().fix_array_length_arg(true).build();
+    let unfixed = DemangleConfig::builder()
+        .fix_array_length_arg(false)
+        .build();
+
+    for (mangled, demangled_fixed, demangled_unfixed) in CASES {
+        assert_eq!(Ok(demangled_fixed), demangle(mangled, &fixed).as_deref());
+        assert_eq!(
+            Ok(demangled_unfixed),
+            demangle(mangled, &unfixed).as_deref()
+        );
+    }
+
+    assert_eq!(
+        demangle("__tie", &fixed),
+        Err(DemangleError::InvalidTypeOnTypeInfoNode("e"))
+    );
+    assert_eq!(
+        demangle("__tfN21", &fixed),
+        Err(DemangleError::InvalidTypeOnTypeInfoFunction("N21"))
+    );
+    assert_eq!(
+        demangle("__tiPFv_vi", &fixed),
+        Err(DemangleError::TrailingDataOnTypeInfoNode("i"))
+    );
+}
+
+#[test]
+fn test_demangle_constructor_names_of_nested_templates() {
+    static CASES: [(&str, &str); 20] = [
+        // Templated class
+        ("__t5TList1Zi", "TList<int>::TList(void)"),
+        ("_$_t5TList1Zi", "TList<int>::~TList(void)"),
+        (
+            "__t5TList1ZiRCt5TList1Zi",
+            "TList<int>::TList(TList<int> const &)",
+        ),
+        (
+            "__as__t10MapElement2ZiZcRCt10MapElement2ZiZc",
+            "MapElement<int, char>::operator=(MapElement<int, char> const &)",
+        ),
+        (
+            "__opi__t17ContiguousBinNode1Z11SpatialNode",
+            "ContiguousBinNode<SpatialNode>::operator int(void)",
+        ),
+        // Plain class nested in a templated class
+        ("__Q2t5Outer1Zi5Inner", "Outer<int>::Inner::Inner(void)"),
+        ("_$_Q2t5Outer1Zi5Inner", "Outer<int>::Inner::~Inner(void)"),
+        (
+            "__Q2t5Outer1Zi5InnerRCQ2t5Outer1Zi5Inner",
+            "Outer<int>::Inner::Inner(Outer<int>::Inner const &)",
+        ),
+        (
+            "__as__Q2t5Outer1Zi5InnerRCQ2t5Outer1Zi5Inner",
+            "Outer<int>::Inner::operator=(Outer<int>::Inner const &)",
+        ),
+        (
+            "__opi__Q2t5Outer1Zi5Inner",
+            "Outer<int>::Inner::operator int(void)",
+        ),
+        (
+            "_$_Q3t5Outer1Zit5Inner1Zc4Leaf",
+            "Outer<int>::Inner<char>::Leaf::~Leaf(void)",
+        ),
+        (
+            "__Q33simt5TList1Zi4Node",
+            "sim::TList<int>::Node::Node(void)",
+        ),
+        (
+            "_._Q33simt5TList1Zi4Node",
+            "sim::TList<int>::Node::~Node(void)",
+        ),
+        // Templated class nested in a namespace
+        (
+            "__Q26choreot13BlendPriority1ZQ25poser9Transform",
+            "choreo::BlendPriority<poser::Transform>::BlendPriority(void)",
+        ),
+        (
+            "_$_Q23simt5TList1ZPQ23sim15CollisionObject",
+            "sim::TList<sim::CollisionObject *>::~TList(void)",
+        ),
+        (
+            "__as__Q26choreot13BlendPriority1ZQ25poser9TransformRCQ26choreot13BlendPriority1ZQ25poser9Transform",
+            "choreo::BlendPriority<poser::Transform>::operator=(choreo::BlendPriority<poser::Transform> const &)",
+        ),
+        (
+            "__opi__Q26choreot13BlendPriority1ZQ25poser9Transform",
+            "choreo::BlendPriority<poser::Transform>::operator int(void)",
+        ),
+        (
+            "_$_Q23simt5TList1Zt4Pair2ZiZc",
+            "sim::TList<Pair<int, char> >::~TList(void)",
+        ),
+        (
+            "__Q2t5Outer1Zit5Inner1Zc",
+            "Outer<int>::Inner<char>::Inner(void)",
+        ),
+        (
+            "_$_Q2t5Outer1Zit5Inner1Zc",
+            "Outer<int>::Inner<char>::~Inner(void)",
+        ),
+    ];
+
+    for config in [DemangleConfig::new_g2dem(), DemangleConfig::new_cfilt()] {
+        for (mangled, demangled) in CASES {
+            assert_eq!(Ok(demangled), demangle(mangled, &config).as_deref());
+        }
+    }
+
+    let config = DemangleConfig::new();
+    for (mangled, leaf, leaf_kind) in [
+        ("_$_Q2t5Outer1Zi5Inner", "Inner", LeafKind::Destructor),
+        ("__Q2t5Outer1Zit5Inner1Zc", "Inner", LeafKind::Constructor),
+        (
+            "_$_Q23simt5TList1ZPQ23sim15CollisionObject",
+            "TList",
+            LeafKind::Destructor,
+        ),
+    ] {
+        let components = demangle_components(mangled, &config).unwrap();
+        assert_eq!(components.leaf(), leaf);
+        assert_eq!(components.leaf_kind(), leaf_kind);
+    }
+}
+
+#[test]
+fn test_demangle_stray_underscores_between_args() {
+    static CASES: [(&str, &str, DemangleError); 3] = [
+        (
+            "Debug_Assert__FPcN20_i",
+            "Debug_Assert(char *, char *, char *, int)",
+            DemangleError::TrailingDataAfterArgumentList("_i"),
+        ),
+        (
+            "Print__7ConsolePc_i",
+            "Console::Print(char *, int)",
+            DemangleError::TrailingDataAfterArgumentList("_i"),
+        ),
+        // Repeaters after the `_` can still refer to the arguments before it.
+        (
+            "Set__3FooiN21_Pc",
+            "Foo::Set(int, int, int, char *)",
+            DemangleError::TrailingDataAfterArgumentList("_Pc"),
+        ),
+    ];
+    let strict = DemangleConfig::new();
+    let tolerant = DemangleConfig::builder()
+        .tolerate_stray_underscores_between_args(true)
+        .build();
+
+    for (mangled, demangled, error) in CASES {
+        assert_eq!(demangle(mangled, &strict), Err(error));
+        assert_eq!(demangle(mangled, &DemangleConfig::new_cfilt()), Err(error));
+        assert_eq!(demangle(mangled, &tolerant).as_deref(), Ok(demangled));
+    }
+
+    // Only a lone `_` followed by more arguments is skipped.
+    assert_eq!(
+        demangle("foo__Fi_", &tolerant),
+        Err(DemangleError::TrailingDataAfterArgumentList("_"))
+    );
+    assert_eq!(
+        demangle("foo__Fi__i", &tolerant),
+        Err(DemangleError::TrailingDataAfterArgumentList("__i"))
+    );
+    assert_eq!(
+        demangle("foo__Fie_i", &tolerant),
+        Err(DemangleError::TrailingDataAfterEllipsis("_i"))
+    );
+}
+
+#[test]
+fn test_demangle_lenient() {
+    static CASES: [(&str, &str, DemangleError); 9] = [
+        // Zero namespace count
+        (
+            "Id__Q03Foo",
+            "Foo::Id(void)",
+            DemangleError::InvalidNamespaceCount("03Foo"),
+        ),
+        (
+            "Id__Q_0_3Fooi",
+            "Foo::Id(int)",
+            DemangleError::InvalidNamespaceCount("_0_3Fooi"),
+        ),
+        (
+            "__Q03Fooi",
+            "Foo::Foo(int)",
+            DemangleError::InvalidNamespaceCount("03Fooi"),
+        ),
+        (
+            "f__FRCQ03FooT0",
+            "f(Foo const &, Foo const &)",
+            DemangleError::InvalidNamespaceCount("03FooT0"),
+        ),
+        (
+            "_Q03Foo$bar",
+            "Foo::bar",
+            DemangleError::InvalidNamespaceCount("03Foo$bar"),
+        ),
+        // Trailing underscore
+        (
+            "Id__3Fooi_",
+            "Foo::Id(int)",
+            DemangleError::TrailingDataAfterArgumentList("_"),
+        ),
+        (
+            "Id__3Foo_",
+            "Foo::Id(void)",
+            DemangleError::TrailingDataAfterArgumentList("_"),
+        ),
+        (
+            "f__FPFi_v_",
+            "f(void (*)(int))",
+            DemangleError::TrailingDataAfterArgumentList("_"),
+        ),
+        // Both
+        (
+            "Id__Q03Foo_",
+            "Foo::Id(void)",
+            DemangleError::InvalidNamespaceCount("03Foo_"),
+        ),
+    ];
+    let strict = DemangleConfig::new();
+    let lenient = DemangleConfig::builder().lenient(true).build();
+
+    for (mangled, demangled, error) in CASES {
+        assert_eq!(demangle(mangled, &strict), Err(error));
+        assert_eq!(demangle(mangled, &DemangleConfig::new_cfilt()), Err(error));
+        assert_eq!(demangle(mangled, &lenient).as_deref(), Ok(demangled));
+    }
+
+    // Repeated method qualifiers are collapsed either way.
+    for config in [strict, lenient] {
+        assert_eq!(
+            demangle("Id__CC3Fooi", &config).as_deref(),
+            Ok("Foo::Id(int) const")
+        );
+        assert_eq!(
+            demangle("Id__CVC3Foo", &config).as_deref(),
+            Ok("Foo::Id(void) const volatile")
+        );
+    }
+
+    // Only a single `_` at the very end is skipped.
+    assert_eq!(
+        demangle("f__Fi__", &lenient),
+        Err(DemangleError::TrailingDataAfterArgumentList("__"))
+    );
+}
+
+#[test]
+fn test_demangle_empty_custom_names() {
+    static CASES: [(&str, &str, DemangleError); 11] = [
+        // Namespaces
+        (
+            "Get__Q23foo0",
+            "foo::<unnamed>::Get(void)",
+            DemangleError::EmptyCustomName("0"),
+        ),
+        (
+            "f__FQ23Foo0",
+            "f(Foo::<unnamed>)",
+            DemangleError::EmptyCustomName("0"),
+        ),
+        (
+            "_Q23foo0$bar",
+            "foo::<unnamed>::bar",
+            DemangleError::EmptyCustomName("0$bar"),
+        ),
+        // Classes
+        (
+            "get__C0",
+            "<unnamed>::get(void) const",
+            DemangleError::EmptyCustomName("0"),
+        ),
+        (
+            "__as__0R3Foo",
+            "<unnamed>::operator=(Foo &)",
+            DemangleError::EmptyCustomName("0R3Foo"),
+        ),
+        (
+            "_vt$0",
+            "<unnamed> virtual table",
+            DemangleError::EmptyCustomName("0"),
+        ),
+        // Constructors and destructors
+        (
+            "__Q23foo0i",
+            "foo::<unnamed>::<unnamed>(int)",
+            DemangleError::EmptyCustomName("0i"),
+        ),
+        (
+            "_$_0",
+            "<unnamed>::~<unnamed>(void)",
+            DemangleError::EmptyCustomName("0"),
+        ),
+        // Templated functions
+        (
+            "Get__H1Zi_Q23foo0_v",
+            "void foo::<unnamed>::Get<int>()",
+            DemangleError::EmptyCustomName("0_v"),
+        ),
+        // Symbols in template values
+        (
+            "f__t3Foo1P3Bar0",
+            "Foo<&<unnamed> >::f(void)",
+            DemangleError::EmptyCustomName("0"),
+        ),
+        (
+            "f__t3Foo1R3Bar0",
+            "Foo<<unnamed> >::f(void)",
+            DemangleError::EmptyCustomName("0"),
+        ),
+    ];
+    let strict = DemangleConfig::new();
+    let lenient = DemangleConfig::builder().lenient(true).build();
+
+    for (mangled, demangled, error) in CASES {
+        assert_eq!(demangle(mangled, &strict), Err(error));
+        assert_eq!(demangle(mangled, &DemangleConfig::new_cfilt()), Err(error));
+        assert_eq!(demangle(mangled, &lenient).as_deref(), Ok(demangled));
+    }
+
+    // Templates need a count after the name, which can't follow an empty
+    // name without being read as part of its length.
+    assert_eq!(
+        demangle("Get__t0", &strict),
+        Err(DemangleError::EmptyCustomName("0"))
+    );
+    assert_eq!(
+        demangle("Get__t0", &lenient),
+        Err(DemangleError::InvalidTemplateCount(""))
+    );
+}
+
+#[test]
+fn test_demangle_function_pointer_template_lookbacks() {
+    static CASES: [(&str, &str, &str); 6] = [
+        (
+            "call__H1ZPM6WidgetFP6Widgeti_v_X01_v",
+            "void call<void (Widget::*)(int)>(void (Widget::*)(int))",
+            "void call<void (Widget::*)(int)>(void (Widget::*)(int))",
+        ),
+        (
+            "call__H1ZPM6WidgetFP6Widgeti_v_PX01_v",
+            "void call<void (Widget::*)(int)>(void (Widget::**)(int))",
+            "void call<void (Widget::*)(int)>(void (Widget::*)(int) *)",
+        ),
+        (
+            "call__H1ZPM6WidgetFP6Widgeti_v_RX01_v",
+            "void call<void (Widget::*)(int)>(void (Widget::*&)(int))",
+            "void call<void (Widget::*)(int)>(void (Widget::*)(int) &)",
+        ),
+        (
+            "call__H1ZPFi_v_RCX01_v",
+            "void call<void (*)(int)>(void (*const &)(int))",
+            "void call<void (*)(int)>(void (*)(int) const &)",
+        ),
+        (
+            "call__H1ZPFPFi_v_c_PX01_v",
+            "void call<char (*)(void (*)(int))>(char (**)(void (*)(int)))",
+            "void call<char (*)(void (*)(int))>(char (*)(void (*)(int)) *)",
+        ),
+        // Arrays of function pointers are left as they are.
+        (
+            "call__H1ZPA2_PFi_v_PX01_v",
+            "void call<void (*(*)[3])(int)>(void (*(*)[3])(int) *)",
+            "void call<void (*(*)[2])(int)>(void (*(*)[2])(int) *)",
+        ),
+    ];
+    let g2dem = DemangleConfig::new_g2dem();
+    let cfilt = DemangleConfig::new_cfilt();
+
+    for (mangled, demangled_g2dem, demangled_cfilt) in CASES {
+        assert_eq!(Ok(demangled_g2dem), demangle(mangled, &g2dem).as_deref());
+        assert_eq!(Ok(demangled_cfilt), demangle(mangled, &cfilt).as_deref());
+    }
+
+    // Same as spelling out the qualified pointer instead of the lookback.
+    for spacing in [
+        None,
+        Some(TypePointerSpacing::BeforeStar),
+        Some(TypePointerSpacing::AfterType),
+        Some(TypePointerSpacing::Compact),
+    ] {
+        let config = DemangleConfig::builder()
+            .type_pointer_spacing(spacing)
+            .build();
+        assert_eq!(
+            demangle("call__H1ZPM6WidgetFP6Widgeti_v_PCX01_v", &config),
+            demangle(
+                "call__H1ZPM6WidgetFP6Widgeti_v_PCPM6WidgetFP6Widgeti_v_v",
+                &config
+            ),
+            "{spacing:?}"
+        );
+    }
+}
+
+#[test]
+fn test_demangle_ambiguous_lookback_indices() {
+    static CASES: [(&str, &str); 6] = [
+        // Only parseable as the lookback `T10`.
+        (
+            "foo__FcsilxfdrbwPcPsT10",
+            "foo(char, short, int, long, long long, float, double, long double, bool, wchar_t, char *, short *, char *)",
+        ),
+        (
+            "foo__FcsilxfdrbwPcN210",
+            "foo(char, short, int, long, long long, float, double, long double, bool, wchar_t, char *, char *, char *)",
+        ),
+        // Only parseable as the lookback `T1` followed by `3Foo`.
+        (
+            "foo__FcsilxfdrbwPcPsT13Foo",
+            "foo(char, short, int, long, long long, float, double, long double, bool, wchar_t, char *, short *, short, Foo)",
+        ),
+        (
+            "foo__FcsilxfdrbwPcPsPiPlN213Foo",
+            "foo(char, short, int, long, long long, float, double, long double, bool, wchar_t, char *, short *, int *, long *, short, short, Foo)",
+        ),
+        // There is no argument 13, so there is nothing to choose from.
+        ("foo__FiiT13Foo", "foo(int, int, int, Foo)"),
+        ("foo__FiiN213Foo", "foo(int, int, int, int, Foo)"),
+    ];
+
+    for (mangled, demangled) in CASES {
+        for config in [DemangleConfig::new_cfilt(), DemangleConfig::new_g2dem()] {
+            assert_eq!(demangle(mangled, &config).as_deref(), Ok(demangled));
+        }
+    }
+
+    // Many ambiguous indices that can't be parsed either way must fail in
+    // reasonable time instead of trying every combination.
+    let sym = format!("foo__Fi{}Q", "T00".repeat(4000));
+    assert!(demangle(&sym, &DemangleConfig::new()).is_err());
+}
+
+#[test]
+fn test_demangle_components_ambiguous_scope() {
+    static CASES: [(&str, &[ScopeKind]); 7] = [
+        ("a_function__4smolfffi", &[ScopeKind::Ambiguous]),
+        ("GetText__C5tName", &[ScopeKind::Class]),
+        (
+            "a_function__Q26medium3yesfffi",
+            &[ScopeKind::Namespace, ScopeKind::Ambiguous],
+        ),
+        ("__5tNamePCc", &[ScopeKind::Class]),
+        ("_$_5tName", &[ScopeKind::Class]),
+        ("__opi__5tName", &[ScopeKind::Class]),
+        ("Insert__t4List1Zi", &[ScopeKind::TemplateInstance]),
+    ];
+    let annotated = DemangleConfig::builder()
+        .annotate_ambiguous_scope(true)
+        .build();
+    let plain = DemangleConfig::new();
+
+    for (mangled, kinds) in CASES {
+        let components = demangle_components(mangled, &annotated).unwrap();
+        let found: Vec<_> = components.scope().iter().map(|x| x.kind()).collect();
+        assert_eq!(found, kinds, "{mangled}");
+
+        // The demangled output doesn't change.
+        assert_eq!(demangle(mangled, &annotated), demangle(mangled, &plain));
+    }
+
+    // Without the setting the innermost scope is always a class.
+    let components = demangle_components("a_function__4smolfffi", &plain).unwrap();
+    assert_eq!(components.scope()[0].kind(), ScopeKind::Class);
+}
+
+#[test]
+fn test_demangle_template_integer_suffixes() {
+    static CASES: [(&str, &str, &str); 9] = [
+        (
+            "template_with_many_numbers__FRt10Something32s39b1",
+            "template_with_many_numbers(Something3<39, true> &)",
+            "template_with_many_numbers(Something3<39, true> &)",
+        ),
+        (
+            "template_with_many_numbers__FRt10Something32Us39b1",
+            "template_with_many_numbers(Something3<39, true> &)",
+            "template_with_many_numbers(Something3<39, true> &)",
+        ),
+        (
+            "template_with_many_numbers__FRt10Something32i39b1",
+            "template_with_many_numbers(Something3<39, true> &)",
+            "template_with_many_numbers(Something3<39, true> &)",
+        ),
+        (
+            "template_with_many_numbers__FRt10Something32Ui39b1",
+            "template_with_many_numbers(Something3<39, true> &)",
+            "template_with_many_numbers(Something3<39u, true> &)",
+        ),
+        (
+            "template_with_many_numbers__FRt10Something32l39b1",
+            "template_with_many_numbers(Something3<39, true> &)",
+            "template_with_many_numbers(Something3<39l, true> &)",
+        ),
+        (
+            "template_with_many_numbers__FRt10Something32Ul39b1",
+            "template_with_many_numbers(Something3<39, true> &)",
+            "template_with_many_numbers(Something3<39ul, true> &)",
+        ),
+        (
+            "template_with_many_numbers__FRt10Something32x39b1",
+            "template_with_many_numbers(Something3<39, true> &)",
+            "template_with_many_numbers(Something3<39ll, true> &)",
+        ),
+        (
+            "template_with_many_numbers__FRt10Something32xm39b1",
+            "template_with_many_numbers(Something3<-39, true> &)",
+            "template_with_many_numbers(Something3<-39ll, true> &)",
+        ),
+        (
+            "template_with_many_numbers__FRt10Something32Ux_12_b1",
+            "template_with_many_numbers(Something3<12, true> &)",
+            "template_with_many_numbers(Something3<12ull, true> &)",
+        ),
+    ];
+    let plain = DemangleConfig::new();
+    let suffixed = DemangleConfig::builder()
+        .emit_integer_suffixes_in_templates(true)
+        .build();
+
+    for (mangled, demangled_plain, demangled_suffixed) in CASES {
+        assert_eq!(Ok(demangled_plain), demangle(mangled, &plain).as_deref());
+        assert_eq!(
+            Ok(demangled_plain),
+            demangle(mangled, &DemangleConfig::new_cfilt()).as_deref()
+        );
+        assert_eq!(
+            Ok(demangled_suffixed),
+            demangle(mangled, &suffixed).as_deref()
+        );
+    }
+}
+
+#[test]
+fn test_demangle_wide_character_template_values() {
+    static CASES: [(&str, &str, &str); 6] = [
+        (
+            "Get__t3Foo2c97w97",
+            "Foo<'a', L'a'>::Get(void)",
+            "Foo<'a', 'a'>::Get(void)",
+        ),
+        (
+            "Get__t3Foo2w97c97",
+            "Foo<L'a', 'a'>::Get(void)",
+            "Foo<'a', 'a'>::Get(void)",
+        ),
+        // Lookbacks reuse the value as it was demangled.
+        (
+            "Get__Q2t5Outer1w97t5Inner1wY01",
+            "Outer<L'a'>::Inner<L'a'>::Get(void)",
+            "Outer<'a'>::Inner<'a'>::Get(void)",
+        ),
+        (
+            "Get__Q2t5Outer2c97w97t5Inner2cY01wY11",
+            "Outer<'a', L'a'>::Inner<'a', L'a'>::Get(void)",
+            "Outer<'a', 'a'>::Inner<'a', 'a'>::Get(void)",
+        ),
+        (
+            "Get__t3Foo1w128512",
+            "Foo<L'\\U0001F600'>::Get(void)",
+            "Foo<'\u{1F600}'>::Get(void)",
+        ),
+        (
+            "Get__t3Foo1w8364",
+            "Foo<L'\u{20AC}'>::Get(void)",
+            "Foo<'\u{20AC}'>::Get(void)",
+        ),
+    ];
+    let wide = DemangleConfig::new();
+    let cfilt = DemangleConfig::new_cfilt();
+
+    for (mangled, demangled_wide, demangled_cfilt) in CASES {
+        assert_eq!(Ok(demangled_wide), demangle(mangled, &wide).as_deref());
+        assert_eq!(Ok(demangled_cfilt), demangle(mangled, &cfilt).as_deref());
+    }
+
+    // Values that aren't characters can only be spelled as an escape.
+    assert_eq!(
+        demangle("Get__t3Foo1w55296", &wide).as_deref(),
+        Ok("Foo<L'\\xd800'>::Get(void)")
+    );
+    assert_eq!(
+        demangle("Get__t3Foo1w55296", &cfilt),
+        Err(DemangleError::InvalidTemplatedCharacterValue("", 55296))
+    );
+}
+
+#[test]
+fn test_demangle_template_parameter_levels() {
+    static CASES: [(&str, &str, Result<&str, DemangleError>); 4] = [
+        // The level 0 is the template list of the method, the level 1 the
+        // one of its class.
+        (
+            "Get__H1Zf_t4Pair2ZiZdX00X01X11_v",
+            "void Pair<int, double>::Get<float>(float, int, double)",
+            Err(DemangleError::IndexTooBigForXArgument("_v", 1)),
+        ),
+        (
+            "Get__H1Zf_Q23Foot4Pair2ZiZdX00X11_X01",
+            "int Foo::Pair<int, double>::Get<float>(float, double)",
+            Err(DemangleError::IndexTooBigForXArgument("_X01", 1)),
+        ),
+        // Every templated class of the scope is its own level.
+        (
+            "Get__H1Zf_Q2t5Outer1Zct4Pair2ZiZdX00X01X11X02_v",
+            "void Outer<char>::Pair<int, double>::Get<float>(float, int, double, char)",
+            Err(DemangleError::IndexTooBigForXArgument("X02_v", 1)),
+        ),
+        // Methods of plain classes only have their own template list.
+        (
+            "Set__H2ZiZc_3FooX01X11_v",
+            "void Foo::Set<int, char>(int, char)",
+            Ok("void Foo::Set<int, char>(int, char)"),
+        ),
+    ];
+    let g2dem = DemangleConfig::new_g2dem();
+    let cfilt = DemangleConfig::new_cfilt();
+
+    for (mangled, demangled_g2dem, demangled_cfilt) in CASES {
+        assert_eq!(Ok(demangled_g2dem), demangle(mangled, &g2dem).as_deref());
+        assert_eq!(demangled_cfilt.map(String::from), demangle(mangled, &cfilt));
+    }
+
+    // The template list of the method when its class is templated too.
+    assert_eq!(
+        demangle("__eq__H1ZUi_t5MyVec1ZiRCX01_b", &g2dem).as_deref(),
+        Ok("bool MyVec<int>::operator==<unsigned int>(int const &)")
+    );
+    assert_eq!(
+        demangle("__eq__H1ZUi_t5MyVec1ZiRCX01_b", &cfilt).as_deref(),
+        Ok("bool MyVec<int>::operator==<unsigned int>(unsigned int const &)")
+    );
+
+    // Levels past the outermost template list. c++filt ignores the level.
+    assert_eq!(
+        demangle("Get__H1Zf_t4Pair2ZiZdX02_v", &g2dem),
+        Err(DemangleError::InvalidNumber1OnXArgument("_v", 2))
+    );
+    assert_eq!(
+        demangle("Set__H2ZiZc_3FooX02X12_v", &g2dem),
+        Err(DemangleError::InvalidNumber1OnXArgument("X12_v", 2))
+    );
+    assert_eq!(
+        demangle("Set__H2ZiZc_3FooX02X12_v", &cfilt).as_deref(),
+        Ok("void Foo::Set<int, char>(int, char)")
+    );
+}
+
+#[test]
+fn test_demangle_truncated_symbols() {
+    static CASES: [(&str, DemangleError, &str); 7] = [
+        (
+            "CollisionEvent__Q23sim20CollisionSolverAgentPQ23sim8SimStateiPQ23s",
+            DemangleError::LikelyTruncated("3s", 2),
+            "sim::CollisionSolverAgent::CollisionEvent(sim::SimState *, int, <truncated>)",
+        ),
+        (
+            "CollisionEvent__Q23sim20CollisionSolverAgentPQ23sim8SimStateiPQ23sim15Collis",
+            DemangleError::LikelyTruncated("15Collis", 9),
+            "sim::CollisionSolverAgent::CollisionEvent(sim::SimState *, int, <truncated>)",
+        ),
+        (
+            "CreateRoadBlock__12AICopManagerP8IPursui",
+            DemangleError::LikelyTruncated("8IPursui", 1),
+            "AICopManager::CreateRoadBlock(<truncated>)",
+        ),
+        // Inside of a template list.
+        (
+            "CreateRoadBlock__12AICopManagerP8IPursuitiP8IVehiclePQ43UTL11Collectionst11ListableSet4Z8I",
+            DemangleError::LikelyTruncated("8I", 7),
+            "AICopManager::CreateRoadBlock(IPursuit *, int, IVehicle *, <truncated>)",
+        ),
+        (
+            "CreateRoadBlock__12AICopManagerP8IPursuitiP8IVehiclePQ43UTL11Collectionst11ListableSet4Z8IVehiclei10Z12eVehicl",
+            DemangleError::LikelyTruncated("12eVehicl", 5),
+            "AICopManager::CreateRoadBlock(IPursuit *, int, IVehicle *, <truncated>)",
+        ),
+        (
+            "SetText__5tNamePC5tNam",
+            DemangleError::LikelyTruncated("5tNam", 1),
+            "tName::SetText(<truncated>)",
+        ),
+        (
+            "__5tName3i",
+            DemangleError::LikelyTruncated("3i", 2),
+            "tName::tName(<truncated>)",
+        ),
+    ];
+    let config = DemangleConfig::new();
+    let completing = DemangleConfig::builder()
+        .complete_truncated_symbols(true)
+        .build();
+
+    for (mangled, err, completed) in CASES {
+        assert_eq!(Err(err), demangle(mangled, &config));
+        assert_eq!(Err(err), demangle(mangled, &DemangleConfig::new_cfilt()));
+        assert_eq!(Ok(completed), demangle(mangled, &completing).as_deref());
+    }
+
+    // Cut before the argument list, or right after the length of a name.
+    static UNCOMPLETED: [(&str, DemangleError); 3] = [
+        ("SetText__5tNa", DemangleError::LikelyTruncated("5tNa", 2)),
+        ("_$_5tNa", DemangleError::LikelyTruncated("5tNa", 2)),
+        (
+            "CollisionEvent__Q23sim20CollisionSolverAgentPQ23sim8SimStateiPQ23sim15",
+            DemangleError::LikelyTruncated("15", 15),
+        ),
+    ];
+    for (mangled, err) in UNCOMPLETED {
+        assert_eq!(Err(err), demangle(mangled, &completing));
+    }
+
+    // A constructor variant marker is not mistaken for a truncated name.
+    assert_eq!(
+        demangle("__5tName2i", &completing).as_deref(),
+        Ok("tName::tName(int) [not-in-charge]")
+    );
+}
+
+#[test]
+fn test_demangle_output_shaping() {
+    // Demangled symbol, without the parameter list, without the return type,
+    // without the scope template arguments, and with every setting turned on.
+    static CASES: [(&str, [&str; 5]); 14] = [
+        (
+            "SetText__5tNamePCc",
+            [
+                "tName::SetText(char const *)",
+                "tName::SetText",
+                "tName::SetText(char const *)",
+                "tName::SetText(char const *)",
+                "tName::SetText",
+            ],
+        ),
+        (
+            "GetText__C5tNamei",
+            [
+                "tName::GetText(int) const",
+                "tName::GetText",
+                "tName::GetText(int) const",
+                "tName::GetText(int) const",
+                "tName::GetText",
+            ],
+        ),
+        (
+            "__5tNamePCc",
+            [
+                "tName::tName(char const *)",
+                "tName::tName",
+                "tName::tName(char const *)",
+                "tName::tName(char const *)",
+                "tName::tName",
+            ],
+        ),
+        (
+            "_$_5tName",
+            [
+                "tName::~tName(void)",
+                "tName::~tName",
+                "tName::~tName(void)",
+                "tName::~tName(void)",
+                "tName::~tName",
+            ],
+        ),
+        (
+            "__eq__C5tNameRC5tName",
+            [
+                "tName::operator==(tName const &) const",
+                "tName::operator==",
+                "tName::operator==(tName const &) const",
+                "tName::operator==(tName const &) const",
+                "tName::operator==",
+            ],
+        ),
+        (
+            "__opi__C5tName",
+            [
+                "tName::operator int(void) const",
+                "tName::operator int",
+                "tName::operator int(void) const",
+                "tName::operator int(void) const",
+                "tName::operator int",
+            ],
+        ),
+        (
+            "AddPair__Q33sim16CollisionManager4AreaPQ23sim15CollisionObjectT1",
+            [
+                "sim::CollisionManager::Area::AddPair(sim::CollisionObject *, sim::CollisionObject *)",
+                "sim::CollisionManager::Area::AddPair",
+                "sim::CollisionManager::Area::AddPair(sim::CollisionObject *, sim::CollisionObject *)",
+                "sim::CollisionManager::Area::AddPair(sim::CollisionObject *, sim::CollisionObject *)",
+                "sim::CollisionManager::Area::AddPair",
+            ],
+        ),
+        (
+            "Swap__H1Zi_RX01T0_v",
+            [
+                "void Swap<int>(int &, int &)",
+                "void Swap<int>",
+                "Swap<int>(int &, int &)",
+                "void Swap<int>(int &, int &)",
+                "Swap<int>",
+            ],
+        ),
+        (
+            "lookup__H1Z5tName_PFi_X01_PFRCX01_X01",
+            [
+                "tName (*lookup<tName>(tName (*)(int)))(tName const &)",
+                "tName (*lookup<tName>)(tName const &)",
+                "lookup<tName>(tName (*)(int))",
+                "tName (*lookup<tName>(tName (*)(int)))(tName const &)",
+                "lookup<tName>",
+            ],
+        ),
+        (
+            "Insert__t4List1Z5tNameRC5tName",
+            [
+                "List<tName>::Insert(tName const &)",
+                "List<tName>::Insert",
+                "List<tName>::Insert(tName const &)",
+                "List::Insert(tName const &)",
+                "List::Insert",
+            ],
+        ),
+        (
+            "__t4List1Zi",
+            [
+                "List<int>::List(void)",
+                "List<int>::List",
+                "List<int>::List(void)",
+                "List::List(void)",
+                "List::List",
+            ],
+        ),
+        (
+            "_S_oom_malloc__t23__malloc_alloc_template1i0Ui",
+            [
+                "__malloc_alloc_template<0>::_S_oom_malloc(unsigned int)",
+                "__malloc_alloc_template<0>::_S_oom_malloc",
+                "__malloc_alloc_template<0>::_S_oom_malloc(unsigned int)",
+                "__malloc_alloc_template::_S_oom_malloc(unsigned int)",
+                "__malloc_alloc_template::_S_oom_malloc",
+            ],
+        ),
+        (
+            "_M_range_insert__H1ZPC5tName_t6vector2Z5tNameZt7s2alloc1Z5tNameP5tNameX00X00G20forward_iterator_tag_v",
+            [
+                "void vector<tName, s2alloc<tName> >::_M_range_insert<tName const *>(tName *, tName const *, tName const *, forward_iterator_tag)",
+                "void vector<tName, s2alloc<tName> >::_M_range_insert<tName const *>",
+                "vector<tName, s2alloc<tName> >::_M_range_insert<tName const *>(tName *, tName const *, tName const *, forward_iterator_tag)",
+                "void vector::_M_range_insert<tName const *>(tName *, tName const *, tName const *, forward_iterator_tag)",
+                "vector::_M_range_insert<tName const *>",
+            ],
+        ),
+        (
+            "_GLOBAL_$I$SetText__5tNamePCc",
+            [
+                "global constructors keyed to tName::SetText(char const *)",
+                "global constructors keyed to tName::SetText",
+                "global constructors keyed to tName::SetText(char const *)",
+                "global constructors keyed to tName::SetText(char const *)",
+                "global constructors keyed to tName::SetText",
+            ],
+        ),
+    ];
+    let configs = [
+        DemangleConfig::new(),
+        DemangleConfig::builder().omit_parameter_list(true).build(),
+        DemangleConfig::builder()
+            .return_type_style(ReturnTypeStyle::Omit)
+            .build(),
+        DemangleConfig::builder()
+            .omit_template_args_in_scope(true)
+            .build(),
+        DemangleConfig::builder()
+            .omit_parameter_list(true)
+            .return_type_style(ReturnTypeStyle::Omit)
+            .omit_template_args_in_scope(true)
+            .build(),
+    ];
+
+    for (mangled, expected) in CASES {
+        for (config, demangled) in configs.iter().zip(expected) {
+            assert_eq!(demangle(mangled, config).as_deref(), Ok(demangled));
+        }
+    }
+
+    // Symbols that aren't functions are left as they are.
+    for config in &configs {
+        assert_eq!(
+            demangle("_vt$5tName", config).as_deref(),
+            Ok("tName virtual table")
+        );
+    }
+}
+
+#[test]
+fn test_demangle_multi_byte() {
+    // Multi-byte characters at the start, in the middle of a name, right
+    // after a length, inside of a template and in the payload of a keyed
+    // global.
+    static CASES: [&str; 8] = [
+        "ñSetText__5tNamePCc",
+        "SetText__5tNameñPCc",
+        "SetText__5ñNamePCc",
+        "SetText__5tNamePCcñ",
+        "Insert__t4List1Z5tñameRC5tName",
+        "Swap__H1Z€_RX01T0_v",
+        "_GLOBAL_$I$SetText__5tNamePCcñ",
+        "_$_５tName",
+    ];
+    let configs = [DemangleConfig::new(), DemangleConfig::new_cfilt()];
+
+    for sym in CASES {
+        for config in &configs {
+            assert_eq!(demangle(sym, config), Err(DemangleError::NonAscii));
+            assert_eq!(
+                demangle_with_info(sym, config).0,
+                Err(DemangleError::NonAscii)
+            );
+            assert_eq!(
+                demangle_components(sym, config).map(|_| ()),
+                Err(DemangleError::NonAscii)
+            );
+        }
+        assert_eq!(demangle_both(sym).map(|_| ()), Err(DemangleError::NonAscii));
+    }
+}
+
+#[test]
+fn test_demangle_partial() {
+    static CASES: [&str; 10] = [
+        "SetText__5tNamePCc",
+        "GetText__C5tNamei",
+        "_$_5tName",
+        "__eq__C5tNameRC5tName",
+        "Swap__H1Zi_RX01T0_v",
+        "AddPair__Q33sim16CollisionManager4AreaPQ23sim15CollisionObjectT1",
+        "_GLOBAL_$I$SetText__5tNamePCc",
+        "_vt$5tName",
+        "_M_range_insert__H1ZPC5tName_t6vector2Z5tNameZt7s2alloc1Z5tNameP5tNameX00X00G20forward_iterator_tag_v",
+        "CreateRoadBlock__12AICopManagerP8IPursuitiP8IVehiclePQ43UTL11Collectionst11ListableSet4Z8IVehiclei10Z12eVehicleListUi10_4List",
+    ];
+
+    for config in [DemangleConfig::new(), DemangleConfig::new_cfilt()] {
+        for sym in CASES {
+            let expected = demangle(sym, &config).unwrap();
+
+            for chunk_size in [1, 7, 64] {
+                let mut partial = DemanglePartial::new(&config);
+                let mut last = None;
+                for chunk in sym.as_bytes().chunks(chunk_size) {
+                    let result = partial.feed(core::str::from_utf8(chunk).unwrap());
+                    // The start of a valid symbol is never rejected.
+                    assert!(
+                        !matches!(result, FeedResult::Error(_)),
+                        "{:?}: {result:?}",
+                        partial.input()
+                    );
+                    last = Some(result);
+                }
+
+                assert_eq!(last, Some(FeedResult::Complete(expected.clone())));
+                assert_eq!(partial.input(), sym);
+                assert_eq!(partial.finish().as_ref(), Ok(&expected));
+            }
+        }
+    }
+
+    // Missing data, like a name shorter than its length says.
+    let mut partial = DemanglePartial::new(&DemangleConfig::new());
+    assert_eq!(partial.feed("SetText__5tNam"), FeedResult::NeedMoreInput);
+    // A whole symbol by itself, but more input may follow.
+    assert_eq!(
+        partial.feed("e"),
+        FeedResult::Complete("tName::SetText(void)".to_string())
+    );
+    assert_eq!(
+        partial.feed("PCc"),
+        FeedResult::Complete("tName::SetText(char const *)".to_string())
+    );
+    partial.reset();
+    assert_eq!(partial.feed("f__FiN"), FeedResult::NeedMoreInput);
+    assert_eq!(
+        partial.clone().finish(),
+        Err(DemangleError::InvalidRepeatingArgument("N").into_owned())
+    );
+
+    // Data that is already there and can't be fixed by more input.
+    static INVALID: [(&str, DemangleError); 4] = [
+        ("_$_5tNamei", DemangleError::TrailingDataOnDestructor("i")),
+        ("SetTime__5TimerJ", DemangleError::UnknownType('J', "J")),
+        (
+            "f__Ft3Box1ZGi",
+            DemangleError::PrimitiveInsteadOfClass("Gi"),
+        ),
+        ("SetText__5tNamePCcñ", DemangleError::NonAscii),
+    ];
+    for (sym, err) in INVALID {
+        let mut partial = DemanglePartial::new(&DemangleConfig::new());
+        assert_eq!(partial.feed(sym), FeedResult::Error(err.into_owned()));
+        assert_eq!(partial.finish(), Err(err.into_owned()));
+    }
+}
+
+#[test]
+fn test_demangle_output_limits() {
+    static CASES: [&str; 7] = [
+        "i_hope_nobody_actually_writes_something_like_this__FPFPPFGQ213radPs2CdDrive14DirectoryEntryiPCQ213radPs2CdDrive14DirectoryEntry_Q213radPs2CdDrive14DirectoryEntryPFGQ213radPs2CdDrive14DirectoryEntryiPCQ213radPs2CdDrive14DirectoryEntry_Q213radPs2CdDrive14DirectoryEntryGQ213radPs2CdDrive14DirectoryEntry_PFGQ213radPs2CdDrive14DirectoryEntryiPCQ213radPs2CdDrive14DirectoryEntry_Q213radPs2CdDrive14DirectoryEntryPPFGQ213radPs2CdDrive14DirectoryEntryiPCQ213radPs2CdDrive14DirectoryEntry_Q213radPs2CdDrive14DirectoryEntryT0",
+        "do_thing__C6StupidRC6StupidN25_1",
+        "BlendDriverWithContext__H3ZQ218RadicalMathLibrary10QuaternionZRCQ25poser9TransformZQ26choreo15RootBlendDriver_6choreoX11PX21PMX21CFPCX21X11RX01_vfiPQ26choreot13BlendPriority1ZX01iRi_v",
+        "SetState__H9ZQ35Other11CharacterAi4LocoZQ35Other11CharacterAi12StateManagerZiZiZiZiZiZQ213radPs2CdDrive14DirectoryEntryZQ35Other11CharacterAi4Loco_Q25Other11CharacterAiRX11X01X21X31X41X51X61X71X81_v",
+        "find__t8_Rb_tree5ZUiZt4pair2ZCUiZiZt10_Select1st1Zt4pair2ZCUiZiZt4less1ZUiZt9allocator1ZiRCUi",
+        "_M_range_insert__H1ZPC5tName_t6vector2Z5tNameZt7s2alloc1Z5tNameP5tNameX00X00G20forward_iterator_tag_v",
+        "CreateRoadBlock__12AICopManagerP8IPursuitiP8IVehiclePQ43UTL11Collectionst11ListableSet4Z8IVehiclei10Z12eVehicleListUi10_4List",
+    ];
+
+    /// Brackets are balanced, and returns how deep template lists go.
+    fn template_depth(demangled: &str) -> usize {
+        let mut open = Vec::new();
+        let mut deepest = 0;
+        for c in demangled.chars() {
+            match c {
+                '<' | '(' => open.push(c),
+                '>' => assert_eq!(open.pop(), Some('<'), "{demangled}"),
+                ')' => assert_eq!(open.pop(), Some('('), "{demangled}"),
+                _ => {}
+            }
+            deepest = deepest.max(open.iter().filter(|c| **c == '<').count());
+        }
+        assert!(open.is_empty(), "{demangled}");
+        deepest
+    }
+
+    for sym in CASES {
+        let full = demangle(sym, &DemangleConfig::new()).unwrap();
+        let full_depth = template_depth(&full);
+
+        for max_depth in 0..3 {
+            let config = DemangleConfig::builder()
+                .max_template_depth(Some(max_depth))
+                .build();
+            let limited = demangle(sym, &config).unwrap();
+
+            assert_eq!(template_depth(&limited), full_depth.min(max_depth + 1));
+            assert_eq!(limited == full, full_depth <= max_depth, "{limited}");
+        }
+
+        for max_len in (0..full.len() + 10).step_by(7) {
+            let config = DemangleConfig::builder()
+                .max_output_len(Some(max_len))
+                .build();
+            let cut = demangle(sym, &config).unwrap();
+
+            if full.len() <= max_len {
+                assert_eq!(cut, full);
+                continue;
+            }
+            template_depth(&cut);
+
+            let (prefix, closing) = cut.split_once('…').unwrap();
+            assert!(full.starts_with(prefix), "{cut}");
+            assert!(
+                closing.chars().all(|c| matches!(c, '>' | ')' | ' ')),
+                "{cut}"
+            );
+            // Never inside of a name.
+            assert!(prefix.ends_with(['<', '(', ' ', '>', ')']), "{cut}");
+            // Too long only if there was no shorter way to cut it.
+            assert!(
+                cut.chars().count() <= max_len || prefix.find(['<', '(']) == Some(prefix.len() - 1),
+                "{cut}"
+            );
+        }
+    }
+
+    // Brackets of operator names and character values are not structure.
+    let config = DemangleConfig::builder()
+        .max_template_depth(Some(0))
+        .max_output_len(Some(25))
+        .build();
+    assert_eq!(
+        demangle("__ls__FR7ostreamRCt4List1Z5tName", &config).as_deref(),
+        Ok("operator<<(ostream &, …)")
+    );
+    let config = DemangleConfig::builder().max_output_len(Some(20)).build();
+    assert_eq!(
+        demangle("f__FRt3Foo1c40t3Bar1c62", &config).as_deref(),
+        Ok("f(Foo<'('> &, …)")
+    );
+
+    // Simplified containers are limited to the depth they were mangled at.
+    let config = DemangleConfig::builder()
+        .simplify_stl_containers(true)
+        .max_template_depth(Some(1))
+        .build();
+    assert_eq!(
+        demangle(
+            "foo__FRt6vector2Zt6vector2ZiZt9allocator1ZiZt9allocator1Zt6vector2ZiZt9allocator1Zi",
+            &config
+        )
+        .as_deref(),
+        Ok("foo(vector<vector<...> > &)")
+    );
+    assert_eq!(
+        demangle("find__t8_Rb_tree5ZUiZt4pair2ZCUiZiZt10_Select1st1Zt4pair2ZCUiZiZt4less1ZUiZt9allocator1ZiRCUi", &config).as_deref(),
+        Ok("map<unsigned int, int>::find(unsigned int const &)")
+    );
+
+    // Arguments of a templated function are written at the depth they are
+    // used at, not the one of its template list.
+    let config = DemangleConfig::builder()
+        .max_template_depth(Some(1))
+        .build();
+    assert_eq!(
+        demangle("foo__FGt4List1Zt4List1Zi", &config).as_deref(),
+        Ok("foo(List<List<...> >)")
+    );
+    assert_eq!(
+        demangle("foo__H1Zt4List1Zt4List1Zi_X01_v", &config).as_deref(),
+        Ok("void foo<List<...> >(List<List<...> >)")
+    );
+}
+
+#[test]
+fn test_demangle_output_style() {
+    static CASES: [(&str, &str, &str); 4] = [
+        (
+            "BlendDriverNoContext__H2ZQ218RadicalMathLibrary6VectorZQ26choreo15FootBlendDriver_6choreoPX11PMX11CFPCX11RX01_vfiPQ26choreot13BlendPriority1ZX01iRi_v",
+            "void choreo.BlendDriverNoContext[RadicalMathLibrary.Vector, choreo.FootBlendDriver] choreo.FootBlendDriver *, void (choreo.FootBlendDriver.*) RadicalMathLibrary.Vector & const, float, int, choreo.BlendPriority[RadicalMathLibrary.Vector] *, int, int &",
+            "void choreo__BlendDriverNoContext{RadicalMathLibrary__Vector, choreo__FootBlendDriver}(choreo__FootBlendDriver *, void (choreo__FootBlendDriver__*)(RadicalMathLibrary__Vector &) const, float, int, choreo__BlendPriority{RadicalMathLibrary__Vector} *, int, int &)",
+        ),
+        (
+            "__ls__H1Zt4List1Zi_R7ostreamRCt4List1ZX01_R7ostream",
+            "ostream &operator<<[List[int]] ostream &, List[List[int]] const &",
+            "ostream &operator<<{List{int}}(ostream &, List{List{int}} const &)",
+        ),
+        (
+            "__lt__H1Zi_RCX01T0_b",
+            "bool operator<[int] int const &, int const &",
+            "bool operator<{int}(int const &, int const &)",
+        ),
+        (
+            "f__FRt3Foo1c60t3Bar1c58",
+            "f Foo['<'] &, Bar[':']",
+            "f(Foo{'<'} &, Bar{':'})",
+        ),
+    ];
+    let dotted = DemangleConfig::builder()
+        .scope_separator(".")
+        .template_brackets(('[', ']'))
+        .argument_list_parens(false)
+        .build();
+    let braced = DemangleConfig::builder()
+        .scope_separator("__")
+        .template_brackets(('{', '}'))
+        .build();
+
+    for (mangled, demangled_dotted, demangled_braced) in CASES {
+        assert_eq!(
+            demangle(mangled, &dotted).as_deref(),
+            Ok(demangled_dotted),
+            "{mangled}"
+        );
+        assert_eq!(
+            demangle(mangled, &braced).as_deref(),
+            Ok(demangled_braced),
+            "{mangled}"
+        );
+
+        // Only the output is affected.
+        assert_eq!(
+            demangle_components(mangled, &dotted),
+            demangle_components(mangled, &DemangleConfig::new())
+        );
+    }
+
+    let config = DemangleConfig::builder()
+        .template_brackets(('[', ']'))
+        .return_type_style(ReturnTypeStyle::TrailingArrow)
+        .build();
+    assert_eq!(
+        demangle("Swap__H1Zt4List1Zi_RX01T0_v", &config).as_deref(),
+        Ok("Swap[List[int]](List[int] &, List[int] &) -> void")
+    );
+    assert_eq!(
+        demangle_type_name("t4List1Zt4List1Zi", &dotted).as_deref(),
+        Ok("List[List[int]]")
+    );
+
+    // The separator may appear inside of the names it separates.
+    let underscored = DemangleConfig::builder().scope_separator("_").build();
+    assert_eq!(
+        demangle("__H1Zi_Q22ns8my_classX00", &underscored).as_deref(),
+        Ok("ns_my_class_my_class<int>(int)")
+    );
+    let joined = DemangleConfig::builder().scope_separator("").build();
+    assert_eq!(
+        demangle("__H1Zi_Q22nst3Bar1ZQ22ns3BazX00", &joined).as_deref(),
+        Ok("nsBar<nsBaz>Bar<int>(int)")
+    );
+}
+
 /*
 #[test]
 fn test_demangle_single() {
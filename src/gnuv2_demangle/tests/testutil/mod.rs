@@ -0,0 +1,170 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+//! Helpers shared by the tests and the benchmarks.
+//!
+//! Benchmarks include this with
+//! `#[path = "../tests/testutil/mod.rs"] mod testutil;`.
+
+#![allow(dead_code)]
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use gnuv2_demangle::{demangle, DemangleConfig};
+
+/// The mangled lists used by the snapshot tests, by name.
+pub const MANGLED_LISTS: [(&str, &str); 5] = [
+    ("ff2", include_str!("../mangled_lists/ff2.txt")),
+    (
+        "hit_and_run",
+        include_str!("../mangled_lists/hit_and_run.txt"),
+    ),
+    (
+        "most_wanted",
+        include_str!("../mangled_lists/most_wanted.txt"),
+    ),
+    ("parappa2", include_str!("../mangled_lists/parappa2.txt")),
+    (
+        "ty_july_first",
+        include_str!("../mangled_lists/ty_july_first.txt"),
+    ),
+];
+
+/// Symbols that are slow to demangle for their length, by name.
+pub const PATHOLOGICAL: [(&str, &str); 3] = [
+    (
+        "function_pointer_nest",
+        "i_hope_nobody_actually_writes_something_like_this__FPFPPFGQ213radPs2CdDrive14DirectoryEntryiPCQ213radPs2CdDrive14DirectoryEntry_Q213radPs2CdDrive14DirectoryEntryPFGQ213radPs2CdDrive14DirectoryEntryiPCQ213radPs2CdDrive14DirectoryEntry_Q213radPs2CdDrive14DirectoryEntryGQ213radPs2CdDrive14DirectoryEntry_PFGQ213radPs2CdDrive14DirectoryEntryiPCQ213radPs2CdDrive14DirectoryEntry_Q213radPs2CdDrive14DirectoryEntryPPFGQ213radPs2CdDrive14DirectoryEntryiPCQ213radPs2CdDrive14DirectoryEntry_Q213radPs2CdDrive14DirectoryEntryT0",
+    ),
+    ("repeat_expansion", "repeating__FPCcN24_0"),
+    (
+        "deep_namespace",
+        "a_function__Q_18_5silly8my_thing17another_namespace7stacked7stacked7stacked7stacked7stacked7stacked7stacked7stacked7stacked7stacked7stacked7stacked7stacked7stacked7stackedfffi",
+    ),
+];
+
+/// Plain C symbols, which should be rejected right away.
+pub const NOT_MANGLED: [&str; 8] = [
+    "main",
+    "memcpy",
+    "_start",
+    "sceGsSyncV",
+    "__divdi3",
+    "gp_rel_data_start",
+    "InitHeapTable",
+    "_sn_read_table",
+];
+
+/// The presets measured by [`benchmarks`], by name.
+pub fn presets() -> [(&'static str, DemangleConfig); 2] {
+    [
+        ("cfilt", DemangleConfig::new_cfilt()),
+        ("g2dem", DemangleConfig::new_g2dem()),
+    ]
+}
+
+/// Demangle each of `symbols`, throwing away the results. This is what the
+/// benchmarks measure.
+pub fn demangle_each(symbols: &[&str], config: &DemangleConfig) {
+    for sym in symbols {
+        black_box(demangle(black_box(sym), config).ok());
+    }
+}
+
+/// How many times each benchmark is measured. Only the fastest one counts,
+/// since the slower ones are mostly noise from the rest of the system.
+const SAMPLES: u32 = 5;
+
+/// How many nanoseconds it takes to demangle each of `symbols` once, on
+/// average, running for at least `min_time`.
+pub fn nanos_per_symbol(symbols: &[&str], config: &DemangleConfig, min_time: Duration) -> f64 {
+    let run = || demangle_each(symbols, config);
+
+    // Warmup
+    run();
+
+    let sample_time = min_time / SAMPLES;
+    let mut fastest = f64::INFINITY;
+    for _ in 0..SAMPLES {
+        let mut runs = 0u32;
+        let start = Instant::now();
+        while runs == 0 || start.elapsed() < sample_time {
+            run();
+            runs += 1;
+        }
+        let nanos = start.elapsed().as_secs_f64() * 1e9 / f64::from(runs);
+        fastest = fastest.min(nanos);
+    }
+    fastest / symbols.len().max(1) as f64
+}
+
+/// A list of symbols to measure, see [`benchmarks`].
+pub struct Benchmark {
+    pub name: String,
+    pub symbols: Vec<&'static str>,
+    pub config: DemangleConfig,
+}
+
+impl Benchmark {
+    /// See [`nanos_per_symbol`].
+    pub fn run(&self, min_time: Duration) -> f64 {
+        nanos_per_symbol(&self.symbols, &self.config, min_time)
+    }
+
+    /// See [`demangle_each`].
+    pub fn demangle_each(&self) {
+        demangle_each(&self.symbols, &self.config)
+    }
+}
+
+/// Every benchmark, in the order they appear in `benches/baseline.txt`.
+pub fn benchmarks() -> Vec<Benchmark> {
+    let mut benchmarks = Vec::new();
+
+    for (preset, config) in presets() {
+        for (name, contents) in MANGLED_LISTS {
+            benchmarks.push(Benchmark {
+                name: format!("corpus/{name}/{preset}"),
+                symbols: contents.lines().collect(),
+                config,
+            });
+        }
+        for (name, sym) in PATHOLOGICAL {
+            benchmarks.push(Benchmark {
+                name: format!("symbol/{name}/{preset}"),
+                symbols: vec![sym],
+                config,
+            });
+        }
+        benchmarks.push(Benchmark {
+            name: format!("not_mangled/{preset}"),
+            symbols: NOT_MANGLED.to_vec(),
+            config,
+        });
+    }
+
+    benchmarks
+}
+
+/// Parse a baseline, made of `name nanoseconds` lines. Empty lines and lines
+/// starting with `#` are skipped.
+pub fn parse_baseline(contents: &str) -> Vec<(&str, f64)> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let (name, nanos) = line
+                .split_once(' ')
+                .unwrap_or_else(|| panic!("Malformed baseline line: {line:?}"));
+            let nanos = nanos
+                .trim()
+                .parse()
+                .unwrap_or_else(|_| panic!("Malformed baseline line: {line:?}"));
+            (name, nanos)
+        })
+        .collect()
+}
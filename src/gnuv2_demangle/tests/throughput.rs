@@ -0,0 +1,114 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+//! Opt-in check that the benchmarks didn't get slower than the checked-in
+//! baseline, allowing the percentage given by the
+//! `GNUV2_DEMANGLE_BENCH_GATE` environment variable:
+//!
+//! ```bash
+//! GNUV2_DEMANGLE_BENCH_GATE=25 cargo test --release -p gnuv2_demangle --test throughput
+//! ```
+//!
+//! The baseline is only meaningful on the machine it was generated on. It can
+//! be updated with:
+//!
+//! ```bash
+//! GNUV2_DEMANGLE_BENCH_UPDATE=1 cargo test --release -p gnuv2_demangle --test throughput
+//! ```
+//!
+//! See `benches/throughput.rs` for the same benchmarks measured by criterion.
+
+mod testutil;
+
+use std::{env, fmt::Write, fs, time::Duration};
+
+const BASELINE: &str = include_str!("../benches/baseline.txt");
+const BASELINE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/benches/baseline.txt");
+
+const GATE_VAR: &str = "GNUV2_DEMANGLE_BENCH_GATE";
+const UPDATE_VAR: &str = "GNUV2_DEMANGLE_BENCH_UPDATE";
+
+const MIN_TIME: Duration = Duration::from_millis(200);
+const UPDATE_MIN_TIME: Duration = Duration::from_millis(500);
+
+fn check_release() {
+    if cfg!(debug_assertions) {
+        panic!("The baseline is measured on release builds, run this with `--release`");
+    }
+}
+
+#[test]
+fn test_throughput_baseline_is_complete() {
+    let baseline = testutil::parse_baseline(BASELINE);
+
+    let names: Vec<_> = testutil::benchmarks().into_iter().map(|x| x.name).collect();
+
+    assert_eq!(
+        baseline.iter().map(|(name, _)| *name).collect::<Vec<_>>(),
+        names
+    );
+}
+
+#[test]
+fn test_throughput_against_baseline() {
+    let Ok(allowed) = env::var(GATE_VAR) else {
+        return;
+    };
+    let allowed: f64 = allowed
+        .parse()
+        .unwrap_or_else(|_| panic!("{GATE_VAR} must be a percentage, got {allowed:?}"));
+    check_release();
+
+    let baseline = testutil::parse_baseline(BASELINE);
+    let mut regressions = Vec::new();
+    for benchmark in testutil::benchmarks() {
+        let name = &benchmark.name;
+        let (_, expected) = baseline.iter().find(|(x, _)| x == name).unwrap();
+
+        let nanos = benchmark.run(MIN_TIME);
+        let change = (nanos / expected - 1.0) * 100.0;
+        if change > allowed {
+            regressions.push(format!(
+                "{name}: {expected:.1}ns -> {nanos:.1}ns (+{change:.1}%)"
+            ));
+        }
+    }
+
+    assert!(
+        regressions.is_empty(),
+        "Slower than the baseline by more than {allowed}%:\n{}",
+        regressions.join("\n")
+    );
+}
+
+#[test]
+fn test_throughput_update_baseline() {
+    if env::var_os(UPDATE_VAR).is_none() {
+        return;
+    }
+    check_release();
+
+    let mut contents = String::new();
+    writeln!(
+        contents,
+        "# Nanoseconds per symbol, see `tests/throughput.rs`."
+    )
+    .unwrap();
+    writeln!(
+        contents,
+        "# Generated by gnuv2_demangle {}.",
+        gnuv2_demangle::VERSION
+    )
+    .unwrap();
+    for benchmark in testutil::benchmarks() {
+        writeln!(
+            contents,
+            "{} {:.1}",
+            benchmark.name,
+            benchmark.run(UPDATE_MIN_TIME)
+        )
+        .unwrap();
+    }
+
+    fs::write(BASELINE_PATH, contents).unwrap();
+}
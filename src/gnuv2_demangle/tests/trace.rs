@@ -0,0 +1,73 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+
+#![cfg(feature = "trace")]
+
+use gnuv2_demangle::{demangle_traced, DemangleConfig, TraceOutcome};
+
+use pretty_assertions::assert_eq;
+
+fn trace(sym: &str) -> (Option<String>, Vec<String>) {
+    let config = DemangleConfig::new();
+    let (demangled, events) = demangle_traced(sym, &config);
+    (
+        demangled.ok(),
+        events.iter().map(|x| x.to_string()).collect(),
+    )
+}
+
+#[test]
+fn test_trace_simple() {
+    assert_eq!(
+        trace("SetText__5tNamePCc"),
+        (
+            Some("tName::SetText(char const *)".to_string()),
+            vec![
+                "argument list at 15: parsed until 18".to_string(),
+                "  argument at 15: parsed until 18".to_string(),
+            ]
+        )
+    );
+}
+
+#[test]
+fn test_trace_nested() {
+    let (demangled, events) = trace("Set__t4List1ZPFPc_iRCt4pair2ZiZi");
+    assert_eq!(
+        demangled.as_deref(),
+        Some("List<int (*)(char *)>::Set(pair<int, int> const &)")
+    );
+    assert_eq!(
+        events,
+        [
+            "template at 6: parsed until 19",
+            "  argument at 13: parsed until 19",
+            "    function pointer at 15: parsed until 19",
+            "      argument list at 15: parsed until 17",
+            "        argument at 15: parsed until 17",
+            "      argument at 18: parsed until 19",
+            "argument list at 19: parsed until 32",
+            "  argument at 19: parsed until 32",
+            "    template at 22: parsed until 32",
+            "      argument at 29: parsed until 30",
+            "      argument at 31: parsed until 32",
+        ]
+    );
+}
+
+#[test]
+fn test_trace_failure() {
+    let config = DemangleConfig::new();
+    let (demangled, events) = demangle_traced("foo__FiPQ", &config);
+    assert!(demangled.is_err());
+
+    // The failure is reported by every step containing it.
+    let failed = TraceOutcome::Failed(demangled.unwrap_err().kind_name());
+    let last = events.last().unwrap();
+    assert_eq!(last.construct, "namespaces");
+    assert_eq!(last.start, 9);
+    assert_eq!(last.depth, 2);
+    assert_eq!(last.outcome, failed);
+    assert_eq!(events[0].outcome, failed);
+    assert_eq!(events[1].outcome, TraceOutcome::Parsed { end: 7 });
+}